@@ -0,0 +1,10 @@
+extern crate lalrpop;
+
+fn main() {
+    // Generated from the grammar of mizzle itself, so what is fuzzed is always the parser it uses
+    lalrpop::Configuration::new()
+        .set_in_dir("../src")
+        .set_out_dir(std::env::var("OUT_DIR").unwrap())
+        .process()
+        .unwrap();
+}
@@ -0,0 +1,58 @@
+#![no_main]
+
+// Parses anything as a program, which has to give back a tree or an error without panicking
+// Errors are placed in the source like they are when shown, and trees have to read back the same after printing them
+
+#[macro_use]
+extern crate lalrpop_util;
+
+use lalrpop_util::ParseError;
+use libfuzzer_sys::fuzz_target;
+
+// Only what the parser needs is included, since mizzle is a binary that cannot be depended on
+#[allow(dead_code)]
+#[path = "../../src/ast.rs"]
+mod ast;
+#[allow(dead_code)]
+#[path = "../../src/source.rs"]
+mod source;
+
+lalrpop_mod!(#[allow(clippy::all)] pub syntax);
+
+fuzz_target!(|data: &[u8]| {
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    let mut sources = source::SourceMap::default();
+    let file = sources.add("fuzz.mi", text);
+
+    match syntax::ProgramParser::new().parse(file, text) {
+        Ok(program) => {
+            let printed = program.to_string();
+            let reparsed = syntax::ProgramParser::new()
+                .parse(file, &printed)
+                .unwrap_or_else(|e| panic!("{:?} when reading back:\n{}", e, printed));
+
+            assert_eq!(reparsed.to_string(), printed);
+        }
+        Err(e) => {
+            let location = match e {
+                ParseError::InvalidToken { location } => location,
+                ParseError::UnrecognizedEOF { location, .. } => location,
+                ParseError::UnrecognizedToken {
+                    token: (start, _, _),
+                    ..
+                } => start,
+                ParseError::ExtraToken {
+                    token: (start, _, _),
+                } => start,
+                ParseError::User { .. } => 0,
+            };
+            let source = sources.get(file);
+            let (line, _) = source.position(location);
+
+            source.line(line);
+        }
+    }
+});
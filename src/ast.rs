@@ -1,12 +1,31 @@
+use std::collections::HashMap;
 use std::fmt;
 
+use crate::source::FileId;
+
 // LALRPOP is setup to parse into Expr,
 // `Display` trait implemented manually for pretty printing
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Type<T> {
+    // Has the single value `()`, for expressions that are only evaluated for what they do
+    Unit(T),
     Int(T),
     Bool(T),
+    Float(T),
+    String(T),
+    // Always has at least two items
+    Tuple(T, Vec<Type<T>>),
+    // `array<int>`, any number of items of the same type
+    Array(T, Box<Type<T>>),
+    // `fn(int, bool) -> int`, the parameters and then the result
+    Function(T, Vec<Type<T>>, Box<Type<T>>),
+    // Refers to a `type` declaration by name, like `Point` or `Option<int>`
+    Named(T, String, Vec<Type<T>>),
+    // A type parameter of a generic declaration, like the `a` in `Option<a>`
+    Var(T, String),
+    // A type that the type checker has not figured out yet, never written in source
+    Unknown(T, usize),
 }
 
 pub type JustType = Type<()>;
@@ -14,22 +33,99 @@ pub type JustType = Type<()>;
 impl<T> Type<T> {
     pub fn strip(&self) -> JustType {
         match self {
+            Type::Unit(_) => Type::Unit(()),
             Type::Int(_) => Type::Int(()),
             Type::Bool(_) => Type::Bool(()),
+            Type::Float(_) => Type::Float(()),
+            Type::String(_) => Type::String(()),
+            Type::Tuple(_, items) => Type::Tuple((), items.iter().map(|t| t.strip()).collect()),
+            Type::Array(_, item) => Type::Array((), Box::new(item.strip())),
+            Type::Function(_, params, result) => Type::Function(
+                (),
+                params.iter().map(|t| t.strip()).collect(),
+                Box::new(result.strip()),
+            ),
+            Type::Named(_, name, args) => {
+                Type::Named((), name.clone(), args.iter().map(|t| t.strip()).collect())
+            }
+            Type::Var(_, name) => Type::Var((), name.clone()),
+            Type::Unknown(_, id) => Type::Unknown((), *id),
         }
     }
 
     pub fn extra(&self) -> &T {
         match self {
+            Type::Unit(e) => e,
             Type::Int(e) => e,
             Type::Bool(e) => e,
+            Type::Float(e) => e,
+            Type::String(e) => e,
+            Type::Tuple(e, _) => e,
+            Type::Array(e, _) => e,
+            Type::Function(e, _, _) => e,
+            Type::Named(e, _, _) => e,
+            Type::Var(e, _) => e,
+            Type::Unknown(e, _) => e,
         }
     }
 
     pub fn map_extra<U>(&self, f: &dyn Fn(&T) -> U) -> Type<U> {
         match self {
+            Type::Unit(extra) => Type::Unit(f(extra)),
             Type::Int(extra) => Type::Int(f(extra)),
             Type::Bool(extra) => Type::Bool(f(extra)),
+            Type::Float(extra) => Type::Float(f(extra)),
+            Type::String(extra) => Type::String(f(extra)),
+            Type::Tuple(extra, items) => {
+                Type::Tuple(f(extra), items.iter().map(|t| t.map_extra(f)).collect())
+            }
+            Type::Array(extra, item) => Type::Array(f(extra), Box::new(item.map_extra(f))),
+            Type::Function(extra, params, result) => Type::Function(
+                f(extra),
+                params.iter().map(|t| t.map_extra(f)).collect(),
+                Box::new(result.map_extra(f)),
+            ),
+            Type::Named(extra, name, args) => Type::Named(
+                f(extra),
+                name.clone(),
+                args.iter().map(|t| t.map_extra(f)).collect(),
+            ),
+            Type::Var(extra, name) => Type::Var(f(extra), name.clone()),
+            Type::Unknown(extra, id) => Type::Unknown(f(extra), *id),
+        }
+    }
+}
+
+impl JustType {
+    // Replaces every type variable in `params` with the type at the same index in `args`
+    pub fn substitute(&self, params: &[String], args: &[JustType]) -> JustType {
+        match self {
+            Type::Tuple(_, items) => Type::Tuple(
+                (),
+                items.iter().map(|t| t.substitute(params, args)).collect(),
+            ),
+            Type::Array(_, item) => Type::Array((), Box::new(item.substitute(params, args))),
+            Type::Function(_, fn_params, result) => Type::Function(
+                (),
+                fn_params
+                    .iter()
+                    .map(|t| t.substitute(params, args))
+                    .collect(),
+                Box::new(result.substitute(params, args)),
+            ),
+            Type::Named(_, name, type_args) => Type::Named(
+                (),
+                name.clone(),
+                type_args
+                    .iter()
+                    .map(|t| t.substitute(params, args))
+                    .collect(),
+            ),
+            Type::Var(_, name) => match params.iter().position(|p| p == name) {
+                Some(i) => args[i].clone(),
+                None => self.clone(),
+            },
+            _ => self.clone(),
         }
     }
 }
@@ -37,28 +133,187 @@ impl<T> Type<T> {
 impl<T> PartialEq for Type<T> {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (Self::Int(_), Self::Int(_)) => true,
-            (Self::Bool(_), Self::Bool(_)) => true,
-            _ => false,
+            (Self::Tuple(_, l), Self::Tuple(_, r)) => l == r,
+            (Self::Array(_, l), Self::Array(_, r)) => l == r,
+            (Self::Function(_, l, l_result), Self::Function(_, r, r_result)) => {
+                l == r && l_result == r_result
+            }
+            // Types are nominal, so two declarations with the same fields are still different
+            (Self::Named(_, l, l_args), Self::Named(_, r, r_args)) => l == r && l_args == r_args,
+            (Self::Var(_, l), Self::Var(_, r)) => l == r,
+            (Self::Unknown(_, l), Self::Unknown(_, r)) => l == r,
+            _ => matches!(
+                (self, other),
+                (Self::Unit(_), Self::Unit(_))
+                    | (Self::Int(_), Self::Int(_))
+                    | (Self::Bool(_), Self::Bool(_))
+                    | (Self::Float(_), Self::Float(_))
+                    | (Self::String(_), Self::String(_))
+            ),
         }
     }
 }
 
 impl<T> Eq for Type<T> {}
 
+fn comma_separated<D: fmt::Display>(items: &[D]) -> String {
+    items
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 impl<T> fmt::Display for Type<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match self {
+            Type::Unit(_) => write!(fmt, "unit"),
             Type::Int(_) => write!(fmt, "int"),
             Type::Bool(_) => write!(fmt, "bool"),
+            Type::Float(_) => write!(fmt, "float"),
+            Type::String(_) => write!(fmt, "string"),
+            Type::Tuple(_, items) => write!(fmt, "({})", comma_separated(items)),
+            Type::Array(_, item) => write!(fmt, "array<{}>", item),
+            Type::Function(_, params, result) => {
+                write!(fmt, "fn({}) -> {}", comma_separated(params), result)
+            }
+            Type::Named(_, name, args) if args.is_empty() => write!(fmt, "{}", name),
+            Type::Named(_, name, args) => write!(fmt, "{}<{}>", name, comma_separated(args)),
+            Type::Var(_, name) => write!(fmt, "{}", name),
+            Type::Unknown(_, _) => write!(fmt, "_"),
+        }
+    }
+}
+
+// What the left side of a `let` can be
+#[derive(Clone, Debug)]
+pub enum Pattern<T> {
+    Var(T, String),
+    // Written as `_`, ignores the value
+    Wildcard(T),
+    Tuple(T, Vec<Pattern<T>>),
+    // `Circle(r)`, or just `Empty` when the constructor has no arguments
+    Constructor(T, String, Vec<Pattern<T>>),
+    Bool(T, bool),
+    Int(T, i64),
+}
+
+impl<T> Pattern<T> {
+    pub fn extra(&self) -> &T {
+        match self {
+            Pattern::Var(extra, _) => extra,
+            Pattern::Wildcard(extra) => extra,
+            Pattern::Tuple(extra, _) => extra,
+            Pattern::Constructor(extra, _, _) => extra,
+            Pattern::Bool(extra, _) => extra,
+            Pattern::Int(extra, _) => extra,
+        }
+    }
+
+    pub fn map_extra<U>(&self, f: &dyn Fn(&T) -> U) -> Pattern<U> {
+        match self {
+            Pattern::Var(extra, name) => Pattern::Var(f(extra), name.clone()),
+            Pattern::Wildcard(extra) => Pattern::Wildcard(f(extra)),
+            Pattern::Tuple(extra, items) => {
+                Pattern::Tuple(f(extra), items.iter().map(|p| p.map_extra(f)).collect())
+            }
+            Pattern::Constructor(extra, name, args) => Pattern::Constructor(
+                f(extra),
+                name.clone(),
+                args.iter().map(|p| p.map_extra(f)).collect(),
+            ),
+            Pattern::Bool(extra, b) => Pattern::Bool(f(extra), *b),
+            Pattern::Int(extra, n) => Pattern::Int(f(extra), *n),
+        }
+    }
+}
+
+impl<T> fmt::Display for Pattern<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Pattern::Var(_, name) => write!(fmt, "{}", name),
+            Pattern::Wildcard(_) => write!(fmt, "_"),
+            Pattern::Tuple(_, items) => write!(fmt, "({})", comma_separated(items)),
+            Pattern::Constructor(_, name, args) if args.is_empty() => write!(fmt, "{}", name),
+            Pattern::Constructor(_, name, args) => {
+                write!(fmt, "{}({})", name, comma_separated(args))
+            }
+            Pattern::Bool(_, b) => write!(fmt, "{}", b),
+            Pattern::Int(_, n) => write!(fmt, "{}", n),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinOp {
+    Concat,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            BinOp::Concat => write!(fmt, "++"),
+            BinOp::Add => write!(fmt, "+"),
+            BinOp::Sub => write!(fmt, "-"),
+            BinOp::Mul => write!(fmt, "*"),
+            BinOp::Div => write!(fmt, "/"),
+            BinOp::Rem => write!(fmt, "%"),
+            BinOp::Eq => write!(fmt, "=="),
+            BinOp::Ne => write!(fmt, "!="),
+            BinOp::Lt => write!(fmt, "<"),
+            BinOp::Le => write!(fmt, "<="),
+            BinOp::Gt => write!(fmt, ">"),
+            BinOp::Ge => write!(fmt, ">="),
+        }
+    }
+}
+
+// Functions that are part of the language, called like `len("abc")`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Builtin {
+    Len,
+    // The number of items in an array, instead of bytes in a string
+    Length,
+    ToFloat,
+    ToInt,
+    // Both write their argument out and give back `unit`,
+    // `debug` writes it like a literal, after the file and line of the call
+    Print,
+    Debug,
+}
+
+impl fmt::Display for Builtin {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Builtin::Len => write!(fmt, "len"),
+            Builtin::Length => write!(fmt, "length"),
+            Builtin::ToFloat => write!(fmt, "to_float"),
+            Builtin::ToInt => write!(fmt, "to_int"),
+            Builtin::Print => write!(fmt, "print"),
+            Builtin::Debug => write!(fmt, "debug"),
         }
     }
 }
 
 #[derive(Clone, Debug)]
 pub enum Expr<T> {
+    // `()`
+    UnitLit(T),
     IntLit(T, i64),
     BoolLit(T, bool),
+    FloatLit(T, f64),
+    StringLit(T, String),
     TypeAnno {
         extra: T,
         term: Box<Expr<T>>,
@@ -70,22 +325,240 @@ pub enum Expr<T> {
         on_true: Box<Expr<T>>,
         on_false: Box<Expr<T>>,
     },
+    BinOp {
+        extra: T,
+        op: BinOp,
+        left: Box<Expr<T>>,
+        right: Box<Expr<T>>,
+    },
+    Builtin {
+        extra: T,
+        builtin: Builtin,
+        arg: Box<Expr<T>>,
+    },
+    Var(T, String),
+    // Always has at least two items
+    Tuple {
+        extra: T,
+        items: Vec<Expr<T>>,
+    },
+    // Gets a single item out of a tuple, like `pair.0`
+    Project {
+        extra: T,
+        term: Box<Expr<T>>,
+        index: usize,
+    },
+    Let {
+        extra: T,
+        pattern: Pattern<T>,
+        value: Box<Expr<T>>,
+        body: Box<Expr<T>>,
+    },
+    // `Point { x = 1, y = 2 }`, the fields can be in any order
+    Record {
+        extra: T,
+        name: String,
+        fields: Vec<FieldValue<T>>,
+    },
+    // Gets a field out of a record, like `point.x`
+    Field {
+        extra: T,
+        term: Box<Expr<T>>,
+        field: String,
+    },
+    // `{ point with x = 3 }`, copies the record with some of the fields replaced
+    Update {
+        extra: T,
+        term: Box<Expr<T>>,
+        fields: Vec<FieldValue<T>>,
+    },
+    // `Circle(1)`, or just `Empty` when the constructor has no arguments
+    Construct {
+        extra: T,
+        name: String,
+        args: Vec<Expr<T>>,
+    },
+    // The first arm whose pattern matches is evaluated
+    Match {
+        extra: T,
+        term: Box<Expr<T>>,
+        arms: Vec<Arm<T>>,
+    },
+    // Calls a function declared with `fn`, the type arguments of a generic function are inferred
+    Call {
+        extra: T,
+        name: String,
+        args: Vec<Expr<T>>,
+    },
+    // `first; second`, the value of `first` is thrown away
+    Seq {
+        extra: T,
+        first: Box<Expr<T>>,
+        second: Box<Expr<T>>,
+    },
+    // `var x = 1 in body`, like `let` but `x` can be assigned to in `body`
+    VarDecl {
+        extra: T,
+        name: String,
+        value: Box<Expr<T>>,
+        body: Box<Expr<T>>,
+    },
+    // `x := 2`, where `x` was declared with `var`
+    Assign {
+        extra: T,
+        name: String,
+        value: Box<Expr<T>>,
+    },
+    // Evaluates `body` for as long as `cond` is `true`
+    While {
+        extra: T,
+        cond: Box<Expr<T>>,
+        body: Box<Expr<T>>,
+    },
+    // Leaves the innermost `while`
+    Break(T),
+    // `[1, 2, 3]`, or `[]` when the type of the items is found out from elsewhere
+    Array {
+        extra: T,
+        items: Vec<Expr<T>>,
+    },
+    // `array(3, x)`, an array of `length` copies of `value`
+    Repeat {
+        extra: T,
+        length: Box<Expr<T>>,
+        value: Box<Expr<T>>,
+    },
+    // `items[i]`, which stops the program when `i` is out of bounds
+    Index {
+        extra: T,
+        term: Box<Expr<T>>,
+        index: Box<Expr<T>>,
+    },
+    // `items[i] := 2`, arrays can always be changed, no matter how they are bound
+    SetIndex {
+        extra: T,
+        term: Box<Expr<T>>,
+        index: Box<Expr<T>>,
+        value: Box<Expr<T>>,
+    },
+}
+
+// `pattern => body`, one case of a `match`
+#[derive(Clone, Debug)]
+pub struct Arm<T> {
+    pub extra: T,
+    pub pattern: Pattern<T>,
+    pub body: Expr<T>,
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldValue<T> {
+    pub extra: T,
+    pub name: String,
+    pub value: Expr<T>,
+}
+
+impl<T> FieldValue<T> {
+    pub fn map_extra<U>(&self, f: &dyn Fn(&T) -> U) -> FieldValue<U> {
+        FieldValue {
+            extra: f(&self.extra),
+            name: self.name.clone(),
+            value: self.value.map_extra(f),
+        }
+    }
+}
+
+impl<T> fmt::Display for FieldValue<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{} = {}", self.name, self.value)
+    }
 }
 
 impl<T> Expr<T> {
     pub fn extra(&self) -> &T {
         match self {
+            Expr::UnitLit(extra) => extra,
             Expr::IntLit(extra, _) => extra,
             Expr::BoolLit(extra, _) => extra,
+            Expr::FloatLit(extra, _) => extra,
+            Expr::StringLit(extra, _) => extra,
             Expr::TypeAnno { extra, .. } => extra,
             Expr::IfFlow { extra, .. } => extra,
+            Expr::BinOp { extra, .. } => extra,
+            Expr::Builtin { extra, .. } => extra,
+            Expr::Var(extra, _) => extra,
+            Expr::Tuple { extra, .. } => extra,
+            Expr::Project { extra, .. } => extra,
+            Expr::Let { extra, .. } => extra,
+            Expr::Record { extra, .. } => extra,
+            Expr::Field { extra, .. } => extra,
+            Expr::Update { extra, .. } => extra,
+            Expr::Construct { extra, .. } => extra,
+            Expr::Match { extra, .. } => extra,
+            Expr::Call { extra, .. } => extra,
+            Expr::Seq { extra, .. } => extra,
+            Expr::VarDecl { extra, .. } => extra,
+            Expr::Assign { extra, .. } => extra,
+            Expr::While { extra, .. } => extra,
+            Expr::Break(extra) => extra,
+            Expr::Array { extra, .. } => extra,
+            Expr::Repeat { extra, .. } => extra,
+            Expr::Index { extra, .. } => extra,
+            Expr::SetIndex { extra, .. } => extra,
+        }
+    }
+
+    // The expressions directly inside of this one, in the order they are written
+    pub fn children(&self) -> Vec<&Expr<T>> {
+        match self {
+            Expr::UnitLit(_)
+            | Expr::IntLit(_, _)
+            | Expr::BoolLit(_, _)
+            | Expr::FloatLit(_, _)
+            | Expr::StringLit(_, _)
+            | Expr::Var(_, _)
+            | Expr::Break(_) => vec![],
+            Expr::TypeAnno { term, .. } | Expr::Project { term, .. } | Expr::Field { term, .. } => {
+                vec![term]
+            }
+            Expr::Builtin { arg, .. } => vec![arg],
+            Expr::Assign { value, .. } => vec![value],
+            Expr::IfFlow {
+                cond,
+                on_true,
+                on_false,
+                ..
+            } => vec![cond, on_true, on_false],
+            Expr::BinOp { left, right, .. } => vec![left, right],
+            Expr::Let { value, body, .. } | Expr::VarDecl { value, body, .. } => {
+                vec![value, body]
+            }
+            Expr::Tuple { items, .. } | Expr::Array { items, .. } => items.iter().collect(),
+            Expr::Construct { args, .. } | Expr::Call { args, .. } => args.iter().collect(),
+            Expr::Record { fields, .. } => fields.iter().map(|f| &f.value).collect(),
+            Expr::Update { term, fields, .. } => std::iter::once(&**term)
+                .chain(fields.iter().map(|f| &f.value))
+                .collect(),
+            Expr::Match { term, arms, .. } => std::iter::once(&**term)
+                .chain(arms.iter().map(|a| &a.body))
+                .collect(),
+            Expr::Seq { first, second, .. } => vec![first, second],
+            Expr::While { cond, body, .. } => vec![cond, body],
+            Expr::Repeat { length, value, .. } => vec![length, value],
+            Expr::Index { term, index, .. } => vec![term, index],
+            Expr::SetIndex {
+                term, index, value, ..
+            } => vec![term, index, value],
         }
     }
 
     pub fn map_extra<U>(&self, f: &dyn Fn(&T) -> U) -> Expr<U> {
         match self {
+            Expr::UnitLit(extra) => Expr::UnitLit(f(extra)),
             Expr::IntLit(extra, n) => Expr::IntLit(f(extra), *n),
             Expr::BoolLit(extra, b) => Expr::BoolLit(f(extra), *b),
+            Expr::FloatLit(extra, x) => Expr::FloatLit(f(extra), *x),
+            Expr::StringLit(extra, s) => Expr::StringLit(f(extra), s.clone()),
             Expr::TypeAnno { extra, term, ty } => Expr::TypeAnno {
                 extra: f(extra),
                 term: Box::new(term.map_extra(f)),
@@ -102,39 +575,411 @@ impl<T> Expr<T> {
                 on_true: Box::new(on_true.map_extra(f)),
                 on_false: Box::new(on_false.map_extra(f)),
             },
+            Expr::BinOp {
+                extra,
+                op,
+                left,
+                right,
+            } => Expr::BinOp {
+                extra: f(extra),
+                op: *op,
+                left: Box::new(left.map_extra(f)),
+                right: Box::new(right.map_extra(f)),
+            },
+            Expr::Builtin {
+                extra,
+                builtin,
+                arg,
+            } => Expr::Builtin {
+                extra: f(extra),
+                builtin: *builtin,
+                arg: Box::new(arg.map_extra(f)),
+            },
+            Expr::Var(extra, name) => Expr::Var(f(extra), name.clone()),
+            Expr::Tuple { extra, items } => Expr::Tuple {
+                extra: f(extra),
+                items: items.iter().map(|i| i.map_extra(f)).collect(),
+            },
+            Expr::Project { extra, term, index } => Expr::Project {
+                extra: f(extra),
+                term: Box::new(term.map_extra(f)),
+                index: *index,
+            },
+            Expr::Let {
+                extra,
+                pattern,
+                value,
+                body,
+            } => Expr::Let {
+                extra: f(extra),
+                pattern: pattern.map_extra(f),
+                value: Box::new(value.map_extra(f)),
+                body: Box::new(body.map_extra(f)),
+            },
+            Expr::Record {
+                extra,
+                name,
+                fields,
+            } => Expr::Record {
+                extra: f(extra),
+                name: name.clone(),
+                fields: fields.iter().map(|v| v.map_extra(f)).collect(),
+            },
+            Expr::Field { extra, term, field } => Expr::Field {
+                extra: f(extra),
+                term: Box::new(term.map_extra(f)),
+                field: field.clone(),
+            },
+            Expr::Update {
+                extra,
+                term,
+                fields,
+            } => Expr::Update {
+                extra: f(extra),
+                term: Box::new(term.map_extra(f)),
+                fields: fields.iter().map(|v| v.map_extra(f)).collect(),
+            },
+            Expr::Construct { extra, name, args } => Expr::Construct {
+                extra: f(extra),
+                name: name.clone(),
+                args: args.iter().map(|a| a.map_extra(f)).collect(),
+            },
+            Expr::Match { extra, term, arms } => Expr::Match {
+                extra: f(extra),
+                term: Box::new(term.map_extra(f)),
+                arms: arms
+                    .iter()
+                    .map(|a| Arm {
+                        extra: f(&a.extra),
+                        pattern: a.pattern.map_extra(f),
+                        body: a.body.map_extra(f),
+                    })
+                    .collect(),
+            },
+            Expr::Call { extra, name, args } => Expr::Call {
+                extra: f(extra),
+                name: name.clone(),
+                args: args.iter().map(|a| a.map_extra(f)).collect(),
+            },
+            Expr::Seq {
+                extra,
+                first,
+                second,
+            } => Expr::Seq {
+                extra: f(extra),
+                first: Box::new(first.map_extra(f)),
+                second: Box::new(second.map_extra(f)),
+            },
+            Expr::VarDecl {
+                extra,
+                name,
+                value,
+                body,
+            } => Expr::VarDecl {
+                extra: f(extra),
+                name: name.clone(),
+                value: Box::new(value.map_extra(f)),
+                body: Box::new(body.map_extra(f)),
+            },
+            Expr::Assign { extra, name, value } => Expr::Assign {
+                extra: f(extra),
+                name: name.clone(),
+                value: Box::new(value.map_extra(f)),
+            },
+            Expr::While { extra, cond, body } => Expr::While {
+                extra: f(extra),
+                cond: Box::new(cond.map_extra(f)),
+                body: Box::new(body.map_extra(f)),
+            },
+            Expr::Break(extra) => Expr::Break(f(extra)),
+            Expr::Array { extra, items } => Expr::Array {
+                extra: f(extra),
+                items: items.iter().map(|i| i.map_extra(f)).collect(),
+            },
+            Expr::Repeat {
+                extra,
+                length,
+                value,
+            } => Expr::Repeat {
+                extra: f(extra),
+                length: Box::new(length.map_extra(f)),
+                value: Box::new(value.map_extra(f)),
+            },
+            Expr::Index { extra, term, index } => Expr::Index {
+                extra: f(extra),
+                term: Box::new(term.map_extra(f)),
+                index: Box::new(index.map_extra(f)),
+            },
+            Expr::SetIndex {
+                extra,
+                term,
+                index,
+                value,
+            } => Expr::SetIndex {
+                extra: f(extra),
+                term: Box::new(term.map_extra(f)),
+                index: Box::new(index.map_extra(f)),
+                value: Box::new(value.map_extra(f)),
+            },
         }
     }
 }
 
-pub type JustExpr = Expr<()>;
+// The inverse of the escapes that the lexer understands
+pub fn escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\\' => "\\\\".into(),
+            '"' => "\\\"".into(),
+            '\n' => "\\n".into(),
+            '\t' => "\\t".into(),
+            '\r' => "\\r".into(),
+            '\0' => "\\0".into(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+pub fn unescape(s: &str) -> Option<String> {
+    let mut unescaped = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                '"' => '"',
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                '0' => '\0',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+
+    Some(unescaped)
+}
+
+// How tightly each kind of expression binds, from the loosest to the tightest,
+// Following the rules of `syntax.lalrpop` with the same names
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Term,
+    Assign,
+    Anno,
+    Compare,
+    Concat,
+    Additive,
+    Multiplicative,
+    Postfix,
+    Atom,
+}
+
+fn op_precedence(op: BinOp) -> Precedence {
+    match op {
+        BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => {
+            Precedence::Compare
+        }
+        BinOp::Concat => Precedence::Concat,
+        BinOp::Add | BinOp::Sub => Precedence::Additive,
+        BinOp::Mul | BinOp::Div | BinOp::Rem => Precedence::Multiplicative,
+    }
+}
+
+fn precedence<T>(e: &Expr<T>) -> Precedence {
+    match e {
+        Expr::Let { .. } | Expr::VarDecl { .. } | Expr::Seq { .. } => Precedence::Term,
+        Expr::Assign { .. } | Expr::SetIndex { .. } => Precedence::Assign,
+        Expr::TypeAnno { .. } => Precedence::Anno,
+        Expr::BinOp { op, .. } => op_precedence(*op),
+        Expr::Project { .. } | Expr::Field { .. } | Expr::Index { .. } => Precedence::Postfix,
+        _ => Precedence::Atom,
+    }
+}
+
+const INDENT: &str = "    ";
+
+// Prints `e` where the grammar expects `at`, in parentheses only when it binds looser than that
+fn pretty_at<T>(e: &Expr<T>, at: Precedence, indent: usize) -> String {
+    if precedence(e) < at {
+        format!("({})", pretty_expr(e, indent))
+    } else {
+        pretty_expr(e, indent)
+    }
+}
+
+// The term before `.`, `[` and `:=`
+fn pretty_postfix<T>(term: &Expr<T>, indent: usize) -> String {
+    match term {
+        // `1.0` would read back as a float
+        Expr::IntLit(_, _) => format!("({})", pretty_expr(term, indent)),
+        _ => pretty_at(term, Precedence::Postfix, indent),
+    }
+}
+
+fn pretty_items<T>(items: &[Expr<T>], indent: usize) -> String {
+    items
+        .iter()
+        .map(|i| pretty_expr(i, indent))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn pretty_fields<T>(fields: &[FieldValue<T>], indent: usize) -> String {
+    fields
+        .iter()
+        .map(|f| format!("{} = {}", f.name, pretty_expr(&f.value, indent)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
+// Prints `e` without parentheses around it, indenting new lines by `indent` levels
 fn pretty_expr<T>(e: &Expr<T>, indent: usize) -> String {
+    let indents = INDENT.repeat(indent);
+
     match e {
+        Expr::UnitLit(_) => "()".into(),
         Expr::IntLit(_, n) => n.to_string(),
         Expr::BoolLit(_, b) => b.to_string(),
+        // `Debug` always includes a `.` or an exponent, so it reads back as a float
+        Expr::FloatLit(_, x) => format!("{:?}", x),
+        Expr::StringLit(_, s) => format!("\"{}\"", escape(s)),
         Expr::TypeAnno { term, ty, .. } => {
-            format!("{}: {}", pretty_expr(term, indent), ty.to_string())
+            format!("{}: {}", pretty_at(term, Precedence::Anno, indent), ty)
         }
         Expr::IfFlow {
             cond,
             on_true,
             on_false,
             ..
+        } => format!(
+            "if {1} then\n{0}{4}{2}\n{0}else\n{0}{4}{3}\n{0}end",
+            indents,
+            pretty_expr(cond, indent),
+            pretty_expr(on_true, indent + 1),
+            pretty_expr(on_false, indent + 1),
+            INDENT
+        ),
+        Expr::BinOp {
+            op, left, right, ..
         } => {
-            let indents = "\t".repeat(indent);
-            let pretty_cond = pretty_expr(cond, indent);
-            let pretty_on_true = pretty_expr(on_true, indent + 1);
-            let pretty_on_false = pretty_expr(on_false, indent + 1);
+            // Comparisons do not chain, and the other operators are left associative
+            let (left_at, right_at) = match op_precedence(*op) {
+                Precedence::Compare => (Precedence::Concat, Precedence::Concat),
+                Precedence::Concat => (Precedence::Concat, Precedence::Additive),
+                Precedence::Additive => (Precedence::Additive, Precedence::Multiplicative),
+                _ => (Precedence::Multiplicative, Precedence::Postfix),
+            };
 
             format!(
-                r#"if {1} then
-{0}	{2}
-{0}else
-{0}	{3}
-{0}end"#,
-                indents, pretty_cond, pretty_on_true, pretty_on_false
+                "{} {} {}",
+                pretty_at(left, left_at, indent),
+                op,
+                pretty_at(right, right_at, indent)
             )
         }
+        Expr::Builtin { builtin, arg, .. } => {
+            format!("{}({})", builtin, pretty_expr(arg, indent))
+        }
+        Expr::Var(_, name) => name.clone(),
+        Expr::Tuple { items, .. } => format!("({})", pretty_items(items, indent)),
+        Expr::Project { term, index, .. } => {
+            format!("{}.{}", pretty_postfix(term, indent), index)
+        }
+        Expr::Let {
+            pattern,
+            value,
+            body,
+            ..
+        } => format!(
+            "let {} = {} in\n{}{}",
+            pattern,
+            pretty_expr(value, indent),
+            indents,
+            pretty_expr(body, indent)
+        ),
+        Expr::Record { name, fields, .. } => {
+            format!("{} {{ {} }}", name, pretty_fields(fields, indent))
+        }
+        Expr::Field { term, field, .. } => {
+            format!("{}.{}", pretty_postfix(term, indent), field)
+        }
+        Expr::Update { term, fields, .. } => format!(
+            "{{ {} with {} }}",
+            pretty_expr(term, indent),
+            pretty_fields(fields, indent)
+        ),
+        Expr::Construct { name, args, .. } if args.is_empty() => name.clone(),
+        Expr::Construct { name, args, .. } => format!("{}({})", name, pretty_items(args, indent)),
+        Expr::Match { term, arms, .. } => {
+            let arms = arms
+                .iter()
+                .map(|a| {
+                    format!(
+                        "{}| {} => {}\n",
+                        indents,
+                        a.pattern,
+                        pretty_expr(&a.body, indent + 1)
+                    )
+                })
+                .collect::<String>();
+
+            format!(
+                "match {} with\n{}{}end",
+                pretty_expr(term, indent),
+                arms,
+                indents
+            )
+        }
+        Expr::Call { name, args, .. } => format!("{}({})", name, pretty_items(args, indent)),
+        // The first expression is followed by `;`, so it cannot take everything after it
+        Expr::Seq { first, second, .. } => format!(
+            "{};\n{}{}",
+            pretty_at(first, Precedence::Assign, indent),
+            indents,
+            pretty_expr(second, indent)
+        ),
+        Expr::VarDecl {
+            name, value, body, ..
+        } => format!(
+            "var {} = {} in\n{}{}",
+            name,
+            pretty_expr(value, indent),
+            indents,
+            pretty_expr(body, indent)
+        ),
+        Expr::Assign { name, value, .. } => {
+            format!("{} := {}", name, pretty_at(value, Precedence::Anno, indent))
+        }
+        Expr::While { cond, body, .. } => format!(
+            "while {} do\n{1}{3}{2}\n{1}end",
+            pretty_expr(cond, indent),
+            indents,
+            pretty_expr(body, indent + 1),
+            INDENT
+        ),
+        Expr::Break(_) => "break".into(),
+        Expr::Array { items, .. } => format!("[{}]", pretty_items(items, indent)),
+        Expr::Repeat { length, value, .. } => format!(
+            "array({}, {})",
+            pretty_expr(length, indent),
+            pretty_expr(value, indent)
+        ),
+        Expr::Index { term, index, .. } => format!(
+            "{}[{}]",
+            pretty_postfix(term, indent),
+            pretty_expr(index, indent)
+        ),
+        Expr::SetIndex {
+            term, index, value, ..
+        } => format!(
+            "{}[{}] := {}",
+            pretty_postfix(term, indent),
+            pretty_expr(index, indent),
+            pretty_at(value, Precedence::Anno, indent)
+        ),
     }
 }
 
@@ -144,8 +989,412 @@ impl<T> fmt::Display for Expr<T> {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Span(pub usize, pub usize);
+#[derive(Clone, Debug)]
+pub struct FieldDecl<T> {
+    pub extra: T,
+    pub name: String,
+    pub ty: Type<T>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Param<T> {
+    pub extra: T,
+    pub name: String,
+    pub ty: Type<T>,
+}
+
+// Everything that can be written before the body of a program
+// `type_params` are the names between `<` and `>`, empty when the declaration is not generic
+// `public` is set by `pub`, which lets other modules import the declaration
+#[derive(Clone, Debug)]
+pub enum Decl<T> {
+    // `type Point = { x: int, y: int }`, always has at least one field
+    Record {
+        extra: T,
+        public: bool,
+        name: String,
+        type_params: Vec<String>,
+        fields: Vec<FieldDecl<T>>,
+    },
+    // `type Shape = Circle(int) | Square(int) | Empty`
+    Variant {
+        extra: T,
+        public: bool,
+        name: String,
+        type_params: Vec<String>,
+        constructors: Vec<ConstructorDecl<T>>,
+    },
+    // `fn add(x: int, y: int): int = x + y end`
+    Function {
+        extra: T,
+        public: bool,
+        name: String,
+        type_params: Vec<String>,
+        params: Vec<Param<T>>,
+        result: Type<T>,
+        body: Expr<T>,
+    },
+    // `test "adds" = 1 + 2 == 3 end`, which only `mizzle test` runs, and is never public
+    Test {
+        extra: T,
+        name: String,
+        body: Expr<T>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct ConstructorDecl<T> {
+    pub extra: T,
+    pub name: String,
+    pub args: Vec<Type<T>>,
+}
+
+impl<T> Decl<T> {
+    pub fn extra(&self) -> &T {
+        match self {
+            Decl::Record { extra, .. } => extra,
+            Decl::Variant { extra, .. } => extra,
+            Decl::Function { extra, .. } => extra,
+            Decl::Test { extra, .. } => extra,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Decl::Record { name, .. } => name,
+            Decl::Variant { name, .. } => name,
+            Decl::Function { name, .. } => name,
+            Decl::Test { name, .. } => name,
+        }
+    }
+
+    pub fn type_params(&self) -> &[String] {
+        match self {
+            Decl::Record { type_params, .. } => type_params,
+            Decl::Variant { type_params, .. } => type_params,
+            Decl::Function { type_params, .. } => type_params,
+            Decl::Test { .. } => &[],
+        }
+    }
+
+    pub fn public(&self) -> bool {
+        match self {
+            Decl::Record { public, .. } => *public,
+            Decl::Variant { public, .. } => *public,
+            Decl::Function { public, .. } => *public,
+            Decl::Test { .. } => false,
+        }
+    }
+
+    pub fn map_extra<U>(&self, f: &dyn Fn(&T) -> U) -> Decl<U> {
+        match self {
+            Decl::Record {
+                extra,
+                public,
+                name,
+                type_params,
+                fields,
+            } => Decl::Record {
+                extra: f(extra),
+                public: *public,
+                name: name.clone(),
+                type_params: type_params.clone(),
+                fields: fields
+                    .iter()
+                    .map(|field| FieldDecl {
+                        extra: f(&field.extra),
+                        name: field.name.clone(),
+                        ty: field.ty.map_extra(f),
+                    })
+                    .collect(),
+            },
+            Decl::Variant {
+                extra,
+                public,
+                name,
+                type_params,
+                constructors,
+            } => Decl::Variant {
+                extra: f(extra),
+                public: *public,
+                name: name.clone(),
+                type_params: type_params.clone(),
+                constructors: constructors
+                    .iter()
+                    .map(|c| ConstructorDecl {
+                        extra: f(&c.extra),
+                        name: c.name.clone(),
+                        args: c.args.iter().map(|a| a.map_extra(f)).collect(),
+                    })
+                    .collect(),
+            },
+            Decl::Function {
+                extra,
+                public,
+                name,
+                type_params,
+                params,
+                result,
+                body,
+            } => Decl::Function {
+                extra: f(extra),
+                public: *public,
+                name: name.clone(),
+                type_params: type_params.clone(),
+                params: params
+                    .iter()
+                    .map(|p| Param {
+                        extra: f(&p.extra),
+                        name: p.name.clone(),
+                        ty: p.ty.map_extra(f),
+                    })
+                    .collect(),
+                result: result.map_extra(f),
+                body: body.map_extra(f),
+            },
+            Decl::Test { extra, name, body } => Decl::Test {
+                extra: f(extra),
+                name: name.clone(),
+                body: body.map_extra(f),
+            },
+        }
+    }
+}
+
+fn pretty_type_params(type_params: &[String]) -> String {
+    if type_params.is_empty() {
+        "".into()
+    } else {
+        format!("<{}>", type_params.join(", "))
+    }
+}
+
+impl<T> fmt::Display for Decl<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if self.public() {
+            write!(fmt, "pub ")?;
+        }
+
+        match self {
+            Decl::Record { fields, .. } => {
+                let fields = fields
+                    .iter()
+                    .map(|f| format!("{}: {}", f.name, f.ty))
+                    .collect::<Vec<_>>();
+
+                write!(
+                    fmt,
+                    "type {}{} = {{ {} }}",
+                    self.name(),
+                    pretty_type_params(self.type_params()),
+                    fields.join(", ")
+                )
+            }
+            Decl::Variant { constructors, .. } => {
+                let constructors = constructors
+                    .iter()
+                    .map(|c| match c.args.as_slice() {
+                        [] => c.name.clone(),
+                        args => format!("{}({})", c.name, comma_separated(args)),
+                    })
+                    .collect::<Vec<_>>();
+
+                write!(
+                    fmt,
+                    "type {}{} = {}",
+                    self.name(),
+                    pretty_type_params(self.type_params()),
+                    constructors.join(" | ")
+                )
+            }
+            Decl::Function {
+                params,
+                result,
+                body,
+                ..
+            } => {
+                let params = params
+                    .iter()
+                    .map(|p| format!("{}: {}", p.name, p.ty))
+                    .collect::<Vec<_>>();
+
+                write!(
+                    fmt,
+                    "fn {}{}({}): {} =\n{}{}\nend",
+                    self.name(),
+                    pretty_type_params(self.type_params()),
+                    params.join(", "),
+                    result,
+                    INDENT,
+                    pretty_expr(body, 1)
+                )
+            }
+            Decl::Test { name, body, .. } => write!(
+                fmt,
+                "test \"{}\" =\n{}{}\nend",
+                escape(name),
+                INDENT,
+                pretty_expr(body, 1)
+            ),
+        }
+    }
+}
+
+// The fields of the record declared as `name`, in the order they were declared
+pub fn record_fields<'a, T>(decls: &'a [Decl<T>], name: &str) -> Option<&'a [FieldDecl<T>]> {
+    decls.iter().find_map(|d| match d {
+        Decl::Record {
+            name: n, fields, ..
+        } if n == name => Some(fields.as_slice()),
+        _ => None,
+    })
+}
+
+// The type parameters of the declaration `name`, which is assumed to exist
+// Tests are skipped, since their names can be anything
+pub fn type_params<'a, T>(decls: &'a [Decl<T>], name: &str) -> &'a [String] {
+    decls
+        .iter()
+        .find(|d| d.name() == name && !matches!(d, Decl::Test { .. }))
+        .map(|d| d.type_params())
+        .unwrap()
+}
+
+// Finds the types that the type parameters in `declared` stand for in `actual`,
+// Which is the same type with every type parameter replaced
+fn match_type_args(declared: &JustType, actual: &JustType, found: &mut HashMap<String, JustType>) {
+    match (declared, actual) {
+        (Type::Var(_, name), _) => {
+            found.insert(name.clone(), actual.clone());
+        }
+        (Type::Tuple(_, declared), Type::Tuple(_, actual))
+        | (Type::Named(_, _, declared), Type::Named(_, _, actual)) => {
+            for (d, a) in declared.iter().zip(actual) {
+                match_type_args(d, a, found);
+            }
+        }
+        (Type::Array(_, declared), Type::Array(_, actual)) => {
+            match_type_args(declared, actual, found)
+        }
+        (
+            Type::Function(_, declared, declared_result),
+            Type::Function(_, actual, actual_result),
+        ) => {
+            for (d, a) in declared.iter().zip(actual) {
+                match_type_args(d, a, found);
+            }
+            match_type_args(declared_result, actual_result, found);
+        }
+        _ => {}
+    }
+}
+
+// The types that the type parameters of `function` stand for,
+// When it is called with arguments of `arg_tys` and gives back `result`
+pub fn type_args(function: &TypedDecl, arg_tys: &[JustType], result: &JustType) -> Vec<JustType> {
+    let (type_params, params, declared) = match function {
+        Decl::Function {
+            type_params,
+            params,
+            result,
+            ..
+        } => (type_params, params, result),
+        _ => panic!("Internal compiler error"),
+    };
+
+    let mut found = HashMap::new();
+    for (param, arg_ty) in params.iter().zip(arg_tys) {
+        match_type_args(&param.extra.1, arg_ty, &mut found);
+    }
+    match_type_args(&declared.strip(), result, &mut found);
+
+    // A type parameter that is in neither the parameters nor the result never has a value,
+    // So any type works for it
+    type_params
+        .iter()
+        .map(|p| found.get(p).cloned().unwrap_or(Type::Int(())))
+        .collect()
+}
+
+// The constructors of the variant declared as `name`, in the order they were declared
+pub fn variant_constructors<'a, T>(
+    decls: &'a [Decl<T>],
+    name: &str,
+) -> Option<&'a [ConstructorDecl<T>]> {
+    decls.iter().find_map(|d| match d {
+        Decl::Variant {
+            name: n,
+            constructors,
+            ..
+        } if n == name => Some(constructors.as_slice()),
+        _ => None,
+    })
+}
+
+// `import math`, which refers to `math.mi` next to the file that imports it
+#[derive(Clone, Debug)]
+pub struct Import<T> {
+    pub extra: T,
+    pub name: String,
+}
+
+// A whole source file, declarations followed by the expression that is evaluated
+#[derive(Clone, Debug)]
+pub struct Program<T> {
+    pub imports: Vec<Import<T>>,
+    pub decls: Vec<Decl<T>>,
+    pub body: Expr<T>,
+}
+
+impl<T> fmt::Display for Program<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for i in &self.imports {
+            writeln!(fmt, "import {}", i.name)?;
+        }
+
+        for d in &self.decls {
+            writeln!(fmt, "{}", d)?;
+        }
+
+        write!(fmt, "{}", self.body)
+    }
+}
+
+// A source file that is only imported, so it has no expression to evaluate
+#[derive(Clone, Debug)]
+pub struct Library<T> {
+    pub imports: Vec<Import<T>>,
+    pub decls: Vec<Decl<T>>,
+}
+
+// Byte offsets into the file, `end` is one after the last character
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Span {
+    pub file: FileId,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(file: FileId, start: usize, end: usize) -> Span {
+        Span { file, start, end }
+    }
+}
 
 pub type SpanType = Type<Span>;
 pub type SpanExpr = Expr<Span>;
+pub type SpanPattern = Pattern<Span>;
+pub type SpanDecl = Decl<Span>;
+pub type SpanProgram = Program<Span>;
+pub type SpanImport = Import<Span>;
+pub type SpanLibrary = Library<Span>;
+
+// The output of type checking
+pub type TypedExpr = Expr<(Span, JustType)>;
+pub type TypedPattern = Pattern<(Span, JustType)>;
+pub type TypedDecl = Decl<(Span, JustType)>;
+pub type TypedProgram = Program<(Span, JustType)>;
+
+// Trees without spans, which the property tests make up instead of parsing
+#[cfg(test)]
+pub type JustExpr = Expr<()>;
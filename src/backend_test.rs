@@ -0,0 +1,346 @@
+// What the C and JavaScript backends have in common in their tests, which run programs both ways and compare them with wasm
+// Running them needs `cc` or `node`, so the code each backend writes is also compared with a snapshot in `tests/snapshots`,
+// Which is checked without them, and can only have been written when it ran the same as wasm, if they are installed
+// `MIZZLE_BLESS=1` writes the snapshots from what the backends write now
+// Every other test that needs a program also loads and checks it with `compile` from here
+#![cfg(test)]
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::ast;
+use crate::modules;
+use crate::source::SourceMap;
+use crate::wasm;
+
+pub trait Backend {
+    // The name of its snapshots, along with the extension of the code it writes
+    const NAME: &'static str;
+    const EXTENSION: &'static str;
+    // What runs the code, which is only used when it is installed
+    const COMMAND: &'static str;
+
+    fn generate(typed: &ast::TypedProgram, sources: &SourceMap) -> String;
+
+    // What the program writes, and whether it finished without an error, after writing it to `dir`
+    fn run(typed: &ast::TypedProgram, sources: &SourceMap, dir: &Path) -> (String, bool);
+}
+
+// Loads `source` as the file `test.mi`, without checking it
+pub fn load(source: &str) -> (Vec<modules::Module>, SourceMap) {
+    let mut sources = SourceMap::default();
+    let modules = modules::load(Path::new("test.mi"), source, &mut sources).unwrap();
+
+    (modules, sources)
+}
+
+pub fn compile(source: &str) -> (ast::TypedProgram, SourceMap) {
+    let (modules, sources) = load(source);
+    let typed = modules::check(&modules).result.unwrap();
+
+    (typed, sources)
+}
+
+// Writes `files` to a directory of their own, and loads and checks `main.mi` from it
+pub fn compile_files(name: &str, files: &[(&str, &str)]) -> (ast::TypedProgram, SourceMap) {
+    let dir = env::temp_dir().join(format!("mizzle-files-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    for (file, source) in files {
+        fs::write(dir.join(file), source).unwrap();
+    }
+
+    let main = dir.join("main.mi");
+    let source = fs::read_to_string(&main).unwrap();
+    let mut sources = SourceMap::default();
+    let modules = modules::load(&main, &source, &mut sources).unwrap();
+    let typed = modules::check(&modules).result.unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    (typed, sources)
+}
+
+fn installed(command: &'static str) -> bool {
+    static INSTALLED: Mutex<BTreeMap<&str, bool>> = Mutex::new(BTreeMap::new());
+
+    *INSTALLED.lock().unwrap().entry(command).or_insert_with(|| {
+        Command::new(command)
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+fn run_wasm(typed: &ast::TypedProgram, sources: &SourceMap) -> (String, bool) {
+    let output = Arc::new(Mutex::new(vec![]));
+    let result = wasm::run(&wasm::ast_to_wasm(typed), sources, output.clone());
+
+    let mut printed = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+    match result {
+        Ok(value) if typed.body.extra().1 != ast::Type::Unit(()) => {
+            printed += &format!("{}\n", value);
+            (printed, true)
+        }
+        Ok(_) => (printed, true),
+        Err(_) => (printed, false),
+    }
+}
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+// Runs each program with the backend and with wasm, and keeps what the backend wrote for the snapshot
+struct Check<B> {
+    snapshot: PathBuf,
+    written: String,
+    backend: std::marker::PhantomData<B>,
+}
+
+impl<B: Backend> Check<B> {
+    fn new(name: &str) -> Check<B> {
+        Check {
+            snapshot: Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/snapshots")
+                .join(format!("{}_{}.{}", B::NAME, name, B::EXTENSION)),
+            written: String::new(),
+            backend: std::marker::PhantomData,
+        }
+    }
+
+    fn same(&mut self, source: &str) {
+        let (typed, sources) = compile(source);
+        self.same_program(source, &typed, &sources);
+    }
+
+    // A program of several files, which are all written before it for the snapshot
+    fn same_files(&mut self, name: &str, files: &[(&str, &str)]) {
+        let (typed, sources) = compile_files(&format!("{}-{}", B::NAME, name), files);
+        let source = files
+            .iter()
+            .map(|(file, source)| format!("{}:\n{}", file, source.trim()))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        self.same_program(&source, &typed, &sources);
+    }
+
+    fn same_program(&mut self, source: &str, typed: &ast::TypedProgram, sources: &SourceMap) {
+        let code = B::generate(typed, sources);
+
+        if installed(B::COMMAND) {
+            let dir = env::temp_dir().join(format!(
+                "mizzle-{}-{}-{}",
+                B::NAME,
+                std::process::id(),
+                NEXT_DIR.fetch_add(1, Ordering::SeqCst)
+            ));
+            fs::create_dir_all(&dir).unwrap();
+            let ran = B::run(typed, sources, &dir);
+            fs::remove_dir_all(&dir).unwrap();
+
+            assert_eq!(ran, run_wasm(typed, sources), "{}", code);
+        }
+
+        for line in source.trim().lines().map(str::trim) {
+            self.written += &format!("//{}{}\n", if line.is_empty() { "" } else { " " }, line);
+        }
+        self.written += &format!("\n{}\n", code);
+    }
+
+    fn finish(self) {
+        if env::var("MIZZLE_BLESS").is_ok() {
+            fs::create_dir_all(self.snapshot.parent().unwrap()).unwrap();
+            fs::write(&self.snapshot, &self.written).unwrap();
+        }
+
+        match fs::read_to_string(&self.snapshot) {
+            Ok(snapshot) => assert!(
+                snapshot == self.written,
+                "{} is different from what the backend writes now, which is:\n{}\nrun with `MIZZLE_BLESS=1` if this is right",
+                self.snapshot.display(),
+                self.written
+            ),
+            Err(_) => panic!(
+                "{} is missing, run with `MIZZLE_BLESS=1` to write it",
+                self.snapshot.display()
+            ),
+        }
+    }
+}
+
+pub fn same_as_wasm_for_primitives<B: Backend>() {
+    let mut check = Check::<B>::new("primitives");
+
+    check.same("1 + 2 * 3");
+    check.same("0 - 9223372036854775807 - 1 - 1");
+    check.same("(9223372036854775807 * 2, 7 / 2, (0 - 7) / 2, (0 - 7) % 3, 7 % 0 - 7)");
+    check.same("1 / 0");
+    check.same("(0 - 9223372036854775807 - 1) / (0 - 1)");
+    check.same("(0 - 9223372036854775807 - 1) % (0 - 1)");
+    check.same("(to_int(2.9), to_int(0.0 - 2.9), to_float(3), len(\"é\"), to_int(1e300))");
+    check.same(r#"("a\"\\\n\r\t\0é??=" ++ "", "", 1 == 1, "a" != "a", 1.5 < 2.0)"#);
+    check.same("((1 < 2) == (3 < 4)) != false");
+
+    for x in [
+        "0.0",
+        "2.5",
+        "0.1",
+        "1e15",
+        "1e16",
+        "1.5e16",
+        "0.0001",
+        "1e-5",
+        "1e300",
+        "5e-324",
+        "123456.789",
+    ] {
+        check.same(x);
+        check.same(&format!("0.0 - {}", x));
+    }
+    check.same("(0.1 + 0.2, 1.0 / 3.0, 1e300 * 1e10, 0.0 - 1e300 * 1e10, 0.0 / 0.0)");
+
+    check.finish();
+}
+
+pub fn same_as_wasm_for_values<B: Backend>() {
+    let mut check = Check::<B>::new("values");
+    let decls = r#"
+        type Shape = Circle(float) | Rect(int, int) | Empty
+        type List<a> = Cons(a, List<a>) | Nil
+        type Point = { x: int, y: float, name: string }
+        fn inc(x: int): int = x + 1 end
+        fn name(x: int): string = if x > 2 then "big" else "small" end end
+    "#;
+
+    check.same(&format!("{} (Rect(1, 2), Empty, Circle(0.5))", decls));
+    check.same(&format!(
+        "{} Cons((1, \"a\"), Cons((2, \"b\"), Nil))",
+        decls
+    ));
+    check.same(&format!(
+        r#"{} let p = Point {{ name = "p", x = 1, y = 2.5 }} in [p, {{ p with x = 3 }}]"#,
+        decls
+    ));
+    check.same(&format!(
+        r#"{} let p = Point {{ name = "p", x = 1, y = 2.5 }} in (p.name, p.y, len(p.name))"#,
+        decls
+    ));
+    check.same(&format!("{} (inc, [[1, 2], [], [3]], [(), ()])", decls));
+    check.same(&format!("{} map(range(0, 5), name)", decls));
+    check.same(&format!(
+        "{} (array(2, ()), array(0 - 1, 1), length(array(3, 1.5)))",
+        decls
+    ));
+    check.same(&format!("{} let f = inc in (f(1), f(f(2)))", decls));
+
+    check.finish();
+}
+
+pub fn same_as_wasm_for_control_flow<B: Backend>() {
+    let mut check = Check::<B>::new("control_flow");
+
+    check.same(
+        r#"
+        type Shape = Circle(float) | Rect(int, int) | Empty
+
+        fn area(s: Shape): int =
+            match s with
+            | Circle(r) => to_int(r * r * 3.0)
+            | Rect(1, h) => h
+            | Rect(w, h) => w * h
+            | Empty => 0
+            end
+        end
+
+        fn triangle(n: int): int =
+            var i = 0 in var sum = 0 in
+            while true do
+                if i > n then break else sum := sum + i; i := i + 1 end
+            end;
+            sum
+        end
+
+        let (a, (_, b)) = (area(Rect(2, 3)) + area(Rect(1, 5)), (true, triangle(4))) in
+        let a = a + 1 in
+        match (a, b > 5) with
+        | (0, _) => "zero"
+        | (_, true) => "big " ++ (if a > 10 then "a" else if a > 5 then "b" else "c" end end)
+        | _ => "small"
+        end
+        "#,
+    );
+    check.same("var x = 1 in x + (x := 10; x)");
+    check.same("var x = 1 in (x := x + 1; x, let y = x in (x := 5; y), x)");
+    // Names that are keywords or taken in one of the backends
+    check.same("let char = 1 in let double = 2 in let char = char + double in char");
+    check.same("let new = 1 in let this = 2 in let new = new + this in new");
+    check.same("fn main(): int = 1 end fn mz_show(x: int): int = x end mz_show(main())");
+    check.same("fn main(): int = 1 end fn show(x: int): int = x end show(main())");
+    check.same(
+        "var i = 0 in while (i := i + 1; print(i); i < 3) do print(if i == 2 then 0 else i end) end",
+    );
+    check.same("let items = [1, 2, 3] in items[1] := 5; print(items); items[3]");
+    check.same("let t0 = 1 in (t0, if t0 > 0 then (t0, 2) else (3, t0) end)");
+    check.same(
+        "match (1, true, ()) with (1, false, _) => (0, ()) | (n, true, u) => (n, u) | _ => (2, ()) end",
+    );
+
+    check.finish();
+}
+
+pub fn same_as_wasm_for_output<B: Backend>() {
+    let mut check = Check::<B>::new("output");
+
+    check.same(
+        r#"
+        type Shape = Circle(float) | Rect(int, int)
+        fn show<a>(x: a): unit = debug(x) end
+        fn say(x: int): int = print(x); x end
+        print("a\tb");
+        debug("a\tb");
+        debug(Rect(1, 2));
+        show(1);
+        show((Circle(0.5), [true]));
+        print(print(1));
+        print(say(1) + say(2) * say(3));
+        debug((say(4), say(5)));
+        var i = 0 in
+        while i < 3 do print(to_float(i) / 4.0); i := i + 1 end
+        "#,
+    );
+
+    check.finish();
+}
+
+pub fn same_as_wasm_for_modules<B: Backend>() {
+    let mut check = Check::<B>::new("modules");
+
+    // Private functions keep their own names in each module, even when another module's looks like them
+    check.same_files(
+        "names",
+        &[
+            (
+                "shapes.mi",
+                r#"
+                fn area(x: int): int = x * x end
+                pub fn total(x: int): int = area(x) + 1 end
+                "#,
+            ),
+            (
+                "main.mi",
+                r#"
+                import shapes
+                fn shapes__area(x: int): int = x end
+                fn shapes_area(x: int): int = x + 10 end
+                (total(3), shapes__area(2), shapes_area(2))
+                "#,
+            ),
+        ],
+    );
+
+    check.finish();
+}
@@ -0,0 +1,1802 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast;
+use crate::codegen::{self, indent, mangle, Dest, Kind, Scope, Target};
+use crate::instances::Instances;
+use crate::ir;
+use crate::source;
+use crate::wasm;
+
+// Translates a typed program into portable C99, a source file and a header that declares `mizzle_main`,
+// Which runs the program, and `mizzle_show`, which writes what it gives back like running it directly
+// `int` is an `int64_t` that wraps around like an `i64`, `bool` is a `bool` and `float` is a `double`,
+// Tuples, records, variants and arrays are pointers to `mz_slot`s that are never freed,
+// And `if` and `match` become statements that assign to a temporary, so no compiler extensions are needed
+
+// Words that cannot be the name of a variable, since C or the headers the output includes use them
+const RESERVED: &[&str] = &[
+    "_Bool",
+    "_Complex",
+    "_Imaginary",
+    "asm",
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "char",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "int16_t",
+    "int32_t",
+    "int64_t",
+    "int8_t",
+    "long",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "size_t",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "true",
+    "typedef",
+    "typeof",
+    "uint16_t",
+    "uint32_t",
+    "uint64_t",
+    "uint8_t",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+];
+
+// Functions that the output calls, each is only included when it is used,
+// After the helpers it calls, which come before it in this list
+const HELPERS: &[(&str, &[&str], &str)] = &[
+    (
+        "mz_trap",
+        &[],
+        r##"// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}"##,
+    ),
+    (
+        "mz_alloc",
+        &["mz_trap"],
+        r##"// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}"##,
+    ),
+    (
+        "mz_copy",
+        &["mz_alloc"],
+        r##"static mz_slot *mz_copy(const mz_slot *from, int64_t slots) {
+    mz_slot *items = mz_alloc(slots);
+    memcpy(items, from, (size_t)slots * sizeof(mz_slot));
+    return items;
+}"##,
+    ),
+    (
+        "mz_array",
+        &["mz_alloc", "mz_trap"],
+        r##"// An array has its length in the first slot and its items after that, it is empty for a negative length
+static mz_slot *mz_array(int64_t length) {
+    mz_slot *items;
+    if (length < 0) {
+        length = 0;
+    }
+    if (length >= INT64_MAX / (int64_t)sizeof(mz_slot)) {
+        mz_trap("out of memory");
+    }
+    items = mz_alloc(length + 1);
+    items[0].i = length;
+    return items;
+}"##,
+    ),
+    (
+        "mz_repeat",
+        &["mz_array"],
+        r##"// `array(length, item)`
+static mz_slot *mz_repeat(int64_t length, mz_slot item) {
+    mz_slot *items = mz_array(length);
+    int64_t i;
+    for (i = 1; i <= items[0].i; i++) {
+        items[i] = item;
+    }
+    return items;
+}"##,
+    ),
+    (
+        "mz_item",
+        &["mz_trap"],
+        r##"// The slot of `index` in `items`, which stops the program when it is outside of them,
+// `at` is where the index is in the source
+static mz_slot *mz_item(mz_slot *items, int64_t index, const char *at) {
+    if (index < 0 || index >= items[0].i) {
+        char message[512];
+        snprintf(
+            message,
+            sizeof message,
+            "%s: index `%lld` is out of bounds of an array with length `%lld`",
+            at,
+            (long long)index,
+            (long long)items[0].i
+        );
+        mz_trap(message);
+    }
+    return &items[index + 1];
+}"##,
+    ),
+    (
+        "mz_add",
+        &[],
+        r##"// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}"##,
+    ),
+    (
+        "mz_sub",
+        &[],
+        r##"static int64_t mz_sub(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a - (uint64_t)b);
+}"##,
+    ),
+    (
+        "mz_mul",
+        &[],
+        r##"static int64_t mz_mul(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a * (uint64_t)b);
+}"##,
+    ),
+    (
+        "mz_div",
+        &["mz_trap"],
+        r##"// Stops the program in the same cases as wasm does
+static int64_t mz_div(int64_t a, int64_t b) {
+    if (b == 0) {
+        mz_trap("integer divide by zero");
+    }
+    if (a == INT64_MIN && b == -1) {
+        mz_trap("integer overflow");
+    }
+    return a / b;
+}"##,
+    ),
+    (
+        "mz_rem",
+        &["mz_trap"],
+        r##"static int64_t mz_rem(int64_t a, int64_t b) {
+    if (b == 0) {
+        mz_trap("integer divide by zero");
+    }
+    // `INT64_MIN % -1` is undefined, and every remainder of dividing by -1 is 0
+    if (b == -1) {
+        return 0;
+    }
+    return a % b;
+}"##,
+    ),
+    (
+        "mz_to_int",
+        &["mz_trap"],
+        r##"static int64_t mz_to_int(double x) {
+    if (isnan(x)) {
+        mz_trap("invalid conversion to integer");
+    }
+    if (x < -9223372036854775808.0 || x >= 9223372036854775808.0) {
+        mz_trap("integer overflow");
+    }
+    return (int64_t)x;
+}"##,
+    ),
+    (
+        "mz_concat",
+        &["mz_trap"],
+        r##"// The bytes of the new string are right after it
+static mz_string *mz_concat(const mz_string *a, const mz_string *b) {
+    mz_string *s = malloc(sizeof(mz_string) + (size_t)(a->len + b->len));
+    char *bytes;
+    if (s == NULL) {
+        mz_trap("out of memory");
+    }
+    bytes = (char *)(s + 1);
+    memcpy(bytes, a->bytes, (size_t)a->len);
+    memcpy(bytes + a->len, b->bytes, (size_t)b->len);
+    s->len = a->len + b->len;
+    s->bytes = bytes;
+    return s;
+}"##,
+    ),
+    (
+        "mz_str_eq",
+        &[],
+        r##"static bool mz_str_eq(const mz_string *a, const mz_string *b) {
+    return a->len == b->len && memcmp(a->bytes, b->bytes, (size_t)a->len) == 0;
+}"##,
+    ),
+    (
+        "mz_write_bytes",
+        &[],
+        r##"// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}"##,
+    ),
+    (
+        "mz_write_text",
+        &["mz_write_bytes"],
+        r##"static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}"##,
+    ),
+    (
+        "mz_write_int",
+        &["mz_write_text"],
+        r##"static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}"##,
+    ),
+    (
+        "mz_write_bool",
+        &["mz_write_text"],
+        r##"static void mz_write_bool(bool b) {
+    mz_write_text(b ? "true" : "false");
+}"##,
+    ),
+    (
+        "mz_write_float",
+        &["mz_write_bytes", "mz_write_text"],
+        r##"// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}"##,
+    ),
+    (
+        "mz_write_string",
+        &["mz_write_bytes"],
+        r##"static void mz_write_string(const mz_string *s) {
+    mz_write_bytes(s->bytes, (size_t)s->len);
+}"##,
+    ),
+    (
+        "mz_write_quoted",
+        &["mz_write_bytes", "mz_write_text"],
+        r##"// Strings inside of other values are written like literals
+static void mz_write_quoted(const mz_string *s) {
+    int64_t i;
+    mz_write_text("\"");
+    for (i = 0; i < s->len; i++) {
+        switch (s->bytes[i]) {
+        case '\\':
+            mz_write_text("\\\\");
+            break;
+        case '"':
+            mz_write_text("\\\"");
+            break;
+        case '\n':
+            mz_write_text("\\n");
+            break;
+        case '\t':
+            mz_write_text("\\t");
+            break;
+        case '\r':
+            mz_write_text("\\r");
+            break;
+        case '\0':
+            mz_write_text("\\0");
+            break;
+        default:
+            mz_write_bytes(&s->bytes[i], 1);
+        }
+    }
+    mz_write_text("\"");
+}"##,
+    ),
+];
+
+// The types that the output and the code that embeds it share
+const TYPES: &str = r##"// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;"##;
+
+// A C expression
+#[derive(Clone, Debug)]
+struct C {
+    code: String,
+    kind: Kind,
+    ty: ast::JustType,
+    // Whether it calls a function or can stop the program,
+    // Since C evaluates the operands of an operator and the arguments of a call in any order
+    effects: bool,
+}
+
+impl codegen::Value for C {
+    fn code(&self) -> &str {
+        &self.code
+    }
+
+    fn kind(&self) -> Kind {
+        self.kind
+    }
+}
+
+impl C {
+    fn new(code: impl Into<String>, kind: Kind, ty: ast::JustType, effects: bool) -> C {
+        C {
+            code: code.into(),
+            kind,
+            ty,
+            effects,
+        }
+    }
+
+    fn unit() -> C {
+        C::new("0", Kind::Literal, ast::Type::Unit(()), false)
+    }
+
+    // The expression as the operand of `!`, a cast, `->` or `[]`
+    fn operand(&self) -> String {
+        match self.kind {
+            Kind::Operator(_) | Kind::Conditional | Kind::Prefix => format!("({})", self.code),
+            // String literals are `&` of their data
+            Kind::Literal if self.code.starts_with('&') => format!("({})", self.code),
+            _ => self.code.clone(),
+        }
+    }
+
+    // The expression on the left of an operator that binds as tightly as `precedence`,
+    // Operators are left associative, so only looser ones need parentheses
+    fn left(&self, precedence: u8) -> String {
+        match self.kind {
+            Kind::Operator(p) if p >= precedence => self.code.clone(),
+            Kind::Prefix => self.code.clone(),
+            _ => self.operand(),
+        }
+    }
+
+    fn right(&self, precedence: u8) -> String {
+        match self.kind {
+            Kind::Operator(p) if p > precedence => self.code.clone(),
+            Kind::Prefix => self.code.clone(),
+            _ => self.operand(),
+        }
+    }
+
+    // The expression as an operand of a comparison, which has parentheses when it is a comparison too
+    fn compared(&self) -> String {
+        match self.kind {
+            Kind::Operator(p) if p > 9 => self.code.clone(),
+            Kind::Prefix => self.code.clone(),
+            _ => self.operand(),
+        }
+    }
+
+    // The expression as a part of `cond ? a : b`
+    fn branch(&self) -> String {
+        match self.kind {
+            Kind::Conditional => format!("({})", self.code),
+            _ => self.code.clone(),
+        }
+    }
+}
+
+// Pieces of what is written for a value
+enum Part {
+    Text(String),
+    Statement(String),
+}
+
+// Statements that write the parts, text that is next to each other is written at once
+fn writes(parts: Vec<Part>) -> Vec<String> {
+    let mut merged: Vec<Part> = vec![];
+
+    for part in parts {
+        match (merged.last_mut(), part) {
+            (Some(Part::Text(last)), Part::Text(text)) => last.push_str(&text),
+            (_, part) => merged.push(part),
+        }
+    }
+
+    merged
+        .into_iter()
+        .filter(|part| !matches!(part, Part::Text(text) if text.is_empty()))
+        .map(|part| match part {
+            Part::Text(text) => format!("mz_write_text({});", string(&text)),
+            Part::Statement(statement) => statement,
+        })
+        .collect()
+}
+
+// A C string literal, anything that is not printable ASCII is written as an octal escape,
+// And `?` is escaped so that it never forms a trigraph
+fn string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for b in s.bytes() {
+        match b {
+            b'\\' => quoted.push_str("\\\\"),
+            b'"' => quoted.push_str("\\\""),
+            b'?' => quoted.push_str("\\?"),
+            b'\n' => quoted.push_str("\\n"),
+            b'\r' => quoted.push_str("\\r"),
+            b'\t' => quoted.push_str("\\t"),
+            0x20..=0x7e => quoted.push(b as char),
+            b => quoted.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+fn float(x: f64) -> String {
+    if x.is_nan() {
+        "NAN".into()
+    } else if x.is_infinite() {
+        if x > 0.0 { "INFINITY" } else { "-INFINITY" }.into()
+    } else {
+        format!("{:?}", x)
+    }
+}
+
+fn int(n: i64) -> String {
+    // The literal for the smallest `int64_t` would not fit before it is negated
+    if n == i64::MIN {
+        "INT64_MIN".into()
+    } else {
+        n.to_string()
+    }
+}
+
+// Names of variables that C reserves get a `_` after them,
+// And ones that could be mistaken for what the output declares get a `v_` before them
+fn ident(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{}_", name)
+    } else if name.starts_with("mz_") || name.starts_with("fn_") {
+        format!("v_{}", name)
+    } else {
+        name.into()
+    }
+}
+
+// Functions start with `fn_`, and `math::square` becomes `fn_math__square`
+fn function_name(name: &str) -> String {
+    format!("fn_{}", name.replace("::", "__"))
+}
+
+fn c_type(ty: &ast::JustType) -> &'static str {
+    match ty {
+        ast::Type::Unit(_) => "mz_unit",
+        ast::Type::Int(_) => "int64_t",
+        ast::Type::Bool(_) => "bool",
+        ast::Type::Float(_) => "double",
+        ast::Type::String(_) => "mz_string *",
+        ast::Type::Function(_, _, _) => "mz_fn",
+        ast::Type::Tuple(_, _) | ast::Type::Array(_, _) | ast::Type::Named(_, _, _) => "mz_slot *",
+        ast::Type::Var(_, _) | ast::Type::Unknown(_, _) => panic!("Internal compiler error"),
+    }
+}
+
+// `int64_t x` or `mz_slot *x`
+fn declare(ty: &ast::JustType, name: &str) -> String {
+    match c_type(ty) {
+        pointer if pointer.ends_with('*') => format!("{}{}", pointer, name),
+        other => format!("{} {}", other, name),
+    }
+}
+
+// The member of `mz_slot` that holds a `ty`, which is `None` for `unit` since it is never stored
+fn member(ty: &ast::JustType) -> Option<&'static str> {
+    match ty {
+        ast::Type::Unit(_) => None,
+        ast::Type::Int(_) => Some("i"),
+        ast::Type::Bool(_) => Some("b"),
+        ast::Type::Float(_) => Some("f"),
+        ast::Type::String(_) => Some("s"),
+        ast::Type::Function(_, _, _) => Some("fn"),
+        ast::Type::Tuple(_, _) | ast::Type::Array(_, _) | ast::Type::Named(_, _, _) => Some("p"),
+        ast::Type::Var(_, _) | ast::Type::Unknown(_, _) => panic!("Internal compiler error"),
+    }
+}
+
+// The type of a pointer to a function, for casting an `mz_fn` back before calling it
+fn function_pointer(params: &[ast::JustType], result: &ast::JustType) -> String {
+    let params = if params.is_empty() {
+        "void".into()
+    } else {
+        params.iter().map(c_type).collect::<Vec<_>>().join(", ")
+    };
+
+    format!("{}(*)({})", declare(result, ""), params)
+}
+
+// Whether `pattern` declares any variables
+fn binds(pattern: &ast::TypedPattern) -> bool {
+    match pattern {
+        ast::Pattern::Var(_, _) => true,
+        ast::Pattern::Wildcard(_) | ast::Pattern::Bool(_, _) | ast::Pattern::Int(_, _) => false,
+        ast::Pattern::Tuple(_, patterns) | ast::Pattern::Constructor(_, _, patterns) => {
+            patterns.iter().any(binds)
+        }
+    }
+}
+
+struct Generator<'a> {
+    sources: &'a source::SourceMap,
+    decls: &'a [ast::TypedDecl],
+    helpers: HashSet<&'static str>,
+    // The name of each string literal by its text, and their definitions
+    strings: HashMap<String, String>,
+    string_defs: Vec<String>,
+    // The name of the function that writes each type, by the type, and the definitions of those functions
+    shows: HashMap<String, String>,
+    show_functions: Vec<(String, String)>,
+    instances: Instances<'a>,
+    scope: Scope,
+}
+
+impl<'a> Generator<'a> {
+    fn concrete(&self, ty: &ast::JustType) -> ast::JustType {
+        self.instances.concrete(ty)
+    }
+
+    fn local(&mut self, name: &str) -> String {
+        self.scope.local(name, ident(name))
+    }
+
+    // Where the index at `span` is in the source, for the error when it is out of bounds
+    fn location(&self, span: ast::Span) -> String {
+        string(&codegen::location(self.sources, span))
+    }
+
+    fn helper(&mut self, name: &'static str) -> &'static str {
+        let (_, dependencies, _) = HELPERS.iter().find(|(n, _, _)| *n == name).unwrap();
+        for dependency in dependencies.iter() {
+            self.helper(dependency);
+        }
+        self.helpers.insert(name);
+
+        name
+    }
+
+    // The name of the data of a string literal
+    fn string_literal(&mut self, s: &str) -> String {
+        if let Some(name) = self.strings.get(s) {
+            return name.clone();
+        }
+
+        let name = format!("mz_str_{}", self.strings.len());
+        self.string_defs.push(format!(
+            "static mz_string {} = {{ {}, {} }};",
+            name,
+            s.len(),
+            string(s)
+        ));
+        self.strings.insert(s.into(), name.clone());
+
+        name
+    }
+
+    // The instance of the function `name` called with `arg_tys` that returns `result`
+    fn instance(
+        &mut self,
+        name: &str,
+        arg_tys: &[ast::JustType],
+        result: &ast::JustType,
+    ) -> String {
+        // `shapes::area` and `shapes__area` are both `fn_shapes__area`, so names are made unique as they are used
+        let scope = &mut self.scope;
+        self.instances.instance(name, arg_tys, result, |type_args| {
+            let words = type_args.iter().map(mangle);
+            let base = std::iter::once(function_name(name)).chain(words);
+
+            scope.global(base.collect::<Vec<_>>().join("_"))
+        })
+    }
+
+    // The slot `index` of `base`, as a `ty`
+    fn slot(base: &str, index: impl std::fmt::Display, ty: &ast::JustType) -> String {
+        match member(ty) {
+            Some(member) => format!("{}[{}].{}", base, index, member),
+            None => "0".into(),
+        }
+    }
+
+    fn store(&mut self, base: &str, index: usize, value: C, out: &mut Vec<String>) {
+        match member(&value.ty) {
+            Some(member) => out.push(format!("{}[{}].{} = {};", base, index, member, value.code)),
+            None => self.discard(value, out),
+        }
+    }
+
+    // Allocates `slots` with `alloc` and stores each of `values` in them, starting at `first`
+    fn slots(
+        &mut self,
+        alloc: String,
+        first: Vec<String>,
+        values: Vec<(usize, C)>,
+        ty: ast::JustType,
+        out: &mut Vec<String>,
+    ) -> C {
+        let temp = self.scope.temp();
+        out.push(format!("mz_slot *{} = {};", temp, alloc));
+        out.extend(first.into_iter().map(|s| format!("{}{}", temp, s)));
+
+        for (i, value) in values {
+            self.store(&temp, i, value, out);
+        }
+
+        C::new(temp, Kind::Name, ty, false)
+    }
+
+    // The index of the constructor `name` in its variant
+    fn tag(&self, name: &str) -> String {
+        format!("TAG_{}", name)
+    }
+
+    // What writes `value`, which is a `ty`, strings are written like literals when `quoted` is set
+    fn write(&mut self, ty: &ast::JustType, value: &str, quoted: bool) -> Part {
+        let call = |helper: &str| Part::Statement(format!("{}({});", helper, value));
+
+        match ty {
+            ast::Type::Unit(_) => Part::Text("()".into()),
+            ast::Type::Int(_) => call(self.helper("mz_write_int")),
+            ast::Type::Bool(_) => call(self.helper("mz_write_bool")),
+            ast::Type::Float(_) => call(self.helper("mz_write_float")),
+            ast::Type::String(_) if quoted => call(self.helper("mz_write_quoted")),
+            ast::Type::String(_) => call(self.helper("mz_write_string")),
+            // Functions are shown by their type, like when running the program directly
+            ast::Type::Function(_, _, _) => Part::Text(format!("<{}>", ty)),
+            ast::Type::Tuple(_, _) | ast::Type::Array(_, _) | ast::Type::Named(_, _, _) => {
+                let function = self.show_function(ty);
+                call(&function)
+            }
+            ast::Type::Var(_, _) | ast::Type::Unknown(_, _) => panic!("Internal compiler error"),
+        }
+    }
+
+    // The function that writes values of `ty`, which is generated the first time it is needed
+    fn show_function(&mut self, ty: &ast::JustType) -> String {
+        if let Some(name) = self.shows.get(&ty.to_string()) {
+            return name.clone();
+        }
+
+        let base = format!("mz_show_{}", mangle(ty));
+        let mut name = base.clone();
+        let mut n = 1;
+        while self.shows.values().any(|existing| existing == &name) {
+            name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        // Added before the body is generated, since types like lists refer to themselves
+        self.shows.insert(ty.to_string(), name.clone());
+        self.helper("mz_write_text");
+
+        let body = match ty {
+            ast::Type::Tuple(_, items) => {
+                let mut parts = vec![Part::Text("(".into())];
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        parts.push(Part::Text(", ".into()));
+                    }
+                    parts.push(self.write(item, &Generator::slot("value", i, item), true));
+                }
+                parts.push(Part::Text(")".into()));
+
+                writes(parts)
+            }
+            ast::Type::Array(_, item) => {
+                let write = writes(vec![self.write(
+                    item,
+                    &Generator::slot("value", "i", item),
+                    true,
+                )]);
+                let mut lines = vec![
+                    "int64_t i;".to_string(),
+                    "mz_write_text(\"[\");".into(),
+                    "for (i = 1; i <= value[0].i; i++) {".into(),
+                    "    if (i > 1) {".into(),
+                    "        mz_write_text(\", \");".into(),
+                    "    }".into(),
+                ];
+                lines.extend(indent(write));
+                lines.push("}".into());
+                lines.push("mz_write_text(\"]\");".into());
+
+                lines
+            }
+            ast::Type::Named(_, type_name, type_args) => {
+                let type_params = ast::type_params(self.decls, type_name);
+
+                match ast::record_fields(self.decls, type_name) {
+                    Some(fields) => {
+                        let mut parts = vec![Part::Text(format!("{} {{ ", type_name))];
+                        for (i, f) in fields.iter().enumerate() {
+                            if i > 0 {
+                                parts.push(Part::Text(", ".into()));
+                            }
+                            let field_ty = f.ty.strip().substitute(type_params, type_args);
+                            parts.push(Part::Text(format!("{} = ", f.name)));
+                            parts.push(self.write(
+                                &field_ty,
+                                &Generator::slot("value", i, &field_ty),
+                                true,
+                            ));
+                        }
+                        parts.push(Part::Text(" }".into()));
+
+                        writes(parts)
+                    }
+                    None => {
+                        let constructors =
+                            ast::variant_constructors(self.decls, type_name).unwrap();
+                        let mut lines = vec!["switch (value[0].i) {".to_string()];
+
+                        for c in constructors {
+                            let mut parts = vec![Part::Text(c.name.clone())];
+                            for (i, arg) in c.args.iter().enumerate() {
+                                parts.push(Part::Text(if i == 0 { "(" } else { ", " }.into()));
+                                let arg_ty = arg.strip().substitute(type_params, type_args);
+                                parts.push(self.write(
+                                    &arg_ty,
+                                    &Generator::slot("value", i + 1, &arg_ty),
+                                    true,
+                                ));
+                            }
+                            if !c.args.is_empty() {
+                                parts.push(Part::Text(")".into()));
+                            }
+
+                            lines.push(format!("case {}:", self.tag(&c.name)));
+                            lines.extend(indent(writes(parts)));
+                            lines.push("    break;".into());
+                        }
+                        lines.push("}".into());
+
+                        lines
+                    }
+                }
+            }
+            _ => panic!("Internal compiler error"),
+        };
+
+        self.show_functions.push((
+            format!("static void {}(mz_slot *value)", name),
+            indent(body).join("\n"),
+        ));
+
+        name
+    }
+
+    // An expression that is true when `value` matches `pattern`, or `None` when every value does
+    fn test(&self, pattern: &ast::TypedPattern, value: &str) -> Option<String> {
+        match pattern {
+            ast::Pattern::Var(_, _) | ast::Pattern::Wildcard(_) => None,
+            ast::Pattern::Bool(_, true) => Some(value.into()),
+            ast::Pattern::Bool(_, false) => Some(format!("!{}", value)),
+            ast::Pattern::Int(_, n) => Some(format!("{} == {}", value, int(*n))),
+            ast::Pattern::Tuple(_, items) => {
+                let tests = items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, p)| {
+                        let ty = self.concrete(&p.extra().1);
+                        self.test(p, &Generator::slot(value, i, &ty))
+                    })
+                    .collect::<Vec<_>>();
+
+                (!tests.is_empty()).then(|| tests.join(" && "))
+            }
+            ast::Pattern::Constructor(_, name, args) => {
+                let tag = format!("{}[0].i == {}", value, self.tag(name));
+                let tests = std::iter::once(tag)
+                    .chain(args.iter().enumerate().filter_map(|(i, p)| {
+                        let ty = self.concrete(&p.extra().1);
+                        self.test(p, &Generator::slot(value, i + 1, &ty))
+                    }))
+                    .collect::<Vec<_>>();
+
+                Some(tests.join(" && "))
+            }
+        }
+    }
+
+    // Declares the variables in `pattern`, which matches `value`
+    fn bindings(&mut self, pattern: &ast::TypedPattern, value: &str, out: &mut Vec<String>) {
+        let ty = self.concrete(&pattern.extra().1);
+
+        match pattern {
+            ast::Pattern::Var(_, name) => {
+                let c = self.local(name);
+                out.push(format!("{} = {};", declare(&ty, &c), value));
+            }
+            ast::Pattern::Wildcard(_) | ast::Pattern::Bool(_, _) | ast::Pattern::Int(_, _) => {}
+            ast::Pattern::Tuple(_, items) => {
+                for (i, p) in items.iter().enumerate() {
+                    let item_ty = self.concrete(&p.extra().1);
+                    self.bindings(p, &Generator::slot(value, i, &item_ty), out);
+                }
+            }
+            ast::Pattern::Constructor(_, _, args) => {
+                for (i, p) in args.iter().enumerate() {
+                    let arg_ty = self.concrete(&p.extra().1);
+                    self.bindings(p, &Generator::slot(value, i + 1, &arg_ty), out);
+                }
+            }
+        }
+    }
+
+    // The slot of the field `name` in a record of type `ty`
+    fn field_index(&self, ty: &ast::JustType, name: &str) -> usize {
+        match ty {
+            ast::Type::Named(_, type_name, _) => ast::record_fields(self.decls, type_name)
+                .and_then(|fields| fields.iter().position(|f| f.name == name))
+                .expect("Internal compiler error"),
+            _ => panic!("Internal compiler error"),
+        }
+    }
+
+    fn field_count(&self, ty: &ast::JustType) -> usize {
+        match ty {
+            ast::Type::Named(_, type_name, _) => ast::record_fields(self.decls, type_name)
+                .expect("Internal compiler error")
+                .len(),
+            _ => panic!("Internal compiler error"),
+        }
+    }
+
+    // The parameters and statements of the body of a function
+    fn function(
+        &mut self,
+        params: &[ast::Param<(ast::Span, ast::JustType)>],
+        body: &ast::TypedExpr,
+    ) -> (Vec<String>, Vec<String>) {
+        self.scope.function();
+
+        let params = params
+            .iter()
+            .map(|p| {
+                let ty = self.concrete(&p.ty.strip());
+                let c = self.local(&p.name);
+                declare(&ty, &c)
+            })
+            .collect();
+
+        let mut out = vec![];
+        match self.concrete(&body.extra().1) {
+            // Functions that give back `unit` still return its value, so that every value is the same in C
+            ast::Type::Unit(_) => {
+                self.complete(body, &mut out, Dest::Discard);
+                out.push("return 0;".into());
+            }
+            _ => self.complete(body, &mut out, Dest::Return),
+        }
+
+        (params, out)
+    }
+}
+
+impl<'a> Target for Generator<'a> {
+    type Value = C;
+
+    fn scope(&mut self) -> &mut Scope {
+        &mut self.scope
+    }
+
+    fn ident(name: &str) -> String {
+        ident(name)
+    }
+
+    fn inline(e: &ast::TypedExpr) -> bool {
+        // Values made of slots are filled in by statements
+        codegen::inline(e, false)
+    }
+
+    fn declare(&mut self, name: &str, value: &C, _mutable: bool) -> String {
+        format!("{} = {};", declare(&value.ty, name), value.code)
+    }
+
+    fn named(name: String, value: C) -> C {
+        C::new(name, Kind::Name, value.ty, false)
+    }
+
+    fn discard(&mut self, value: C, out: &mut Vec<String>) {
+        match value.kind {
+            Kind::Literal | Kind::Name => {}
+            _ if !value.effects => {}
+            _ => out.push(format!("{};", value.code)),
+        }
+    }
+
+    fn bind(&mut self, pattern: &ast::TypedPattern, value: C, out: &mut Vec<String>) {
+        match pattern {
+            ast::Pattern::Var(_, _) => self.bindings(pattern, &value.code, out),
+            _ if !binds(pattern) => self.discard(value, out),
+            _ => {
+                let value = match value.kind {
+                    Kind::Literal | Kind::Name => value,
+                    _ => self.keep(value, out),
+                };
+                self.bindings(pattern, &value.code, out);
+            }
+        }
+    }
+
+    fn test_arm(&mut self, pattern: &ast::TypedPattern, value: &str, last: bool) -> Option<String> {
+        // Exhaustiveness is checked beforehand, so the last arm matches whatever is left
+        if last {
+            None
+        } else {
+            self.test(pattern, value)
+        }
+    }
+
+    // All but the last operand with effects are kept in temporaries, since C could evaluate them in any order
+    fn in_order(&mut self, values: Vec<C>, out: &mut Vec<String>) -> Vec<C> {
+        let last = values.iter().rposition(|v| v.effects);
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| {
+                if v.effects && Some(i) != last {
+                    self.spill(v, out)
+                } else {
+                    v
+                }
+            })
+            .collect()
+    }
+
+    // Appends the statements needed before `e` onto `out`, and returns the expression that gives its value
+    fn expr(&mut self, e: &ast::TypedExpr, out: &mut Vec<String>) -> C {
+        let ty = self.concrete(&e.extra().1);
+
+        match e {
+            ast::Expr::UnitLit(_) => C::unit(),
+            ast::Expr::IntLit(_, n) => C::new(int(*n), Kind::Literal, ty, false),
+            ast::Expr::BoolLit(_, b) => C::new(b.to_string(), Kind::Literal, ty, false),
+            ast::Expr::FloatLit(_, x) => C::new(float(*x), Kind::Literal, ty, false),
+            ast::Expr::StringLit(_, s) => {
+                let name = self.string_literal(s);
+                C::new(format!("&{}", name), Kind::Literal, ty, false)
+            }
+            ast::Expr::TypeAnno { term, .. } => self.expr(term, out),
+            ast::Expr::IfFlow {
+                cond,
+                on_true,
+                on_false,
+                ..
+            } if Self::inline(on_true) && Self::inline(on_false) => {
+                let cond = self.expr(cond, out);
+                let on_true = self.expr(on_true, out);
+                let on_false = self.expr(on_false, out);
+
+                C::new(
+                    format!(
+                        "{} ? {} : {}",
+                        cond.branch(),
+                        on_true.branch(),
+                        on_false.branch()
+                    ),
+                    Kind::Conditional,
+                    ty,
+                    cond.effects || on_true.effects || on_false.effects,
+                )
+            }
+            ast::Expr::IfFlow { .. } | ast::Expr::Match { .. } => {
+                if ty == ast::Type::Unit(()) {
+                    self.complete(e, out, Dest::Discard);
+                    return C::unit();
+                }
+
+                let temp = self.scope.temp();
+                out.push(format!("{};", declare(&ty, &temp)));
+                self.complete(e, out, Dest::Assign(temp.clone()));
+
+                C::new(temp, Kind::Name, ty, false)
+            }
+            ast::Expr::BinOp {
+                op, left, right, ..
+            } => {
+                let operand_ty = self.concrete(&left.extra().1);
+                let values = self.operands(&[left, right], out);
+                let (left, right) = (&values[0], &values[1]);
+                let effects = left.effects || right.effects;
+
+                let helper = match (op, &operand_ty) {
+                    (ast::BinOp::Add, ast::Type::Int(_)) => Some("mz_add"),
+                    (ast::BinOp::Sub, ast::Type::Int(_)) => Some("mz_sub"),
+                    (ast::BinOp::Mul, ast::Type::Int(_)) => Some("mz_mul"),
+                    (ast::BinOp::Div, ast::Type::Int(_)) => Some("mz_div"),
+                    (ast::BinOp::Rem, _) => Some("mz_rem"),
+                    (ast::BinOp::Concat, _) => Some("mz_concat"),
+                    (ast::BinOp::Eq | ast::BinOp::Ne, ast::Type::String(_)) => Some("mz_str_eq"),
+                    _ => None,
+                };
+                // The same as C gives its operators
+                let precedence = match op {
+                    ast::BinOp::Mul | ast::BinOp::Div | ast::BinOp::Rem => 12,
+                    ast::BinOp::Concat | ast::BinOp::Add | ast::BinOp::Sub => 11,
+                    ast::BinOp::Lt | ast::BinOp::Le | ast::BinOp::Gt | ast::BinOp::Ge => 9,
+                    ast::BinOp::Eq | ast::BinOp::Ne => 8,
+                };
+
+                match helper {
+                    Some(helper) => {
+                        let call =
+                            format!("{}({}, {})", self.helper(helper), left.code, right.code);
+                        let traps = matches!(op, ast::BinOp::Div | ast::BinOp::Rem);
+
+                        if *op == ast::BinOp::Ne {
+                            C::new(format!("!{}", call), Kind::Prefix, ty, effects)
+                        } else {
+                            C::new(call, Kind::Atom, ty, effects || traps)
+                        }
+                    }
+                    // Compilers warn about comparisons of comparisons without parentheses
+                    None if precedence <= 9 => C::new(
+                        format!("{} {} {}", left.compared(), op, right.compared()),
+                        Kind::Operator(precedence),
+                        ty,
+                        effects,
+                    ),
+                    None => C::new(
+                        format!(
+                            "{} {} {}",
+                            left.left(precedence),
+                            op,
+                            right.right(precedence)
+                        ),
+                        Kind::Operator(precedence),
+                        ty,
+                        effects,
+                    ),
+                }
+            }
+            ast::Expr::Builtin {
+                extra,
+                builtin: builtin @ (ast::Builtin::Print | ast::Builtin::Debug),
+                arg,
+            } => {
+                let arg_ty = self.concrete(&arg.extra().1);
+                let value = self.expr(arg, out);
+                // The value is found before anything is written, since finding it could write too
+                let value = if value.effects {
+                    self.spill(value, out)
+                } else {
+                    value
+                };
+
+                let prefix = wasm::print_prefix(
+                    &ir::Printed {
+                        ty: arg_ty.clone(),
+                        debug: (*builtin == ast::Builtin::Debug).then_some(extra.0),
+                    },
+                    self.sources,
+                );
+                let write = self.write(&arg_ty, &value.code, *builtin == ast::Builtin::Debug);
+                self.helper("mz_write_text");
+                out.extend(writes(vec![
+                    Part::Text(prefix),
+                    write,
+                    Part::Text("\n".into()),
+                ]));
+
+                C::unit()
+            }
+            ast::Expr::Builtin { builtin, arg, .. } => {
+                let value = self.expr(arg, out);
+
+                match builtin {
+                    ast::Builtin::Len => C::new(
+                        format!("{}->len", value.operand()),
+                        Kind::Atom,
+                        ty,
+                        value.effects,
+                    ),
+                    ast::Builtin::Length => C::new(
+                        format!("{}[0].i", value.operand()),
+                        Kind::Atom,
+                        ty,
+                        value.effects,
+                    ),
+                    ast::Builtin::ToFloat => C::new(
+                        format!("(double){}", value.operand()),
+                        Kind::Prefix,
+                        ty,
+                        value.effects,
+                    ),
+                    ast::Builtin::ToInt => C::new(
+                        format!("{}({})", self.helper("mz_to_int"), value.code),
+                        Kind::Atom,
+                        ty,
+                        true,
+                    ),
+                    ast::Builtin::Print | ast::Builtin::Debug => panic!("Internal compiler error"),
+                }
+            }
+            ast::Expr::Var(_, name) if !self.scope.vars.contains_key(name) => match &ty {
+                ast::Type::Function(_, params, result) => {
+                    let function = self.instance(name, params, result);
+                    C::new(format!("(mz_fn){}", function), Kind::Prefix, ty, false)
+                }
+                _ => panic!("Internal compiler error"),
+            },
+            ast::Expr::Var(_, name) => C::new(self.scope.vars[name].clone(), Kind::Name, ty, false),
+            ast::Expr::Tuple { items, .. } => {
+                let values = self.operands(&items.iter().collect::<Vec<_>>(), out);
+                let alloc = format!("{}({})", self.helper("mz_alloc"), items.len());
+
+                self.slots(
+                    alloc,
+                    vec![],
+                    values.into_iter().enumerate().collect(),
+                    ty,
+                    out,
+                )
+            }
+            ast::Expr::Array { items, .. } => {
+                let values = self.operands(&items.iter().collect::<Vec<_>>(), out);
+                let alloc = format!("{}({})", self.helper("mz_array"), items.len());
+                let values = values.into_iter().enumerate().map(|(i, v)| (i + 1, v));
+
+                self.slots(alloc, vec![], values.collect(), ty, out)
+            }
+            ast::Expr::Project { term, index, .. } => {
+                let term = self.expr(term, out);
+
+                if ty == ast::Type::Unit(()) {
+                    self.discard(term, out);
+                    return C::unit();
+                }
+                C::new(
+                    Generator::slot(&term.operand(), *index, &ty),
+                    Kind::Atom,
+                    ty,
+                    term.effects,
+                )
+            }
+            ast::Expr::Let { .. } | ast::Expr::Seq { .. } | ast::Expr::VarDecl { .. } => {
+                if ty == ast::Type::Unit(()) {
+                    self.complete(e, out, Dest::Discard);
+                    return C::unit();
+                }
+
+                // The value of the body is what is left after the statements that bind variables
+                let mut value = None;
+                self.complete_value(e, out, &mut value);
+
+                value.unwrap()
+            }
+            ast::Expr::Record { fields, .. } => {
+                let values =
+                    self.operands(&fields.iter().map(|f| &f.value).collect::<Vec<_>>(), out);
+                let indices = fields
+                    .iter()
+                    .map(|f| self.field_index(&ty, &f.name))
+                    .collect::<Vec<_>>();
+                let count = self.field_count(&ty);
+                let alloc = format!("{}({})", self.helper("mz_alloc"), count);
+
+                self.slots(
+                    alloc,
+                    vec![],
+                    indices.into_iter().zip(values).collect(),
+                    ty,
+                    out,
+                )
+            }
+            ast::Expr::Field { term, field, .. } => {
+                let term_ty = self.concrete(&term.extra().1);
+                let index = self.field_index(&term_ty, field);
+                let term = self.expr(term, out);
+
+                if ty == ast::Type::Unit(()) {
+                    self.discard(term, out);
+                    return C::unit();
+                }
+                C::new(
+                    Generator::slot(&term.operand(), index, &ty),
+                    Kind::Atom,
+                    ty,
+                    term.effects,
+                )
+            }
+            ast::Expr::Update { term, fields, .. } => {
+                let es = std::iter::once(term.as_ref())
+                    .chain(fields.iter().map(|f| &f.value))
+                    .collect::<Vec<_>>();
+                let mut values = self.operands(&es, out).into_iter();
+                let old = values.next().unwrap();
+                let indices = fields
+                    .iter()
+                    .map(|f| self.field_index(&ty, &f.name))
+                    .collect::<Vec<_>>();
+                let count = self.field_count(&ty);
+                let alloc = format!("{}({}, {})", self.helper("mz_copy"), old.code, count);
+
+                self.slots(
+                    alloc,
+                    vec![],
+                    indices.into_iter().zip(values).collect(),
+                    ty,
+                    out,
+                )
+            }
+            ast::Expr::Construct { name, args, .. } => {
+                let values = self.operands(&args.iter().collect::<Vec<_>>(), out);
+                let alloc = format!("{}({})", self.helper("mz_alloc"), args.len() + 1);
+                let tag = format!("[0].i = {};", self.tag(name));
+                let values = values.into_iter().enumerate().map(|(i, v)| (i + 1, v));
+
+                self.slots(alloc, vec![tag], values.collect(), ty, out)
+            }
+            ast::Expr::Call { name, args, .. } if self.scope.vars.contains_key(name) => {
+                let arg_tys = args
+                    .iter()
+                    .map(|a| self.concrete(&a.extra().1))
+                    .collect::<Vec<_>>();
+                let args = self.operands(&args.iter().collect::<Vec<_>>(), out);
+                let args = args.into_iter().map(|a| a.code).collect::<Vec<_>>();
+
+                C::new(
+                    format!(
+                        "(({}){})({})",
+                        function_pointer(&arg_tys, &ty),
+                        self.scope.vars[name],
+                        args.join(", ")
+                    ),
+                    Kind::Atom,
+                    ty,
+                    true,
+                )
+            }
+            ast::Expr::Call { name, args, .. } => {
+                let arg_tys = args
+                    .iter()
+                    .map(|a| self.concrete(&a.extra().1))
+                    .collect::<Vec<_>>();
+                let function = self.instance(name, &arg_tys, &ty);
+                let args = self.operands(&args.iter().collect::<Vec<_>>(), out);
+                let args = args.into_iter().map(|a| a.code).collect::<Vec<_>>();
+
+                C::new(
+                    format!("{}({})", function, args.join(", ")),
+                    Kind::Atom,
+                    ty,
+                    true,
+                )
+            }
+            ast::Expr::Assign { name, value, .. } => {
+                let value = self.expr(value, out);
+                out.push(format!("{} = {};", self.scope.vars[name], value.code));
+
+                C::unit()
+            }
+            ast::Expr::While { cond, body, .. } => {
+                let locals = self.scope.locals.clone();
+                let mut cond_out = vec![];
+                let cond = self.expr(cond, &mut cond_out);
+                let mut body_out = vec![];
+                self.complete(body, &mut body_out, Dest::Discard);
+                self.scope.locals = locals;
+
+                if cond_out.is_empty() {
+                    out.push(format!("while ({}) {{", cond.code));
+                } else {
+                    // The condition needs statements, which are run before every check
+                    out.push("while (true) {".into());
+                    cond_out.push(format!("if (!{}) {{", cond.operand()));
+                    cond_out.push("    break;".into());
+                    cond_out.push("}".into());
+                    out.append(&mut indent(cond_out));
+                }
+                out.append(&mut indent(body_out));
+                out.push("}".into());
+
+                C::unit()
+            }
+            ast::Expr::Break(_) => {
+                out.push("break;".into());
+
+                C::unit()
+            }
+            ast::Expr::Repeat { length, value, .. } => {
+                let mut values = self.operands(&[length, value], out).into_iter();
+                let length = values.next().unwrap();
+                let value = values.next().unwrap();
+                let item = match member(&value.ty) {
+                    Some(member) => format!("(mz_slot){{ .{} = {} }}", member, value.code),
+                    None => {
+                        self.discard(value.clone(), out);
+                        "(mz_slot){ .i = 0 }".into()
+                    }
+                };
+
+                C::new(
+                    format!("{}({}, {})", self.helper("mz_repeat"), length.code, item),
+                    Kind::Atom,
+                    ty,
+                    length.effects || value.effects,
+                )
+            }
+            ast::Expr::Index { term, index, .. } => {
+                let at = self.location(index.extra().0);
+                let values = self.operands(&[term, index], out);
+                let item = format!(
+                    "{}({}, {}, {})",
+                    self.helper("mz_item"),
+                    values[0].code,
+                    values[1].code,
+                    at
+                );
+
+                match member(&ty) {
+                    Some(member) => C::new(format!("{}->{}", item, member), Kind::Atom, ty, true),
+                    None => {
+                        out.push(format!("{};", item));
+                        C::unit()
+                    }
+                }
+            }
+            ast::Expr::SetIndex {
+                term, index, value, ..
+            } => {
+                let at = self.location(index.extra().0);
+                let values = self.operands(&[term, index, value], out);
+                let item = format!(
+                    "{}({}, {}, {})",
+                    self.helper("mz_item"),
+                    values[0].code,
+                    values[1].code,
+                    at
+                );
+
+                match member(&values[2].ty) {
+                    Some(member) => out.push(format!("{}->{} = {};", item, member, values[2].code)),
+                    None => {
+                        out.push(format!("{};", item));
+                        self.discard(values[2].clone(), out);
+                    }
+                }
+
+                C::unit()
+            }
+        }
+    }
+}
+
+fn signature(header: &str, params: &[String]) -> String {
+    if params.is_empty() {
+        format!("{}(void)", header)
+    } else {
+        format!("{}({})", header, params.join(", "))
+    }
+}
+
+fn pretty_function(signature: &str, body: &str) -> String {
+    format!("{} {{\n{}\n}}", signature, body)
+}
+
+// Translates `p` into a header and a source file, in that order, `header` is the name the source includes
+pub fn ast_to_c(
+    p: &ast::TypedProgram,
+    sources: &source::SourceMap,
+    header: &str,
+) -> (String, String) {
+    let mut generator = Generator {
+        sources,
+        decls: &p.decls,
+        helpers: HashSet::new(),
+        strings: HashMap::new(),
+        string_defs: vec![],
+        shows: HashMap::new(),
+        show_functions: vec![],
+        instances: Instances::new(&p.decls),
+        scope: Scope::new("_", "t"),
+    };
+
+    let result = p.body.extra().1.clone();
+    let (_, main_body) = generator.function(&[], &p.body);
+    let main_signature = signature(&declare(&result, "mizzle_main"), &[]);
+    let main = pretty_function(&main_signature, &indent(main_body).join("\n"));
+
+    let mut functions = vec![];
+    while let Some((instance, function)) = generator.instances.next() {
+        if let ast::Decl::Function {
+            params,
+            result,
+            body,
+            ..
+        } = function
+        {
+            let result = generator.concrete(&result.strip());
+            let (params, body) = generator.function(params, body);
+            let header = format!("static {}", declare(&result, &instance));
+            functions.push((signature(&header, &params), indent(body).join("\n")));
+        }
+    }
+
+    // The result is written as it is when it is a string, instead of like a literal
+    generator.instances.leave();
+    generator.helper("mz_write_text");
+    let mut show_body = vec![];
+    let write = generator.write(&result, "value", false);
+    // Values that are written the same way every time are not used
+    if let Part::Text(_) = write {
+        show_body.push("(void)value;".to_string());
+    }
+    show_body.extend(writes(vec![write]));
+    let show_signature = signature("void mizzle_show", &[declare(&result, "value")]);
+    let show = pretty_function(&show_signature, &indent(show_body).join("\n"));
+
+    let guard = format!(
+        "MIZZLE_{}",
+        header
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            })
+            .collect::<String>()
+    );
+    let header_file = format!(
+        "// Generated by mizzle\n\
+         #ifndef {guard}\n\
+         #define {guard}\n\
+         \n\
+         #include <stdbool.h>\n\
+         #include <stdint.h>\n\
+         \n\
+         {TYPES}\n\
+         \n\
+         // Runs the program and gives back its value\n\
+         {main_signature};\n\
+         \n\
+         // Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly\n\
+         {show_signature};\n\
+         \n\
+         #endif\n"
+    );
+
+    let mut items = vec![format!(
+        "// Generated by mizzle\n\n\
+         #include \"{}\"\n\n\
+         #include <math.h>\n\
+         #include <stdio.h>\n\
+         #include <stdlib.h>\n\
+         #include <string.h>",
+        header
+    )];
+
+    // The constructors of each variant are numbered in the order they are declared
+    for decl in &p.decls {
+        if let ast::Decl::Variant {
+            name, constructors, ..
+        } = decl
+        {
+            let tags = constructors
+                .iter()
+                .map(|c| generator.tag(&c.name))
+                .collect::<Vec<_>>();
+            items.push(format!("// `{}`\nenum {{ {} }};", name, tags.join(", ")));
+        }
+    }
+
+    items.extend(
+        HELPERS
+            .iter()
+            .filter(|(name, _, _)| generator.helpers.contains(name))
+            .map(|(_, _, code)| code.to_string()),
+    );
+    if !generator.string_defs.is_empty() {
+        items.push(generator.string_defs.join("\n"));
+    }
+
+    // Declared before any of them are defined, since they can call each other in any order
+    let prototypes = functions
+        .iter()
+        .chain(&generator.show_functions)
+        .map(|(signature, _)| format!("{};", signature))
+        .collect::<Vec<_>>();
+    if !prototypes.is_empty() {
+        items.push(prototypes.join("\n"));
+    }
+    items.extend(
+        functions
+            .iter()
+            .map(|(signature, body)| pretty_function(signature, body)),
+    );
+    items.push(main);
+    items.extend(
+        generator
+            .show_functions
+            .iter()
+            .map(|(signature, body)| pretty_function(signature, body)),
+    );
+    items.push(show);
+
+    (header_file, items.join("\n\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend_test::{self, compile, Backend};
+    use crate::source::SourceMap;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    struct C;
+
+    impl Backend for C {
+        const NAME: &'static str = "c";
+        const EXTENSION: &'static str = "c";
+        const COMMAND: &'static str = "cc";
+
+        fn generate(typed: &ast::TypedProgram, sources: &SourceMap) -> String {
+            let (header, source) = ast_to_c(typed, sources, "program.h");
+            format!("{}\n{}", header, source)
+        }
+
+        fn run(typed: &ast::TypedProgram, sources: &SourceMap, dir: &Path) -> (String, bool) {
+            // Nothing is written for programs that are `unit`, like when running them directly
+            let run = if typed.body.extra().1 == ast::Type::Unit(()) {
+                "mizzle_main();"
+            } else {
+                "mizzle_show(mizzle_main());\n    putchar('\\n');"
+            };
+            let (header, source) = ast_to_c(typed, sources, "program.h");
+            fs::write(dir.join("program.h"), header).unwrap();
+            fs::write(dir.join("program.c"), source).unwrap();
+            fs::write(
+                dir.join("main.c"),
+                format!(
+                    "#include <stdio.h>\n#include \"program.h\"\n\nint main(void) {{\n    {}\n    return 0;\n}}\n",
+                    run
+                ),
+            )
+            .unwrap();
+
+            // Strict C99, where only unused variables from the source are allowed
+            let built = Command::new("cc")
+                .args([
+                    "-std=c99",
+                    "-pedantic-errors",
+                    "-Wall",
+                    "-Wno-unused",
+                    "-Werror",
+                ])
+                .args(["-o", "program", "program.c", "main.c", "-lm"])
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            assert!(
+                built.status.success(),
+                "{}",
+                String::from_utf8_lossy(&built.stderr)
+            );
+
+            let output = Command::new(dir.join("program")).output().unwrap();
+            (
+                String::from_utf8(output.stdout).unwrap(),
+                output.status.success(),
+            )
+        }
+    }
+
+    // Checked even without a C compiler, and shows what the output looks like after the helpers
+    #[test]
+    fn readable_output() {
+        let (typed, sources) = compile(
+            r#"
+            type Shape = Circle(int) | Rect(int, int)
+            fn area(s: Shape): int = match s with Circle(r) => r * r * 3 | Rect(w, h) => w * h end end
+            fn max(a: int, b: int): int = if a > b then a else b end end
+            var total = 0 in
+            total := max(area(Rect(2, 3)), 4);
+            debug(total);
+            if total > 5 then print("big") else () end
+            "#,
+        );
+        let (header, source) = ast_to_c(&typed, &sources, "test.h");
+
+        assert!(header.contains("#ifndef MIZZLE_TEST_H\n"));
+        assert!(header.contains("\nmz_unit mizzle_main(void);\n"));
+        assert!(header.contains("\nvoid mizzle_show(mz_unit value);\n"));
+        assert!(source.contains("#include \"test.h\"\n"));
+        assert!(source.contains("\nenum { TAG_Circle, TAG_Rect };\n"));
+        assert_eq!(
+            &source[source.find("static mz_string").unwrap()..],
+            r#"static mz_string mz_str_0 = { 3, "big" };
+
+static int64_t fn_max(int64_t a, int64_t b);
+static int64_t fn_area(mz_slot *s);
+
+static int64_t fn_max(int64_t a, int64_t b) {
+    return a > b ? a : b;
+}
+
+static int64_t fn_area(mz_slot *s) {
+    if (s[0].i == TAG_Circle) {
+        int64_t r = s[1].i;
+        return mz_mul(mz_mul(r, r), 3);
+    } else {
+        int64_t w = s[1].i;
+        int64_t h = s[2].i;
+        return mz_mul(w, h);
+    }
+}
+
+mz_unit mizzle_main(void) {
+    int64_t total = 0;
+    mz_slot *t0 = mz_alloc(3);
+    t0[0].i = TAG_Rect;
+    t0[1].i = 2;
+    t0[2].i = 3;
+    total = fn_max(fn_area(t0), 4);
+    mz_write_text("[test.mi:7] ");
+    mz_write_int(total);
+    mz_write_text("\n");
+    if (total > 5) {
+        mz_write_string(&mz_str_0);
+        mz_write_text("\n");
+    }
+    return 0;
+}
+
+void mizzle_show(mz_unit value) {
+    (void)value;
+    mz_write_text("()");
+}
+"#
+        );
+    }
+
+    #[test]
+    fn same_as_wasm_for_primitives() {
+        backend_test::same_as_wasm_for_primitives::<C>();
+    }
+
+    #[test]
+    fn same_as_wasm_for_values() {
+        backend_test::same_as_wasm_for_values::<C>();
+    }
+
+    #[test]
+    fn same_as_wasm_for_control_flow() {
+        backend_test::same_as_wasm_for_control_flow::<C>();
+    }
+
+    #[test]
+    fn same_as_wasm_for_output() {
+        backend_test::same_as_wasm_for_output::<C>();
+    }
+
+    #[test]
+    fn same_as_wasm_for_modules() {
+        backend_test::same_as_wasm_for_modules::<C>();
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+// Modules compiled by wasmer, kept on disk so that running a program again skips compiling it
+// Each is named by a hash of its wasm, which changes whenever the source or anything it imports does,
+// And they are kept in a directory for the version of mizzle that compiled them, like `modules/mizzle-0.1.0`,
+// So a new version never loads what an old one wrote
+pub struct Cache {
+    dir: PathBuf,
+}
+
+// Where modules are kept when `MIZZLE_CACHE_DIR` is not set, like `~/.cache/mizzle`
+// There is none without a directory of the user's own, since anyone could write modules to a shared one like `/tmp`
+pub fn default_root() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("MIZZLE_CACHE_DIR") {
+        return Some(dir.into());
+    }
+
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+
+    Some(cache.join("mizzle"))
+}
+
+// A name like `mizzle-0.1.0` or `mizzle-1.0.0-beta.2`, which is only ever used for a version's directory
+fn is_version(name: &str) -> bool {
+    let Some(version) = name.strip_prefix("mizzle-") else {
+        return false;
+    };
+    let core = version.split(['-', '+']).next().unwrap();
+    let parts = core.split('.').collect::<Vec<_>>();
+
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+// Only a directory of modules is removed, so nothing else that happens to be there is lost
+fn only_modules(dir: &Path) -> std::io::Result<bool> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if !path.is_file() || path.extension().is_none_or(|e| e != "wasmu") {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+fn hash(bytes: &[u8]) -> String {
+    let half = |salt: u8| {
+        let mut hasher = DefaultHasher::new();
+        salt.hash(&mut hasher);
+        bytes.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    format!("{:016x}{:016x}", half(0), half(1))
+}
+
+impl Cache {
+    pub fn new(root: &Path) -> Cache {
+        Cache {
+            dir: root
+                .join("modules")
+                .join(format!("mizzle-{}", env!("CARGO_PKG_VERSION"))),
+        }
+    }
+
+    fn path(&self, wasm: &[u8]) -> PathBuf {
+        self.dir.join(format!("{}.wasmu", hash(wasm)))
+    }
+
+    // The module for `wasm`, which is only compiled when it is not in the cache
+    // The cache is only an optimization, so modules that cannot be read or written are compiled instead
+    pub fn module(&self, store: &wasmer::Store, wasm: &[u8]) -> wasmer::Module {
+        let path = self.path(wasm);
+
+        if let Ok(bytes) = fs::read(&path) {
+            // wasmer runs what it reads as native code without checking it,
+            // Which is only safe since the cache is in a directory that only this user writes to
+            if let Ok(module) = unsafe { wasmer::Module::deserialize(store, &bytes) } {
+                return module;
+            }
+        }
+
+        let module = wasmer::Module::from_binary(store, wasm).unwrap();
+        let _ = self.save(&path, &module);
+
+        module
+    }
+
+    fn save(&self, path: &Path, module: &wasmer::Module) -> std::io::Result<()> {
+        self.prune()?;
+        fs::create_dir_all(&self.dir)?;
+
+        // Written next to where it goes first, so that another run never reads half of it,
+        // Under a name that is still a module's, so that the directory can be removed if it is left there
+        let bytes = module.serialize().map_err(std::io::Error::other)?;
+        let partial = path.with_extension(format!("{}.wasmu", std::process::id()));
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, path)
+    }
+
+    // Removes what other versions of mizzle wrote, since this one never reads it
+    fn prune(&self) -> std::io::Result<()> {
+        let versions = match self.dir.parent() {
+            Some(versions) if versions.exists() => versions,
+            _ => return Ok(()),
+        };
+
+        for entry in fs::read_dir(versions)? {
+            let path = entry?.path();
+            let is_version = path
+                .file_name()
+                .is_some_and(|name| is_version(&name.to_string_lossy()));
+
+            if is_version && path != self.dir && path.is_dir() && only_modules(&path)? {
+                fs::remove_dir_all(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend_test::compile;
+    use crate::source::SourceMap;
+    use crate::wasm;
+    use std::sync::{Arc, Mutex};
+
+    fn program(source: &str) -> (wasm::Program, SourceMap) {
+        let (typed, sources) = compile(source);
+
+        (wasm::ast_to_wasm(&typed), sources)
+    }
+
+    fn run(cache: &Cache, program: &wasm::Program, sources: &SourceMap) -> String {
+        let wasm = program.module.clone().to_bytes().unwrap();
+        let module = cache.module(&wasm::store(), &wasm);
+
+        wasm::run_module(program, &module, sources, Arc::new(Mutex::new(vec![]))).unwrap()
+    }
+
+    #[test]
+    fn reuses_compiled_modules() {
+        let root = env::temp_dir().join(format!("mizzle-cache-test-{}", std::process::id()));
+        let cache = Cache::new(&root);
+        let (first, sources) = program("1 + 2");
+        let (second, _) = program("(\"changed\", 4)");
+        let wasm = first.module.clone().to_bytes().unwrap();
+
+        // What an older version wrote is removed once a module is saved
+        let old = root.join("modules/mizzle-0.0.0");
+        fs::create_dir_all(&old).unwrap();
+        fs::write(old.join("0123.wasmu"), b"old").unwrap();
+
+        assert_eq!(run(&cache, &first, &sources), "3");
+        assert!(cache.path(&wasm).exists());
+        assert!(!old.exists());
+        assert_eq!(run(&cache, &first, &sources), "3");
+
+        // A different program is compiled on its own
+        assert_eq!(run(&cache, &second, &sources), "(\"changed\", 4)");
+        assert_eq!(fs::read_dir(&cache.dir).unwrap().count(), 2);
+
+        // A module that cannot be loaded is compiled again, and replaces what was there
+        fs::write(cache.path(&wasm), b"not a module").unwrap();
+        assert_eq!(run(&cache, &first, &sources), "3");
+        assert_ne!(fs::read(cache.path(&wasm)).unwrap(), b"not a module");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn keeps_what_mizzle_did_not_write() {
+        let root = env::temp_dir().join(format!("mizzle-cache-kept-{}", std::process::id()));
+        let cache = Cache::new(&root);
+        let (program, sources) = program("1 + 2");
+
+        // Named like a version, but next to the modules instead of with them, or holding something else
+        let kept = [
+            root.join("mizzle-0.0.0"),
+            root.join("mizzle-notes"),
+            root.join("modules/mizzle-notes"),
+            root.join("modules/mizzle-lang-checkout"),
+            root.join("modules/mizzle-0.0.1"),
+        ];
+        for dir in &kept {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join("todo.txt"), b"keep").unwrap();
+        }
+
+        assert_eq!(run(&cache, &program, &sources), "3");
+        for dir in &kept {
+            assert_eq!(fs::read(dir.join("todo.txt")).unwrap(), b"keep");
+        }
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn versions() {
+        assert!(is_version("mizzle-0.1.0"));
+        assert!(is_version("mizzle-12.0.3-beta.2+build.5"));
+        assert!(!is_version("mizzle-notes"));
+        assert!(!is_version("mizzle-1.2"));
+        assert!(!is_version("mizzle-1..2"));
+        assert!(!is_version("mizzle-lang-checkout"));
+        assert!(!is_version("other-0.1.0"));
+    }
+}
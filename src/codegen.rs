@@ -0,0 +1,452 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast;
+use crate::source;
+
+// What the C and JavaScript backends have in common, since both write the tree as statements and expressions
+// Of a language where `if` is a statement, blocks declare variables, and `cond ? a : b` is an expression
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    // Constants, which can be moved anywhere
+    Literal,
+    // Variables, which can be moved unless they are assigned to
+    Name,
+    // Calls, slots, arrays, objects and anything else that never needs parentheses
+    Atom,
+    // `!` and casts in C, which need parentheses before `->` or `[]`
+    Prefix,
+    // Binary operators, with how tightly they bind
+    Operator(u8),
+    // `cond ? a : b`, which binds looser than any operator
+    Conditional,
+}
+
+// Where the value of an expression that is generated as statements goes
+#[derive(Clone, Debug)]
+pub enum Dest {
+    Return,
+    // The value is thrown away, which is also where every `unit` value goes
+    Discard,
+    Assign(String),
+}
+
+// An expression in the language being generated
+pub trait Value: Clone {
+    fn code(&self) -> &str;
+    fn kind(&self) -> Kind;
+}
+
+// The names at the top of the output, and of the variables in the function being generated
+#[derive(Default)]
+pub struct Scope {
+    // What goes between a name and the number that makes it unique
+    separator: &'static str,
+    // Temporaries are this followed by a number
+    temp_prefix: &'static str,
+    // Every function declared at the top of the output
+    pub globals: HashSet<String>,
+    // The name in the output of each variable in scope
+    pub vars: HashMap<String, String>,
+    // Variables declared with `var`, which are the only ones that change
+    pub mutable: HashSet<String>,
+    // Every name declared in the enclosing blocks of the function being generated
+    pub locals: HashSet<String>,
+    temps: usize,
+}
+
+impl Scope {
+    pub fn new(separator: &'static str, temp_prefix: &'static str) -> Scope {
+        Scope {
+            separator,
+            temp_prefix,
+            ..Scope::default()
+        }
+    }
+
+    fn unique(&self, base: String, taken: impl Fn(&str) -> bool) -> String {
+        let mut name = base.clone();
+        let mut n = 1;
+
+        while taken(&name) {
+            name = format!("{}{}{}", base, self.separator, n);
+            n += 1;
+        }
+
+        name
+    }
+
+    // A name at the top of the output, which is `base` unless that is already declared
+    pub fn global(&mut self, base: String) -> String {
+        let name = self.unique(base, |name| self.globals.contains(name));
+        self.globals.insert(name.clone());
+
+        name
+    }
+
+    // Declares the variable `name` as `base` in the function being generated,
+    // It is renamed when it would shadow another variable or a function
+    pub fn local(&mut self, name: &str, base: String) -> String {
+        let local = self.unique(base, |local| {
+            self.locals.contains(local) || self.globals.contains(local)
+        });
+        self.locals.insert(local.clone());
+        self.vars.insert(name.into(), local.clone());
+
+        local
+    }
+
+    pub fn temp(&mut self) -> String {
+        let mut temp = format!("{}{}", self.temp_prefix, self.temps);
+        while self.locals.contains(&temp) {
+            self.temps += 1;
+            temp = format!("{}{}", self.temp_prefix, self.temps);
+        }
+        self.temps += 1;
+        self.locals.insert(temp.clone());
+
+        temp
+    }
+
+    // Starts a function, where none of the variables before are in scope
+    pub fn function(&mut self) {
+        self.vars = HashMap::new();
+        self.mutable = HashSet::new();
+        self.locals = HashSet::new();
+        self.temps = 0;
+    }
+}
+
+pub trait Target {
+    type Value: Value;
+
+    fn scope(&mut self) -> &mut Scope;
+
+    // The name of the variable `name`, before it is made unique
+    fn ident(name: &str) -> String;
+
+    // Appends the statements needed before `e` onto `out`, and returns the expression that gives its value
+    fn expr(&mut self, e: &ast::TypedExpr, out: &mut Vec<String>) -> Self::Value;
+
+    // Whether `e` is generated as a single expression, without any statements before it
+    fn inline(e: &ast::TypedExpr) -> bool;
+
+    // The statement that declares the variable `name` with `value`, which is only assigned to when it is `mutable`
+    fn declare(&mut self, name: &str, value: &Self::Value, mutable: bool) -> String;
+
+    // `value` once it is kept in the variable `name`
+    fn named(name: String, value: Self::Value) -> Self::Value;
+
+    fn discard(&mut self, value: Self::Value, out: &mut Vec<String>);
+
+    fn bind(&mut self, pattern: &ast::TypedPattern, value: Self::Value, out: &mut Vec<String>);
+
+    // An expression that is true when `value` matches the `pattern` of an arm, or `None` when every value does,
+    // `last` is whether it is the last arm
+    fn test_arm(&mut self, pattern: &ast::TypedPattern, value: &str, last: bool) -> Option<String>;
+
+    // The values `operands` gives back, which are in the order they have to be evaluated in,
+    // For languages that evaluate the operands of an operator or the arguments of a call in any order
+    fn in_order(&mut self, values: Vec<Self::Value>, _out: &mut Vec<String>) -> Vec<Self::Value> {
+        values
+    }
+
+    // Moves `value` into a temporary
+    fn keep(&mut self, value: Self::Value, out: &mut Vec<String>) -> Self::Value {
+        let temp = self.scope().temp();
+        let declaration = self.declare(&temp, &value, false);
+        out.push(declaration);
+
+        Self::named(temp, value)
+    }
+
+    // Moves `value` into a temporary, unless it is a constant or a variable that cannot change
+    fn spill(&mut self, value: Self::Value, out: &mut Vec<String>) -> Self::Value {
+        match value.kind() {
+            Kind::Literal => value,
+            Kind::Name if !self.scope().mutable.contains(value.code()) => value,
+            _ => self.keep(value, out),
+        }
+    }
+
+    // Generates each of `es` in order,
+    // Values that come before one that needs statements are kept in temporaries first,
+    // Since those statements could change what they would give
+    fn operands(&mut self, es: &[&ast::TypedExpr], out: &mut Vec<String>) -> Vec<Self::Value> {
+        let mut values: Vec<Self::Value> = vec![];
+
+        for e in es {
+            let mut statements = vec![];
+            let value = self.expr(e, &mut statements);
+
+            if !statements.is_empty() {
+                values = values.into_iter().map(|v| self.spill(v, out)).collect();
+                out.append(&mut statements);
+            }
+
+            values.push(value);
+        }
+
+        self.in_order(values, out)
+    }
+
+    fn finish(&mut self, value: Self::Value, out: &mut Vec<String>, dest: Dest) {
+        match dest {
+            Dest::Return => out.push(format!("return {};", value.code())),
+            Dest::Discard => self.discard(value, out),
+            Dest::Assign(temp) => out.push(format!("{} = {};", temp, value.code())),
+        }
+    }
+
+    // Declares the variable `name` with `value`, which can be assigned to since it is declared with `var`
+    fn var(&mut self, name: &str, value: Self::Value, out: &mut Vec<String>) {
+        let local = self.scope().local(name, Self::ident(name));
+        self.scope().mutable.insert(local.clone());
+        let declaration = self.declare(&local, &value, true);
+        out.push(declaration);
+    }
+
+    // Generates `e` as statements that send its value to `dest`,
+    // Which lets `if` and `match` be statements instead of expressions
+    fn complete(&mut self, e: &ast::TypedExpr, out: &mut Vec<String>, dest: Dest) {
+        match e {
+            ast::Expr::TypeAnno { term, .. } => self.complete(term, out, dest),
+            ast::Expr::IfFlow {
+                cond,
+                on_true,
+                on_false,
+                ..
+            } if matches!(dest, Dest::Discard)
+                || !Self::inline(on_true)
+                || !Self::inline(on_false) =>
+            {
+                let cond = self.expr(cond, out);
+
+                // Each branch is a block of its own, so both can declare the same names
+                let locals = self.scope().locals.clone();
+                let mut true_out = vec![];
+                self.complete(on_true, &mut true_out, dest.clone());
+                self.scope().locals = locals.clone();
+                let mut false_out = vec![];
+                self.complete(on_false, &mut false_out, dest);
+                self.scope().locals = locals;
+
+                out.push(format!("if ({}) {{", cond.code()));
+                out.append(&mut indent(true_out));
+
+                // An `if` that is all of the `else` continues the chain, instead of being nested
+                let chained = false_out.len() >= 2
+                    && false_out[0].starts_with("if (")
+                    && false_out.last().is_some_and(|l| l == "}")
+                    && false_out[1..false_out.len() - 1]
+                        .iter()
+                        .all(|l| l.starts_with(' ') || l.starts_with("} else"));
+
+                if chained {
+                    out.push(format!("}} else {}", false_out[0]));
+                    out.extend(false_out.into_iter().skip(1));
+                } else {
+                    if !false_out.is_empty() {
+                        out.push("} else {".into());
+                        out.append(&mut indent(false_out));
+                    }
+                    out.push("}".into());
+                }
+            }
+            ast::Expr::Let {
+                pattern,
+                value,
+                body,
+                ..
+            } => {
+                let value = self.expr(value, out);
+
+                // Restore the outer scope afterwards, since the body may shadow variables
+                let outer = self.scope().vars.clone();
+                self.bind(pattern, value, out);
+                self.complete(body, out, dest);
+                self.scope().vars = outer;
+            }
+            ast::Expr::Match { term, arms, .. } => {
+                let value = self.expr(term, out);
+                let value = match value.kind() {
+                    Kind::Literal | Kind::Name => value,
+                    _ => self.keep(value, out),
+                };
+                let mut opened = false;
+                let locals = self.scope().locals.clone();
+
+                for (i, arm) in arms.iter().enumerate() {
+                    let test = self.test_arm(&arm.pattern, value.code(), i + 1 == arms.len());
+
+                    let outer = self.scope().vars.clone();
+                    let mut arm_out = vec![];
+                    self.bind(&arm.pattern, value.clone(), &mut arm_out);
+                    self.complete(&arm.body, &mut arm_out, dest.clone());
+                    self.scope().vars = outer;
+
+                    // Arms are blocks of their own, unless the first one is all there is
+                    if test.is_some() || opened {
+                        self.scope().locals = locals.clone();
+                    }
+
+                    match (test, opened) {
+                        (Some(test), false) => out.push(format!("if ({}) {{", test)),
+                        (Some(test), true) => out.push(format!("}} else if ({}) {{", test)),
+                        // Arms after one that matches everything are never reached
+                        (None, false) => {
+                            out.append(&mut arm_out);
+                            return;
+                        }
+                        (None, true) => {
+                            if !arm_out.is_empty() {
+                                out.push("} else {".into());
+                                out.append(&mut indent(arm_out));
+                            }
+                            break;
+                        }
+                    }
+
+                    out.append(&mut indent(arm_out));
+                    opened = true;
+                }
+
+                // Exhaustiveness is checked beforehand, so some arm always matches
+                out.push("}".into());
+            }
+            ast::Expr::Seq { first, second, .. } => {
+                self.complete(first, out, Dest::Discard);
+                self.complete(second, out, dest);
+            }
+            ast::Expr::VarDecl {
+                name, value, body, ..
+            } => {
+                let value = self.expr(value, out);
+
+                let outer = self.scope().vars.clone();
+                self.var(name, value, out);
+                self.complete(body, out, dest);
+                self.scope().vars = outer;
+            }
+            _ => {
+                let value = self.expr(e, out);
+                self.finish(value, out, dest);
+            }
+        }
+    }
+
+    // Like `complete`, but leaves the value of the innermost body in `value` instead of sending it anywhere
+    fn complete_value(
+        &mut self,
+        e: &ast::TypedExpr,
+        out: &mut Vec<String>,
+        value: &mut Option<Self::Value>,
+    ) {
+        match e {
+            ast::Expr::Let {
+                pattern,
+                value: bound,
+                body,
+                ..
+            } => {
+                let bound = self.expr(bound, out);
+
+                let outer = self.scope().vars.clone();
+                self.bind(pattern, bound, out);
+                self.complete_value(body, out, value);
+                self.scope().vars = outer;
+            }
+            ast::Expr::Seq { first, second, .. } => {
+                self.complete(first, out, Dest::Discard);
+                self.complete_value(second, out, value);
+            }
+            ast::Expr::VarDecl {
+                name,
+                value: initial,
+                body,
+                ..
+            } => {
+                let initial = self.expr(initial, out);
+
+                let outer = self.scope().vars.clone();
+                self.var(name, initial, out);
+                self.complete_value(body, out, value);
+                self.scope().vars = outer;
+            }
+            _ => *value = Some(self.expr(e, out)),
+        }
+    }
+}
+
+// The words of a type, for the names of what is generated for it, like `array_int` for `array<int>`
+pub fn mangle(ty: &ast::JustType) -> String {
+    ty.to_string()
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+pub fn indent(lines: Vec<String>) -> Vec<String> {
+    lines.into_iter().map(|l| format!("    {}", l)).collect()
+}
+
+// Where `span` is in the source, like `main.mi:3:7`, for the error when an index there is out of bounds
+pub fn location(sources: &source::SourceMap, span: ast::Span) -> String {
+    let file = sources.get(span.file);
+    let (line, column) = file.position(span.start);
+
+    format!("{}:{}:{}", file.name, line + 1, column + 1)
+}
+
+// Whether `e` is generated as a single expression, without any statements before it,
+// `compound` is whether tuples, arrays, records and variants are expressions,
+// Instead of being filled in by statements
+pub fn inline(e: &ast::TypedExpr, compound: bool) -> bool {
+    let inline = |e| inline(e, compound);
+
+    match e {
+        ast::Expr::UnitLit(_)
+        | ast::Expr::IntLit(_, _)
+        | ast::Expr::BoolLit(_, _)
+        | ast::Expr::FloatLit(_, _)
+        | ast::Expr::StringLit(_, _)
+        | ast::Expr::Var(_, _) => true,
+        ast::Expr::TypeAnno { term, .. }
+        | ast::Expr::Project { term, .. }
+        | ast::Expr::Field { term, .. } => inline(term),
+        ast::Expr::IfFlow {
+            cond,
+            on_true,
+            on_false,
+            ..
+        } => inline(cond) && inline(on_true) && inline(on_false),
+        ast::Expr::BinOp { left, right, .. } => inline(left) && inline(right),
+        ast::Expr::Builtin {
+            builtin: ast::Builtin::Print | ast::Builtin::Debug,
+            ..
+        } => false,
+        ast::Expr::Builtin { arg, .. } => inline(arg),
+        ast::Expr::Call { args, .. } => args.iter().all(inline),
+        ast::Expr::Repeat { length, value, .. } => inline(length) && inline(value),
+        ast::Expr::Index { term, index, .. } => inline(term) && inline(index),
+        ast::Expr::Tuple { items, .. } | ast::Expr::Array { items, .. } => {
+            compound && items.iter().all(inline)
+        }
+        ast::Expr::Record { fields, .. } => compound && fields.iter().all(|f| inline(&f.value)),
+        ast::Expr::Update { term, fields, .. } => {
+            compound && inline(term) && fields.iter().all(|f| inline(&f.value))
+        }
+        ast::Expr::Construct { args, .. } => compound && args.iter().all(inline),
+        ast::Expr::SetIndex {
+            term, index, value, ..
+        } => compound && inline(term) && inline(index) && inline(value),
+        ast::Expr::Let { .. }
+        | ast::Expr::Match { .. }
+        | ast::Expr::Seq { .. }
+        | ast::Expr::VarDecl { .. }
+        | ast::Expr::Assign { .. }
+        | ast::Expr::While { .. }
+        | ast::Expr::Break(_) => false,
+    }
+}
@@ -0,0 +1,429 @@
+use crate::ast::{self, Expr, JustType, Pattern, Span, Type};
+use crate::modules::Module;
+use crate::source::SourceMap;
+
+// Version of the JSON that `mizzle ast --format=json` writes,
+// Which only changes when a field is removed or changes its meaning
+const JSON_VERSION: usize = 1;
+
+// A node of the syntax tree of a file, in a form that is the same for every kind of node
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    // The name of the variant in `ast`, like `BinOp`, with `Decl` or `Pattern` after declarations and patterns
+    pub kind: &'static str,
+    pub span: Span,
+    // Only known for expressions and patterns, and only when the program checks
+    pub ty: Option<JustType>,
+    // What the node holds besides other nodes, like the name of a variable, always as text
+    pub attrs: Vec<(&'static str, String)>,
+    // In the order they are written in the source
+    pub children: Vec<Node>,
+}
+
+type Info<'a, T> = &'a dyn Fn(&T) -> (Span, Option<JustType>);
+
+fn node(kind: &'static str, (span, ty): (Span, Option<JustType>)) -> Node {
+    Node {
+        kind,
+        span,
+        ty,
+        attrs: vec![],
+        children: vec![],
+    }
+}
+
+impl Node {
+    fn attr(mut self, name: &'static str, value: impl ToString) -> Node {
+        self.attrs.push((name, value.to_string()));
+        self
+    }
+
+    fn child(mut self, child: Node) -> Node {
+        self.children.push(child);
+        self
+    }
+
+    fn children(mut self, children: impl IntoIterator<Item = Node>) -> Node {
+        self.children.extend(children);
+        self
+    }
+}
+
+fn type_node<T>(t: &Type<T>, info: Info<T>) -> Node {
+    let span = info(t.extra()).0;
+
+    node("Type", (span, None)).attr("text", t)
+}
+
+fn pattern<T>(p: &Pattern<T>, info: Info<T>) -> Node {
+    let at = info(p.extra());
+
+    match p {
+        Pattern::Var(_, name) => node("VarPattern", at).attr("name", name),
+        Pattern::Wildcard(_) => node("WildcardPattern", at),
+        Pattern::Tuple(_, items) => {
+            node("TuplePattern", at).children(items.iter().map(|i| pattern(i, info)))
+        }
+        Pattern::Constructor(_, name, args) => node("ConstructorPattern", at)
+            .attr("name", name)
+            .children(args.iter().map(|a| pattern(a, info))),
+        Pattern::Bool(_, b) => node("BoolPattern", at).attr("value", b),
+        Pattern::Int(_, n) => node("IntPattern", at).attr("value", n),
+    }
+}
+
+fn field_values<'a, T>(
+    fields: &'a [ast::FieldValue<T>],
+    info: Info<'a, T>,
+) -> impl Iterator<Item = Node> + 'a {
+    fields.iter().map(move |f| {
+        node("FieldValue", (info(&f.extra).0, None))
+            .attr("name", &f.name)
+            .child(expr(&f.value, info))
+    })
+}
+
+fn exprs<'a, T>(items: &'a [Expr<T>], info: Info<'a, T>) -> impl Iterator<Item = Node> + 'a {
+    items.iter().map(move |i| expr(i, info))
+}
+
+fn expr<T>(e: &Expr<T>, info: Info<T>) -> Node {
+    let at = info(e.extra());
+
+    match e {
+        Expr::UnitLit(_) => node("UnitLit", at),
+        // Literals are written the way they would be in a program
+        Expr::IntLit(_, _) => node("IntLit", at).attr("value", e),
+        Expr::BoolLit(_, _) => node("BoolLit", at).attr("value", e),
+        Expr::FloatLit(_, _) => node("FloatLit", at).attr("value", e),
+        Expr::StringLit(_, _) => node("StringLit", at).attr("value", e),
+        Expr::TypeAnno { term, ty, .. } => node("TypeAnno", at)
+            .child(expr(term, info))
+            .child(type_node(ty, info)),
+        Expr::IfFlow {
+            cond,
+            on_true,
+            on_false,
+            ..
+        } => node("IfFlow", at).children([
+            expr(cond, info),
+            expr(on_true, info),
+            expr(on_false, info),
+        ]),
+        Expr::BinOp {
+            op, left, right, ..
+        } => node("BinOp", at)
+            .attr("op", op)
+            .children([expr(left, info), expr(right, info)]),
+        Expr::Builtin { builtin, arg, .. } => node("Builtin", at)
+            .attr("name", builtin)
+            .child(expr(arg, info)),
+        Expr::Var(_, name) => node("Var", at).attr("name", name),
+        Expr::Tuple { items, .. } => node("Tuple", at).children(exprs(items, info)),
+        Expr::Project { term, index, .. } => node("Project", at)
+            .attr("index", index)
+            .child(expr(term, info)),
+        Expr::Let {
+            pattern: p,
+            value,
+            body,
+            ..
+        } => node("Let", at).children([pattern(p, info), expr(value, info), expr(body, info)]),
+        Expr::Record { name, fields, .. } => node("Record", at)
+            .attr("name", name)
+            .children(field_values(fields, info)),
+        Expr::Field { term, field, .. } => node("Field", at)
+            .attr("name", field)
+            .child(expr(term, info)),
+        Expr::Update { term, fields, .. } => node("Update", at)
+            .child(expr(term, info))
+            .children(field_values(fields, info)),
+        Expr::Construct { name, args, .. } => node("Construct", at)
+            .attr("name", name)
+            .children(exprs(args, info)),
+        Expr::Match { term, arms, .. } => {
+            node("Match", at)
+                .child(expr(term, info))
+                .children(arms.iter().map(|a| {
+                    node("Arm", (info(&a.extra).0, None))
+                        .children([pattern(&a.pattern, info), expr(&a.body, info)])
+                }))
+        }
+        Expr::Call { name, args, .. } => node("Call", at)
+            .attr("name", name)
+            .children(exprs(args, info)),
+        Expr::Seq { first, second, .. } => {
+            node("Seq", at).children([expr(first, info), expr(second, info)])
+        }
+        Expr::VarDecl {
+            name, value, body, ..
+        } => node("VarDecl", at)
+            .attr("name", name)
+            .children([expr(value, info), expr(body, info)]),
+        Expr::Assign { name, value, .. } => node("Assign", at)
+            .attr("name", name)
+            .child(expr(value, info)),
+        Expr::While { cond, body, .. } => {
+            node("While", at).children([expr(cond, info), expr(body, info)])
+        }
+        Expr::Break(_) => node("Break", at),
+        Expr::Array { items, .. } => node("Array", at).children(exprs(items, info)),
+        Expr::Repeat { length, value, .. } => {
+            node("Repeat", at).children([expr(length, info), expr(value, info)])
+        }
+        Expr::Index { term, index, .. } => {
+            node("Index", at).children([expr(term, info), expr(index, info)])
+        }
+        Expr::SetIndex {
+            term, index, value, ..
+        } => {
+            node("SetIndex", at).children([expr(term, info), expr(index, info), expr(value, info)])
+        }
+    }
+}
+
+// Declarations carry no type of their own, only the expressions and patterns in them do
+fn decl<T>(d: &ast::Decl<T>, info: Info<T>) -> Node {
+    let at = (info(d.extra()).0, None);
+    let kind = match d {
+        ast::Decl::Record { .. } => "RecordDecl",
+        ast::Decl::Variant { .. } => "VariantDecl",
+        ast::Decl::Function { .. } => "FunctionDecl",
+        ast::Decl::Test { .. } => "TestDecl",
+    };
+    let mut n = node(kind, at).attr("name", d.name());
+    if !matches!(d, ast::Decl::Test { .. }) {
+        n = n.attr("public", d.public());
+    }
+    if !d.type_params().is_empty() {
+        n = n.attr("type_params", d.type_params().join(", "));
+    }
+
+    match d {
+        ast::Decl::Record { fields, .. } => n.children(fields.iter().map(|f| {
+            node("FieldDecl", (info(&f.extra).0, None))
+                .attr("name", &f.name)
+                .child(type_node(&f.ty, info))
+        })),
+        ast::Decl::Variant { constructors, .. } => n.children(constructors.iter().map(|c| {
+            node("ConstructorDecl", (info(&c.extra).0, None))
+                .attr("name", &c.name)
+                .children(c.args.iter().map(|a| type_node(a, info)))
+        })),
+        ast::Decl::Function {
+            params,
+            result,
+            body,
+            ..
+        } => n
+            .children(params.iter().map(|p| {
+                node("Param", (info(&p.extra).0, None))
+                    .attr("name", &p.name)
+                    .child(type_node(&p.ty, info))
+            }))
+            .child(type_node(result, info))
+            .child(expr(body, info)),
+        ast::Decl::Test { body, .. } => n.child(expr(body, info)),
+    }
+}
+
+// The tree of the main module, which is the last one, with types when `typed` is the program that it checked as
+pub fn main_module(
+    modules: &[Module],
+    typed: Option<&ast::TypedProgram>,
+    sources: &SourceMap,
+) -> Node {
+    let module = modules.last().unwrap();
+    let body = module.body.as_ref().unwrap();
+    let file = body.extra().file;
+    let span = Span::new(file, 0, sources.get(file).source.len());
+
+    let imports = module
+        .imports
+        .iter()
+        .map(|(span, m)| node("Import", (*span, None)).attr("name", &modules[*m].name));
+    let root = node("Program", (span, None)).children(imports);
+
+    match typed {
+        // The checked program has the declarations of every module, the ones of this file keep their order
+        Some(typed) => {
+            let info: Info<(Span, JustType)> = &|(span, ty)| (*span, Some(ty.clone()));
+
+            root.children(
+                typed
+                    .decls
+                    .iter()
+                    .filter(|d| d.extra().0.file == file)
+                    .map(|d| decl(d, info)),
+            )
+            .child(expr(&typed.body, info))
+        }
+        None => {
+            let info: Info<Span> = &|span| (*span, None);
+
+            root.children(module.decls.iter().map(|d| decl(d, info)))
+                .child(expr(body, info))
+        }
+    }
+}
+
+// Lines and columns are counted from 1, with columns in bytes
+fn positions(span: Span, sources: &SourceMap) -> ((usize, usize), (usize, usize)) {
+    let file = sources.get(span.file);
+    let (line, column) = file.position(span.start);
+    let (end_line, end_column) = file.position(span.end);
+
+    ((line + 1, column + 1), (end_line + 1, end_column + 1))
+}
+
+// Like `(BinOp 0..5 1:1-1:6 : int op="+"`, followed by the children indented on their own lines
+pub fn to_sexp(n: &Node, sources: &SourceMap) -> String {
+    let mut out = String::new();
+    write_sexp(n, sources, 0, &mut out);
+    out
+}
+
+fn write_sexp(n: &Node, sources: &SourceMap, depth: usize, out: &mut String) {
+    let ((line, column), (end_line, end_column)) = positions(n.span, sources);
+
+    out.push_str(&format!(
+        "{}({} {}..{} {}:{}-{}:{}",
+        "  ".repeat(depth),
+        n.kind,
+        n.span.start,
+        n.span.end,
+        line,
+        column,
+        end_line,
+        end_column
+    ));
+    if let Some(ty) = &n.ty {
+        out.push_str(&format!(" : {}", ty));
+    }
+    for (name, value) in &n.attrs {
+        out.push_str(&format!(" {}=\"{}\"", name, ast::escape(value)));
+    }
+    for child in &n.children {
+        out.push('\n');
+        write_sexp(child, sources, depth + 1, out);
+    }
+    out.push(')');
+}
+
+fn json_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+// Every node has the same fields, in the same order, which are documented in the README
+fn json_node(n: &Node, sources: &SourceMap) -> String {
+    let ((line, column), (end_line, end_column)) = positions(n.span, sources);
+    let span = format!(
+        r#"{{"start":{},"end":{},"line":{},"column":{},"end_line":{},"end_column":{}}}"#,
+        n.span.start, n.span.end, line, column, end_line, end_column
+    );
+    let ty = match &n.ty {
+        Some(ty) => json_string(&ty.to_string()),
+        None => "null".into(),
+    };
+    let attrs = n
+        .attrs
+        .iter()
+        .map(|(name, value)| format!("{}:{}", json_string(name), json_string(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    let children = n
+        .children
+        .iter()
+        .map(|c| json_node(c, sources))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        r#"{{"kind":{},"span":{},"type":{},"attrs":{{{}}},"children":[{}]}}"#,
+        json_string(n.kind),
+        span,
+        ty,
+        attrs,
+        children
+    )
+}
+
+pub fn to_json(n: &Node, sources: &SourceMap) -> String {
+    format!(
+        r#"{{"version":{},"file":{},"checked":{},"tree":{}}}"#,
+        JSON_VERSION,
+        json_string(&sources.get(n.span.file).name),
+        // The body of a program that checks always has a type
+        n.children.last().is_some_and(|body| body.ty.is_some()),
+        json_node(n, sources)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend_test;
+    use crate::modules;
+
+    fn dump(source: &str) -> (Node, SourceMap) {
+        let (modules, sources) = backend_test::load(source);
+        let typed = modules::check(&modules).result.ok();
+        let tree = main_module(&modules, typed.as_ref(), &sources);
+
+        (tree, sources)
+    }
+
+    #[test]
+    fn dumps_kinds_spans_and_types() {
+        let (tree, sources) =
+            dump("fn double(x: int): int = x * 2 end\nlet y = double(1) in\ny + 1");
+        assert_eq!(
+            to_sexp(&tree, &sources),
+            r#"(Program 0..61 1:1-3:6
+  (FunctionDecl 0..34 1:1-1:35 name="double" public="false"
+    (Param 10..16 1:11-1:17 name="x"
+      (Type 13..16 1:14-1:17 text="int"))
+    (Type 19..22 1:20-1:23 text="int")
+    (BinOp 25..30 1:26-1:31 : int op="*"
+      (Var 25..26 1:26-1:27 : int name="x")
+      (IntLit 29..30 1:30-1:31 : int value="2")))
+  (Let 35..61 2:1-3:6 : int
+    (VarPattern 39..40 2:5-2:6 : int name="y")
+    (Call 43..52 2:9-2:18 : int name="double"
+      (IntLit 50..51 2:16-2:17 : int value="1"))
+    (BinOp 56..61 3:1-3:6 : int op="+"
+      (Var 56..57 3:1-3:2 : int name="y")
+      (IntLit 60..61 3:5-3:6 : int value="1"))))"#
+        );
+    }
+
+    #[test]
+    fn leaves_out_types_when_not_checked() {
+        let (tree, sources) = dump("\"a\\tb\" + 1");
+        assert_eq!(
+            to_json(&tree, &sources),
+            concat!(
+                r#"{"version":1,"file":"test.mi","checked":false,"tree":"#,
+                r#"{"kind":"Program","span":{"start":0,"end":10,"line":1,"column":1,"end_line":1,"end_column":11},"type":null,"attrs":{},"children":["#,
+                r#"{"kind":"BinOp","span":{"start":0,"end":10,"line":1,"column":1,"end_line":1,"end_column":11},"type":null,"attrs":{"op":"+"},"children":["#,
+                r#"{"kind":"StringLit","span":{"start":0,"end":6,"line":1,"column":1,"end_line":1,"end_column":7},"type":null,"attrs":{"value":"\"a\\tb\""},"children":[]},"#,
+                r#"{"kind":"IntLit","span":{"start":9,"end":10,"line":1,"column":10,"end_line":1,"end_column":11},"type":null,"attrs":{"value":"1"},"children":[]}]}]}}"#
+            )
+        );
+    }
+}
@@ -1,9 +1,12 @@
-use lalrpop_util::lexer::Token;
 use lalrpop_util::ParseError;
 
 use colored::*;
 
+use crate::ast;
+use crate::modules;
+use crate::source::{FileId, SourceMap};
 use crate::type_check;
+use crate::wasm;
 
 // Functions for formatting parser errors
 fn format_expected(expected: Vec<String>) -> String {
@@ -25,29 +28,9 @@ fn format_expected(expected: Vec<String>) -> String {
     )
 }
 
-fn loc_to_pnt(source: &Vec<String>, mut l: usize) -> (usize, usize) {
-    let line_lengths = source.iter().map(|s| s.len()).collect::<Vec<_>>();
-
-    let mut i = 0;
-    loop {
-        // If we reach the end of the file,
-        // Return a point one after the last character of the last line
-        if i > line_lengths.len() - 1 {
-            i = line_lengths.len() - 1;
-            l = line_lengths[line_lengths.len() - 1];
-            break;
-        }
-
-        if l <= line_lengths[i] {
-            break;
-        }
-
-        // `+ 1` because the newline character is removed when split
-        l -= line_lengths[i] + 1;
-        i += 1;
-    }
-
-    (i, l)
+// The source code that a span covers
+fn source_text(source: &SourceMap, span: ast::Span) -> String {
+    source.get(span.file).source[span.start..span.end].into()
 }
 
 #[derive(Debug, Clone)]
@@ -56,7 +39,8 @@ enum AccentColor {
     Warning,
 }
 
-fn format_source(source: &Vec<String>, l1: usize, l2: Option<usize>, color: AccentColor) -> String {
+// Points at `span` under the line it starts on, below a `file.mi:line:column` header
+fn format_source(source: &SourceMap, span: ast::Span, color: AccentColor) -> String {
     let fmt_accent = |s: String| -> ColoredString {
         match &color {
             AccentColor::Error => s.bright_red(),
@@ -64,36 +48,53 @@ fn format_source(source: &Vec<String>, l1: usize, l2: Option<usize>, color: Acce
         }
     };
 
-    let p1 = loc_to_pnt(source, l1);
-    let p2 = l2.map(|l| loc_to_pnt(source, l)).unwrap_or(p1);
+    let file = source.get(span.file);
+    let p1 = file.position(span.start);
+    let p2 = file.position(span.end);
 
-    let line_number = format!("{} |", p1.0).bright_blue();
+    // Lines and columns are counted from 1 when shown
+    let header = format!("{}:{}:{}", file.name, p1.0 + 1, p1.1 + 1);
+    let line_number = format!("{} |", p1.0 + 1).bright_blue();
     let indicator_offset = line_number.len() + p1.1;
 
-    // Assuming that the points are always on the same line
+    // Spans that continue on later lines are only pointed at until the end of the first one
+    let width = if p2.0 == p1.0 {
+        p2.1 - p1.1
+    } else {
+        file.line(p1.0).len() - p1.1
+    };
+
     format!(
-        "{}{}\n{}{}{}{}",
+        "{}{}\n{}{}\n{}{}{}{}",
+        "--> ".bright_blue(),
+        header,
         line_number,
-        source[p1.0],
+        file.line(p1.0),
         " ".repeat(indicator_offset),
         fmt_accent("^".into()),
-        fmt_accent("^".repeat(if p2.1 - p1.1 == 0 { 0 } else { p2.1 - p1.1 - 1 })),
+        fmt_accent("^".repeat(width.saturating_sub(1))),
         fmt_accent("here".into())
     )
 }
 
+// Tokens are owned, since the error can outlive the source of the file that it is in
 pub fn format_parse_err(
-    err: ParseError<usize, Token<'_>, &'static str>,
-    source: &Vec<String>,
+    err: ParseError<usize, String, &'static str>,
+    file: FileId,
+    source: &SourceMap,
 ) -> String {
-    let prefix = format!("{}: ", "Parse error".bright_red());
+    let prefix = format!("{}: ", "Parse error[E0001]".bright_red());
 
     match err {
         ParseError::InvalidToken { location } => {
             format!(
                 "{}illegal character(s).\n{}",
                 prefix,
-                format_source(source, location, None, AccentColor::Error)
+                format_source(
+                    source,
+                    ast::Span::new(file, location, location),
+                    AccentColor::Error
+                )
             )
         }
         ParseError::UnrecognizedEOF { location, expected } => {
@@ -101,7 +102,11 @@ pub fn format_parse_err(
                 "{}file ended, but expected {}.\n{}",
                 prefix,
                 format_expected(expected),
-                format_source(source, location, None, AccentColor::Error)
+                format_source(
+                    source,
+                    ast::Span::new(file, location, location),
+                    AccentColor::Error
+                )
             )
         }
         ParseError::UnrecognizedToken {
@@ -113,7 +118,11 @@ pub fn format_parse_err(
                 prefix,
                 token,
                 format_expected(expected),
-                format_source(source, l_start, Some(l_end), AccentColor::Error)
+                format_source(
+                    source,
+                    ast::Span::new(file, l_start, l_end),
+                    AccentColor::Error
+                )
             )
         }
         ParseError::ExtraToken {
@@ -123,15 +132,27 @@ pub fn format_parse_err(
                 "{}extra `{}`.\n{}",
                 prefix,
                 token,
-                format_source(source, l_start, Some(l_end), AccentColor::Error)
+                format_source(
+                    source,
+                    ast::Span::new(file, l_start, l_end),
+                    AccentColor::Error
+                )
             )
         }
         ParseError::User { error } => format!("{}{}.", prefix, error),
     }
 }
 
-fn format_type_err(e: type_check::TypeError, source: &Vec<String>) -> String {
-    let prefix = format!("{}: ", "Type error".bright_red());
+// Ends the sentence of an error message
+fn format_suggestion(suggestion: Option<String>) -> String {
+    match suggestion {
+        Some(s) => format!(", did you mean `{}`?", s),
+        None => ".".into(),
+    }
+}
+
+fn format_type_err(e: type_check::TypeError, source: &SourceMap) -> String {
+    let prefix = format!("{}: ", format!("Type error[{}]", e.code()).bright_red());
 
     match e {
         type_check::TypeError::AnnotationIncorrect {
@@ -144,19 +165,62 @@ fn format_type_err(e: type_check::TypeError, source: &Vec<String>) -> String {
                 prefix,
                 got,
                 annotation,
-                format_source(source, span.0, Some(span.1), AccentColor::Error)
+                format_source(source, span, AccentColor::Error)
             )
         }
-        type_check::TypeError::IfCondMustBeBool { end, got } => {
+        type_check::TypeError::IfCondMustBeBool { span, got } => {
+            let end = span.end - 1;
+
             format!(
                 "{}the condition of `if` should be `bool`, but is `{}`.\n{}",
                 prefix,
                 got,
-                format_source(source, end - 1, None, AccentColor::Error)
+                format_source(
+                    source,
+                    ast::Span {
+                        start: end,
+                        end,
+                        ..span
+                    },
+                    AccentColor::Error
+                )
+            )
+        }
+        type_check::TypeError::WhileCondMustBeBool { span, got } => {
+            let end = span.end - 1;
+
+            format!(
+                "{}the condition of `while` should be `bool`, but is `{}`.\n{}",
+                prefix,
+                got,
+                format_source(
+                    source,
+                    ast::Span {
+                        start: end,
+                        end,
+                        ..span
+                    },
+                    AccentColor::Error
+                )
+            )
+        }
+        type_check::TypeError::AssignImmutable { span, name } => {
+            format!(
+                "{}`{}` cannot be assigned to, since it is not declared with `var`.\n{}",
+                prefix,
+                name,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::BreakOutsideLoop { span } => {
+            format!(
+                "{}`break` can only be used inside of `while`.\n{}",
+                prefix,
+                format_source(source, span, AccentColor::Error)
             )
         }
         type_check::TypeError::IfBranchesMustBeSame {
-            start,
+            span,
             first,
             second,
         } => {
@@ -165,50 +229,497 @@ fn format_type_err(e: type_check::TypeError, source: &Vec<String>) -> String {
                 prefix,
                 first,
                 second,
-                format_source(source, start, None, AccentColor::Error)
+                format_source(
+                    source,
+                    ast::Span {
+                        end: span.start,
+                        ..span
+                    },
+                    AccentColor::Error
+                )
+            )
+        }
+        type_check::TypeError::ArgumentIncorrect {
+            span,
+            name,
+            got,
+            expected,
+        } => {
+            format!(
+                "{}`{}` expects `{}`, but was given `{}`.\n{}",
+                prefix,
+                name,
+                expected,
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::OperandNotSupported {
+            span,
+            name,
+            got,
+            allowed,
+        } => {
+            let allowed = allowed
+                .iter()
+                .map(|ty| format!("`{}`", ty))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!(
+                "{}`{}` does not work on `{}`, only on {}.\n{}",
+                prefix,
+                name,
+                got,
+                allowed,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::NumericMixing { span, name } => {
+            format!(
+                "{}`{}` cannot mix `int` and `float`, try converting with `to_float({})`.\n{}",
+                prefix,
+                name,
+                source_text(source, span),
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::UnknownVariable {
+            span,
+            name,
+            suggestion,
+        } => {
+            format!(
+                "{}`{}` is not defined{}\n{}",
+                prefix,
+                name,
+                format_suggestion(suggestion),
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::ProjectionIncorrect { span, index, got } => {
+            let reason = match &got {
+                ast::Type::Tuple(_, items) => format!("only has {} items", items.len()),
+                _ => "is not a tuple".into(),
+            };
+
+            format!(
+                "{}cannot get item `{}`, since `{}` {}.\n{}",
+                prefix,
+                index,
+                got,
+                reason,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::UnknownType {
+            span,
+            name,
+            suggestion,
+        } => {
+            format!(
+                "{}the type `{}` is not declared{}\n{}",
+                prefix,
+                name,
+                format_suggestion(suggestion),
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::DuplicateType { span, name } => {
+            format!(
+                "{}the type `{}` is already declared.\n{}",
+                prefix,
+                name,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::DuplicateField { span, name } => {
+            format!(
+                "{}the field `{}` is given more than once.\n{}",
+                prefix,
+                name,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::MissingFields {
+            span,
+            record,
+            fields,
+        } => {
+            let fields = fields
+                .iter()
+                .map(|f| format!("`{}`", f))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!(
+                "{}`{}` is missing the field(s) {}.\n{}",
+                prefix,
+                record,
+                fields,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::UnknownField {
+            span,
+            field,
+            record,
+            suggestion,
+        } => {
+            format!(
+                "{}no field `{}` on `{}`{}\n{}",
+                prefix,
+                field,
+                record,
+                format_suggestion(suggestion),
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::NotARecord { span, got } => {
+            format!(
+                "{}only records have fields, but this is `{}`.\n{}",
+                prefix,
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::PatternMismatch { span, pattern, got } => {
+            format!(
+                "{}the pattern `{}` does not match the type `{}`.\n{}",
+                prefix,
+                pattern,
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::DuplicateConstructor { span, name } => {
+            format!(
+                "{}the constructor `{}` is already declared.\n{}",
+                prefix,
+                name,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::UnknownConstructor {
+            span,
+            name,
+            suggestion,
+        } => {
+            format!(
+                "{}the constructor `{}` is not declared{}\n{}",
+                prefix,
+                name,
+                format_suggestion(suggestion),
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::ArgumentCount {
+            span,
+            name,
+            expected,
+            got,
+        } => {
+            format!(
+                "{}`{}` takes {} argument(s), but was given {}.\n{}",
+                prefix,
+                name,
+                expected,
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::MatchArmsMustBeSame {
+            span,
+            first,
+            second,
+        } => {
+            format!(
+                "{}arms of `match` have to return the same type, `{}` is not equal to `{}`.\n{}",
+                prefix,
+                first,
+                second,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::NonExhaustive { span, missing } => {
+            let missing = missing
+                .iter()
+                .map(|m| format!("`{}`", m))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!(
+                "{}patterns do not cover every value, missing {}.\n{}",
+                prefix,
+                missing,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::TypeArgumentCount {
+            span,
+            name,
+            expected,
+            got,
+        } => {
+            format!(
+                "{}`{}` takes {} type argument(s), but was given {}.\n{}",
+                prefix,
+                name,
+                expected,
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::UnknownFunction {
+            span,
+            name,
+            suggestion,
+        } => {
+            format!(
+                "{}the function `{}` is not defined{}\n{}",
+                prefix,
+                name,
+                format_suggestion(suggestion),
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::DuplicateFunction { span, name } => {
+            format!(
+                "{}the function `{}` is already declared.\n{}",
+                prefix,
+                name,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::DuplicateParameter { span, name } => {
+            format!(
+                "{}the parameter `{}` is given more than once.\n{}",
+                prefix,
+                name,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::ReturnIncorrect {
+            span,
+            name,
+            got,
+            expected,
+        } => {
+            format!(
+                "{}`{}` returns `{}`, but its body is `{}`.\n{}",
+                prefix,
+                name,
+                expected,
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::CannotInfer { span, got } => {
+            format!(
+                "{}cannot infer the type `{}`, try adding an annotation.\n{}",
+                prefix,
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::ArrayItemsMustBeSame {
+            span,
+            first,
+            second,
+        } => {
+            format!(
+                "{}items of an array have to be the same type, `{}` is not equal to `{}`.\n{}",
+                prefix,
+                first,
+                second,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::NotAnArray { span, got } => {
+            format!(
+                "{}only arrays can be indexed, but this is `{}`.\n{}",
+                prefix,
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::NotAFunction { span, name, got } => {
+            format!(
+                "{}`{}` cannot be called, since it is `{}` instead of a function.\n{}",
+                prefix,
+                name,
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::ImportConflict { span, name, first } => {
+            format!(
+                "{}the function `{}` is already imported from `{}`.\n{}",
+                prefix,
+                name,
+                first,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::TestMustBeBool { span, name, got } => {
+            format!(
+                "{}the test \"{}\" should be `bool`, but is `{}`.\n{}",
+                prefix,
+                ast::escape(&name),
+                got,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::DuplicateTest { span, name } => {
+            format!(
+                "{}the test \"{}\" is declared more than once.\n{}",
+                prefix,
+                ast::escape(&name),
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        type_check::TypeError::PolymorphicRecursion {
+            span,
+            name,
+            param,
+            ty,
+        } => {
+            format!(
+                "{}`{}` is used with `{}` for `{}` by itself, so it would have to be compiled for ever larger types.\n{}",
+                prefix,
+                name,
+                ty,
+                param,
+                format_source(source, span, AccentColor::Error)
             )
         }
     }
 }
 
-fn format_type_warn(w: type_check::TypeWarning, source: &Vec<String>) -> String {
-    let prefix = format!("{}: ", "Warning".truecolor(255, 165, 0));
+fn format_type_warn(w: type_check::TypeWarning, source: &SourceMap) -> String {
+    let prefix = format!(
+        "{}: ",
+        format!("Warning[{}]", w.code()).truecolor(255, 165, 0)
+    );
 
     match w {
-        type_check::TypeWarning::CondAlways { span, value } => {
+        type_check::TypeWarning::CondAlways {
+            span,
+            value,
+            keyword,
+        } => {
             format!(
-                "{}condition of `if` is always `{}`.\n{}",
+                "{}condition of `{}` is always `{}`.\n{}",
                 prefix,
+                keyword,
                 value,
-                format_source(source, span.0, Some(span.1), AccentColor::Warning)
+                format_source(source, span, AccentColor::Warning)
+            )
+        }
+        type_check::TypeWarning::UnreachableArm { span } => {
+            format!(
+                "{}this arm is never used, since the arms before it already match.\n{}",
+                prefix,
+                format_source(source, span, AccentColor::Warning)
+            )
+        }
+        type_check::TypeWarning::UnusedValue { span, ty, by } => {
+            format!(
+                "{}this value of type `{}` is thrown away by {}.\n{}",
+                prefix,
+                ty,
+                by,
+                format_source(source, span, AccentColor::Warning)
             )
         }
     }
 }
 
-pub fn format_type_issue(ti: type_check::TypeIssue, source: &Vec<String>) -> String {
+pub fn format_type_issue(ti: type_check::TypeIssue, source: &SourceMap) -> String {
     match ti {
         type_check::TypeIssue::Error(e) => format_type_err(e, source),
         type_check::TypeIssue::Warning(w) => format_type_warn(w, source),
     }
 }
 
+pub fn format_load_err(e: modules::LoadError, source: &SourceMap) -> String {
+    let prefix = format!("{}: ", format!("Import error[{}]", e.code()).bright_red());
+
+    match e {
+        modules::LoadError::Parse { file, error } => format_parse_err(error, file, source),
+        modules::LoadError::NotFound { span, name, path } => {
+            format!(
+                "{}cannot find the module `{}`, it should be at `{}`.\n{}",
+                prefix,
+                name,
+                path,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        modules::LoadError::Cycle { span, cycle } => {
+            format!(
+                "{}modules cannot import each other in a cycle, `{}`.\n{}",
+                prefix,
+                cycle.join("` imports `"),
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+    }
+}
+
+pub fn format_runtime_err(e: wasm::RuntimeError, source: &SourceMap) -> String {
+    let prefix = format!("{}: ", format!("Runtime error[{}]", e.code()).bright_red());
+
+    match e {
+        wasm::RuntimeError::IndexOutOfBounds {
+            span,
+            index,
+            length,
+        } => {
+            format!(
+                "{}index `{}` is out of bounds of an array with length `{}`.\n{}",
+                prefix,
+                index,
+                length,
+                format_source(source, span, AccentColor::Error)
+            )
+        }
+        wasm::RuntimeError::Trap(message) => format!("{}{}.", prefix, message),
+    }
+}
+
+// A test that gave back `false`, `span` is its body
+pub fn format_test_failure(name: &str, span: ast::Span, source: &SourceMap) -> String {
+    format!(
+        "{}: the test \"{}\" is `false`.\n{}",
+        "Test failed".bright_red(),
+        ast::escape(name),
+        format_source(source, span, AccentColor::Error)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn location_coversion() {
-        let source = r#"abc
-df"#
-        .split("\n")
-        .map(|s| s.into())
-        .collect::<Vec<String>>();
-
-        assert_eq!(loc_to_pnt(&source, 0), (0, 0));
-        assert_eq!(loc_to_pnt(&source, 2), (0, 2));
-        assert_eq!(loc_to_pnt(&source, 4), (1, 0));
-        assert_eq!(loc_to_pnt(&source, 6), (1, 2));
-        assert_eq!(loc_to_pnt(&source, 20), (1, 2));
+    fn shows_file_line_and_column() {
+        colored::control::set_override(false);
+
+        let mut source = SourceMap::default();
+        source.add("a.mi", "1");
+        let file = source.add("b.mi", "x\n  1 + true");
+
+        let e = type_check::TypeError::ArgumentIncorrect {
+            span: ast::Span::new(file, 8, 12),
+            name: "+".into(),
+            got: ast::Type::Bool(()),
+            expected: ast::Type::Int(()),
+        };
+
+        assert_eq!(
+            format_type_issue(type_check::TypeIssue::Error(e), &source),
+            "Type error[E0008]: `+` expects `int`, but was given `bool`.\n--> b.mi:2:7\n2 |  1 + true\n         ^^^^here"
+        );
     }
 }
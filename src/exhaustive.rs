@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use crate::ast;
+
+// Finds the values that no pattern matches, and the patterns that can never match
+// Based on the usefulness algorithm from "Warnings for pattern matching" by Luc Maranget
+
+// The type parameters and constructors of every declared variant, by type name
+pub type Variants = HashMap<String, (Vec<String>, Vec<(String, Vec<ast::JustType>)>)>;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Ctor {
+    Bool(bool),
+    Int(i64),
+    Tuple,
+    // Index into the constructors of the variant
+    Variant(usize),
+}
+
+#[derive(Clone, Debug)]
+enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>),
+}
+
+fn to_pat(p: &ast::TypedPattern, variants: &Variants) -> Pat {
+    match p {
+        ast::Pattern::Var(_, _) | ast::Pattern::Wildcard(_) => Pat::Wild,
+        ast::Pattern::Tuple(_, items) => Pat::Ctor(
+            Ctor::Tuple,
+            items.iter().map(|i| to_pat(i, variants)).collect(),
+        ),
+        ast::Pattern::Constructor((_, ty), name, args) => {
+            let index = match ty {
+                ast::Type::Named(_, ty_name, _) => variants[ty_name]
+                    .1
+                    .iter()
+                    .position(|(c, _)| c == name)
+                    .unwrap(),
+                _ => panic!("Internal compiler error"),
+            };
+
+            Pat::Ctor(
+                Ctor::Variant(index),
+                args.iter().map(|a| to_pat(a, variants)).collect(),
+            )
+        }
+        ast::Pattern::Bool(_, b) => Pat::Ctor(Ctor::Bool(*b), vec![]),
+        ast::Pattern::Int(_, n) => Pat::Ctor(Ctor::Int(*n), vec![]),
+    }
+}
+
+// `first` followed by everything in `rest`
+fn concat<A: Clone>(first: &[A], rest: &[A]) -> Vec<A> {
+    first.iter().chain(rest).cloned().collect()
+}
+
+struct Matrix<'a> {
+    variants: &'a Variants,
+}
+
+impl<'a> Matrix<'a> {
+    // Every constructor of `ty` along with the types of its arguments,
+    // `None` when there are too many constructors to list, like for `int`
+    fn all_ctors(&self, ty: &ast::JustType) -> Option<Vec<(Ctor, Vec<ast::JustType>)>> {
+        match ty {
+            ast::Type::Bool(_) => Some(vec![
+                (Ctor::Bool(true), vec![]),
+                (Ctor::Bool(false), vec![]),
+            ]),
+            ast::Type::Tuple(_, items) => Some(vec![(Ctor::Tuple, items.clone())]),
+            ast::Type::Named(_, name, _) => self.variants.get(name).map(|(_, constructors)| {
+                (0..constructors.len())
+                    .map(|i| (Ctor::Variant(i), self.ctor_args(ty, &Ctor::Variant(i))))
+                    .collect()
+            }),
+            _ => None,
+        }
+    }
+
+    fn ctor_args(&self, ty: &ast::JustType, ctor: &Ctor) -> Vec<ast::JustType> {
+        match (ctor, ty) {
+            (Ctor::Tuple, ast::Type::Tuple(_, items)) => items.clone(),
+            (Ctor::Variant(i), ast::Type::Named(_, name, type_args)) => {
+                let (type_params, constructors) = &self.variants[name];
+
+                constructors[*i]
+                    .1
+                    .iter()
+                    .map(|arg| arg.substitute(type_params, type_args))
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    // How a value built with `ctor` is written in source, given how its arguments are written
+    fn show(&self, ty: &ast::JustType, ctor: &Ctor, args: &[String]) -> String {
+        match (ctor, ty) {
+            (Ctor::Bool(b), _) => b.to_string(),
+            (Ctor::Int(n), _) => n.to_string(),
+            (Ctor::Tuple, _) => format!("({})", args.join(", ")),
+            (Ctor::Variant(i), ast::Type::Named(_, name, _)) => {
+                let constructor = &self.variants[name].1[*i].0;
+
+                if args.is_empty() {
+                    constructor.clone()
+                } else {
+                    format!("{}({})", constructor, args.join(", "))
+                }
+            }
+            _ => panic!("Internal compiler error"),
+        }
+    }
+
+    // The rows that can match a value built with `ctor`, with its arguments spread out into columns
+    fn specialize(&self, rows: &[Vec<Pat>], ctor: &Ctor, arity: usize) -> Vec<Vec<Pat>> {
+        rows.iter()
+            .filter_map(|row| match &row[0] {
+                Pat::Wild => Some(concat(&vec![Pat::Wild; arity], &row[1..])),
+                Pat::Ctor(c, args) if c == ctor => Some(concat(args, &row[1..])),
+                Pat::Ctor(_, _) => None,
+            })
+            .collect()
+    }
+
+    // The rows that match any value in the first column
+    fn default(&self, rows: &[Vec<Pat>]) -> Vec<Vec<Pat>> {
+        rows.iter()
+            .filter(|row| matches!(row[0], Pat::Wild))
+            .map(|row| row[1..].to_vec())
+            .collect()
+    }
+
+    // The constructors of `ty` that are not written in the first column,
+    // `None` when there are too many constructors to list
+    fn unused(
+        &self,
+        rows: &[Vec<Pat>],
+        ty: &ast::JustType,
+    ) -> Option<Vec<(Ctor, Vec<ast::JustType>)>> {
+        let all = self.all_ctors(ty)?;
+
+        Some(
+            all.into_iter()
+                .filter(|(c, _)| {
+                    !rows
+                        .iter()
+                        .any(|row| matches!(&row[0], Pat::Ctor(r, _) if r == c))
+                })
+                .collect(),
+        )
+    }
+
+    // Whether there is a value that `q` matches, but none of `rows` do
+    fn useful(&self, rows: &[Vec<Pat>], q: &[Pat], tys: &[ast::JustType]) -> bool {
+        if q.is_empty() {
+            return rows.is_empty();
+        }
+
+        match &q[0] {
+            Pat::Ctor(ctor, args) => self.useful(
+                &self.specialize(rows, ctor, args.len()),
+                &concat(args, &q[1..]),
+                &concat(&self.ctor_args(&tys[0], ctor), &tys[1..]),
+            ),
+            Pat::Wild => match self.unused(rows, &tys[0]) {
+                // Every constructor is written somewhere, so try each of them
+                Some(unused) if unused.is_empty() => {
+                    self.all_ctors(&tys[0])
+                        .unwrap()
+                        .iter()
+                        .any(|(ctor, arg_tys)| {
+                            self.useful(
+                                &self.specialize(rows, ctor, arg_tys.len()),
+                                &concat(&vec![Pat::Wild; arg_tys.len()], &q[1..]),
+                                &concat(arg_tys, &tys[1..]),
+                            )
+                        })
+                }
+                _ => self.useful(&self.default(rows), &q[1..], &tys[1..]),
+            },
+        }
+    }
+
+    // Examples of values that none of `rows` match, written like patterns
+    fn missing(&self, rows: &[Vec<Pat>], tys: &[ast::JustType]) -> Vec<Vec<String>> {
+        if tys.is_empty() {
+            return if rows.is_empty() {
+                vec![vec![]]
+            } else {
+                vec![]
+            };
+        }
+
+        let used = |ctor: &Ctor| {
+            rows.iter()
+                .any(|row| matches!(&row[0], Pat::Ctor(c, _) if c == ctor))
+        };
+        // Any value is missing when there are too many constructors to list, or none are written
+        let wildcard_missing = || {
+            self.missing(&self.default(rows), &tys[1..])
+                .into_iter()
+                .map(|values| concat(&["_".to_string()], &values))
+                .collect()
+        };
+
+        let all = match self.all_ctors(&tys[0]) {
+            Some(all) if all.iter().any(|(ctor, _)| used(ctor)) => all,
+            _ => return wildcard_missing(),
+        };
+
+        all.iter()
+            .flat_map(|(ctor, arg_tys)| {
+                // Constructors that are not written are missing entirely,
+                // So their arguments are shown as `_` instead of every possible value
+                if !used(ctor) {
+                    let head = self.show(&tys[0], ctor, &vec!["_".to_string(); arg_tys.len()]);
+
+                    return self
+                        .missing(&self.default(rows), &tys[1..])
+                        .into_iter()
+                        .map(|values| concat(std::slice::from_ref(&head), &values))
+                        .collect::<Vec<_>>();
+                }
+
+                self.missing(
+                    &self.specialize(rows, ctor, arg_tys.len()),
+                    &concat(arg_tys, &tys[1..]),
+                )
+                .into_iter()
+                .map(|values| {
+                    let (args, rest) = values.split_at(arg_tys.len());
+                    concat(&[self.show(&tys[0], ctor, args)], rest)
+                })
+                .collect()
+            })
+            .collect()
+    }
+}
+
+pub struct Report {
+    // Written like patterns, so they can be pasted as new arms
+    pub missing: Vec<String>,
+    // Indices of the patterns that are already covered by the patterns before them
+    pub unreachable: Vec<usize>,
+}
+
+// `patterns` are tried in order against a value of the type `ty`
+pub fn check(variants: &Variants, ty: &ast::JustType, patterns: &[&ast::TypedPattern]) -> Report {
+    let matrix = Matrix { variants };
+    let rows = patterns
+        .iter()
+        .map(|p| vec![to_pat(p, variants)])
+        .collect::<Vec<_>>();
+    let tys = vec![ty.clone()];
+
+    Report {
+        missing: matrix
+            .missing(&rows, &tys)
+            .into_iter()
+            .map(|mut values| values.remove(0))
+            .collect(),
+        unreachable: (0..rows.len())
+            .filter(|&i| !matrix.useful(&rows[..i], &rows[i], &tys))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str) -> ast::JustType {
+        ast::Type::Named((), name.into(), vec![])
+    }
+
+    fn constructor(name: &str, args: Vec<ast::TypedPattern>, ty: &str) -> ast::TypedPattern {
+        ast::Pattern::Constructor((ast::Span::default(), named(ty)), name.into(), args)
+    }
+
+    fn wildcard(ty: ast::JustType) -> ast::TypedPattern {
+        ast::Pattern::Wildcard((ast::Span::default(), ty))
+    }
+
+    fn shapes() -> Variants {
+        let mut variants = HashMap::new();
+        variants.insert(
+            "Shape".into(),
+            (
+                vec![],
+                vec![
+                    ("Circle".into(), vec![ast::Type::Bool(())]),
+                    ("Empty".into(), vec![]),
+                ],
+            ),
+        );
+
+        variants
+    }
+
+    #[test]
+    fn finds_missing_constructors() {
+        let variants = shapes();
+        let circle = constructor(
+            "Circle",
+            vec![ast::Pattern::Bool(
+                (ast::Span::default(), ast::Type::Bool(())),
+                true,
+            )],
+            "Shape",
+        );
+
+        let report = check(&variants, &named("Shape"), &[&circle]);
+        assert_eq!(report.missing, vec!["Circle(false)", "Empty"]);
+        assert!(report.unreachable.is_empty());
+    }
+
+    #[test]
+    fn finds_unreachable_patterns() {
+        let variants = shapes();
+        let any = wildcard(named("Shape"));
+        let empty = constructor("Empty", vec![], "Shape");
+
+        let report = check(&variants, &named("Shape"), &[&any, &empty]);
+        assert!(report.missing.is_empty());
+        assert_eq!(report.unreachable, vec![1]);
+    }
+
+    #[test]
+    fn ints_need_a_catch_all() {
+        let one = ast::Pattern::Int((ast::Span::default(), ast::Type::Int(())), 1);
+
+        let report = check(&HashMap::new(), &ast::Type::Int(()), &[&one]);
+        assert_eq!(report.missing, vec!["_"]);
+    }
+}
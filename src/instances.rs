@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::ast;
+
+// The functions a program uses, which wasm, C and JavaScript are all generated from the same way,
+// Generic functions are generated once for every combination of type arguments they are used with,
+// Since the types they are compiled to, and what `print` writes, depend on them
+pub struct Instances<'a> {
+    functions: HashMap<String, &'a ast::TypedDecl>,
+    // Every instance of a function that is used, by its name and then the function and type arguments
+    // Each one is generated after the function being generated, which may add more
+    used: Vec<(String, String, Vec<ast::JustType>)>,
+    generated: usize,
+    // The type parameters of the function being generated, and the types they stand for in this instance
+    type_args: (Vec<String>, Vec<ast::JustType>),
+}
+
+impl<'a> Instances<'a> {
+    pub fn new(decls: &'a [ast::TypedDecl]) -> Instances<'a> {
+        let functions = decls
+            .iter()
+            .filter(|d| matches!(d, ast::Decl::Function { .. }))
+            .map(|d| (d.name().to_string(), d))
+            .collect();
+
+        Instances {
+            functions,
+            used: vec![],
+            generated: 0,
+            type_args: (vec![], vec![]),
+        }
+    }
+
+    // `ty` with the type parameters of the function being generated replaced
+    pub fn concrete(&self, ty: &ast::JustType) -> ast::JustType {
+        ty.substitute(&self.type_args.0, &self.type_args.1)
+    }
+
+    // The name of the instance of the function `name` called with `arg_tys` that returns `result`,
+    // Which is given by `name_of` from the type arguments the first time that instance is used
+    pub fn instance(
+        &mut self,
+        name: &str,
+        arg_tys: &[ast::JustType],
+        result: &ast::JustType,
+        name_of: impl FnOnce(&[ast::JustType]) -> String,
+    ) -> String {
+        let type_args = ast::type_args(self.functions[name], arg_tys, result);
+
+        if let Some((instance, _, _)) = self
+            .used
+            .iter()
+            .find(|(_, n, args)| n == name && args == &type_args)
+        {
+            return instance.clone();
+        }
+
+        let instance = name_of(&type_args);
+        self.used.push((instance.clone(), name.into(), type_args));
+
+        instance
+    }
+
+    // The next instance that is used but has not been generated, by its name and then the function,
+    // Which is the one being generated from then on
+    // Functions that are never used are never given back, so they are left out
+    pub fn next(&mut self) -> Option<(String, &'a ast::TypedDecl)> {
+        let (instance, name, type_args) = self.used.get(self.generated)?.clone();
+        self.generated += 1;
+
+        let function = self.functions[&name];
+        if let ast::Decl::Function { type_params, .. } = function {
+            self.type_args = (type_params.clone(), type_args);
+        }
+
+        Some((instance, function))
+    }
+
+    // Generates what is outside of any function from then on, like the body of the program
+    pub fn leave(&mut self) {
+        self.type_args = (vec![], vec![]);
+    }
+}
@@ -0,0 +1,1560 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::ast;
+use crate::instances::Instances;
+
+// An A-normal form representation that sits between the AST and wasm,
+// Every intermediate value is bound to an explicit temporary,
+// But control flow stays structured since wasm requires that anyway
+
+// Value types, which map one to one onto wasm value types
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ty {
+    I32,
+    I64,
+    F64,
+}
+
+// `bool` is an `i32` since that is what wasm instructions like `if` consume,
+// And `string`, tuples and arrays are `i32` pointers into linear memory,
+// Functions are an `i32` index into the table of functions that are used as values
+pub fn ty_of(ty: &ast::JustType) -> Ty {
+    match ty {
+        // The only value of `unit` is never looked at, so it is a placeholder `0`
+        ast::Type::Unit(_) => Ty::I32,
+        ast::Type::Int(_) => Ty::I64,
+        ast::Type::Bool(_) => Ty::I32,
+        ast::Type::Float(_) => Ty::F64,
+        ast::Type::String(_) => Ty::I32,
+        ast::Type::Tuple(_, _) => Ty::I32,
+        ast::Type::Array(_, _) => Ty::I32,
+        ast::Type::Function(_, _, _) => Ty::I32,
+        ast::Type::Named(_, _, _) => Ty::I32,
+        // Functions are lowered once for every type they are used at, so type parameters are always replaced
+        ast::Type::Var(_, _) | ast::Type::Unknown(_, _) => panic!("Internal compiler error"),
+    }
+}
+
+// Every item of a tuple or field of a record gets an 8 byte slot, no matter its type
+// Variants store the index of their constructor as an `i32` in the first slot, followed by the arguments
+// Arrays store their length as an `i32` in the first slot, followed by the items
+pub const SLOT_SIZE: u32 = 8;
+
+impl fmt::Display for Ty {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Ty::I32 => write!(fmt, "i32"),
+            Ty::I64 => write!(fmt, "i64"),
+            Ty::F64 => write!(fmt, "f64"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Temp(pub u32);
+
+impl fmt::Display for Temp {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "t{}", self.0)
+    }
+}
+
+// Arguments of instructions are always atomic, either a constant or a temporary
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operand {
+    I32(i32),
+    I64(i64),
+    F64(f64),
+    // Index into `Module::strings`
+    Str(u32),
+    // Index into `Module::table`
+    Func(u32),
+    Temp(Temp),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Operand::I32(n) => write!(fmt, "{}", n),
+            Operand::I64(n) => write!(fmt, "{}", n),
+            Operand::F64(x) => write!(fmt, "{:?}", x),
+            Operand::Str(i) => write!(fmt, "s{}", i),
+            Operand::Func(i) => write!(fmt, "f{}", i),
+            Operand::Temp(t) => write!(fmt, "{}", t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Concat,
+    Len,
+    // Compares the contents of two strings, instead of their addresses
+    StrEq,
+    Not,
+    // Arithmetic and comparisons work on whichever type their arguments are
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    ToFloat,
+    ToInt,
+    // Reserves the number of bytes given by the argument, and returns the address
+    Alloc,
+    // Reads the value at the argument address plus the offset
+    Load(u32),
+    // Allocates an array with the argument number of items, which are all zero,
+    // A negative number gives an empty array
+    NewArray,
+    // The address of the item at the second argument in the array at the first argument
+    Element,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let name = match self {
+            Op::Concat => "concat",
+            Op::Len => "len",
+            Op::StrEq => "str_eq",
+            Op::Not => "not",
+            Op::Add => "add",
+            Op::Sub => "sub",
+            Op::Mul => "mul",
+            Op::Div => "div",
+            Op::Rem => "rem",
+            Op::Eq => "eq",
+            Op::Ne => "ne",
+            Op::Lt => "lt",
+            Op::Le => "le",
+            Op::Gt => "gt",
+            Op::Ge => "ge",
+            Op::ToFloat => "to_float",
+            Op::ToInt => "to_int",
+            Op::Alloc => "alloc",
+            Op::Load(offset) => return write!(fmt, "load[{}]", offset),
+            Op::NewArray => "new_array",
+            Op::Element => "element",
+        };
+
+        write!(fmt, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Inst {
+    // Applies a primitive operation to its arguments
+    Op {
+        dest: Temp,
+        op: Op,
+        args: Vec<Operand>,
+    },
+    // Runs exactly one of the blocks, and binds its result to `dest`
+    If {
+        dest: Temp,
+        cond: Operand,
+        on_true: Block,
+        on_false: Block,
+    },
+    // Writes `value` at `address` plus `offset`
+    Store {
+        address: Operand,
+        offset: u32,
+        value: Operand,
+    },
+    // Runs the block at `index`, which has to be in bounds, and binds its result to `dest`
+    Switch {
+        dest: Temp,
+        index: Operand,
+        cases: Vec<Block>,
+    },
+    // Replaces the value of `dest`, which belongs to a variable declared with `var`
+    Assign {
+        dest: Temp,
+        value: Operand,
+    },
+    // Runs `cond` and then `body` for as long as the result of `cond` is true
+    Loop {
+        cond: Block,
+        body: Vec<Inst>,
+    },
+    // Leaves the innermost `Loop`
+    Break,
+    // Calls the function named `function`, and binds what it returns to `dest`,
+    // Which is `None` when the function returns `unit`
+    Call {
+        dest: Option<Temp>,
+        function: String,
+        args: Vec<Operand>,
+    },
+    // Same as `Call`, but the function is a value that is only known at runtime
+    CallIndirect {
+        dest: Option<Temp>,
+        function: Operand,
+        args: Vec<Operand>,
+    },
+    // Stops the program with an error pointing at `span` when `index` is out of bounds of `array`
+    CheckIndex {
+        array: Operand,
+        index: Operand,
+        span: ast::Span,
+    },
+    // Stops the program, used where a value is needed but can never be computed
+    Trap,
+}
+
+// A sequence of instructions, followed by the value the sequence evaluates to
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub insts: Vec<Inst>,
+    pub result: Operand,
+}
+
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub name: String,
+    // Types of the parameters, which are also the first temporaries
+    pub params: Vec<Ty>,
+    // `None` when the function returns `unit`
+    pub result: Option<Ty>,
+    // Indexed by `Temp`
+    pub temps: Vec<Ty>,
+    pub body: Block,
+}
+
+// A call of `print` or `debug`, the host gets its index in `Module::printed` along with the value,
+// Which tells it how to show the value
+#[derive(Clone, Debug, PartialEq)]
+pub struct Printed {
+    pub ty: ast::JustType,
+    // Where `debug` was called, `None` for `print`
+    pub debug: Option<ast::Span>,
+}
+
+// The function imported from the host that shows values which are a `ty`,
+// It takes the value and the index of the call in `Module::printed`
+pub fn print_function(ty: Ty) -> String {
+    format!("print.{}", ty)
+}
+
+#[derive(Clone, Debug)]
+pub struct Module {
+    pub functions: Vec<Function>,
+    // String literals, without duplicates
+    pub strings: Vec<String>,
+    // The names of the functions that are used as values, without duplicates
+    pub table: Vec<String>,
+    pub printed: Vec<Printed>,
+}
+
+type Fields = Vec<(String, ast::JustType)>;
+type Constructors = Vec<(String, Vec<ast::JustType>)>;
+
+struct Lowering<'a> {
+    temps: Vec<Ty>,
+    strings: Vec<String>,
+    table: Vec<String>,
+    printed: Vec<Printed>,
+    // Where the value of each variable in scope can be found
+    vars: HashMap<String, Operand>,
+    // The temporaries of variables declared with `var`, which are the only ones that change
+    mutable: Vec<Temp>,
+    // Type parameters, then field names and types of each record, in the order they are laid out in memory
+    records: HashMap<String, (Vec<String>, Fields)>,
+    // Type parameters, then constructor names and argument types of each variant,
+    // The index of a constructor is its tag
+    variants: HashMap<String, (Vec<String>, Constructors)>,
+    instances: Instances<'a>,
+}
+
+impl<'a> Lowering<'a> {
+    fn concrete(&self, ty: &ast::JustType) -> ast::JustType {
+        self.instances.concrete(ty)
+    }
+
+    fn ty(&self, ty: &ast::JustType) -> Ty {
+        ty_of(&self.concrete(ty))
+    }
+
+    fn fresh(&mut self, ty: Ty) -> Temp {
+        self.temps.push(ty);
+        Temp(self.temps.len() as u32 - 1)
+    }
+
+    fn string(&mut self, s: &str) -> Operand {
+        let index = match self.strings.iter().position(|existing| existing == s) {
+            Some(index) => index,
+            None => {
+                self.strings.push(s.into());
+                self.strings.len() - 1
+            }
+        };
+
+        Operand::Str(index as u32)
+    }
+
+    // The instance of the function `name` used as a value of the type `ty`
+    fn function_value(&mut self, name: &str, ty: &ast::JustType) -> Operand {
+        let (params, result) = match ty {
+            ast::Type::Function(_, params, result) => (params, result),
+            _ => panic!("Internal compiler error"),
+        };
+        let instance = self.instance(name, params, result);
+
+        let index = match self.table.iter().position(|existing| existing == &instance) {
+            Some(index) => index,
+            None => {
+                self.table.push(instance);
+                self.table.len() - 1
+            }
+        };
+
+        Operand::Func(index as u32)
+    }
+
+    // The address of the item at `index` in `array`, after checking that it is in bounds
+    fn element(
+        &mut self,
+        array: Operand,
+        index: Operand,
+        span: ast::Span,
+        insts: &mut Vec<Inst>,
+    ) -> Operand {
+        insts.push(Inst::CheckIndex { array, index, span });
+        let dest = self.fresh(Ty::I32);
+        insts.push(Inst::Op {
+            dest,
+            op: Op::Element,
+            args: vec![array, index],
+        });
+
+        Operand::Temp(dest)
+    }
+
+    fn op(
+        &mut self,
+        op: Op,
+        args: Vec<Operand>,
+        ty: &ast::JustType,
+        insts: &mut Vec<Inst>,
+    ) -> Operand {
+        let dest = self.fresh(self.ty(ty));
+        insts.push(Inst::Op { dest, op, args });
+
+        Operand::Temp(dest)
+    }
+
+    // The offset and type of `field` within the record `ty`
+    fn field(&self, ty: &ast::JustType, field: &str) -> (u32, ast::JustType) {
+        let (type_params, fields) = match ty {
+            ast::Type::Named(_, name, _) => &self.records[name],
+            _ => panic!("Internal compiler error"),
+        };
+        let index = fields.iter().position(|(f, _)| f == field).unwrap();
+
+        (
+            index as u32 * SLOT_SIZE,
+            Lowering::field_ty(ty, type_params, &fields[index].1),
+        )
+    }
+
+    // The tag of the constructor `name` of the variant `ty`
+    fn tag(&self, ty: &ast::JustType, name: &str) -> usize {
+        match ty {
+            ast::Type::Named(_, ty_name, _) => self.variants[ty_name]
+                .1
+                .iter()
+                .position(|(c, _)| c == name)
+                .unwrap(),
+            _ => panic!("Internal compiler error"),
+        }
+    }
+
+    // The type of a field or constructor argument, as declared in `ty` which has `type_params`
+    fn field_ty(
+        ty: &ast::JustType,
+        type_params: &[String],
+        declared: &ast::JustType,
+    ) -> ast::JustType {
+        match ty {
+            ast::Type::Named(_, _, type_args) => declared.substitute(type_params, type_args),
+            _ => panic!("Internal compiler error"),
+        }
+    }
+
+    // Offset of the `i`th argument of a constructor, after the tag
+    fn arg_offset(i: usize) -> u32 {
+        (i as u32 + 1) * SLOT_SIZE
+    }
+
+    fn load_tag(&mut self, value: Operand, insts: &mut Vec<Inst>) -> Operand {
+        self.op(Op::Load(0), vec![value], &ast::Type::Bool(()), insts)
+    }
+
+    // Whether `pattern` matches every value, so that it needs no test
+    fn irrefutable(pattern: &ast::TypedPattern) -> bool {
+        match pattern {
+            ast::Pattern::Var(_, _) | ast::Pattern::Wildcard(_) => true,
+            ast::Pattern::Tuple(_, patterns) => patterns.iter().all(Lowering::irrefutable),
+            ast::Pattern::Constructor(_, _, _)
+            | ast::Pattern::Bool(_, _)
+            | ast::Pattern::Int(_, _) => false,
+        }
+    }
+
+    // An `i32` that is 1 when `value` matches `pattern`, and 0 otherwise
+    fn test(
+        &mut self,
+        pattern: &ast::TypedPattern,
+        value: Operand,
+        insts: &mut Vec<Inst>,
+    ) -> Operand {
+        let bool = ast::Type::Bool(());
+
+        match pattern {
+            ast::Pattern::Var(_, _) | ast::Pattern::Wildcard(_) => Operand::I32(1),
+            ast::Pattern::Bool(_, true) => value,
+            ast::Pattern::Bool(_, false) => self.op(Op::Not, vec![value], &bool, insts),
+            ast::Pattern::Int(_, n) => self.op(Op::Eq, vec![value, Operand::I64(*n)], &bool, insts),
+            ast::Pattern::Tuple(_, patterns) => {
+                let items = patterns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (i as u32 * SLOT_SIZE, p))
+                    .collect::<Vec<_>>();
+
+                self.test_all(&items, value, insts)
+            }
+            ast::Pattern::Constructor((_, ty), name, patterns) => {
+                let tag = self.load_tag(value, insts);
+                let is_tag = self.op(
+                    Op::Eq,
+                    vec![tag, Operand::I32(self.tag(ty, name) as i32)],
+                    &bool,
+                    insts,
+                );
+
+                if patterns.iter().all(Lowering::irrefutable) {
+                    return is_tag;
+                }
+
+                // The arguments may only be read once the tag is known to be right,
+                // Since other constructors can have fewer slots
+                let mut args_insts = vec![];
+                let args = patterns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (Lowering::arg_offset(i), p))
+                    .collect::<Vec<_>>();
+                let args_match = self.test_all(&args, value, &mut args_insts);
+                let dest = self.fresh(Ty::I32);
+
+                insts.push(Inst::If {
+                    dest,
+                    cond: is_tag,
+                    on_true: Block {
+                        insts: args_insts,
+                        result: args_match,
+                    },
+                    on_false: Block {
+                        insts: vec![],
+                        result: Operand::I32(0),
+                    },
+                });
+
+                Operand::Temp(dest)
+            }
+        }
+    }
+
+    // Whether every pattern matches the slot at its offset in `value`, stopping at the first that doesn't
+    fn test_all(
+        &mut self,
+        items: &[(u32, &ast::TypedPattern)],
+        value: Operand,
+        insts: &mut Vec<Inst>,
+    ) -> Operand {
+        let i = match items.iter().position(|(_, p)| !Lowering::irrefutable(p)) {
+            Some(i) => i,
+            None => return Operand::I32(1),
+        };
+        let (offset, pattern) = items[i];
+        let rest = &items[i + 1..];
+
+        let item = self.op(Op::Load(offset), vec![value], &pattern.extra().1, insts);
+        let matches = self.test(pattern, item, insts);
+
+        if rest.iter().all(|(_, p)| Lowering::irrefutable(p)) {
+            return matches;
+        }
+
+        let mut rest_insts = vec![];
+        let rest_match = self.test_all(rest, value, &mut rest_insts);
+        let dest = self.fresh(Ty::I32);
+
+        insts.push(Inst::If {
+            dest,
+            cond: matches,
+            on_true: Block {
+                insts: rest_insts,
+                result: rest_match,
+            },
+            on_false: Block {
+                insts: vec![],
+                result: Operand::I32(0),
+            },
+        });
+
+        Operand::Temp(dest)
+    }
+
+    // Makes every variable in `pattern` refer to the matching part of `value`
+    fn bind(&mut self, pattern: &ast::TypedPattern, value: Operand, insts: &mut Vec<Inst>) {
+        match pattern {
+            ast::Pattern::Var(_, name) => {
+                self.vars.insert(name.clone(), value);
+            }
+            ast::Pattern::Wildcard(_) => {}
+            ast::Pattern::Tuple(_, patterns) => {
+                for (i, p) in patterns.iter().enumerate() {
+                    // No need to read items that are ignored anyway
+                    if let ast::Pattern::Wildcard(_) = p {
+                        continue;
+                    }
+
+                    let item = self.op(
+                        Op::Load(i as u32 * SLOT_SIZE),
+                        vec![value],
+                        &p.extra().1,
+                        insts,
+                    );
+                    self.bind(p, item, insts);
+                }
+            }
+            ast::Pattern::Constructor(_, _, patterns) => {
+                for (i, p) in patterns.iter().enumerate() {
+                    // Literals bind nothing either
+                    if let ast::Pattern::Wildcard(_)
+                    | ast::Pattern::Bool(_, _)
+                    | ast::Pattern::Int(_, _) = p
+                    {
+                        continue;
+                    }
+
+                    let arg = self.op(
+                        Op::Load(Lowering::arg_offset(i)),
+                        vec![value],
+                        &p.extra().1,
+                        insts,
+                    );
+                    self.bind(p, arg, insts);
+                }
+            }
+            ast::Pattern::Bool(_, _) | ast::Pattern::Int(_, _) => {}
+        }
+    }
+
+    // Evaluates the body of the first arm whose pattern matches `value`,
+    // `tag` is the constructor that `value` is already known to have, so it is not tested again
+    fn arms(
+        &mut self,
+        arms: &[&ast::Arm<(ast::Span, ast::JustType)>],
+        value: Operand,
+        tag: Option<usize>,
+        ty: &ast::JustType,
+    ) -> Block {
+        let mut insts = vec![];
+
+        let arm = match arms.first() {
+            Some(arm) => arm,
+            // Exhaustiveness is checked beforehand, so no value gets here
+            None => {
+                insts.push(Inst::Trap);
+                let result = Operand::Temp(self.fresh(self.ty(ty)));
+
+                return Block { insts, result };
+            }
+        };
+
+        let cond = match (&arm.pattern, tag) {
+            (ast::Pattern::Constructor(_, _, patterns), Some(_)) => {
+                let args = patterns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (Lowering::arg_offset(i), p))
+                    .collect::<Vec<_>>();
+
+                self.test_all(&args, value, &mut insts)
+            }
+            (pattern, _) => self.test(pattern, value, &mut insts),
+        };
+
+        let outer = self.vars.clone();
+        let mut body_insts = vec![];
+        self.bind(&arm.pattern, value, &mut body_insts);
+        let result = self.expr(&arm.body, &mut body_insts);
+        self.vars = outer;
+
+        if cond == Operand::I32(1) {
+            insts.append(&mut body_insts);
+            return Block { insts, result };
+        }
+
+        let on_false = self.arms(&arms[1..], value, tag, ty);
+        let dest = self.fresh(self.ty(ty));
+
+        insts.push(Inst::If {
+            dest,
+            cond,
+            on_true: Block {
+                insts: body_insts,
+                result,
+            },
+            on_false,
+        });
+
+        Block {
+            insts,
+            result: Operand::Temp(dest),
+        }
+    }
+
+    // Appends the instructions needed to compute `e` onto `insts`,
+    // And returns where its value can be found afterwards
+    fn expr(&mut self, e: &ast::TypedExpr, insts: &mut Vec<Inst>) -> Operand {
+        match e {
+            ast::Expr::UnitLit(_) => Operand::I32(0),
+            ast::Expr::IntLit(_, n) => Operand::I64(*n),
+            ast::Expr::BoolLit(_, b) => Operand::I32(*b as i32),
+            ast::Expr::FloatLit(_, x) => Operand::F64(*x),
+            ast::Expr::StringLit(_, s) => self.string(s),
+            ast::Expr::TypeAnno { term, .. } => self.expr(term, insts),
+            ast::Expr::IfFlow {
+                cond,
+                on_true,
+                on_false,
+                extra,
+            } => {
+                let cond = self.expr(cond, insts);
+                let on_true = self.block(on_true);
+                let on_false = self.block(on_false);
+                let dest = self.fresh(self.ty(&extra.1));
+
+                insts.push(Inst::If {
+                    dest,
+                    cond,
+                    on_true,
+                    on_false,
+                });
+
+                Operand::Temp(dest)
+            }
+            ast::Expr::BinOp {
+                extra,
+                op,
+                left,
+                right,
+            } => {
+                let is_string = left.extra().1 == ast::Type::String(());
+                let left = self.expr(left, insts);
+                let right = self.expr(right, insts);
+
+                let op = match op {
+                    ast::BinOp::Concat => Op::Concat,
+                    ast::BinOp::Add => Op::Add,
+                    ast::BinOp::Sub => Op::Sub,
+                    ast::BinOp::Mul => Op::Mul,
+                    ast::BinOp::Div => Op::Div,
+                    ast::BinOp::Rem => Op::Rem,
+                    ast::BinOp::Eq if is_string => Op::StrEq,
+                    ast::BinOp::Ne if is_string => {
+                        let equal = self.op(Op::StrEq, vec![left, right], &extra.1, insts);
+                        return self.op(Op::Not, vec![equal], &extra.1, insts);
+                    }
+                    ast::BinOp::Eq => Op::Eq,
+                    ast::BinOp::Ne => Op::Ne,
+                    ast::BinOp::Lt => Op::Lt,
+                    ast::BinOp::Le => Op::Le,
+                    ast::BinOp::Gt => Op::Gt,
+                    ast::BinOp::Ge => Op::Ge,
+                };
+
+                self.op(op, vec![left, right], &extra.1, insts)
+            }
+            ast::Expr::Builtin {
+                extra,
+                builtin: builtin @ (ast::Builtin::Print | ast::Builtin::Debug),
+                arg,
+            } => {
+                let ty = self.concrete(&arg.extra().1);
+                let value = self.expr(arg, insts);
+
+                // Each call is its own entry, since `debug` needs to know where it is
+                self.printed.push(Printed {
+                    ty: ty.clone(),
+                    debug: (*builtin == ast::Builtin::Debug).then_some(extra.0),
+                });
+                insts.push(Inst::Call {
+                    dest: None,
+                    function: print_function(ty_of(&ty)),
+                    args: vec![value, Operand::I32(self.printed.len() as i32 - 1)],
+                });
+
+                Operand::I32(0)
+            }
+            ast::Expr::Builtin {
+                extra,
+                builtin,
+                arg,
+            } => {
+                let arg = self.expr(arg, insts);
+
+                let op = match builtin {
+                    // Both strings and arrays start with their length
+                    ast::Builtin::Len | ast::Builtin::Length => Op::Len,
+                    ast::Builtin::ToFloat => Op::ToFloat,
+                    ast::Builtin::ToInt => Op::ToInt,
+                    ast::Builtin::Print | ast::Builtin::Debug => {
+                        panic!("Internal compiler error")
+                    }
+                };
+
+                self.op(op, vec![arg], &extra.1, insts)
+            }
+            ast::Expr::Var(extra, name) if !self.vars.contains_key(name) => {
+                let ty = self.concrete(&extra.1);
+                self.function_value(name, &ty)
+            }
+            ast::Expr::Var(extra, name) => match self.vars[name] {
+                // The value is copied, since the variable could be assigned to before the value is used
+                Operand::Temp(t) if self.mutable.contains(&t) => {
+                    let dest = self.fresh(self.ty(&extra.1));
+                    insts.push(Inst::Assign {
+                        dest,
+                        value: Operand::Temp(t),
+                    });
+
+                    Operand::Temp(dest)
+                }
+                value => value,
+            },
+            ast::Expr::Tuple { extra, items } => {
+                let size = Operand::I32((items.len() as u32 * SLOT_SIZE) as i32);
+                let address = self.op(Op::Alloc, vec![size], &extra.1, insts);
+
+                for (i, item) in items.iter().enumerate() {
+                    let value = self.expr(item, insts);
+                    insts.push(Inst::Store {
+                        address,
+                        offset: i as u32 * SLOT_SIZE,
+                        value,
+                    });
+                }
+
+                address
+            }
+            ast::Expr::Project { extra, term, index } => {
+                let address = self.expr(term, insts);
+                self.op(
+                    Op::Load(*index as u32 * SLOT_SIZE),
+                    vec![address],
+                    &extra.1,
+                    insts,
+                )
+            }
+            ast::Expr::Let {
+                pattern,
+                value,
+                body,
+                ..
+            } => {
+                let value = self.expr(value, insts);
+
+                // Restore the outer scope afterwards, since the body may shadow variables
+                let outer = self.vars.clone();
+                self.bind(pattern, value, insts);
+                let result = self.expr(body, insts);
+                self.vars = outer;
+
+                result
+            }
+            ast::Expr::Record { extra, fields, .. } => {
+                let values = fields
+                    .iter()
+                    .map(|f| (f.name.as_str(), self.expr(&f.value, insts)))
+                    .collect::<Vec<_>>();
+
+                self.record(&extra.1, values, None, insts)
+            }
+            ast::Expr::Field { extra, term, field } => {
+                let (offset, _) = self.field(&term.extra().1, field);
+                let address = self.expr(term, insts);
+
+                self.op(Op::Load(offset), vec![address], &extra.1, insts)
+            }
+            ast::Expr::Update {
+                extra,
+                term,
+                fields,
+            } => {
+                let old = self.expr(term, insts);
+                let values = fields
+                    .iter()
+                    .map(|f| (f.name.as_str(), self.expr(&f.value, insts)))
+                    .collect::<Vec<_>>();
+
+                self.record(&extra.1, values, Some(old), insts)
+            }
+            ast::Expr::Construct { extra, name, args } => {
+                let size = Operand::I32(Lowering::arg_offset(args.len()) as i32);
+                let address = self.op(Op::Alloc, vec![size], &extra.1, insts);
+
+                insts.push(Inst::Store {
+                    address,
+                    offset: 0,
+                    value: Operand::I32(self.tag(&extra.1, name) as i32),
+                });
+
+                for (i, arg) in args.iter().enumerate() {
+                    let value = self.expr(arg, insts);
+                    insts.push(Inst::Store {
+                        address,
+                        offset: Lowering::arg_offset(i),
+                        value,
+                    });
+                }
+
+                address
+            }
+            ast::Expr::Match { extra, term, arms } => {
+                let value = self.expr(term, insts);
+                let arms = arms.iter().collect::<Vec<_>>();
+
+                let constructors = match &term.extra().1 {
+                    ast::Type::Named(_, name, _) => self
+                        .variants
+                        .get(name)
+                        .map(|(_, constructors)| constructors.clone()),
+                    _ => None,
+                };
+
+                match constructors {
+                    // Jumps straight to the arms that can match the constructor of the value
+                    Some(constructors) => {
+                        let index = self.load_tag(value, insts);
+                        let cases = (0..constructors.len())
+                            .map(|tag| {
+                                let applicable = arms
+                                    .iter()
+                                    .filter(|arm| match &arm.pattern {
+                                        ast::Pattern::Constructor(_, name, _) => {
+                                            name == &constructors[tag].0
+                                        }
+                                        _ => true,
+                                    })
+                                    .cloned()
+                                    .collect::<Vec<_>>();
+
+                                self.arms(&applicable, value, Some(tag), &extra.1)
+                            })
+                            .collect();
+                        let dest = self.fresh(self.ty(&extra.1));
+
+                        insts.push(Inst::Switch { dest, index, cases });
+
+                        Operand::Temp(dest)
+                    }
+                    None => {
+                        let mut block = self.arms(&arms, value, None, &extra.1);
+                        insts.append(&mut block.insts);
+
+                        block.result
+                    }
+                }
+            }
+            ast::Expr::Call { extra, name, args } if self.vars.contains_key(name) => {
+                let values = args.iter().map(|a| self.expr(a, insts)).collect();
+                let dest = match self.concrete(&extra.1) {
+                    ast::Type::Unit(_) => None,
+                    _ => Some(self.fresh(self.ty(&extra.1))),
+                };
+
+                insts.push(Inst::CallIndirect {
+                    dest,
+                    function: self.vars[name],
+                    args: values,
+                });
+
+                dest.map_or(Operand::I32(0), Operand::Temp)
+            }
+            ast::Expr::Call { extra, name, args } => {
+                let values = args.iter().map(|a| self.expr(a, insts)).collect();
+                let arg_tys = args
+                    .iter()
+                    .map(|a| self.concrete(&a.extra().1))
+                    .collect::<Vec<_>>();
+                let result = self.concrete(&extra.1);
+                let function = self.instance(name, &arg_tys, &result);
+                let dest = match result {
+                    ast::Type::Unit(_) => None,
+                    _ => Some(self.fresh(self.ty(&extra.1))),
+                };
+
+                insts.push(Inst::Call {
+                    dest,
+                    function,
+                    args: values,
+                });
+
+                dest.map_or(Operand::I32(0), Operand::Temp)
+            }
+            ast::Expr::Seq { first, second, .. } => {
+                self.expr(first, insts);
+                self.expr(second, insts)
+            }
+            ast::Expr::VarDecl {
+                name, value, body, ..
+            } => {
+                let value_ty = self.ty(&value.extra().1);
+                let value = self.expr(value, insts);
+                let dest = self.fresh(value_ty);
+                insts.push(Inst::Assign { dest, value });
+                self.mutable.push(dest);
+
+                let outer = self.vars.clone();
+                self.vars.insert(name.clone(), Operand::Temp(dest));
+                let result = self.expr(body, insts);
+                self.vars = outer;
+
+                result
+            }
+            ast::Expr::Assign { name, value, .. } => {
+                let value = self.expr(value, insts);
+                let dest = match self.vars[name] {
+                    Operand::Temp(dest) => dest,
+                    _ => panic!("Internal compiler error"),
+                };
+                insts.push(Inst::Assign { dest, value });
+
+                Operand::I32(0)
+            }
+            ast::Expr::While { cond, body, .. } => {
+                let cond = self.block(cond);
+                let mut body_insts = vec![];
+                self.expr(body, &mut body_insts);
+
+                insts.push(Inst::Loop {
+                    cond,
+                    body: body_insts,
+                });
+
+                Operand::I32(0)
+            }
+            ast::Expr::Break(_) => {
+                insts.push(Inst::Break);
+
+                Operand::I32(0)
+            }
+            ast::Expr::Array { extra, items } => {
+                let size = Operand::I32(((items.len() as u32 + 1) * SLOT_SIZE) as i32);
+                let address = self.op(Op::Alloc, vec![size], &extra.1, insts);
+
+                insts.push(Inst::Store {
+                    address,
+                    offset: 0,
+                    value: Operand::I32(items.len() as i32),
+                });
+
+                for (i, item) in items.iter().enumerate() {
+                    let value = self.expr(item, insts);
+                    insts.push(Inst::Store {
+                        address,
+                        offset: (i as u32 + 1) * SLOT_SIZE,
+                        value,
+                    });
+                }
+
+                address
+            }
+            ast::Expr::Repeat {
+                extra,
+                length,
+                value,
+            } => {
+                let length = self.expr(length, insts);
+                let value = self.expr(value, insts);
+                let address = self.op(Op::NewArray, vec![length], &extra.1, insts);
+
+                // Every item is filled in with a loop, `i` stays in bounds so it is not checked
+                let int = ast::Type::Int(());
+                let i = self.fresh(Ty::I64);
+                insts.push(Inst::Assign {
+                    dest: i,
+                    value: Operand::I64(0),
+                });
+
+                let mut cond_insts = vec![];
+                let in_bounds = self.op(
+                    Op::Lt,
+                    vec![Operand::Temp(i), length],
+                    &ast::Type::Bool(()),
+                    &mut cond_insts,
+                );
+
+                let mut body = vec![];
+                let item = self.op(
+                    Op::Element,
+                    vec![address, Operand::Temp(i)],
+                    &extra.1,
+                    &mut body,
+                );
+                body.push(Inst::Store {
+                    address: item,
+                    offset: 0,
+                    value,
+                });
+                let next = self.op(
+                    Op::Add,
+                    vec![Operand::Temp(i), Operand::I64(1)],
+                    &int,
+                    &mut body,
+                );
+                body.push(Inst::Assign {
+                    dest: i,
+                    value: next,
+                });
+
+                insts.push(Inst::Loop {
+                    cond: Block {
+                        insts: cond_insts,
+                        result: in_bounds,
+                    },
+                    body,
+                });
+
+                address
+            }
+            ast::Expr::Index { extra, term, index } => {
+                let span = index.extra().0;
+                let array = self.expr(term, insts);
+                let index = self.expr(index, insts);
+                let item = self.element(array, index, span, insts);
+
+                self.op(Op::Load(0), vec![item], &extra.1, insts)
+            }
+            ast::Expr::SetIndex {
+                term, index, value, ..
+            } => {
+                let span = index.extra().0;
+                let array = self.expr(term, insts);
+                let index = self.expr(index, insts);
+                let value = self.expr(value, insts);
+                let item = self.element(array, index, span, insts);
+
+                insts.push(Inst::Store {
+                    address: item,
+                    offset: 0,
+                    value,
+                });
+
+                Operand::I32(0)
+            }
+        }
+    }
+
+    // The name of the instance of the function `name` called with `arg_tys` that returns `result`
+    fn instance(
+        &mut self,
+        name: &str,
+        arg_tys: &[ast::JustType],
+        result: &ast::JustType,
+    ) -> String {
+        // `$` keeps the names of functions apart from `main` and the runtime
+        self.instances.instance(name, arg_tys, result, |type_args| {
+            if type_args.is_empty() {
+                format!("${}", name)
+            } else {
+                let type_args = type_args.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+                format!("${}<{}>", name, type_args.join(", "))
+            }
+        })
+    }
+
+    fn function(
+        &mut self,
+        name: String,
+        params: &[ast::Param<(ast::Span, ast::JustType)>],
+        body: &ast::TypedExpr,
+    ) -> Function {
+        self.temps = vec![];
+        self.vars = HashMap::new();
+        self.mutable = vec![];
+
+        for p in params {
+            let temp = self.fresh(self.ty(&p.extra.1));
+            self.vars.insert(p.name.clone(), Operand::Temp(temp));
+        }
+
+        let block = self.block(body);
+
+        Function {
+            name,
+            params: self.temps[..params.len()].to_vec(),
+            result: match self.concrete(&body.extra().1) {
+                ast::Type::Unit(_) => None,
+                ty => Some(ty_of(&ty)),
+            },
+            temps: std::mem::take(&mut self.temps),
+            body: block,
+        }
+    }
+
+    // Allocates a record of the type `ty` and fills in its fields,
+    // Fields without a value are copied over from `old`
+    fn record(
+        &mut self,
+        ty: &ast::JustType,
+        values: Vec<(&str, Operand)>,
+        old: Option<Operand>,
+        insts: &mut Vec<Inst>,
+    ) -> Operand {
+        let (type_params, fields) = match ty {
+            ast::Type::Named(_, name, _) => self.records[name].clone(),
+            _ => panic!("Internal compiler error"),
+        };
+
+        let size = Operand::I32((fields.len() as u32 * SLOT_SIZE) as i32);
+        let address = self.op(Op::Alloc, vec![size], ty, insts);
+
+        for (i, (name, field_ty)) in fields.iter().enumerate() {
+            let offset = i as u32 * SLOT_SIZE;
+            let value = match (values.iter().find(|(n, _)| n == name), old) {
+                (Some((_, value)), _) => *value,
+                (None, Some(old)) => {
+                    let field_ty = Lowering::field_ty(ty, &type_params, field_ty);
+                    self.op(Op::Load(offset), vec![old], &field_ty, insts)
+                }
+                (None, None) => panic!("Internal compiler error"),
+            };
+
+            insts.push(Inst::Store {
+                address,
+                offset,
+                value,
+            });
+        }
+
+        address
+    }
+
+    fn block(&mut self, e: &ast::TypedExpr) -> Block {
+        let mut insts = vec![];
+        let result = self.expr(e, &mut insts);
+
+        Block { insts, result }
+    }
+}
+
+pub fn lower(p: &ast::TypedProgram) -> Module {
+    lower_entries(p, vec![("main".into(), &p.body)])
+}
+
+// The function that runs the test at `index` in what `lower_tests` is given, and gives back whether it passed
+pub fn test_function(index: usize) -> String {
+    format!("test.{}", index)
+}
+
+// Lowers each of `tests` as a function of its own, instead of the body
+pub fn lower_tests(p: &ast::TypedProgram, tests: &[&ast::TypedDecl]) -> Module {
+    let entries = tests
+        .iter()
+        .enumerate()
+        .map(|(i, test)| match test {
+            ast::Decl::Test { body, .. } => (test_function(i), body),
+            _ => panic!("Internal compiler error"),
+        })
+        .collect();
+
+    lower_entries(p, entries)
+}
+
+// `entries` are the functions that the host calls, by their name and body
+fn lower_entries(p: &ast::TypedProgram, entries: Vec<(String, &ast::TypedExpr)>) -> Module {
+    let mut records = HashMap::new();
+    let mut variants = HashMap::new();
+
+    for d in &p.decls {
+        match d {
+            ast::Decl::Record {
+                name,
+                type_params,
+                fields,
+                ..
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|f| (f.name.clone(), f.ty.strip()))
+                    .collect();
+
+                records.insert(name.clone(), (type_params.clone(), fields));
+            }
+            ast::Decl::Variant {
+                name,
+                type_params,
+                constructors,
+                ..
+            } => {
+                let constructors = constructors
+                    .iter()
+                    .map(|c| (c.name.clone(), c.args.iter().map(|a| a.strip()).collect()))
+                    .collect();
+
+                variants.insert(name.clone(), (type_params.clone(), constructors));
+            }
+            ast::Decl::Function { .. } | ast::Decl::Test { .. } => {}
+        }
+    }
+
+    let mut lowering = Lowering {
+        temps: vec![],
+        strings: vec![],
+        table: vec![],
+        printed: vec![],
+        vars: HashMap::new(),
+        mutable: vec![],
+        records,
+        variants,
+        instances: Instances::new(&p.decls),
+    };
+    let mut lowered = entries
+        .into_iter()
+        .map(|(name, body)| lowering.function(name, &[], body))
+        .collect::<Vec<_>>();
+
+    while let Some((instance, function)) = lowering.instances.next() {
+        if let ast::Decl::Function { params, body, .. } = function {
+            lowered.push(lowering.function(instance, params, body));
+        }
+    }
+
+    Module {
+        functions: lowered,
+        strings: lowering.strings,
+        table: lowering.table,
+        printed: lowering.printed,
+    }
+}
+
+fn pretty_block(b: &Block, indent: usize, keyword: &str) -> String {
+    let indents = "    ".repeat(indent);
+
+    b.insts
+        .iter()
+        .map(|i| format!("{}{}\n", indents, pretty_inst(i, indent)))
+        .chain(std::iter::once(format!(
+            "{}{} {}",
+            indents, keyword, b.result
+        )))
+        .collect()
+}
+
+fn pretty_inst(i: &Inst, indent: usize) -> String {
+    let indents = "    ".repeat(indent);
+
+    match i {
+        Inst::Op { dest, op, args } => format!(
+            "{} = {} {}",
+            dest,
+            op,
+            args.iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Inst::If {
+            dest,
+            cond,
+            on_true,
+            on_false,
+        } => format!(
+            "{1} = if {2} then\n{3}\n{0}else\n{4}\n{0}end",
+            indents,
+            dest,
+            cond,
+            pretty_block(on_true, indent + 1, "yield"),
+            pretty_block(on_false, indent + 1, "yield")
+        ),
+        Inst::Store {
+            address,
+            offset,
+            value,
+        } => format!("store {}[{}], {}", address, offset, value),
+        Inst::Switch { dest, index, cases } => {
+            let cases = cases
+                .iter()
+                .enumerate()
+                .map(|(i, case)| {
+                    format!(
+                        "{}case {}\n{}\n",
+                        indents,
+                        i,
+                        pretty_block(case, indent + 1, "yield")
+                    )
+                })
+                .collect::<String>();
+
+            format!("{} = switch {}\n{}{}end", dest, index, cases, indents)
+        }
+        Inst::Call {
+            dest,
+            function,
+            args,
+        } => format!(
+            "{}call {}({})",
+            dest.map_or(String::new(), |d| format!("{} = ", d)),
+            function,
+            args.iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Inst::CallIndirect {
+            dest,
+            function,
+            args,
+        } => format!(
+            "{}call_indirect {}({})",
+            dest.map_or(String::new(), |d| format!("{} = ", d)),
+            function,
+            args.iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Inst::CheckIndex { array, index, span } => {
+            format!(
+                "check_index {}[{}] at {}..{}",
+                array, index, span.start, span.end
+            )
+        }
+        Inst::Assign { dest, value } => format!("{} := {}", dest, value),
+        Inst::Loop { cond, body } => {
+            let body = body
+                .iter()
+                .map(|i| format!("{}    {}\n", indents, pretty_inst(i, indent + 1)))
+                .collect::<String>();
+
+            format!(
+                "loop\n{}\n{}{}end",
+                pretty_block(cond, indent + 1, "exit unless"),
+                body,
+                indents
+            )
+        }
+        Inst::Break => "break".into(),
+        Inst::Trap => "trap".into(),
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let params = self
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| format!("{}: {}", Temp(i as u32), ty))
+            .collect::<Vec<_>>();
+        writeln!(
+            fmt,
+            "fn {}({}): {}",
+            self.name,
+            params.join(", "),
+            self.result.map_or("unit".into(), |ty| ty.to_string())
+        )?;
+
+        for (i, ty) in self.temps.iter().enumerate().skip(self.params.len()) {
+            writeln!(fmt, "    local {}: {}", Temp(i as u32), ty)?;
+        }
+
+        write!(fmt, "{}", pretty_block(&self.body, 1, "return"))
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (i, s) in self.strings.iter().enumerate() {
+            writeln!(fmt, "string s{} = \"{}\"", i, ast::escape(s))?;
+        }
+
+        for (i, f) in self.table.iter().enumerate() {
+            writeln!(fmt, "table f{} = {}", i, f)?;
+        }
+
+        if !self.strings.is_empty() || !self.table.is_empty() {
+            writeln!(fmt)?;
+        }
+
+        let functions = self
+            .functions
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>();
+
+        write!(fmt, "{}", functions.join("\n\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::FileId;
+    use crate::syntax;
+    use crate::type_check;
+
+    fn lower_source(source: &str) -> Module {
+        let a = syntax::ProgramParser::new()
+            .parse(FileId(0), source)
+            .unwrap();
+
+        lower(&type_check::check(&a).result.unwrap())
+    }
+
+    #[test]
+    fn literals_need_no_temps() {
+        let m = lower_source("(1: int)");
+
+        assert!(m.functions[0].temps.is_empty());
+        assert_eq!(m.functions[0].body.result, Operand::I64(1));
+    }
+
+    #[test]
+    fn bools_are_i32() {
+        let m = lower_source("if true then false else true end");
+
+        assert_eq!(m.functions[0].result, Some(Ty::I32));
+        assert_eq!(m.functions[0].temps, vec![Ty::I32]);
+    }
+
+    #[test]
+    fn unit_has_no_result() {
+        let m = lower_source("fn f(): unit = () end 1; f()");
+
+        assert_eq!(m.functions[0].result, None);
+        assert!(matches!(
+            m.functions[0].body.insts[..],
+            [Inst::Call { dest: None, .. }]
+        ));
+    }
+
+    #[test]
+    fn pretty_strings() {
+        let m = lower_source(r#"len("a" ++ "b\n" ++ "a")"#);
+
+        assert_eq!(
+            m.to_string(),
+            r#"string s0 = "a"
+string s1 = "b\n"
+
+fn main(): i64
+    local t0: i32
+    local t1: i32
+    local t2: i64
+    t0 = concat s0, s1
+    t1 = concat t0, s0
+    t2 = len t1
+    return t2"#
+        );
+    }
+
+    #[test]
+    fn pretty_if() {
+        let m = lower_source("if if true then false else true end then 2 else 3 end");
+
+        assert_eq!(
+            m.to_string(),
+            r#"fn main(): i64
+    local t0: i32
+    local t1: i64
+    t0 = if 1 then
+        yield 0
+    else
+        yield 1
+    end
+    t1 = if t0 then
+        yield 2
+    else
+        yield 3
+    end
+    return t1"#
+        );
+    }
+
+    #[test]
+    fn pretty_tuple() {
+        let m = lower_source("let (a, _) = (1, 2.5) in a");
+
+        assert_eq!(
+            m.to_string(),
+            r#"fn main(): i64
+    local t0: i32
+    local t1: i64
+    t0 = alloc 16
+    store t0[0], 1
+    store t0[8], 2.5
+    t1 = load[0] t0
+    return t1"#
+        );
+    }
+
+    #[test]
+    fn pretty_switch() {
+        let m = lower_source("type S = A(bool) | B match B with A(true) => 1 | _ => 2 end");
+
+        assert_eq!(
+            m.to_string(),
+            r#"fn main(): i64
+    local t0: i32
+    local t1: i32
+    local t2: i32
+    local t3: i64
+    local t4: i64
+    t0 = alloc 8
+    store t0[0], 1
+    t1 = load[0] t0
+    t4 = switch t1
+    case 0
+        t2 = load[8] t0
+        t3 = if t2 then
+            yield 1
+        else
+            yield 2
+        end
+        yield t3
+    case 1
+        yield 2
+    end
+    return t4"#
+        );
+    }
+
+    #[test]
+    fn pretty_call() {
+        let m = lower_source("fn id<a>(x: a): a = x end (id(1), id(true), id(2))");
+
+        assert_eq!(m.functions.len(), 3);
+        assert_eq!(
+            m.functions[1].to_string(),
+            r#"fn $id<int>(t0: i64): i64
+    return t0"#
+        );
+        assert_eq!(m.functions[2].params, vec![Ty::I32]);
+    }
+}
@@ -0,0 +1,1136 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast;
+use crate::codegen::{self, indent, mangle, Dest, Kind, Scope, Target};
+use crate::instances::Instances;
+use crate::ir;
+use crate::source;
+use crate::wasm;
+
+// Translates a typed program into a JavaScript module that exports `main`, which runs the program,
+// And `show`, which formats what `main` gives back the same way as running the program directly
+// `int` is a `BigInt` that wraps around like an `i64`, `float` is a number and `unit` is `undefined`,
+// Tuples and arrays are arrays, records are objects with a property for each field,
+// And variants are objects with the name of their constructor as `tag` and its arguments as `args`
+
+// Words that cannot be the name of a variable, and globals that the output refers to
+const RESERVED: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "console",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+// Functions that the output calls, each is only included when it is used
+// They start with a `$`, which names in mizzle never have
+const HELPERS: &[(&str, &str)] = &[
+    (
+        "$wrap",
+        r##"// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}"##,
+    ),
+    (
+        "$div",
+        r##"// Stops the program in the same cases as wasm does
+function $div(a, b) {
+    if (b === 0n) {
+        throw new RangeError("integer divide by zero");
+    }
+    if (a === -9223372036854775808n && b === -1n) {
+        throw new RangeError("integer overflow");
+    }
+    return a / b;
+}"##,
+    ),
+    (
+        "$rem",
+        r##"function $rem(a, b) {
+    if (b === 0n) {
+        throw new RangeError("integer divide by zero");
+    }
+    return a % b;
+}"##,
+    ),
+    (
+        "$len",
+        r##"// Strings are counted in bytes of UTF-8, like `len` does
+function $len(s) {
+    return BigInt(new TextEncoder().encode(s).length);
+}"##,
+    ),
+    (
+        "$toInt",
+        r##"function $toInt(x) {
+    if (Number.isNaN(x)) {
+        throw new RangeError("invalid conversion to integer");
+    }
+    if (x < -(2 ** 63) || x >= 2 ** 63) {
+        throw new RangeError("integer overflow");
+    }
+    return BigInt(Math.trunc(x));
+}"##,
+    ),
+    (
+        "$array",
+        r##"// `array(length, value)`, which is empty for a negative length
+function $array(length, value) {
+    return length > 0n ? new Array(Number(length)).fill(value) : [];
+}"##,
+    ),
+    (
+        "$check",
+        r##"// Stops the program when `index` is outside of `items`, `at` is where the index is in the source
+function $check(items, index, at) {
+    if (index < 0n || index >= BigInt(items.length)) {
+        throw new RangeError(
+            at + ": index `" + index + "` is out of bounds of an array with length `" + items.length + "`"
+        );
+    }
+}"##,
+    ),
+    (
+        "$get",
+        r##"function $get(items, index, at) {
+    $check(items, index, at);
+    return items[Number(index)];
+}"##,
+    ),
+    (
+        "$set",
+        r##"function $set(items, index, value, at) {
+    $check(items, index, at);
+    items[Number(index)] = value;
+}"##,
+    ),
+    (
+        "$quote",
+        r##"// Strings inside of other values are shown like literals
+function $quote(s) {
+    const escapes = { "\\": "\\\\", '"': '\\"', "\n": "\\n", "\t": "\\t", "\r": "\\r", "\0": "\\0" };
+    return '"' + s.replace(/[\\"\n\t\r\0]/g, (c) => escapes[c]) + '"';
+}"##,
+    ),
+    (
+        "$float",
+        r##"// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}"##,
+    ),
+];
+
+// A JavaScript expression
+#[derive(Clone, Debug)]
+struct Js {
+    code: String,
+    kind: Kind,
+}
+
+impl codegen::Value for Js {
+    fn code(&self) -> &str {
+        &self.code
+    }
+
+    fn kind(&self) -> Kind {
+        self.kind
+    }
+}
+
+impl Js {
+    fn new(code: impl Into<String>, kind: Kind) -> Js {
+        Js {
+            code: code.into(),
+            kind,
+        }
+    }
+
+    fn undefined() -> Js {
+        Js::new("undefined", Kind::Literal)
+    }
+
+    // The expression as the operand of `!`, `.` or `[]`
+    fn operand(&self) -> String {
+        match self.kind {
+            Kind::Operator(_) | Kind::Conditional => format!("({})", self.code),
+            _ => self.code.clone(),
+        }
+    }
+
+    // The expression on the left of an operator that binds as tightly as `precedence`,
+    // Operators are left associative, so only looser ones need parentheses
+    fn left(&self, precedence: u8) -> String {
+        match self.kind {
+            Kind::Operator(p) if p >= precedence => self.code.clone(),
+            _ => self.operand(),
+        }
+    }
+
+    fn right(&self, precedence: u8) -> String {
+        match self.kind {
+            Kind::Operator(p) if p > precedence => self.code.clone(),
+            _ => self.operand(),
+        }
+    }
+
+    // The expression as a part of `cond ? a : b`
+    fn branch(&self) -> String {
+        match self.kind {
+            Kind::Conditional => format!("({})", self.code),
+            _ => self.code.clone(),
+        }
+    }
+}
+
+// Pieces of a string built with `+`
+enum Part {
+    Text(String),
+    Code(String),
+}
+
+// Joins the parts with `+`, text that is next to each other is merged into one literal
+fn concat(parts: Vec<Part>) -> String {
+    let mut merged: Vec<Part> = vec![];
+
+    for part in parts {
+        match (merged.last_mut(), part) {
+            (Some(Part::Text(last)), Part::Text(text)) => last.push_str(&text),
+            (_, part) => merged.push(part),
+        }
+    }
+
+    merged
+        .iter()
+        .map(|part| match part {
+            Part::Text(text) => string(text),
+            Part::Code(code) => code.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{2028}' || c == '\u{2029}' => {
+                quoted.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+fn float(x: f64) -> String {
+    if x.is_nan() {
+        "NaN".into()
+    } else if x.is_infinite() {
+        if x > 0.0 { "Infinity" } else { "-Infinity" }.into()
+    } else {
+        format!("{:?}", x)
+    }
+}
+
+// `math::square` becomes `math$square`, and names that JavaScript reserves get a `$` after them
+fn ident(name: &str) -> String {
+    let name = name.replace("::", "$");
+
+    if RESERVED.contains(&name.as_str()) {
+        format!("{}$", name)
+    } else {
+        name
+    }
+}
+
+// Functions that are called `main` or `show` are renamed, since the module exports those names
+fn function_name(name: &str) -> String {
+    match ident(name) {
+        name if name == "main" || name == "show" => format!("{}$", name),
+        name => name,
+    }
+}
+
+struct Generator<'a> {
+    sources: &'a source::SourceMap,
+    decls: &'a [ast::TypedDecl],
+    helpers: HashSet<&'static str>,
+    // The name of the function that shows each type, by the type, and the definitions of those functions
+    shows: HashMap<String, String>,
+    show_functions: Vec<String>,
+    instances: Instances<'a>,
+    scope: Scope,
+}
+
+impl<'a> Generator<'a> {
+    fn concrete(&self, ty: &ast::JustType) -> ast::JustType {
+        self.instances.concrete(ty)
+    }
+
+    // Declares the variable `name` in the function being generated
+    fn local(&mut self, name: &str) -> String {
+        self.scope.local(name, ident(name))
+    }
+
+    // Where the index at `span` is in the source, for the error when it is out of bounds
+    fn location(&self, span: ast::Span) -> String {
+        string(&codegen::location(self.sources, span))
+    }
+
+    // The instance of the function `name` called with `arg_tys` that returns `result`
+    fn instance(
+        &mut self,
+        name: &str,
+        arg_tys: &[ast::JustType],
+        result: &ast::JustType,
+    ) -> String {
+        let scope = &mut self.scope;
+        self.instances.instance(name, arg_tys, result, |type_args| {
+            // Functions are declared before anything is generated, and `$` keeps them apart from each other
+            if type_args.is_empty() {
+                return function_name(name);
+            }
+
+            let words = type_args.iter().map(mangle);
+            let base = std::iter::once(function_name(name)).chain(words);
+
+            scope.global(base.collect::<Vec<_>>().join("$"))
+        })
+    }
+
+    fn helper(&mut self, name: &'static str) -> &'static str {
+        if name == "$get" || name == "$set" {
+            self.helpers.insert("$check");
+        }
+        self.helpers.insert(name);
+
+        name
+    }
+
+    // An expression that gives `value`, which is a `ty`, as a string that is written like a literal
+    fn show(&mut self, ty: &ast::JustType, value: &str) -> String {
+        match ty {
+            ast::Type::Unit(_) => string("()"),
+            ast::Type::Int(_) | ast::Type::Bool(_) => format!("String({})", value),
+            ast::Type::Float(_) => format!("{}({})", self.helper("$float"), value),
+            ast::Type::String(_) => format!("{}({})", self.helper("$quote"), value),
+            // Functions are shown by their type, like when running the program directly
+            ast::Type::Function(_, _, _) => string(&format!("<{}>", ty)),
+            ast::Type::Tuple(_, _) | ast::Type::Array(_, _) | ast::Type::Named(_, _, _) => {
+                format!("{}({})", self.show_function(ty), value)
+            }
+            ast::Type::Var(_, _) | ast::Type::Unknown(_, _) => panic!("Internal compiler error"),
+        }
+    }
+
+    // The function that shows values of `ty`, which is generated the first time it is needed
+    fn show_function(&mut self, ty: &ast::JustType) -> String {
+        if let Some(name) = self.shows.get(&ty.to_string()) {
+            return name.clone();
+        }
+
+        let base = format!("$show_{}", mangle(ty));
+        let mut name = base.clone();
+        let mut n = 1;
+        while self.shows.values().any(|existing| existing == &name) {
+            name = format!("{}${}", base, n);
+            n += 1;
+        }
+        // Added before the body is generated, since types like lists refer to themselves
+        self.shows.insert(ty.to_string(), name.clone());
+
+        let body = match ty {
+            ast::Type::Tuple(_, items) => {
+                let mut parts = vec![Part::Text("(".into())];
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        parts.push(Part::Text(", ".into()));
+                    }
+                    parts.push(Part::Code(self.show(item, &format!("value[{}]", i))));
+                }
+                parts.push(Part::Text(")".into()));
+
+                vec![format!("return {};", concat(parts))]
+            }
+            ast::Type::Array(_, item) => vec![format!(
+                "return \"[\" + value.map((item) => {}).join(\", \") + \"]\";",
+                self.show(item, "item")
+            )],
+            ast::Type::Named(_, type_name, type_args) => {
+                let type_params = ast::type_params(self.decls, type_name);
+
+                match ast::record_fields(self.decls, type_name) {
+                    Some(fields) => {
+                        let mut parts = vec![Part::Text(format!("{} {{ ", type_name))];
+                        for (i, f) in fields.iter().enumerate() {
+                            if i > 0 {
+                                parts.push(Part::Text(", ".into()));
+                            }
+                            let field_ty = f.ty.strip().substitute(type_params, type_args);
+                            parts.push(Part::Text(format!("{} = ", f.name)));
+                            parts.push(Part::Code(
+                                self.show(&field_ty, &format!("value.{}", f.name)),
+                            ));
+                        }
+                        parts.push(Part::Text(" }".into()));
+
+                        vec![format!("return {};", concat(parts))]
+                    }
+                    None => {
+                        let constructors =
+                            ast::variant_constructors(self.decls, type_name).unwrap();
+                        let mut lines = vec!["switch (value.tag) {".to_string()];
+
+                        for c in constructors {
+                            let mut parts = vec![Part::Text(c.name.clone())];
+                            for (i, arg) in c.args.iter().enumerate() {
+                                parts.push(Part::Text(if i == 0 { "(" } else { ", " }.into()));
+                                let arg_ty = arg.strip().substitute(type_params, type_args);
+                                parts.push(Part::Code(
+                                    self.show(&arg_ty, &format!("value.args[{}]", i)),
+                                ));
+                            }
+                            if !c.args.is_empty() {
+                                parts.push(Part::Text(")".into()));
+                            }
+
+                            lines.push(format!("    case {}:", string(&c.name)));
+                            lines.push(format!("        return {};", concat(parts)));
+                        }
+                        lines.push("}".into());
+
+                        lines
+                    }
+                }
+            }
+            _ => panic!("Internal compiler error"),
+        };
+
+        self.show_functions.push(format!(
+            "function {}(value) {{\n{}\n}}",
+            name,
+            indent(body).join("\n")
+        ));
+
+        name
+    }
+
+    // An expression that is true when `value` matches `pattern`, or `None` when every value does
+    fn test(pattern: &ast::TypedPattern, value: &str) -> Option<String> {
+        match pattern {
+            ast::Pattern::Var(_, _) | ast::Pattern::Wildcard(_) => None,
+            ast::Pattern::Bool(_, true) => Some(value.into()),
+            ast::Pattern::Bool(_, false) => Some(format!("!{}", value)),
+            ast::Pattern::Int(_, n) => Some(format!("{} === {}n", value, n)),
+            ast::Pattern::Tuple(_, items) => {
+                let tests = items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, p)| Generator::test(p, &format!("{}[{}]", value, i)))
+                    .collect::<Vec<_>>();
+
+                (!tests.is_empty()).then(|| tests.join(" && "))
+            }
+            ast::Pattern::Constructor(_, name, args) => {
+                let tag = format!("{}.tag === {}", value, string(name));
+                let tests =
+                    std::iter::once(tag)
+                        .chain(args.iter().enumerate().filter_map(|(i, p)| {
+                            Generator::test(p, &format!("{}.args[{}]", value, i))
+                        }))
+                        .collect::<Vec<_>>();
+
+                Some(tests.join(" && "))
+            }
+        }
+    }
+
+    // What `pattern` looks like on the left of a `const`, or `None` when it binds no variables
+    fn destructure(&mut self, pattern: &ast::TypedPattern) -> Option<String> {
+        let items = |generator: &mut Generator, patterns: &[ast::TypedPattern]| {
+            let items = patterns
+                .iter()
+                .map(|p| generator.destructure(p))
+                .collect::<Vec<_>>();
+
+            items.iter().any(Option::is_some).then(|| {
+                let items = items
+                    .into_iter()
+                    .map(Option::unwrap_or_default)
+                    .collect::<Vec<_>>();
+
+                format!("[{}]", items.join(", "))
+            })
+        };
+
+        match pattern {
+            ast::Pattern::Var(_, name) => Some(self.local(name)),
+            ast::Pattern::Wildcard(_) | ast::Pattern::Bool(_, _) | ast::Pattern::Int(_, _) => None,
+            ast::Pattern::Tuple(_, patterns) => items(self, patterns),
+            ast::Pattern::Constructor(_, _, args) => {
+                items(self, args).map(|args| format!("{{ args: {} }}", args))
+            }
+        }
+    }
+
+    // The parameters and statements of the body of a function
+    fn function(
+        &mut self,
+        params: &[ast::Param<(ast::Span, ast::JustType)>],
+        body: &ast::TypedExpr,
+    ) -> (Vec<String>, Vec<String>) {
+        self.scope.function();
+
+        let params = params.iter().map(|p| self.local(&p.name)).collect();
+
+        let mut out = vec![];
+        let dest = match self.concrete(&body.extra().1) {
+            ast::Type::Unit(_) => Dest::Discard,
+            _ => Dest::Return,
+        };
+        self.complete(body, &mut out, dest);
+
+        (params, out)
+    }
+}
+
+impl<'a> Target for Generator<'a> {
+    type Value = Js;
+
+    fn scope(&mut self) -> &mut Scope {
+        &mut self.scope
+    }
+
+    fn ident(name: &str) -> String {
+        ident(name)
+    }
+
+    fn inline(e: &ast::TypedExpr) -> bool {
+        codegen::inline(e, true)
+    }
+
+    fn declare(&mut self, name: &str, value: &Js, mutable: bool) -> String {
+        let keyword = if mutable { "let" } else { "const" };
+        format!("{} {} = {};", keyword, name, value.code)
+    }
+
+    fn named(name: String, _value: Js) -> Js {
+        Js::new(name, Kind::Name)
+    }
+
+    fn discard(&mut self, value: Js, out: &mut Vec<String>) {
+        match value.kind {
+            Kind::Literal | Kind::Name => {}
+            // Otherwise an object would be read as a block
+            _ if value.code.starts_with('{') => out.push(format!("({});", value.code)),
+            _ => out.push(format!("{};", value.code)),
+        }
+    }
+
+    fn bind(&mut self, pattern: &ast::TypedPattern, value: Js, out: &mut Vec<String>) {
+        match self.destructure(pattern) {
+            Some(target) => out.push(format!("const {} = {};", target, value.code)),
+            None => self.discard(value, out),
+        }
+    }
+
+    fn test_arm(
+        &mut self,
+        pattern: &ast::TypedPattern,
+        value: &str,
+        _last: bool,
+    ) -> Option<String> {
+        Generator::test(pattern, value)
+    }
+
+    // Appends the statements needed before `e` onto `out`, and returns the expression that gives its value
+    fn expr(&mut self, e: &ast::TypedExpr, out: &mut Vec<String>) -> Js {
+        match e {
+            ast::Expr::UnitLit(_) => Js::undefined(),
+            ast::Expr::IntLit(_, n) => Js::new(format!("{}n", n), Kind::Literal),
+            ast::Expr::BoolLit(_, b) => Js::new(b.to_string(), Kind::Literal),
+            ast::Expr::FloatLit(_, x) => Js::new(float(*x), Kind::Literal),
+            ast::Expr::StringLit(_, s) => Js::new(string(s), Kind::Literal),
+            ast::Expr::TypeAnno { term, .. } => self.expr(term, out),
+            ast::Expr::IfFlow {
+                cond,
+                on_true,
+                on_false,
+                ..
+            } if Self::inline(on_true) && Self::inline(on_false) => {
+                let cond = self.expr(cond, out);
+                let on_true = self.expr(on_true, out);
+                let on_false = self.expr(on_false, out);
+
+                Js::new(
+                    format!(
+                        "{} ? {} : {}",
+                        cond.branch(),
+                        on_true.branch(),
+                        on_false.branch()
+                    ),
+                    Kind::Conditional,
+                )
+            }
+            ast::Expr::IfFlow { extra, .. } | ast::Expr::Match { extra, .. } => {
+                if self.concrete(&extra.1) == ast::Type::Unit(()) {
+                    self.complete(e, out, Dest::Discard);
+                    return Js::undefined();
+                }
+
+                let temp = self.scope.temp();
+                out.push(format!("let {};", temp));
+                self.complete(e, out, Dest::Assign(temp.clone()));
+
+                Js::new(temp, Kind::Name)
+            }
+            ast::Expr::BinOp {
+                op, left, right, ..
+            } => {
+                let is_int = self.concrete(&left.extra().1) == ast::Type::Int(());
+                let values = self.operands(&[left, right], out);
+                let (left, right) = (&values[0], &values[1]);
+
+                let (helper, operator) = match op {
+                    ast::BinOp::Add | ast::BinOp::Sub | ast::BinOp::Mul if is_int => {
+                        (Some("$wrap"), op.to_string())
+                    }
+                    ast::BinOp::Div if is_int => (Some("$div"), "".into()),
+                    ast::BinOp::Rem => (Some("$rem"), "".into()),
+                    ast::BinOp::Concat => (None, "+".into()),
+                    ast::BinOp::Eq => (None, "===".into()),
+                    ast::BinOp::Ne => (None, "!==".into()),
+                    op => (None, op.to_string()),
+                };
+                // The same as JavaScript gives its operators
+                let precedence = match op {
+                    ast::BinOp::Mul | ast::BinOp::Div | ast::BinOp::Rem => 12,
+                    ast::BinOp::Concat | ast::BinOp::Add | ast::BinOp::Sub => 11,
+                    ast::BinOp::Lt | ast::BinOp::Le | ast::BinOp::Gt | ast::BinOp::Ge => 9,
+                    ast::BinOp::Eq | ast::BinOp::Ne => 8,
+                };
+
+                match helper {
+                    Some("$wrap") => Js::new(
+                        format!(
+                            "{}({} {} {})",
+                            self.helper("$wrap"),
+                            left.left(precedence),
+                            operator,
+                            right.right(precedence)
+                        ),
+                        Kind::Atom,
+                    ),
+                    Some(helper) => Js::new(
+                        format!("{}({}, {})", self.helper(helper), left.code, right.code),
+                        Kind::Atom,
+                    ),
+                    None => Js::new(
+                        format!(
+                            "{} {} {}",
+                            left.left(precedence),
+                            operator,
+                            right.right(precedence)
+                        ),
+                        Kind::Operator(precedence),
+                    ),
+                }
+            }
+            ast::Expr::Builtin {
+                extra,
+                builtin: builtin @ (ast::Builtin::Print | ast::Builtin::Debug),
+                arg,
+            } => {
+                let ty = self.concrete(&arg.extra().1);
+                let value = self.expr(arg, out);
+
+                let shown = match (&ty, builtin) {
+                    (ast::Type::String(_), ast::Builtin::Print) => value.code,
+                    (ast::Type::Unit(_), _) => {
+                        self.discard(value, out);
+                        string("()")
+                    }
+                    _ => self.show(&ty, &value.code),
+                };
+                let prefix = wasm::print_prefix(
+                    &ir::Printed {
+                        ty: ty.clone(),
+                        debug: (*builtin == ast::Builtin::Debug).then_some(extra.0),
+                    },
+                    self.sources,
+                );
+
+                if prefix.is_empty() {
+                    out.push(format!("console.log({});", shown));
+                } else {
+                    out.push(format!("console.log({} + {});", string(&prefix), shown));
+                }
+
+                Js::undefined()
+            }
+            ast::Expr::Builtin { builtin, arg, .. } => {
+                let value = self.expr(arg, out);
+
+                let code = match builtin {
+                    ast::Builtin::Len => format!("{}({})", self.helper("$len"), value.code),
+                    ast::Builtin::Length => format!("BigInt({}.length)", value.operand()),
+                    ast::Builtin::ToFloat => format!("Number({})", value.code),
+                    ast::Builtin::ToInt => format!("{}({})", self.helper("$toInt"), value.code),
+                    ast::Builtin::Print | ast::Builtin::Debug => panic!("Internal compiler error"),
+                };
+
+                Js::new(code, Kind::Atom)
+            }
+            ast::Expr::Var(extra, name) if !self.scope.vars.contains_key(name) => {
+                match self.concrete(&extra.1) {
+                    ast::Type::Function(_, params, result) => {
+                        Js::new(self.instance(name, &params, &result), Kind::Name)
+                    }
+                    _ => panic!("Internal compiler error"),
+                }
+            }
+            ast::Expr::Var(_, name) => Js::new(self.scope.vars[name].clone(), Kind::Name),
+            ast::Expr::Tuple { items, .. } | ast::Expr::Array { items, .. } => {
+                let items = self.operands(&items.iter().collect::<Vec<_>>(), out);
+                let items = items.into_iter().map(|i| i.code).collect::<Vec<_>>();
+
+                Js::new(format!("[{}]", items.join(", ")), Kind::Atom)
+            }
+            ast::Expr::Project { term, index, .. } => {
+                let term = self.expr(term, out);
+
+                Js::new(format!("{}[{}]", term.operand(), index), Kind::Atom)
+            }
+            ast::Expr::Let { extra, .. }
+            | ast::Expr::Seq { extra, .. }
+            | ast::Expr::VarDecl { extra, .. } => {
+                if self.concrete(&extra.1) == ast::Type::Unit(()) {
+                    self.complete(e, out, Dest::Discard);
+                    return Js::undefined();
+                }
+
+                // The value of the body is what is left after the statements that bind variables
+                let mut value = None;
+                self.complete_value(e, out, &mut value);
+
+                value.unwrap()
+            }
+            ast::Expr::Record { fields, .. } => {
+                let values =
+                    self.operands(&fields.iter().map(|f| &f.value).collect::<Vec<_>>(), out);
+                let fields = fields
+                    .iter()
+                    .zip(values)
+                    .map(|(f, v)| format!("{}: {}", f.name, v.code))
+                    .collect::<Vec<_>>();
+
+                Js::new(format!("{{ {} }}", fields.join(", ")), Kind::Atom)
+            }
+            ast::Expr::Field { term, field, .. } => {
+                let term = self.expr(term, out);
+
+                Js::new(format!("{}.{}", term.operand(), field), Kind::Atom)
+            }
+            ast::Expr::Update { term, fields, .. } => {
+                let es = std::iter::once(term.as_ref())
+                    .chain(fields.iter().map(|f| &f.value))
+                    .collect::<Vec<_>>();
+                let mut values = self.operands(&es, out).into_iter();
+                let old = values.next().unwrap();
+                let fields = fields
+                    .iter()
+                    .zip(values)
+                    .map(|(f, v)| format!("{}: {}", f.name, v.code))
+                    .collect::<Vec<_>>();
+
+                Js::new(
+                    format!("{{ ...{}, {} }}", old.operand(), fields.join(", ")),
+                    Kind::Atom,
+                )
+            }
+            ast::Expr::Construct { name, args, .. } => {
+                let args = self.operands(&args.iter().collect::<Vec<_>>(), out);
+                let args = args.into_iter().map(|a| a.code).collect::<Vec<_>>();
+
+                Js::new(
+                    format!("{{ tag: {}, args: [{}] }}", string(name), args.join(", ")),
+                    Kind::Atom,
+                )
+            }
+            ast::Expr::Call { name, args, .. } if self.scope.vars.contains_key(name) => {
+                let args = self.operands(&args.iter().collect::<Vec<_>>(), out);
+                let args = args.into_iter().map(|a| a.code).collect::<Vec<_>>();
+
+                Js::new(
+                    format!("{}({})", self.scope.vars[name], args.join(", ")),
+                    Kind::Atom,
+                )
+            }
+            ast::Expr::Call { extra, name, args } => {
+                let arg_tys = args
+                    .iter()
+                    .map(|a| self.concrete(&a.extra().1))
+                    .collect::<Vec<_>>();
+                let result = self.concrete(&extra.1);
+                let function = self.instance(name, &arg_tys, &result);
+                let args = self.operands(&args.iter().collect::<Vec<_>>(), out);
+                let args = args.into_iter().map(|a| a.code).collect::<Vec<_>>();
+
+                Js::new(format!("{}({})", function, args.join(", ")), Kind::Atom)
+            }
+            ast::Expr::Assign { name, value, .. } => {
+                let value = self.expr(value, out);
+                out.push(format!("{} = {};", self.scope.vars[name], value.code));
+
+                Js::undefined()
+            }
+            ast::Expr::While { cond, body, .. } => {
+                let locals = self.scope.locals.clone();
+                let mut cond_out = vec![];
+                let cond = self.expr(cond, &mut cond_out);
+                let mut body_out = vec![];
+                self.complete(body, &mut body_out, Dest::Discard);
+                self.scope.locals = locals;
+
+                if cond_out.is_empty() {
+                    out.push(format!("while ({}) {{", cond.code));
+                } else {
+                    // The condition needs statements, which are run before every check
+                    out.push("while (true) {".into());
+                    cond_out.push(format!("if (!{}) {{", cond.operand()));
+                    cond_out.push("    break;".into());
+                    cond_out.push("}".into());
+                    out.append(&mut indent(cond_out));
+                }
+                out.append(&mut indent(body_out));
+                out.push("}".into());
+
+                Js::undefined()
+            }
+            ast::Expr::Break(_) => {
+                out.push("break;".into());
+
+                Js::undefined()
+            }
+            ast::Expr::Repeat { length, value, .. } => {
+                let values = self.operands(&[length, value], out);
+
+                Js::new(
+                    format!(
+                        "{}({}, {})",
+                        self.helper("$array"),
+                        values[0].code,
+                        values[1].code
+                    ),
+                    Kind::Atom,
+                )
+            }
+            ast::Expr::Index { term, index, .. } => {
+                let at = self.location(index.extra().0);
+                let values = self.operands(&[term, index], out);
+
+                Js::new(
+                    format!(
+                        "{}({}, {}, {})",
+                        self.helper("$get"),
+                        values[0].code,
+                        values[1].code,
+                        at
+                    ),
+                    Kind::Atom,
+                )
+            }
+            ast::Expr::SetIndex {
+                term, index, value, ..
+            } => {
+                let at = self.location(index.extra().0);
+                let values = self.operands(&[term, index, value], out);
+                out.push(format!(
+                    "{}({}, {}, {}, {});",
+                    self.helper("$set"),
+                    values[0].code,
+                    values[1].code,
+                    values[2].code,
+                    at
+                ));
+
+                Js::undefined()
+            }
+        }
+    }
+}
+
+fn pretty_function(header: &str, params: &[String], body: Vec<String>) -> String {
+    if body.is_empty() {
+        return format!("{}({}) {{}}", header, params.join(", "));
+    }
+
+    format!(
+        "{}({}) {{\n{}\n}}",
+        header,
+        params.join(", "),
+        indent(body).join("\n")
+    )
+}
+
+pub fn ast_to_js(p: &ast::TypedProgram, sources: &source::SourceMap) -> String {
+    let mut generator = Generator {
+        sources,
+        decls: &p.decls,
+        helpers: HashSet::new(),
+        shows: HashMap::new(),
+        show_functions: vec![],
+        instances: Instances::new(&p.decls),
+        scope: Scope::new("$", "$"),
+    };
+    // Every function is declared at the top of the module, and `main` and `show` are exported
+    generator.scope.globals = p
+        .decls
+        .iter()
+        .filter(|d| matches!(d, ast::Decl::Function { .. }))
+        .map(|d| function_name(d.name()))
+        .chain(["main".to_string(), "show".to_string()])
+        .collect();
+
+    let (_, main_body) = generator.function(&[], &p.body);
+    let main = pretty_function("export function main", &[], main_body);
+
+    let mut functions = vec![];
+    while let Some((instance, function)) = generator.instances.next() {
+        if let ast::Decl::Function { params, body, .. } = function {
+            let (params, body) = generator.function(params, body);
+            functions.push(pretty_function(
+                &format!("function {}", instance),
+                &params,
+                body,
+            ));
+        }
+    }
+
+    // The result is written as it is when it is a string, instead of like a literal
+    generator.instances.leave();
+    let shown = match &p.body.extra().1 {
+        ast::Type::String(_) => "value".into(),
+        ty => generator.show(ty, "value"),
+    };
+    let show = format!("export function show(value) {{\n    return {};\n}}", shown);
+
+    let helpers = HELPERS
+        .iter()
+        .filter(|(name, _)| generator.helpers.contains(name))
+        .map(|(_, code)| code.to_string());
+
+    let mut items = vec!["// Generated by mizzle".to_string()];
+    items.extend(helpers);
+    items.extend(functions);
+    items.push(main);
+    items.extend(generator.show_functions);
+    items.push(show);
+
+    items.join("\n\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend_test::{self, compile, Backend};
+    use crate::source::SourceMap;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    struct Js;
+
+    impl Backend for Js {
+        const NAME: &'static str = "js";
+        const EXTENSION: &'static str = "mjs";
+        const COMMAND: &'static str = "node";
+
+        fn generate(typed: &ast::TypedProgram, sources: &SourceMap) -> String {
+            ast_to_js(typed, sources)
+        }
+
+        fn run(typed: &ast::TypedProgram, sources: &SourceMap, dir: &Path) -> (String, bool) {
+            // Nothing is written for programs that are `unit`, like when running them directly
+            let show = if typed.body.extra().1 == ast::Type::Unit(()) {
+                ""
+            } else {
+                "console.log(show(value));"
+            };
+            fs::write(dir.join("program.mjs"), ast_to_js(typed, sources)).unwrap();
+            fs::write(
+                dir.join("run.mjs"),
+                format!(
+                    "import {{ main, show }} from \"./program.mjs\";\nconst value = main();\n{}\n",
+                    show
+                ),
+            )
+            .unwrap();
+
+            let output = Command::new("node")
+                .arg(dir.join("run.mjs"))
+                .output()
+                .unwrap();
+            (
+                String::from_utf8(output.stdout).unwrap(),
+                output.status.success(),
+            )
+        }
+    }
+
+    // Checked even without a JavaScript engine, and shows what the output looks like
+    #[test]
+    fn readable_output() {
+        let (typed, sources) = compile(
+            r#"
+            type Shape = Circle(int) | Rect(int, int)
+            fn area(s: Shape): int = match s with Circle(r) => r * r * 3 | Rect(w, h) => w * h end end
+            fn max(a: int, b: int): int = if a > b then a else b end end
+            var total = 0 in
+            total := max(area(Rect(2, 3)), 4);
+            debug(total);
+            if total > 5 then print("big") else () end
+            "#,
+        );
+
+        assert_eq!(
+            ast_to_js(&typed, &sources),
+            r#"// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+function max(a, b) {
+    return a > b ? a : b;
+}
+
+function area(s) {
+    if (s.tag === "Circle") {
+        const { args: [r] } = s;
+        return $wrap($wrap(r * r) * 3n);
+    } else if (s.tag === "Rect") {
+        const { args: [w, h] } = s;
+        return $wrap(w * h);
+    }
+}
+
+export function main() {
+    let total = 0n;
+    total = max(area({ tag: "Rect", args: [2n, 3n] }), 4n);
+    console.log("[test.mi:7] " + String(total));
+    if (total > 5n) {
+        console.log("big");
+    }
+}
+
+export function show(value) {
+    return "()";
+}
+"#
+        );
+    }
+
+    #[test]
+    fn same_as_wasm_for_primitives() {
+        backend_test::same_as_wasm_for_primitives::<Js>();
+    }
+
+    #[test]
+    fn same_as_wasm_for_values() {
+        backend_test::same_as_wasm_for_values::<Js>();
+    }
+
+    #[test]
+    fn same_as_wasm_for_control_flow() {
+        backend_test::same_as_wasm_for_control_flow::<Js>();
+    }
+
+    #[test]
+    fn same_as_wasm_for_output() {
+        backend_test::same_as_wasm_for_output::<Js>();
+    }
+
+    #[test]
+    fn same_as_wasm_for_modules() {
+        backend_test::same_as_wasm_for_modules::<Js>();
+    }
+}
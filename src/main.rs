@@ -1,7 +1,23 @@
 mod ast;
+mod backend_test;
+mod c;
+mod cache;
+mod codegen;
+mod dump;
 mod error_fmt;
+mod exhaustive;
+mod instances;
+mod ir;
+mod js;
+mod modules;
+mod optimize;
+mod property_test;
+mod runtime;
+mod source;
 mod syntax_test;
+mod testing;
 mod type_check;
+mod wasi;
 mod wasm;
 
 #[macro_use]
@@ -9,31 +25,65 @@ extern crate lalrpop_util;
 
 use std::env;
 use std::fs;
-
-use crate::syntax::TermParser;
+use std::path::Path;
 
 // Synthesized by LALRPOP
-lalrpop_mod!(pub syntax);
+lalrpop_mod!(#[allow(clippy::all)] pub syntax);
 
 fn main() -> std::io::Result<()> {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() < 2 {
-        println!("Please supply a filename to run. Like `$ mizzle ok.mi`");
-        return Ok(());
-    }
+    // Flags start with a `-`, like `-O`, everything else is the filename
+    let (flags, rest): (Vec<&String>, Vec<&String>) =
+        args.iter().skip(1).partition(|a| a.starts_with('-'));
 
-    let filename = &args[1];
+    // `ast`, `build`, `run` or `test` can come before the filename, running is what happens without any
+    let (command, rest) = match rest.split_first() {
+        Some((first, rest)) if ["ast", "build", "run", "test"].contains(&first.as_str()) => {
+            (first.as_str(), rest)
+        }
+        _ => ("run", &rest[..]),
+    };
+
+    let filename = match rest.first() {
+        Some(filename) => filename,
+        None => {
+            println!("Please supply a filename to run. Like `$ mizzle ok.mi`");
+            return Ok(());
+        }
+    };
+
+    let should_optimize = flags.iter().any(|f| f.as_str() == "-O");
+    let emit = flags.iter().find_map(|f| f.strip_prefix("--emit="));
+    let target = flags.iter().find_map(|f| f.strip_prefix("--target="));
+    let native = flags.iter().any(|f| f.as_str() == "--native");
+    let no_cache = flags.iter().any(|f| f.as_str() == "--no-cache");
+    let format = flags.iter().find_map(|f| f.strip_prefix("--format="));
+    // `$ mizzle test ok.mi adds` only runs the tests with `adds` in their name
+    let filter = rest.get(1).map(|f| f.as_str());
+    // Tests that do not pass, or cannot run at all, make `test` exit with an error
+    let mut failed = false;
 
     let source = fs::read_to_string(filename)?;
-    let source_lines = source
-        .split('\n')
-        .map(|s| s.into())
-        .collect::<Vec<String>>();
-
-    match TermParser::new().parse(&source) {
-        Ok(a) => {
-            let check_result = type_check::check(&a);
+    let mut sources = source::SourceMap::default();
+
+    match modules::load(Path::new(filename), &source, &mut sources) {
+        // The tree is written without any types when the program does not check
+        Ok(modules) if command == "ast" => {
+            let typed = modules::check(&modules).result.ok();
+            let tree = dump::main_module(&modules, typed.as_ref(), &sources);
+
+            match format.unwrap_or("sexp") {
+                "sexp" => println!("{}", dump::to_sexp(&tree, &sources)),
+                "json" => println!("{}", dump::to_json(&tree, &sources)),
+                other => println!(
+                    "Cannot format the tree as `{}`, the options are `sexp` and `json`.",
+                    other
+                ),
+            }
+        }
+        Ok(modules) => {
+            let check_result = modules::check(&modules);
 
             let mut issues = vec![];
 
@@ -46,14 +96,84 @@ fn main() -> std::io::Result<()> {
             issues.append(&mut warn_issues);
 
             match check_result.result {
-                Ok(final_ty) => {
+                Ok(typed) => {
                     issues.sort();
 
                     for issue in issues {
-                        print!("{}\n\n", error_fmt::format_type_issue(issue, &source_lines));
+                        print!("{}\n\n", error_fmt::format_type_issue(issue, &sources));
                     }
 
-                    wasm::eval(wasm::ast_to_wasm(&a.map_extra(&|_| ())), &final_ty)
+                    let typed = if should_optimize {
+                        optimize::optimize_program(&typed)
+                    } else {
+                        typed
+                    };
+
+                    match (emit, target) {
+                        (Some("ir"), _) => println!("{}", ir::lower(&typed)),
+                        (Some(other), _) => {
+                            println!("Cannot emit `{}`, the only option is `ir`.", other)
+                        }
+                        // The module is written next to the source, like `ok.mi` to `ok.wasm`
+                        (None, Some("wasi")) => {
+                            let bytes = wasi::ast_to_wasm(&typed, &sources).to_bytes().unwrap();
+                            fs::write(Path::new(filename).with_extension("wasm"), bytes)?
+                        }
+                        (None, Some("js")) => fs::write(
+                            Path::new(filename).with_extension("mjs"),
+                            js::ast_to_js(&typed, &sources),
+                        )?,
+                        // The source includes the header by its name, since they are written next to each other
+                        (None, Some("c")) => {
+                            let header = Path::new(filename).with_extension("h");
+                            let header_name = header.file_name().unwrap().to_string_lossy();
+                            let (h, c) = c::ast_to_c(&typed, &sources, &header_name);
+                            fs::write(&header, h)?;
+                            fs::write(Path::new(filename).with_extension("c"), c)?
+                        }
+                        (None, Some(other)) => {
+                            println!(
+                                "Cannot target `{}`, the options are `wasi`, `js` and `c`.",
+                                other
+                            )
+                        }
+                        // Loaded by wasmer instead of compiled, like `ok.mi` to `ok.wasmu`
+                        (None, None) if command == "build" && native => {
+                            let program = wasm::ast_to_wasm(&typed);
+                            let module = wasm::compile(&program, &wasm::store());
+                            fs::write(
+                                Path::new(filename).with_extension("wasmu"),
+                                module.serialize().unwrap(),
+                            )?
+                        }
+                        (None, None) if command == "build" => println!(
+                            "Please supply what to build. Like `$ mizzle build --native ok.mi`"
+                        ),
+                        (None, None) if command == "test" => {
+                            failed = !testing::run(&typed, &sources, filter)
+                        }
+                        (None, None) => {
+                            let program = wasm::ast_to_wasm(&typed);
+                            // Without a cache directory of this user's own, nothing is cached
+                            let result = match cache::default_root() {
+                                Some(root) if !no_cache => {
+                                    let store = wasm::store();
+                                    let wasm = program.module.clone().to_bytes().unwrap();
+                                    let cache = cache::Cache::new(&root);
+                                    wasm::eval_module(
+                                        &program,
+                                        &cache.module(&store, &wasm),
+                                        &sources,
+                                    )
+                                }
+                                _ => wasm::eval(&program, &sources),
+                            };
+
+                            if let Err(e) = result {
+                                println!("{}\n", error_fmt::format_runtime_err(e, &sources))
+                            }
+                        }
+                    }
                 }
                 Err(errors) => {
                     let mut err_issues = errors
@@ -65,12 +185,22 @@ fn main() -> std::io::Result<()> {
                     issues.sort();
 
                     for issue in issues {
-                        print!("{}\n\n", error_fmt::format_type_issue(issue, &source_lines));
+                        print!("{}\n\n", error_fmt::format_type_issue(issue, &sources));
                     }
+                    failed = true;
                 }
             }
         }
-        Err(e) => println!("{}\n", error_fmt::format_parse_err(e, &source_lines)),
+        Err(errors) => {
+            for e in errors {
+                println!("{}\n", error_fmt::format_load_err(e, &sources))
+            }
+            failed = true;
+        }
+    }
+
+    if failed && command == "test" {
+        std::process::exit(1);
     }
 
     Ok(())
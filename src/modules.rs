@@ -0,0 +1,457 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lalrpop_util::ParseError;
+
+use crate::ast;
+use crate::source::{FileId, SourceMap};
+use crate::syntax;
+use crate::type_check;
+
+// Functions that every module can use, written in mizzle itself
+const PRELUDE: &str = include_str!("prelude.mi");
+
+// One source file of a program
+#[derive(Clone, Debug)]
+pub struct Module {
+    // What the module is imported as, which is the name of its file
+    pub name: String,
+    // The span of each `import`, and the index of the module it refers to
+    pub imports: Vec<(ast::Span, usize)>,
+    pub decls: Vec<ast::SpanDecl>,
+    // Only the main module has a body
+    pub body: Option<ast::SpanExpr>,
+}
+
+#[derive(Clone, Debug)]
+pub enum LoadError {
+    Parse {
+        file: FileId,
+        error: ParseError<usize, String, &'static str>,
+    },
+    // `path` is where the file of the module was looked for
+    NotFound {
+        span: ast::Span,
+        name: String,
+        path: String,
+    },
+    // `cycle` starts and ends with the same module, `span` is the `import` that closes it
+    Cycle {
+        span: ast::Span,
+        cycle: Vec<String>,
+    },
+}
+
+impl LoadError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LoadError::Parse { .. } => "E0001",
+            LoadError::NotFound { .. } => "E0034",
+            LoadError::Cycle { .. } => "E0035",
+        }
+    }
+}
+
+struct Loader<'a> {
+    sources: &'a mut SourceMap,
+    // Every module is only loaded once, even when it is imported by several others
+    loaded: HashMap<PathBuf, usize>,
+    // The chain of imports that led to the module that is being loaded
+    loading: Vec<(PathBuf, String)>,
+    modules: Vec<Module>,
+    errors: Vec<LoadError>,
+}
+
+fn parse_err(
+    e: ParseError<usize, lalrpop_util::lexer::Token<'_>, &'static str>,
+    file: FileId,
+) -> LoadError {
+    LoadError::Parse {
+        file,
+        error: e.map_token(|t| t.to_string()),
+    }
+}
+
+impl<'a> Loader<'a> {
+    // Returns `None` when the module has errors, which have been added to `errors`
+    fn load(
+        &mut self,
+        path: &Path,
+        name: &str,
+        source: &str,
+        file: FileId,
+        is_main: bool,
+    ) -> Option<usize> {
+        let (imports, decls, body) = if is_main {
+            match syntax::ProgramParser::new().parse(file, source) {
+                Ok(p) => (p.imports, p.decls, Some(p.body)),
+                Err(e) => {
+                    self.errors.push(parse_err(e, file));
+                    return None;
+                }
+            }
+        } else {
+            match syntax::LibraryParser::new().parse(file, source) {
+                Ok(l) => (l.imports, l.decls, None),
+                Err(e) => {
+                    self.errors.push(parse_err(e, file));
+                    return None;
+                }
+            }
+        };
+
+        let identity = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.loading.push((identity.clone(), name.into()));
+
+        let mut imported = vec![];
+        for import in imports {
+            if let Some(i) = self.import(path, &import.name, import.extra) {
+                imported.push((import.extra, i));
+            }
+        }
+
+        self.loading.pop();
+        self.modules.push(Module {
+            name: name.into(),
+            imports: imported,
+            decls,
+            body,
+        });
+        self.loaded.insert(identity, self.modules.len() - 1);
+
+        Some(self.modules.len() - 1)
+    }
+
+    // `import name` refers to `name.mi`, in the same directory as `from`
+    fn import(&mut self, from: &Path, name: &str, span: ast::Span) -> Option<usize> {
+        let path = from
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(format!("{}.mi", name));
+        let identity = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+
+        if let Some(i) = self.loading.iter().position(|(p, _)| p == &identity) {
+            let mut cycle = self.loading[i..]
+                .iter()
+                .map(|(_, n)| n.clone())
+                .collect::<Vec<_>>();
+            cycle.push(name.into());
+
+            self.errors.push(LoadError::Cycle { span, cycle });
+            return None;
+        }
+
+        if let Some(i) = self.loaded.get(&identity) {
+            return Some(*i);
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(source) => {
+                let file = self.sources.add(&path.to_string_lossy(), &source);
+                self.load(&path, name, &source, file, false)
+            }
+            Err(_) => {
+                self.errors.push(LoadError::NotFound {
+                    span,
+                    name: name.into(),
+                    path: path.to_string_lossy().into(),
+                });
+                None
+            }
+        }
+    }
+}
+
+// Parses the main module from `source`, and every module that it imports from the files next to `path`
+// Modules come before the ones that import them, so the prelude is first and the main module last
+pub fn load(
+    path: &Path,
+    source: &str,
+    sources: &mut SourceMap,
+) -> Result<Vec<Module>, Vec<LoadError>> {
+    let prelude_file = sources.add("prelude.mi", PRELUDE);
+    let main_file = sources.add(&path.to_string_lossy(), source);
+
+    let mut loader = Loader {
+        sources,
+        loaded: HashMap::new(),
+        loading: vec![],
+        modules: vec![],
+        errors: vec![],
+    };
+
+    // The prelude is part of the compiler, so it always parses
+    let prelude = syntax::LibraryParser::new()
+        .parse(prelude_file, PRELUDE)
+        .unwrap();
+    loader.modules.push(Module {
+        name: "prelude".into(),
+        imports: vec![],
+        decls: prelude.decls,
+        body: None,
+    });
+
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    loader.load(path, &name, source, main_file, true);
+
+    if loader.errors.is_empty() {
+        Ok(loader.modules)
+    } else {
+        Err(loader.errors)
+    }
+}
+
+// Checks every module on its own, with only what it imports in scope besides its own declarations,
+// Then links them all into a single program
+pub fn check(modules: &[Module]) -> type_check::CheckResult {
+    let mut exports: Vec<type_check::Scope> = vec![];
+    let mut errors = vec![];
+    let mut warnings = vec![];
+    let mut decls = vec![];
+    let mut body = None;
+
+    for (i, module) in modules.iter().enumerate() {
+        let mut imported = type_check::Scope::default();
+        let mut imported_from: HashMap<String, &str> = HashMap::new();
+
+        for (span, m) in &module.imports {
+            for name in exports[*m].function_names() {
+                match imported_from.insert(name.clone(), &modules[*m].name) {
+                    Some(first) if first != modules[*m].name => {
+                        errors.push(type_check::TypeError::ImportConflict {
+                            span: *span,
+                            name: name.clone(),
+                            first: first.into(),
+                        })
+                    }
+                    _ => (),
+                }
+            }
+
+            imported.import(&exports[*m]);
+        }
+
+        // Everything in the prelude is in scope, unless an import replaces it
+        let scope = match exports.first() {
+            Some(prelude) if i > 0 => {
+                let mut scope = prelude.clone();
+                scope.import(&imported);
+                scope
+            }
+            _ => imported,
+        };
+
+        // Functions of the main module keep their names
+        let name = module.body.is_none().then_some(module.name.as_str());
+        let (checked, module_exports) =
+            type_check::check_module(&scope, &module.decls, module.body.as_ref(), name);
+
+        exports.push(module_exports);
+        warnings.extend(checked.warnings);
+        match checked.result {
+            Ok((module_decls, module_body)) => {
+                decls.extend(module_decls);
+                body = body.or(module_body);
+            }
+            Err(module_errors) => errors.extend(module_errors),
+        }
+    }
+
+    if errors.is_empty() {
+        errors = check_links(modules);
+    }
+
+    type_check::CheckResult {
+        result: match body {
+            Some(body) if errors.is_empty() => Ok(ast::Program {
+                imports: vec![],
+                decls,
+                body,
+            }),
+            _ => Err(errors),
+        },
+        warnings,
+    }
+}
+
+// Functions are renamed to include their module, but types and constructors keep their names,
+// So they have to be unique across every module
+fn check_links(modules: &[Module]) -> Vec<type_check::TypeError> {
+    let mut types = HashSet::new();
+    let mut constructors = HashSet::new();
+    let mut errors = vec![];
+
+    for decl in modules.iter().flat_map(|m| &m.decls) {
+        match decl {
+            ast::Decl::Function { .. } | ast::Decl::Test { .. } => continue,
+            ast::Decl::Variant {
+                constructors: cs, ..
+            } => {
+                for c in cs {
+                    if !constructors.insert(c.name.as_str()) {
+                        errors.push(type_check::TypeError::DuplicateConstructor {
+                            span: c.extra,
+                            name: c.name.clone(),
+                        });
+                    }
+                }
+            }
+            ast::Decl::Record { .. } => (),
+        }
+
+        if !types.insert(decl.name()) {
+            errors.push(type_check::TypeError::DuplicateType {
+                span: *decl.extra(),
+                name: decl.name().into(),
+            });
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wasm;
+    use std::sync::{Arc, Mutex};
+
+    // Writes `files` to a directory of their own, and loads `main.mi` from it
+    fn load_files(test: &str, files: &[(&str, &str)]) -> Result<Vec<Module>, Vec<LoadError>> {
+        let dir = std::env::temp_dir().join(format!("mizzle_modules_{}", test));
+        fs::create_dir_all(&dir).unwrap();
+
+        for (name, source) in files {
+            fs::write(dir.join(name), source).unwrap();
+        }
+
+        let main = dir.join("main.mi");
+        let source = fs::read_to_string(&main).unwrap();
+
+        load(&main, &source, &mut SourceMap::default())
+    }
+
+    #[test]
+    fn links_modules() {
+        let modules = load_files(
+            "links",
+            &[
+                (
+                    "main.mi",
+                    "import math\nfn helper(x: int): int = x end\nlet v = double(Vec { x = 1, y = 2 }) in (v, helper(3), map([1], helper))",
+                ),
+                (
+                    "math.mi",
+                    "import util\npub type Vec = { x: int, y: int }\nfn helper(x: int): int = x * 2 end\npub fn double(v: Vec): Vec = Vec { x = helper(v.x), y = twice(v.y) } end",
+                ),
+                (
+                    "util.mi",
+                    "fn helper(x: int): int = x + 100 end\npub fn twice(x: int): int = helper(x) * 2 end",
+                ),
+            ],
+        )
+        .unwrap();
+
+        // Dependencies come first, and `util` is only loaded once
+        let names = modules.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["prelude", "util", "math", "main"]);
+
+        let typed = check(&modules).result.unwrap();
+        assert_eq!(
+            wasm::run(
+                &wasm::ast_to_wasm(&typed),
+                &SourceMap::default(),
+                Arc::new(Mutex::new(std::io::sink()))
+            )
+            .unwrap(),
+            "(Vec { x = 2, y = 204 }, 3, [1])"
+        );
+    }
+
+    #[test]
+    fn only_public_declarations_are_imported() {
+        let modules = load_files(
+            "private",
+            &[
+                ("main.mi", "import util\n(twice(1), helper(1))"),
+                (
+                    "util.mi",
+                    "fn helper(x: int): int = x end\npub fn twice(x: int): int = helper(x) * 2 end",
+                ),
+            ],
+        )
+        .unwrap();
+
+        let errors = check(&modules).result.unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [type_check::TypeError::UnknownFunction { name, .. }] if name == "helper"
+        ));
+    }
+
+    #[test]
+    fn conflicting_declarations() {
+        let modules = load_files(
+            "conflicts",
+            &[
+                ("main.mi", "import a\nimport b\nf(1)"),
+                ("a.mi", "pub fn f(x: int): int = x end\ntype T = T"),
+                ("b.mi", "pub fn f(x: int): int = x end"),
+            ],
+        )
+        .unwrap();
+
+        let errors = check(&modules).result.unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [type_check::TypeError::ImportConflict { name, first, .. }] if name == "f" && first == "a"
+        ));
+
+        // Types are not renamed when linking, so even private ones cannot have the same name
+        let modules = load_files(
+            "shared_types",
+            &[
+                ("main.mi", "import a\ntype T = { x: int }\n1"),
+                ("a.mi", "type T = T"),
+            ],
+        )
+        .unwrap();
+
+        let errors = check(&modules).result.unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [type_check::TypeError::DuplicateType { name, .. }] if name == "T"
+        ));
+    }
+
+    #[test]
+    fn load_errors() {
+        let errors = load_files(
+            "cycle",
+            &[
+                ("main.mi", "import a\n1"),
+                ("a.mi", "import b"),
+                ("b.mi", "import a"),
+            ],
+        )
+        .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [LoadError::Cycle { cycle, .. }] if cycle == &["a", "b", "a"]
+        ));
+
+        let errors = load_files("missing", &[("main.mi", "import missing\n1")]).unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [LoadError::NotFound { name, .. }] if name == "missing"
+        ));
+
+        // Imported files cannot have a body
+        let errors = load_files("body", &[("main.mi", "import a\n1"), ("a.mi", "1")]).unwrap_err();
+        assert!(matches!(&errors[..], [LoadError::Parse { .. }]));
+    }
+}
@@ -0,0 +1,402 @@
+use crate::ast;
+
+// Simplifies an expression before code generation, without changing its result
+// Type checking has to happen before this, since annotations are thrown away
+pub fn optimize<T: Clone>(e: &ast::Expr<T>) -> ast::Expr<T> {
+    match e {
+        ast::Expr::UnitLit(_)
+        | ast::Expr::IntLit(_, _)
+        | ast::Expr::BoolLit(_, _)
+        | ast::Expr::FloatLit(_, _)
+        | ast::Expr::StringLit(_, _) => e.clone(),
+        // Annotations only matter to the type checker
+        ast::Expr::TypeAnno { term, .. } => optimize(term),
+        ast::Expr::IfFlow {
+            cond,
+            on_true,
+            on_false,
+            extra,
+        } => match optimize(cond) {
+            // The same case as `TypeWarning::CondAlways`, so the other branch is unreachable
+            ast::Expr::BoolLit(_, true) => optimize(on_true),
+            ast::Expr::BoolLit(_, false) => optimize(on_false),
+            cond => ast::Expr::IfFlow {
+                extra: extra.clone(),
+                cond: Box::new(cond),
+                on_true: Box::new(optimize(on_true)),
+                on_false: Box::new(optimize(on_false)),
+            },
+        },
+        ast::Expr::BinOp {
+            extra,
+            op,
+            left,
+            right,
+        } => {
+            let left = optimize(left);
+            let right = optimize(right);
+
+            fold_bin_op(extra, *op, &left, &right).unwrap_or(ast::Expr::BinOp {
+                extra: extra.clone(),
+                op: *op,
+                left: Box::new(left),
+                right: Box::new(right),
+            })
+        }
+        ast::Expr::Builtin {
+            extra,
+            builtin,
+            arg,
+        } => match (builtin, optimize(arg)) {
+            // The length is in bytes, same as at runtime
+            (ast::Builtin::Len, ast::Expr::StringLit(_, s)) => {
+                ast::Expr::IntLit(extra.clone(), s.len() as i64)
+            }
+            (ast::Builtin::ToFloat, ast::Expr::IntLit(_, n)) => {
+                ast::Expr::FloatLit(extra.clone(), n as f64)
+            }
+            // Converting a float that does not fit traps at runtime, so leave those alone
+            (ast::Builtin::ToInt, ast::Expr::FloatLit(_, x))
+                if x.trunc() >= i64::MIN as f64 && x.trunc() < i64::MAX as f64 =>
+            {
+                ast::Expr::IntLit(extra.clone(), x as i64)
+            }
+            (_, arg) => ast::Expr::Builtin {
+                extra: extra.clone(),
+                builtin: *builtin,
+                arg: Box::new(arg),
+            },
+        },
+        ast::Expr::Var(_, _) => e.clone(),
+        ast::Expr::Tuple { extra, items } => ast::Expr::Tuple {
+            extra: extra.clone(),
+            items: items.iter().map(optimize).collect(),
+        },
+        ast::Expr::Project { extra, term, index } => ast::Expr::Project {
+            extra: extra.clone(),
+            term: Box::new(optimize(term)),
+            index: *index,
+        },
+        ast::Expr::Let {
+            extra,
+            pattern,
+            value,
+            body,
+        } => ast::Expr::Let {
+            extra: extra.clone(),
+            pattern: pattern.clone(),
+            value: Box::new(optimize(value)),
+            body: Box::new(optimize(body)),
+        },
+        ast::Expr::Record {
+            extra,
+            name,
+            fields,
+        } => ast::Expr::Record {
+            extra: extra.clone(),
+            name: name.clone(),
+            fields: optimize_fields(fields),
+        },
+        ast::Expr::Field { extra, term, field } => ast::Expr::Field {
+            extra: extra.clone(),
+            term: Box::new(optimize(term)),
+            field: field.clone(),
+        },
+        ast::Expr::Update {
+            extra,
+            term,
+            fields,
+        } => ast::Expr::Update {
+            extra: extra.clone(),
+            term: Box::new(optimize(term)),
+            fields: optimize_fields(fields),
+        },
+        ast::Expr::Construct { extra, name, args } => ast::Expr::Construct {
+            extra: extra.clone(),
+            name: name.clone(),
+            args: args.iter().map(optimize).collect(),
+        },
+        ast::Expr::Match { extra, term, arms } => ast::Expr::Match {
+            extra: extra.clone(),
+            term: Box::new(optimize(term)),
+            arms: arms
+                .iter()
+                .map(|a| ast::Arm {
+                    extra: a.extra.clone(),
+                    pattern: a.pattern.clone(),
+                    body: optimize(&a.body),
+                })
+                .collect(),
+        },
+        ast::Expr::Call { extra, name, args } => ast::Expr::Call {
+            extra: extra.clone(),
+            name: name.clone(),
+            args: args.iter().map(optimize).collect(),
+        },
+        ast::Expr::VarDecl {
+            extra,
+            name,
+            value,
+            body,
+        } => ast::Expr::VarDecl {
+            extra: extra.clone(),
+            name: name.clone(),
+            value: Box::new(optimize(value)),
+            body: Box::new(optimize(body)),
+        },
+        ast::Expr::Assign { extra, name, value } => ast::Expr::Assign {
+            extra: extra.clone(),
+            name: name.clone(),
+            value: Box::new(optimize(value)),
+        },
+        ast::Expr::While { extra, cond, body } => match optimize(cond) {
+            ast::Expr::BoolLit(_, false) => ast::Expr::UnitLit(extra.clone()),
+            cond => ast::Expr::While {
+                extra: extra.clone(),
+                cond: Box::new(cond),
+                body: Box::new(optimize(body)),
+            },
+        },
+        ast::Expr::Break(_) => e.clone(),
+        ast::Expr::Array { extra, items } => ast::Expr::Array {
+            extra: extra.clone(),
+            items: items.iter().map(optimize).collect(),
+        },
+        ast::Expr::Repeat {
+            extra,
+            length,
+            value,
+        } => ast::Expr::Repeat {
+            extra: extra.clone(),
+            length: Box::new(optimize(length)),
+            value: Box::new(optimize(value)),
+        },
+        // Indexing can be out of bounds, so it is never folded away
+        ast::Expr::Index { extra, term, index } => ast::Expr::Index {
+            extra: extra.clone(),
+            term: Box::new(optimize(term)),
+            index: Box::new(optimize(index)),
+        },
+        ast::Expr::SetIndex {
+            extra,
+            term,
+            index,
+            value,
+        } => ast::Expr::SetIndex {
+            extra: extra.clone(),
+            term: Box::new(optimize(term)),
+            index: Box::new(optimize(index)),
+            value: Box::new(optimize(value)),
+        },
+        ast::Expr::Seq {
+            extra,
+            first,
+            second,
+        } => match optimize(first) {
+            // Literals and variables do nothing when their value is thrown away
+            ast::Expr::UnitLit(_)
+            | ast::Expr::IntLit(_, _)
+            | ast::Expr::BoolLit(_, _)
+            | ast::Expr::FloatLit(_, _)
+            | ast::Expr::StringLit(_, _)
+            | ast::Expr::Var(_, _) => optimize(second),
+            first => ast::Expr::Seq {
+                extra: extra.clone(),
+                first: Box::new(first),
+                second: Box::new(optimize(second)),
+            },
+        },
+    }
+}
+
+fn optimize_fields<T: Clone>(fields: &[ast::FieldValue<T>]) -> Vec<ast::FieldValue<T>> {
+    fields
+        .iter()
+        .map(|f| ast::FieldValue {
+            extra: f.extra.clone(),
+            name: f.name.clone(),
+            value: optimize(&f.value),
+        })
+        .collect()
+}
+
+// Besides the body, only functions and tests have expressions, other declarations are left as they are
+pub fn optimize_program<T: Clone>(p: &ast::Program<T>) -> ast::Program<T> {
+    let decls = p
+        .decls
+        .iter()
+        .map(|d| match d {
+            ast::Decl::Function {
+                extra,
+                public,
+                name,
+                type_params,
+                params,
+                result,
+                body,
+            } => ast::Decl::Function {
+                extra: extra.clone(),
+                public: *public,
+                name: name.clone(),
+                type_params: type_params.clone(),
+                params: params.clone(),
+                result: result.clone(),
+                body: optimize(body),
+            },
+            ast::Decl::Test { extra, name, body } => ast::Decl::Test {
+                extra: extra.clone(),
+                name: name.clone(),
+                body: optimize(body),
+            },
+            d => d.clone(),
+        })
+        .collect();
+
+    ast::Program {
+        imports: p.imports.clone(),
+        decls,
+        body: optimize(&p.body),
+    }
+}
+
+// Computes an operator applied to two literals, the same way that wasm would
+// Returns `None` when the operator can't be computed ahead of time, like dividing by zero
+fn fold_bin_op<T: Clone>(
+    extra: &T,
+    op: ast::BinOp,
+    left: &ast::Expr<T>,
+    right: &ast::Expr<T>,
+) -> Option<ast::Expr<T>> {
+    use ast::BinOp::*;
+    use ast::Expr::{BoolLit, FloatLit, IntLit, StringLit};
+
+    let e = extra.clone();
+
+    Some(match (op, left, right) {
+        (Concat, StringLit(_, l), StringLit(_, r)) => StringLit(e, l.clone() + r),
+        (Add, IntLit(_, l), IntLit(_, r)) => IntLit(e, l.wrapping_add(*r)),
+        (Sub, IntLit(_, l), IntLit(_, r)) => IntLit(e, l.wrapping_sub(*r)),
+        (Mul, IntLit(_, l), IntLit(_, r)) => IntLit(e, l.wrapping_mul(*r)),
+        (Div, IntLit(_, l), IntLit(_, r)) => IntLit(e, l.checked_div(*r)?),
+        (Rem, IntLit(_, l), IntLit(_, r)) => IntLit(e, l.checked_rem(*r)?),
+        (Lt, IntLit(_, l), IntLit(_, r)) => BoolLit(e, l < r),
+        (Le, IntLit(_, l), IntLit(_, r)) => BoolLit(e, l <= r),
+        (Gt, IntLit(_, l), IntLit(_, r)) => BoolLit(e, l > r),
+        (Ge, IntLit(_, l), IntLit(_, r)) => BoolLit(e, l >= r),
+        (Eq, IntLit(_, l), IntLit(_, r)) => BoolLit(e, l == r),
+        (Ne, IntLit(_, l), IntLit(_, r)) => BoolLit(e, l != r),
+        (Add, FloatLit(_, l), FloatLit(_, r)) => FloatLit(e, l + r),
+        (Sub, FloatLit(_, l), FloatLit(_, r)) => FloatLit(e, l - r),
+        (Mul, FloatLit(_, l), FloatLit(_, r)) => FloatLit(e, l * r),
+        (Div, FloatLit(_, l), FloatLit(_, r)) => FloatLit(e, l / r),
+        (Lt, FloatLit(_, l), FloatLit(_, r)) => BoolLit(e, l < r),
+        (Le, FloatLit(_, l), FloatLit(_, r)) => BoolLit(e, l <= r),
+        (Gt, FloatLit(_, l), FloatLit(_, r)) => BoolLit(e, l > r),
+        (Ge, FloatLit(_, l), FloatLit(_, r)) => BoolLit(e, l >= r),
+        (Eq, FloatLit(_, l), FloatLit(_, r)) => BoolLit(e, l == r),
+        (Ne, FloatLit(_, l), FloatLit(_, r)) => BoolLit(e, l != r),
+        (Eq, BoolLit(_, l), BoolLit(_, r)) => BoolLit(e, l == r),
+        (Ne, BoolLit(_, l), BoolLit(_, r)) => BoolLit(e, l != r),
+        (Eq, StringLit(_, l), StringLit(_, r)) => BoolLit(e, l == r),
+        (Ne, StringLit(_, l), StringLit(_, r)) => BoolLit(e, l != r),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{FileId, SourceMap};
+    use crate::syntax;
+    use crate::type_check;
+    use crate::wasm;
+    use std::sync::{Arc, Mutex};
+
+    fn instruction_count(e: &ast::TypedProgram) -> usize {
+        wasm::ast_to_wasm(e).module.code_section().unwrap().bodies()[0]
+            .code()
+            .elements()
+            .len()
+    }
+
+    // Returns the instruction count of the unoptimized and optimized programs,
+    // After asserting that both evaluate to the same value
+    fn compare(source: &str) -> (usize, usize) {
+        let a = syntax::ProgramParser::new()
+            .parse(FileId(0), source)
+            .unwrap();
+        let typed = type_check::check(&a).result.unwrap();
+        let optimized = optimize_program(&typed);
+
+        let run = |typed| {
+            let output = Arc::new(Mutex::new(vec![]));
+            wasm::run(&wasm::ast_to_wasm(typed), &SourceMap::default(), output)
+        };
+        assert_eq!(run(&typed), run(&optimized));
+
+        (instruction_count(&typed), instruction_count(&optimized))
+    }
+
+    #[test]
+    fn strips_annotations() {
+        let (plain, optimized) = compare("(1: int): int");
+        assert_eq!(plain, optimized);
+        assert!(matches!(
+            optimize(&ast::Expr::TypeAnno {
+                extra: (),
+                term: Box::new(ast::Expr::IntLit((), 1)),
+                ty: ast::Type::Int(()),
+            }),
+            ast::Expr::IntLit(_, 1)
+        ));
+    }
+
+    #[test]
+    fn removes_dead_branches() {
+        let (plain, optimized) = compare("if true then 2 else 3 end");
+        assert!(optimized < plain);
+
+        let (plain, optimized) =
+            compare("if false: bool then 2 else if true then 3 else 4 end end");
+        assert!(optimized < plain);
+        assert_eq!(optimized, 2);
+    }
+
+    #[test]
+    fn folds_strings() {
+        let (plain, optimized) = compare(r#"len("ab" ++ "c" ++ "d")"#);
+        assert!(optimized < plain);
+        assert_eq!(optimized, 2);
+
+        let (plain, optimized) = compare(r#"if false then "a" else "b" ++ "c" end"#);
+        assert!(optimized < plain);
+    }
+
+    #[test]
+    fn folds_numbers() {
+        let (plain, optimized) = compare("if 1 + 2 * 3 < 10 then to_float(4) / 8.0 else 1.0 end");
+        assert!(optimized < plain);
+        assert_eq!(optimized, 2);
+
+        // Dividing by zero has to trap at runtime, so it is kept
+        let a = syntax::ProgramParser::new()
+            .parse(FileId(0), "1 / 0")
+            .unwrap();
+        let typed = type_check::check(&a).result.unwrap();
+        assert!(matches!(optimize(&typed.body), ast::Expr::BinOp { .. }));
+    }
+
+    #[test]
+    fn folds_conditions() {
+        let (plain, optimized) = compare("if if true then false else true end then 2 else 3 end");
+        assert!(optimized < plain);
+        assert_eq!(optimized, 2);
+
+        let (plain, optimized) = compare("2");
+        assert_eq!(plain, optimized);
+
+        let (plain, optimized) = compare("while 1 > 2 do () end");
+        assert!(optimized < plain);
+        assert_eq!(optimized, 1);
+    }
+}
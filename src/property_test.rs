@@ -0,0 +1,1153 @@
+// Programs made up at random, which are well typed because of how they are made,
+// And have to get through every step of the compiler like written ones do,
+// Along with trees of anything the grammar can read, which have to be printed so that they read back the same
+// A failure shows its seed, which `MIZZLE_SEED=...` runs again on its own
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::sync::{Arc, Mutex};
+
+    use crate::ast::{self, BinOp, Builtin, Expr, JustExpr, JustType, Pattern, Type};
+    use crate::backend_test::{self, compile};
+    use crate::modules;
+    use crate::optimize;
+    use crate::source::{FileId, SourceMap};
+    use crate::syntax;
+    use crate::wasm;
+
+    // What the generated programs can use besides the prelude
+    const DECLS: &str = "type Point = { x: int, y: float }
+type Shape = Circle(float) | Rect(int, int) | Empty
+type Option<a> = Some(a) | None
+
+fn double(x: int): int = x * 2 end
+fn max(a: int, b: int): int = if a > b then a else b end end
+fn first<a, b>(pair: (a, b)): a = pair.0 end
+";
+
+    // xorshift, which is all that is needed to make up programs that can be made again from their seed
+    struct Rng(u64);
+
+    impl Rng {
+        fn new(seed: u64) -> Rng {
+            // Never zero, which xorshift would stay at
+            Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+        }
+
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        fn chance(&mut self, percent: u64) -> bool {
+            self.next() % 100 < percent
+        }
+
+        fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+            &items[self.below(items.len())]
+        }
+    }
+
+    fn named(name: &str, args: Vec<JustType>) -> JustType {
+        Type::Named((), name.into(), args)
+    }
+
+    fn int_fn() -> JustType {
+        Type::Function((), vec![Type::Int(())], Box::new(Type::Int(())))
+    }
+
+    fn boxed(e: JustExpr) -> Box<JustExpr> {
+        Box::new(e)
+    }
+
+    fn bin_op(op: BinOp, left: JustExpr, right: JustExpr) -> JustExpr {
+        Expr::BinOp {
+            extra: (),
+            op,
+            left: boxed(left),
+            right: boxed(right),
+        }
+    }
+
+    fn call(name: &str, args: Vec<JustExpr>) -> JustExpr {
+        Expr::Call {
+            extra: (),
+            name: name.into(),
+            args,
+        }
+    }
+
+    fn construct(name: &str, args: Vec<JustExpr>) -> JustExpr {
+        Expr::Construct {
+            extra: (),
+            name: name.into(),
+            args,
+        }
+    }
+
+    fn field(name: &str, value: JustExpr) -> ast::FieldValue<()> {
+        ast::FieldValue {
+            extra: (),
+            name: name.into(),
+            value,
+        }
+    }
+
+    struct Generator {
+        rng: Rng,
+        // Variables in scope, the innermost last, and whether they were declared with `var`
+        vars: Vec<(String, JustType, bool)>,
+        names: usize,
+    }
+
+    impl Generator {
+        fn name(&mut self) -> String {
+            self.names += 1;
+            format!("v{}", self.names)
+        }
+
+        fn ty(&mut self, depth: usize) -> JustType {
+            if depth == 0 || self.rng.chance(60) {
+                return match self.rng.below(8) {
+                    0 => Type::Unit(()),
+                    1 => Type::Int(()),
+                    2 => Type::Bool(()),
+                    3 => Type::Float(()),
+                    4 => Type::String(()),
+                    5 => named("Point", vec![]),
+                    6 => named("Shape", vec![]),
+                    _ => int_fn(),
+                };
+            }
+
+            match self.rng.below(3) {
+                0 => {
+                    let items = (0..2 + self.rng.below(2))
+                        .map(|_| self.ty(depth - 1))
+                        .collect();
+
+                    Type::Tuple((), items)
+                }
+                1 => Type::Array((), Box::new(self.ty(depth - 1))),
+                _ => named("Option", vec![self.ty(depth - 1)]),
+            }
+        }
+
+        // An expression that is `ty`, and at most `depth` deep
+        fn expr(&mut self, ty: &JustType, depth: usize) -> JustExpr {
+            if depth == 0 || self.rng.chance(15) {
+                self.leaf(ty)
+            } else if self.rng.chance(40) {
+                self.any(ty, depth - 1)
+            } else {
+                self.typed(ty, depth - 1)
+            }
+        }
+
+        fn var(&mut self, ty: &JustType, mutable: bool) -> Option<(String, JustType)> {
+            let vars = self
+                .vars
+                .iter()
+                .filter(|(_, var_ty, var_mutable)| var_ty == ty && (*var_mutable || !mutable))
+                .map(|(name, ty, _)| (name.clone(), ty.clone()))
+                .collect::<Vec<_>>();
+
+            (!vars.is_empty()).then(|| self.rng.pick(&vars).clone())
+        }
+
+        // A variable or a literal, with only other literals inside of it
+        fn leaf(&mut self, ty: &JustType) -> JustExpr {
+            if self.rng.chance(50) {
+                if let Some((name, _)) = self.var(ty, false) {
+                    return Expr::Var((), name);
+                }
+            }
+
+            match ty {
+                Type::Unit(_) => Expr::UnitLit(()),
+                Type::Int(_) => {
+                    let n = match self.rng.below(10) {
+                        0 => i64::MIN,
+                        1 => i64::MAX,
+                        2 => self.rng.next() as i64,
+                        _ => self.rng.below(21) as i64 - 10,
+                    };
+
+                    Expr::IntLit((), n)
+                }
+                Type::Bool(_) => Expr::BoolLit((), self.rng.chance(50)),
+                Type::Float(_) => {
+                    let floats = [0.0, -0.0, 0.1, 1.5, -2.25, 3.0, 1e20, -1e-7, 123456.789];
+
+                    Expr::FloatLit((), *self.rng.pick(&floats))
+                }
+                Type::String(_) => {
+                    let chars = ['a', 'Z', ' ', '#', '"', '\\', '\n', '\t', '\0', 'é', '💧'];
+                    let s = (0..self.rng.below(5))
+                        .map(|_| *self.rng.pick(&chars))
+                        .collect();
+
+                    Expr::StringLit((), s)
+                }
+                Type::Tuple(_, items) => Expr::Tuple {
+                    extra: (),
+                    items: items.iter().map(|i| self.leaf(i)).collect(),
+                },
+                Type::Array(_, item) => Expr::Array {
+                    extra: (),
+                    items: (0..1 + self.rng.below(2))
+                        .map(|_| self.leaf(item))
+                        .collect(),
+                },
+                Type::Function(_, _, _) => Expr::Var((), "double".into()),
+                Type::Named(_, name, _) if name == "Point" => {
+                    let mut fields = vec![
+                        field("x", self.leaf(&Type::Int(()))),
+                        field("y", self.leaf(&Type::Float(()))),
+                    ];
+                    if self.rng.chance(50) {
+                        fields.reverse();
+                    }
+
+                    Expr::Record {
+                        extra: (),
+                        name: name.clone(),
+                        fields,
+                    }
+                }
+                Type::Named(_, name, _) if name == "Shape" => match self.rng.below(3) {
+                    0 => construct("Circle", vec![self.leaf(&Type::Float(()))]),
+                    1 => construct(
+                        "Rect",
+                        vec![self.leaf(&Type::Int(())), self.leaf(&Type::Int(()))],
+                    ),
+                    _ => construct("Empty", vec![]),
+                },
+                // `None` on its own cannot be inferred when nothing else uses it
+                Type::Named(_, _, args) if self.rng.chance(50) => Expr::TypeAnno {
+                    extra: (),
+                    term: boxed(construct("None", vec![])),
+                    ty: named("Option", args.clone()),
+                },
+                Type::Named(_, _, args) => construct("Some", vec![self.leaf(&args[0])]),
+                Type::Var(_, _) | Type::Unknown(_, _) => {
+                    panic!("Only concrete types are generated")
+                }
+            }
+        }
+
+        // Binds `name` while the body is generated
+        fn scoped(
+            &mut self,
+            bound: Vec<(String, JustType)>,
+            mutable: bool,
+            body: impl FnOnce(&mut Generator) -> JustExpr,
+        ) -> JustExpr {
+            let count = bound.len();
+            self.vars
+                .extend(bound.into_iter().map(|(name, ty)| (name, ty, mutable)));
+            let body = body(self);
+            self.vars.truncate(self.vars.len() - count);
+
+            body
+        }
+
+        // A pattern that binds what the value of `ty` is made of
+        fn pattern(&mut self, ty: &JustType, bound: &mut Vec<(String, JustType)>) -> Pattern<()> {
+            match ty {
+                Type::Tuple(_, items) if self.rng.chance(50) => {
+                    Pattern::Tuple((), items.iter().map(|i| self.pattern(i, bound)).collect())
+                }
+                _ if self.rng.chance(20) => Pattern::Wildcard(()),
+                _ => {
+                    let name = self.name();
+                    bound.push((name.clone(), ty.clone()));
+
+                    Pattern::Var((), name)
+                }
+            }
+        }
+
+        fn arm(
+            &mut self,
+            pattern: Pattern<()>,
+            bound: Vec<(String, JustType)>,
+            ty: &JustType,
+            depth: usize,
+        ) -> ast::Arm<()> {
+            ast::Arm {
+                extra: (),
+                pattern,
+                body: self.scoped(bound, false, |g| g.expr(ty, depth)),
+            }
+        }
+
+        // Expressions that can be any type
+        fn any(&mut self, ty: &JustType, depth: usize) -> JustExpr {
+            match self.rng.below(10) {
+                0 => {
+                    let value_ty = self.ty(1);
+                    let value = self.expr(&value_ty, depth);
+                    let mut bound = vec![];
+                    let pattern = self.pattern(&value_ty, &mut bound);
+
+                    Expr::Let {
+                        extra: (),
+                        pattern,
+                        value: boxed(value),
+                        body: boxed(self.scoped(bound, false, |g| g.expr(ty, depth))),
+                    }
+                }
+                1 => {
+                    let value_ty = self.ty(1);
+                    let value = self.expr(&value_ty, depth);
+                    let name = self.name();
+
+                    Expr::VarDecl {
+                        extra: (),
+                        name: name.clone(),
+                        value: boxed(value),
+                        body: boxed(
+                            self.scoped(vec![(name, value_ty)], true, |g| g.expr(ty, depth)),
+                        ),
+                    }
+                }
+                2 => Expr::IfFlow {
+                    extra: (),
+                    cond: boxed(self.expr(&Type::Bool(()), depth)),
+                    on_true: boxed(self.expr(ty, depth)),
+                    on_false: boxed(self.expr(ty, depth)),
+                },
+                3 => {
+                    let term = self.expr(&named("Shape", vec![]), depth);
+                    let (r, w, h) = (self.name(), self.name(), self.name());
+                    let mut arms = vec![
+                        self.arm(
+                            Pattern::Constructor(
+                                (),
+                                "Circle".into(),
+                                vec![Pattern::Var((), r.clone())],
+                            ),
+                            vec![(r, Type::Float(()))],
+                            ty,
+                            depth,
+                        ),
+                        self.arm(
+                            Pattern::Constructor(
+                                (),
+                                "Rect".into(),
+                                vec![Pattern::Var((), w.clone()), Pattern::Wildcard(())],
+                            ),
+                            vec![(w, Type::Int(()))],
+                            ty,
+                            depth,
+                        ),
+                        self.arm(
+                            Pattern::Constructor((), "Empty".into(), vec![]),
+                            vec![],
+                            ty,
+                            depth,
+                        ),
+                    ];
+                    if self.rng.chance(50) {
+                        arms.swap(0, 2);
+                    }
+                    // An arm that only matches some values, which the others still cover
+                    if self.rng.chance(50) {
+                        let n = self.rng.below(5) as i64 - 2;
+                        let pattern = Pattern::Constructor(
+                            (),
+                            "Rect".into(),
+                            vec![Pattern::Int((), n), Pattern::Var((), h.clone())],
+                        );
+                        let arm = self.arm(pattern, vec![(h, Type::Int(()))], ty, depth);
+                        arms.insert(0, arm);
+                    }
+
+                    Expr::Match {
+                        extra: (),
+                        term: boxed(term),
+                        arms,
+                    }
+                }
+                4 => {
+                    let term = self.expr(&Type::Int(()), depth);
+                    let mut arms = (0..self.rng.below(3))
+                        .map(|_| {
+                            let n = self.rng.below(7) as i64 - 3;
+                            self.arm(Pattern::Int((), n), vec![], ty, depth)
+                        })
+                        .collect::<Vec<_>>();
+                    arms.push(self.arm(Pattern::Wildcard(()), vec![], ty, depth));
+
+                    Expr::Match {
+                        extra: (),
+                        term: boxed(term),
+                        arms,
+                    }
+                }
+                5 => {
+                    let item_ty = self.ty(1);
+                    let term = self.expr(&named("Option", vec![item_ty.clone()]), depth);
+                    let mut bound = vec![];
+                    let pattern = self.pattern(&item_ty, &mut bound);
+                    let some = Pattern::Constructor((), "Some".into(), vec![pattern]);
+
+                    Expr::Match {
+                        extra: (),
+                        term: boxed(term),
+                        arms: vec![
+                            self.arm(some, bound, ty, depth),
+                            self.arm(
+                                Pattern::Constructor((), "None".into(), vec![]),
+                                vec![],
+                                ty,
+                                depth,
+                            ),
+                        ],
+                    }
+                }
+                6 => Expr::Seq {
+                    extra: (),
+                    first: boxed(self.expr(&Type::Unit(()), depth)),
+                    second: boxed(self.expr(ty, depth)),
+                },
+                7 => Expr::TypeAnno {
+                    extra: (),
+                    term: boxed(self.expr(ty, depth)),
+                    ty: ty.clone(),
+                },
+                8 => {
+                    let other = self.ty(1);
+                    let mut items = vec![self.expr(ty, depth), self.expr(&other, depth)];
+                    let index = if self.rng.chance(50) {
+                        items.swap(0, 1);
+                        1
+                    } else {
+                        0
+                    };
+                    let pair = Expr::Tuple { extra: (), items };
+
+                    if index == 0 && self.rng.chance(50) {
+                        call("first", vec![pair])
+                    } else {
+                        Expr::Project {
+                            extra: (),
+                            term: boxed(pair),
+                            index,
+                        }
+                    }
+                }
+                _ => {
+                    let items = (0..1 + self.rng.below(3))
+                        .map(|_| self.expr(ty, depth))
+                        .collect::<Vec<_>>();
+                    // Mostly in bounds, but an index outside of the array only stops the program
+                    let index = if self.rng.chance(90) {
+                        Expr::IntLit((), self.rng.below(items.len()) as i64)
+                    } else {
+                        self.expr(&Type::Int(()), depth)
+                    };
+
+                    Expr::Index {
+                        extra: (),
+                        term: boxed(Expr::Array { extra: (), items }),
+                        index: boxed(index),
+                    }
+                }
+            }
+        }
+
+        // Expressions that make a value of `ty` in particular
+        fn typed(&mut self, ty: &JustType, depth: usize) -> JustExpr {
+            let int = Type::Int(());
+            let float = Type::Float(());
+
+            match ty {
+                Type::Int(_) => match self.rng.below(9) {
+                    0..=2 => {
+                        let op = *self.rng.pick(&[
+                            BinOp::Add,
+                            BinOp::Sub,
+                            BinOp::Mul,
+                            BinOp::Div,
+                            BinOp::Rem,
+                        ]);
+                        bin_op(op, self.expr(&int, depth), self.expr(&int, depth))
+                    }
+                    3 => Expr::Builtin {
+                        extra: (),
+                        builtin: Builtin::Len,
+                        arg: boxed(self.expr(&Type::String(()), depth)),
+                    },
+                    4 => {
+                        let array = Type::Array((), Box::new(self.ty(1)));
+
+                        Expr::Builtin {
+                            extra: (),
+                            builtin: Builtin::Length,
+                            arg: boxed(self.expr(&array, depth)),
+                        }
+                    }
+                    5 => Expr::Builtin {
+                        extra: (),
+                        builtin: Builtin::ToInt,
+                        arg: boxed(self.expr(&float, depth)),
+                    },
+                    6 => match self.var(&int_fn(), false) {
+                        Some((name, _)) if self.rng.chance(50) => {
+                            call(&name, vec![self.expr(&int, depth)])
+                        }
+                        _ => call("double", vec![self.expr(&int, depth)]),
+                    },
+                    7 => Expr::Field {
+                        extra: (),
+                        term: boxed(self.expr(&named("Point", vec![]), depth)),
+                        field: "x".into(),
+                    },
+                    _ => {
+                        let items = self.expr(&Type::Array((), Box::new(int.clone())), depth);
+                        call(
+                            "fold",
+                            vec![items, self.expr(&int, depth), Expr::Var((), "max".into())],
+                        )
+                    }
+                },
+                Type::Float(_) => match self.rng.below(4) {
+                    0 | 1 => {
+                        let op = *self
+                            .rng
+                            .pick(&[BinOp::Add, BinOp::Sub, BinOp::Mul, BinOp::Div]);
+                        bin_op(op, self.expr(&float, depth), self.expr(&float, depth))
+                    }
+                    2 => Expr::Builtin {
+                        extra: (),
+                        builtin: Builtin::ToFloat,
+                        arg: boxed(self.expr(&int, depth)),
+                    },
+                    _ => Expr::Field {
+                        extra: (),
+                        term: boxed(self.expr(&named("Point", vec![]), depth)),
+                        field: "y".into(),
+                    },
+                },
+                Type::Bool(_) => {
+                    let operand = self
+                        .rng
+                        .pick(&[int, float, Type::Bool(()), Type::String(())])
+                        .clone();
+                    let ops = match operand {
+                        Type::Int(_) | Type::Float(_) => {
+                            vec![
+                                BinOp::Eq,
+                                BinOp::Ne,
+                                BinOp::Lt,
+                                BinOp::Le,
+                                BinOp::Gt,
+                                BinOp::Ge,
+                            ]
+                        }
+                        _ => vec![BinOp::Eq, BinOp::Ne],
+                    };
+                    let op = *self.rng.pick(&ops);
+
+                    bin_op(op, self.expr(&operand, depth), self.expr(&operand, depth))
+                }
+                Type::String(_) => bin_op(
+                    BinOp::Concat,
+                    self.expr(&Type::String(()), depth),
+                    self.expr(&Type::String(()), depth),
+                ),
+                Type::Unit(_) => match self.rng.below(5) {
+                    0 => {
+                        let shown = self.ty(1);
+
+                        Expr::Builtin {
+                            extra: (),
+                            builtin: *self.rng.pick(&[Builtin::Print, Builtin::Debug]),
+                            arg: boxed(self.expr(&shown, depth)),
+                        }
+                    }
+                    1 => {
+                        let assigned = self.ty(1);
+
+                        match self.var(&assigned, true) {
+                            Some((name, ty)) => Expr::Assign {
+                                extra: (),
+                                name,
+                                value: boxed(self.expr(&ty, depth)),
+                            },
+                            None => Expr::UnitLit(()),
+                        }
+                    }
+                    2 => {
+                        let item = self.ty(1);
+                        let array = Type::Array((), Box::new(item.clone()));
+
+                        Expr::SetIndex {
+                            extra: (),
+                            term: boxed(self.expr(&array, depth)),
+                            index: boxed(Expr::IntLit((), 0)),
+                            value: boxed(self.expr(&item, depth)),
+                        }
+                    }
+                    // Always leaves after the first time around, so it never runs forever
+                    3 => Expr::While {
+                        extra: (),
+                        cond: boxed(self.expr(&Type::Bool(()), depth)),
+                        body: boxed(Expr::Seq {
+                            extra: (),
+                            first: boxed(self.expr(&Type::Unit(()), depth)),
+                            second: boxed(Expr::Break(())),
+                        }),
+                    },
+                    _ => Expr::UnitLit(()),
+                },
+                Type::Tuple(_, items) => Expr::Tuple {
+                    extra: (),
+                    items: items.iter().map(|i| self.expr(i, depth)).collect(),
+                },
+                Type::Array(_, item) => match self.rng.below(4) {
+                    0 => Expr::Repeat {
+                        extra: (),
+                        length: boxed(Expr::IntLit((), self.rng.below(4) as i64)),
+                        value: boxed(self.expr(item, depth)),
+                    },
+                    1 if **item == int => {
+                        let start = self.rng.below(3) as i64;
+                        let stop = start + self.rng.below(4) as i64;
+                        let range = call(
+                            "range",
+                            vec![Expr::IntLit((), start), Expr::IntLit((), stop)],
+                        );
+
+                        if self.rng.chance(50) {
+                            call("map", vec![range, Expr::Var((), "double".into())])
+                        } else {
+                            range
+                        }
+                    }
+                    _ => Expr::Array {
+                        extra: (),
+                        items: (0..1 + self.rng.below(3))
+                            .map(|_| self.expr(item, depth))
+                            .collect(),
+                    },
+                },
+                Type::Named(_, name, _) if name == "Point" && self.rng.chance(50) => Expr::Update {
+                    extra: (),
+                    term: boxed(self.expr(ty, depth)),
+                    fields: vec![field("x", self.expr(&int, depth))],
+                },
+                Type::Named(_, name, _) if name == "Point" => Expr::Record {
+                    extra: (),
+                    name: name.clone(),
+                    fields: vec![
+                        field("y", self.expr(&float, depth)),
+                        field("x", self.expr(&int, depth)),
+                    ],
+                },
+                Type::Named(_, name, _) if name == "Shape" => match self.rng.below(3) {
+                    0 => construct("Circle", vec![self.expr(&float, depth)]),
+                    1 => construct("Rect", vec![self.expr(&int, depth), self.expr(&int, depth)]),
+                    _ => construct("Empty", vec![]),
+                },
+                Type::Named(_, _, args) => construct("Some", vec![self.expr(&args[0], depth)]),
+                _ => self.leaf(ty),
+            }
+        }
+    }
+
+    // A program made up from `seed`, which is the same every time
+    fn generate(seed: u64) -> JustExpr {
+        let mut generator = Generator {
+            rng: Rng::new(seed),
+            vars: vec![],
+            names: 0,
+        };
+        let ty = generator.ty(2);
+
+        generator.expr(&ty, 5)
+    }
+
+    // Runs `f` for every seed, or only the one in `MIZZLE_SEED`
+    fn for_each_program(cases: u64, f: impl Fn(u64, &JustExpr, &str)) {
+        let seeds = match env::var("MIZZLE_SEED") {
+            Ok(seed) => vec![seed.parse().unwrap()],
+            Err(_) => (0..cases).collect(),
+        };
+
+        for seed in seeds {
+            let e = generate(seed);
+            let source = format!("{}\n{}", DECLS, e);
+
+            f(seed, &e, &source);
+        }
+    }
+
+    // Trees of every kind that the grammar can read, without caring whether they make sense,
+    // For checking that printing them gives back the same tree
+    struct Syntax {
+        rng: Rng,
+    }
+
+    impl Syntax {
+        fn ident(&mut self) -> String {
+            (*self.rng.pick(&["x", "y", "items", "f", "a1", "is_done"])).into()
+        }
+
+        fn type_ident(&mut self) -> String {
+            (*self.rng.pick(&["Point", "Shape", "Some", "None", "A"])).into()
+        }
+
+        fn some<T>(&mut self, min: usize, item: impl FnMut(&mut Syntax) -> T) -> Vec<T> {
+            let count = min + self.rng.below(3);
+            let mut item = item;
+
+            (0..count).map(|_| item(self)).collect()
+        }
+
+        fn ty(&mut self, depth: usize) -> JustType {
+            let leaves = 6;
+            match self.rng.below(if depth == 0 { leaves } else { leaves + 4 }) {
+                0 => Type::Unit(()),
+                1 => Type::Int(()),
+                2 => Type::Bool(()),
+                3 => Type::Float(()),
+                4 => Type::String(()),
+                5 => Type::Var((), self.ident()),
+                6 => Type::Tuple((), self.some(2, |s| s.ty(depth - 1))),
+                7 => Type::Array((), Box::new(self.ty(depth - 1))),
+                8 => Type::Function(
+                    (),
+                    self.some(0, |s| s.ty(depth - 1)),
+                    Box::new(self.ty(depth - 1)),
+                ),
+                _ => {
+                    let name = self.type_ident();
+                    Type::Named((), name, self.some(0, |s| s.ty(depth - 1)))
+                }
+            }
+        }
+
+        fn pattern(&mut self, depth: usize) -> Pattern<()> {
+            let leaves = 4;
+            match self.rng.below(if depth == 0 { leaves } else { leaves + 2 }) {
+                0 => Pattern::Var((), self.ident()),
+                1 => Pattern::Wildcard(()),
+                2 => Pattern::Bool((), self.rng.chance(50)),
+                3 => Pattern::Int((), self.rng.next() as i64 >> self.rng.below(64)),
+                4 => Pattern::Tuple((), self.some(2, |s| s.pattern(depth - 1))),
+                _ => {
+                    let name = self.type_ident();
+                    Pattern::Constructor((), name, self.some(0, |s| s.pattern(depth - 1)))
+                }
+            }
+        }
+
+        fn fields(&mut self, depth: usize) -> Vec<ast::FieldValue<()>> {
+            self.some(1, |s| {
+                let name = s.ident();
+                field(&name, s.expr(depth))
+            })
+        }
+
+        fn expr(&mut self, depth: usize) -> JustExpr {
+            let leaves = 7;
+            if depth == 0 {
+                let kind = self.rng.below(leaves);
+                return self.leaf(kind);
+            }
+
+            let d = depth - 1;
+            match self.rng.below(leaves + 23) {
+                n if n < leaves => self.leaf(n),
+                7 => Expr::TypeAnno {
+                    extra: (),
+                    term: boxed(self.expr(d)),
+                    ty: self.ty(2),
+                },
+                8 => Expr::IfFlow {
+                    extra: (),
+                    cond: boxed(self.expr(d)),
+                    on_true: boxed(self.expr(d)),
+                    on_false: boxed(self.expr(d)),
+                },
+                9..=11 => {
+                    let op = *self.rng.pick(&[
+                        BinOp::Concat,
+                        BinOp::Add,
+                        BinOp::Sub,
+                        BinOp::Mul,
+                        BinOp::Div,
+                        BinOp::Rem,
+                        BinOp::Eq,
+                        BinOp::Ne,
+                        BinOp::Lt,
+                        BinOp::Le,
+                        BinOp::Gt,
+                        BinOp::Ge,
+                    ]);
+                    bin_op(op, self.expr(d), self.expr(d))
+                }
+                12 => Expr::Builtin {
+                    extra: (),
+                    builtin: *self.rng.pick(&[
+                        Builtin::Len,
+                        Builtin::Length,
+                        Builtin::ToFloat,
+                        Builtin::ToInt,
+                        Builtin::Print,
+                        Builtin::Debug,
+                    ]),
+                    arg: boxed(self.expr(d)),
+                },
+                13 => Expr::Tuple {
+                    extra: (),
+                    items: self.some(2, |s| s.expr(d)),
+                },
+                14 => Expr::Project {
+                    extra: (),
+                    term: boxed(self.expr(d)),
+                    index: self.rng.below(12),
+                },
+                15 => Expr::Let {
+                    extra: (),
+                    pattern: self.pattern(2),
+                    value: boxed(self.expr(d)),
+                    body: boxed(self.expr(d)),
+                },
+                16 => Expr::Record {
+                    extra: (),
+                    name: self.type_ident(),
+                    fields: self.fields(d),
+                },
+                17 => Expr::Field {
+                    extra: (),
+                    term: boxed(self.expr(d)),
+                    field: self.ident(),
+                },
+                18 => Expr::Update {
+                    extra: (),
+                    term: boxed(self.expr(d)),
+                    fields: self.fields(d),
+                },
+                19 => {
+                    let name = self.type_ident();
+                    construct(&name, self.some(0, |s| s.expr(d)))
+                }
+                20 => Expr::Match {
+                    extra: (),
+                    term: boxed(self.expr(d)),
+                    arms: self.some(1, |s| ast::Arm {
+                        extra: (),
+                        pattern: s.pattern(2),
+                        body: s.expr(d),
+                    }),
+                },
+                21 => {
+                    let name = self.ident();
+                    call(&name, self.some(0, |s| s.expr(d)))
+                }
+                22 => Expr::Seq {
+                    extra: (),
+                    first: boxed(self.expr(d)),
+                    second: boxed(self.expr(d)),
+                },
+                23 => Expr::VarDecl {
+                    extra: (),
+                    name: self.ident(),
+                    value: boxed(self.expr(d)),
+                    body: boxed(self.expr(d)),
+                },
+                24 => Expr::Assign {
+                    extra: (),
+                    name: self.ident(),
+                    value: boxed(self.expr(d)),
+                },
+                25 => Expr::While {
+                    extra: (),
+                    cond: boxed(self.expr(d)),
+                    body: boxed(self.expr(d)),
+                },
+                26 => Expr::Array {
+                    extra: (),
+                    items: self.some(0, |s| s.expr(d)),
+                },
+                27 => Expr::Repeat {
+                    extra: (),
+                    length: boxed(self.expr(d)),
+                    value: boxed(self.expr(d)),
+                },
+                28 => Expr::Index {
+                    extra: (),
+                    term: boxed(self.expr(d)),
+                    index: boxed(self.expr(d)),
+                },
+                _ => Expr::SetIndex {
+                    extra: (),
+                    term: boxed(self.expr(d)),
+                    index: boxed(self.expr(d)),
+                    value: boxed(self.expr(d)),
+                },
+            }
+        }
+
+        fn leaf(&mut self, kind: usize) -> JustExpr {
+            match kind {
+                0 => Expr::UnitLit(()),
+                1 => Expr::IntLit((), self.rng.next() as i64 >> self.rng.below(64)),
+                2 => Expr::BoolLit((), self.rng.chance(50)),
+                3 => {
+                    let floats = [
+                        0.0,
+                        -0.0,
+                        0.5,
+                        -2.25,
+                        1e20,
+                        -1e-7,
+                        f64::MAX,
+                        f64::MIN,
+                        f64::MIN_POSITIVE,
+                        // The smallest float above zero, which is less precise than the others
+                        5e-324,
+                    ];
+
+                    Expr::FloatLit((), self.float(&floats))
+                }
+                4 => Expr::StringLit((), self.string()),
+                5 => Expr::Break(()),
+                _ => Expr::Var((), self.ident()),
+            }
+        }
+
+        // One of `common`, or any float from its bits, which has an exponent anywhere in the whole range
+        // Infinity and NaN cannot be written as literals, since `1e400` does not parse
+        fn float(&mut self, common: &[f64]) -> f64 {
+            if self.rng.chance(50) {
+                return *self.rng.pick(common);
+            }
+
+            loop {
+                let x = f64::from_bits(self.rng.next());
+                if x.is_finite() {
+                    return x;
+                }
+            }
+        }
+
+        fn string(&mut self) -> String {
+            let chars = ['a', ' ', '#', '"', '\\', '\n', '\t', '\r', '\0', '💧'];
+            (0..self.rng.below(4))
+                .map(|_| *self.rng.pick(&chars))
+                .collect()
+        }
+
+        fn type_params(&mut self) -> Vec<String> {
+            if self.rng.chance(50) {
+                vec![]
+            } else {
+                self.some(1, |s| s.ident())
+            }
+        }
+
+        fn decl(&mut self) -> ast::Decl<()> {
+            let public = self.rng.chance(30);
+            let name = self.type_ident();
+
+            match self.rng.below(4) {
+                0 => ast::Decl::Record {
+                    extra: (),
+                    public,
+                    name,
+                    type_params: self.type_params(),
+                    fields: self.some(1, |s| ast::FieldDecl {
+                        extra: (),
+                        name: s.ident(),
+                        ty: s.ty(2),
+                    }),
+                },
+                1 => ast::Decl::Variant {
+                    extra: (),
+                    public,
+                    name,
+                    type_params: self.type_params(),
+                    constructors: self.some(1, |s| ast::ConstructorDecl {
+                        extra: (),
+                        name: s.type_ident(),
+                        args: s.some(0, |s| s.ty(2)),
+                    }),
+                },
+                2 => ast::Decl::Function {
+                    extra: (),
+                    public,
+                    name: self.ident(),
+                    type_params: self.type_params(),
+                    params: self.some(0, |s| ast::Param {
+                        extra: (),
+                        name: s.ident(),
+                        ty: s.ty(2),
+                    }),
+                    result: self.ty(2),
+                    body: self.expr(3),
+                },
+                _ => ast::Decl::Test {
+                    extra: (),
+                    name: self.string(),
+                    body: self.expr(3),
+                },
+            }
+        }
+
+        fn program(&mut self) -> ast::Program<()> {
+            ast::Program {
+                imports: self.some(0, |s| ast::Import {
+                    extra: (),
+                    name: s.ident(),
+                }),
+                decls: self.some(0, |s| s.decl()),
+                body: self.expr(5),
+            }
+        }
+    }
+
+    // Spans are left out, since they are the only thing that differs between the trees
+    fn shape(e: &ast::Expr<ast::Span>) -> String {
+        format!("{:?}", e.map_extra(&|_| ()))
+    }
+
+    #[test]
+    fn generated_programs_check_and_read_back() {
+        for_each_program(300, |seed, e, source| {
+            let parsed = syntax::ProgramParser::new()
+                .parse(FileId(1), source)
+                .unwrap_or_else(|err| {
+                    panic!("seed {} does not parse, {:?}:\n{}", seed, err, source)
+                });
+            assert_eq!(
+                shape(&parsed.body),
+                format!("{:?}", e),
+                "seed {} reads back as a different tree:\n{}",
+                seed,
+                source
+            );
+
+            let (modules, _) = backend_test::load(source);
+            let checked = modules::check(&modules);
+            assert!(
+                checked.result.is_ok(),
+                "seed {} does not check, {:?}:\n{}",
+                seed,
+                checked.result.unwrap_err(),
+                source
+            );
+        });
+    }
+
+    #[test]
+    fn generated_programs_run_the_same_optimized() {
+        let run = |program: &ast::TypedProgram, sources: &SourceMap| {
+            let output = Arc::new(Mutex::new(vec![]));
+            let result = wasm::run(&wasm::ast_to_wasm(program), sources, output.clone());
+            let printed = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+
+            (result, printed)
+        };
+
+        for_each_program(50, |seed, _, source| {
+            let (typed, sources) = compile(source);
+
+            // Stopping with an error is fine, only panicking is not
+            let ran = run(&typed, &sources);
+            let optimized = run(&optimize::optimize_program(&typed), &sources);
+            assert_eq!(
+                ran, optimized,
+                "seed {} gives something else when optimized:\n{}",
+                seed, source
+            );
+        });
+    }
+
+    #[test]
+    fn printed_trees_read_back_the_same() {
+        let seeds = match env::var("MIZZLE_SEED") {
+            Ok(seed) => vec![seed.parse().unwrap()],
+            Err(_) => (0..1000).collect(),
+        };
+
+        for seed in seeds {
+            let program = Syntax {
+                rng: Rng::new(seed),
+            }
+            .program();
+            let source = program.to_string();
+
+            let parsed = syntax::ProgramParser::new()
+                .parse(FileId(1), &source)
+                .unwrap_or_else(|err| {
+                    panic!("seed {} does not parse, {:?}:\n{}", seed, err, source)
+                });
+            let decls = parsed
+                .decls
+                .iter()
+                .map(|d| d.map_extra(&|_| ()))
+                .collect::<Vec<_>>();
+            let imports = parsed.imports.iter().map(|i| &i.name).collect::<Vec<_>>();
+            assert_eq!(
+                (format!("{:?}", decls), shape(&parsed.body)),
+                (
+                    format!("{:?}", program.decls),
+                    format!("{:?}", program.body)
+                ),
+                "seed {} reads back as a different tree:\n{}",
+                seed,
+                source
+            );
+            assert_eq!(
+                imports,
+                program.imports.iter().map(|i| &i.name).collect::<Vec<_>>()
+            );
+            assert!(
+                !source.contains('\t'),
+                "seed {} prints a tab:\n{}",
+                seed,
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn prints_only_the_parentheses_needed() {
+        let cases = [
+            ("(1 + (2 * 3))", "1 + 2 * 3"),
+            ("(1 + 2) * 3", "(1 + 2) * 3"),
+            ("(1 - 2) - 3", "1 - 2 - 3"),
+            ("1 - (2 - 3)", "1 - (2 - 3)"),
+            ("(a ++ b) == (c ++ d)", "a ++ b == c ++ d"),
+            ("(1 < 2) == true", "(1 < 2) == true"),
+            (
+                "(if a then 1 else 2 end): int",
+                "if a then\n    1\nelse\n    2\nend: int",
+            ),
+            ("(x: int) + 1", "(x: int) + 1"),
+            ("((x := 1): unit)", "(x := 1): unit"),
+            ("x := (y: int)", "x := y: int"),
+            ("(x := 1); (y := 2)", "x := 1;\ny := 2"),
+            ("(a; b); c", "(a;\nb);\nc"),
+            ("(f(x).0)[1]", "f(x).0[1]"),
+            ("(1).0", "(1).0"),
+            ("(a + b).x", "(a + b).x"),
+        ];
+
+        for (source, printed) in cases {
+            let parsed = syntax::TermParser::new().parse(FileId(1), source).unwrap();
+            assert_eq!(parsed.to_string(), printed, "printing {}", source);
+        }
+    }
+}
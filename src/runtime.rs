@@ -0,0 +1,1186 @@
+use std::collections::HashMap;
+
+use parity_wasm::elements::{BlockType, Instruction, ValueType};
+
+// Functions that generated code relies on, written directly as wasm instructions
+// Their names contain a `.` so they can never clash with names from the source
+
+// Index of the mutable global that holds the next free address in linear memory
+pub const HEAP: u32 = 0;
+
+// Mutable globals that `rt.check_index` fills in before stopping the program,
+// So that the host can tell what went wrong and where
+pub const ERROR_START: u32 = 1;
+pub const ERROR_END: u32 = 2;
+pub const ERROR_INDEX: u32 = 3;
+pub const ERROR_LENGTH: u32 = 4;
+pub const ERROR_FILE: u32 = 5;
+
+// Address `0` is never handed out, so it can never be mistaken for a real value
+pub const DATA_START: u32 = 8;
+
+// Linear memory grows in pages of 64 KiB
+pub const PAGE_SIZE: u32 = 1 << 16;
+
+pub const ALLOC: &str = "rt.alloc";
+pub const COPY: &str = "rt.copy";
+pub const CONCAT: &str = "rt.concat";
+pub const STR_EQ: &str = "rt.str_eq";
+pub const NEW_ARRAY: &str = "rt.new_array";
+pub const CHECK_INDEX: &str = "rt.check_index";
+pub const DIGITS: &str = "rt.digits";
+pub const WRITE_DIGITS: &str = "rt.write_digits";
+pub const WRITE_POINT: &str = "rt.write_point";
+pub const SCALE: &str = "rt.scale";
+pub const ROUND_SCALED: &str = "rt.round_scaled";
+pub const SHOW_INT: &str = "rt.show_int";
+pub const SHOW_FLOAT: &str = "rt.show_float";
+pub const ESCAPE: &str = "rt.escape";
+
+pub struct Function {
+    pub params: Vec<ValueType>,
+    pub result: Option<ValueType>,
+    pub locals: Vec<ValueType>,
+    // Does not include the final `End`
+    pub body: Vec<Instruction>,
+}
+
+// Strings are a pointer to a 4 byte length, directly followed by the bytes of the string
+pub fn string_bytes(s: &str) -> Vec<u8> {
+    let mut bytes = (s.len() as u32).to_le_bytes().to_vec();
+    bytes.extend(s.as_bytes());
+
+    bytes
+}
+
+// Rounds `n` up to the closest multiple of 8
+pub fn align(n: u32) -> u32 {
+    (n + 7) & !7
+}
+
+// `functions` maps every function name to its index in the module
+pub fn functions(functions: &HashMap<String, u32>) -> Vec<Function> {
+    use Instruction::*;
+
+    vec![
+        // Bump allocator, memory is never freed
+        // alloc(size: i32) -> i32
+        Function {
+            params: vec![ValueType::I32],
+            result: Some(ValueType::I32),
+            locals: vec![ValueType::I32],
+            body: vec![
+                GetGlobal(HEAP),
+                SetLocal(1),
+                GetLocal(1),
+                GetLocal(0),
+                I32Add,
+                I32Const(7),
+                I32Add,
+                I32Const(!7),
+                I32And,
+                SetGlobal(HEAP),
+                // Grow memory one page at a time, until the new heap pointer fits
+                Block(BlockType::NoResult),
+                Loop(BlockType::NoResult),
+                GetGlobal(HEAP),
+                CurrentMemory(0),
+                I32Const(16),
+                I32Shl,
+                I32LeU,
+                BrIf(1),
+                I32Const(1),
+                GrowMemory(0),
+                I32Const(-1),
+                I32Eq,
+                If(BlockType::NoResult),
+                Unreachable,
+                End,
+                Br(0),
+                End,
+                End,
+                GetLocal(1),
+            ],
+        },
+        // copy(destination: i32, source: i32, length: i32)
+        Function {
+            params: vec![ValueType::I32, ValueType::I32, ValueType::I32],
+            result: None,
+            locals: vec![ValueType::I32],
+            body: vec![
+                Block(BlockType::NoResult),
+                Loop(BlockType::NoResult),
+                GetLocal(3),
+                GetLocal(2),
+                I32GeU,
+                BrIf(1),
+                GetLocal(0),
+                GetLocal(3),
+                I32Add,
+                GetLocal(1),
+                GetLocal(3),
+                I32Add,
+                I32Load8U(0, 0),
+                I32Store8(0, 0),
+                GetLocal(3),
+                I32Const(1),
+                I32Add,
+                SetLocal(3),
+                Br(0),
+                End,
+                End,
+            ],
+        },
+        // concat(left: i32, right: i32) -> i32
+        Function {
+            params: vec![ValueType::I32, ValueType::I32],
+            result: Some(ValueType::I32),
+            locals: vec![ValueType::I32],
+            body: vec![
+                GetLocal(0),
+                I32Load(2, 0),
+                GetLocal(1),
+                I32Load(2, 0),
+                I32Add,
+                I32Const(4),
+                I32Add,
+                Call(functions[ALLOC]),
+                SetLocal(2),
+                // Length
+                GetLocal(2),
+                GetLocal(0),
+                I32Load(2, 0),
+                GetLocal(1),
+                I32Load(2, 0),
+                I32Add,
+                I32Store(2, 0),
+                // Left bytes
+                GetLocal(2),
+                I32Const(4),
+                I32Add,
+                GetLocal(0),
+                I32Const(4),
+                I32Add,
+                GetLocal(0),
+                I32Load(2, 0),
+                Call(functions[COPY]),
+                // Right bytes
+                GetLocal(2),
+                I32Const(4),
+                I32Add,
+                GetLocal(0),
+                I32Load(2, 0),
+                I32Add,
+                GetLocal(1),
+                I32Const(4),
+                I32Add,
+                GetLocal(1),
+                I32Load(2, 0),
+                Call(functions[COPY]),
+                GetLocal(2),
+            ],
+        },
+        // str_eq(left: i32, right: i32) -> i32
+        Function {
+            params: vec![ValueType::I32, ValueType::I32],
+            result: Some(ValueType::I32),
+            locals: vec![ValueType::I32, ValueType::I32],
+            body: vec![
+                GetLocal(0),
+                I32Load(2, 0),
+                SetLocal(3),
+                GetLocal(3),
+                GetLocal(1),
+                I32Load(2, 0),
+                I32Ne,
+                If(BlockType::NoResult),
+                I32Const(0),
+                Return,
+                End,
+                Block(BlockType::NoResult),
+                Loop(BlockType::NoResult),
+                GetLocal(2),
+                GetLocal(3),
+                I32GeU,
+                BrIf(1),
+                // The offset of `4` skips over the lengths
+                GetLocal(0),
+                GetLocal(2),
+                I32Add,
+                I32Load8U(0, 4),
+                GetLocal(1),
+                GetLocal(2),
+                I32Add,
+                I32Load8U(0, 4),
+                I32Ne,
+                If(BlockType::NoResult),
+                I32Const(0),
+                Return,
+                End,
+                GetLocal(2),
+                I32Const(1),
+                I32Add,
+                SetLocal(2),
+                Br(0),
+                End,
+                End,
+                I32Const(1),
+            ],
+        },
+        // Arrays are a pointer to their length, followed by one slot for each item
+        // new_array(length: i64) -> i32
+        Function {
+            params: vec![ValueType::I64],
+            result: Some(ValueType::I32),
+            locals: vec![ValueType::I32, ValueType::I32],
+            body: vec![
+                GetLocal(0),
+                I64Const(0),
+                I64LtS,
+                If(BlockType::NoResult),
+                I64Const(0),
+                SetLocal(0),
+                End,
+                GetLocal(0),
+                I32WrapI64,
+                SetLocal(1),
+                // One extra slot for the length
+                GetLocal(1),
+                I32Const(1),
+                I32Add,
+                I32Const(3),
+                I32Shl,
+                Call(functions[ALLOC]),
+                SetLocal(2),
+                GetLocal(2),
+                GetLocal(1),
+                I32Store(2, 0),
+                GetLocal(2),
+            ],
+        },
+        // `file`, `start` and `end` are the span of the index in the source, for the error message
+        // check_index(array: i32, index: i64, file: i32, start: i32, end: i32)
+        Function {
+            params: vec![
+                ValueType::I32,
+                ValueType::I64,
+                ValueType::I32,
+                ValueType::I32,
+                ValueType::I32,
+            ],
+            result: None,
+            locals: vec![],
+            body: vec![
+                // Negative indices are huge when compared unsigned, so one comparison is enough
+                GetLocal(1),
+                GetLocal(0),
+                I32Load(2, 0),
+                I64ExtendUI32,
+                I64LtU,
+                If(BlockType::NoResult),
+                Return,
+                End,
+                GetLocal(2),
+                SetGlobal(ERROR_FILE),
+                GetLocal(3),
+                SetGlobal(ERROR_START),
+                GetLocal(4),
+                SetGlobal(ERROR_END),
+                GetLocal(1),
+                SetGlobal(ERROR_INDEX),
+                GetLocal(0),
+                I32Load(2, 0),
+                SetGlobal(ERROR_LENGTH),
+                Unreachable,
+            ],
+        },
+        // The functions below format values as strings, for targets where the host cannot read memory
+        // The number of decimal digits in `n`, which is treated as unsigned
+        // digits(n: i64) -> i32
+        Function {
+            params: vec![ValueType::I64],
+            result: Some(ValueType::I32),
+            locals: vec![ValueType::I32],
+            body: vec![
+                I32Const(1),
+                SetLocal(1),
+                Block(BlockType::NoResult),
+                Loop(BlockType::NoResult),
+                GetLocal(0),
+                I64Const(10),
+                I64LtU,
+                BrIf(1),
+                GetLocal(0),
+                I64Const(10),
+                I64DivU,
+                SetLocal(0),
+                GetLocal(1),
+                I32Const(1),
+                I32Add,
+                SetLocal(1),
+                Br(0),
+                End,
+                End,
+                GetLocal(1),
+            ],
+        },
+        // Writes the last `count` digits of `n` at `address`, with zeros in front when `n` has fewer
+        // write_digits(address: i32, n: i64, count: i32)
+        Function {
+            params: vec![ValueType::I32, ValueType::I64, ValueType::I32],
+            result: None,
+            locals: vec![],
+            body: vec![
+                Block(BlockType::NoResult),
+                Loop(BlockType::NoResult),
+                GetLocal(2),
+                I32Eqz,
+                BrIf(1),
+                GetLocal(2),
+                I32Const(1),
+                I32Sub,
+                SetLocal(2),
+                GetLocal(0),
+                GetLocal(2),
+                I32Add,
+                GetLocal(1),
+                I64Const(10),
+                I64RemU,
+                I32WrapI64,
+                I32Const(b'0' as i32),
+                I32Add,
+                I32Store8(0, 0),
+                GetLocal(1),
+                I64Const(10),
+                I64DivU,
+                SetLocal(1),
+                Br(0),
+                End,
+                End,
+            ],
+        },
+        // Same as `write_digits`, with a `.` after the first `point` digits unless that is all of them,
+        // Returns the address after what was written
+        // write_point(address: i32, n: i64, count: i32, point: i32) -> i32
+        Function {
+            params: vec![
+                ValueType::I32,
+                ValueType::I64,
+                ValueType::I32,
+                ValueType::I32,
+            ],
+            result: Some(ValueType::I32),
+            locals: vec![ValueType::I64],
+            body: vec![
+                GetLocal(3),
+                GetLocal(2),
+                I32GeS,
+                If(BlockType::NoResult),
+                GetLocal(0),
+                GetLocal(1),
+                GetLocal(2),
+                Call(functions[WRITE_DIGITS]),
+                GetLocal(0),
+                GetLocal(2),
+                I32Add,
+                Return,
+                End,
+                // The digits after the point are the remainder of this power of ten
+                F64Const(1f64.to_bits()),
+                GetLocal(2),
+                GetLocal(3),
+                I32Sub,
+                Call(functions[SCALE]),
+                I64TruncSF64,
+                SetLocal(4),
+                GetLocal(0),
+                GetLocal(1),
+                GetLocal(4),
+                I64DivU,
+                GetLocal(3),
+                Call(functions[WRITE_DIGITS]),
+                GetLocal(0),
+                GetLocal(3),
+                I32Add,
+                I32Const(b'.' as i32),
+                I32Store8(0, 0),
+                GetLocal(0),
+                GetLocal(3),
+                I32Add,
+                I32Const(1),
+                I32Add,
+                GetLocal(1),
+                GetLocal(4),
+                I64RemU,
+                GetLocal(2),
+                GetLocal(3),
+                I32Sub,
+                Call(functions[WRITE_DIGITS]),
+                GetLocal(0),
+                GetLocal(2),
+                I32Add,
+                I32Const(1),
+                I32Add,
+            ],
+        },
+        // Multiplies `x` by ten to the power of `n`, negative powers divide instead,
+        // Since powers of ten up to 1e22 are exact this gives the closest float to the exact result
+        // scale(x: f64, n: i32) -> f64
+        Function {
+            params: vec![ValueType::F64, ValueType::I32],
+            result: Some(ValueType::F64),
+            locals: vec![ValueType::F64, ValueType::I32],
+            body: vec![
+                // Larger powers are applied in steps, so huge and tiny numbers do not overflow
+                Block(BlockType::NoResult),
+                Loop(BlockType::NoResult),
+                GetLocal(1),
+                I32Const(22),
+                I32LeS,
+                BrIf(1),
+                GetLocal(0),
+                F64Const(1e22f64.to_bits()),
+                F64Mul,
+                SetLocal(0),
+                GetLocal(1),
+                I32Const(22),
+                I32Sub,
+                SetLocal(1),
+                Br(0),
+                End,
+                End,
+                Block(BlockType::NoResult),
+                Loop(BlockType::NoResult),
+                GetLocal(1),
+                I32Const(-22),
+                I32GeS,
+                BrIf(1),
+                GetLocal(0),
+                F64Const(1e22f64.to_bits()),
+                F64Div,
+                SetLocal(0),
+                GetLocal(1),
+                I32Const(22),
+                I32Add,
+                SetLocal(1),
+                Br(0),
+                End,
+                End,
+                F64Const(1f64.to_bits()),
+                SetLocal(2),
+                I32Const(0),
+                GetLocal(1),
+                I32Sub,
+                GetLocal(1),
+                GetLocal(1),
+                I32Const(0),
+                I32LtS,
+                Select,
+                SetLocal(3),
+                Block(BlockType::NoResult),
+                Loop(BlockType::NoResult),
+                GetLocal(3),
+                I32Eqz,
+                BrIf(1),
+                GetLocal(2),
+                F64Const(10f64.to_bits()),
+                F64Mul,
+                SetLocal(2),
+                GetLocal(3),
+                I32Const(1),
+                I32Sub,
+                SetLocal(3),
+                Br(0),
+                End,
+                End,
+                GetLocal(0),
+                GetLocal(2),
+                F64Div,
+                GetLocal(0),
+                GetLocal(2),
+                F64Mul,
+                GetLocal(1),
+                I32Const(0),
+                I32LtS,
+                Select,
+            ],
+        },
+        round_scaled(functions),
+        // show_int(n: i64) -> i32
+        Function {
+            params: vec![ValueType::I64],
+            result: Some(ValueType::I32),
+            locals: vec![
+                ValueType::I32,
+                ValueType::I64,
+                ValueType::I32,
+                ValueType::I32,
+            ],
+            body: vec![
+                GetLocal(0),
+                I64Const(0),
+                I64LtS,
+                SetLocal(1),
+                // The magnitude of the smallest `i64` only fits when it is treated as unsigned
+                I64Const(0),
+                GetLocal(0),
+                I64Sub,
+                GetLocal(0),
+                GetLocal(1),
+                Select,
+                SetLocal(2),
+                GetLocal(2),
+                Call(functions[DIGITS]),
+                GetLocal(1),
+                I32Add,
+                SetLocal(3),
+                GetLocal(3),
+                I32Const(4),
+                I32Add,
+                Call(functions[ALLOC]),
+                SetLocal(4),
+                GetLocal(4),
+                GetLocal(3),
+                I32Store(2, 0),
+                GetLocal(1),
+                If(BlockType::NoResult),
+                GetLocal(4),
+                I32Const(b'-' as i32),
+                I32Store8(0, 4),
+                End,
+                GetLocal(4),
+                I32Const(4),
+                I32Add,
+                GetLocal(1),
+                I32Add,
+                GetLocal(2),
+                GetLocal(3),
+                GetLocal(1),
+                I32Sub,
+                Call(functions[WRITE_DIGITS]),
+                GetLocal(4),
+            ],
+        },
+        show_float(functions),
+        // Escapes the same characters as string literals in the source
+        // escape(s: i32) -> i32
+        Function {
+            params: vec![ValueType::I32],
+            result: Some(ValueType::I32),
+            locals: vec![
+                ValueType::I32,
+                ValueType::I32,
+                ValueType::I32,
+                ValueType::I32,
+                ValueType::I32,
+            ],
+            body: [
+                vec![
+                    // Every byte takes at most two
+                    GetLocal(0),
+                    I32Load(2, 0),
+                    I32Const(1),
+                    I32Shl,
+                    I32Const(4),
+                    I32Add,
+                    Call(functions[ALLOC]),
+                    SetLocal(1),
+                    GetLocal(1),
+                    I32Const(4),
+                    I32Add,
+                    SetLocal(3),
+                    Block(BlockType::NoResult),
+                    Loop(BlockType::NoResult),
+                    GetLocal(2),
+                    GetLocal(0),
+                    I32Load(2, 0),
+                    I32GeU,
+                    BrIf(1),
+                    GetLocal(0),
+                    GetLocal(2),
+                    I32Add,
+                    I32Load8U(0, 4),
+                    SetLocal(4),
+                    // The character after the `\`, or `0` when the byte is written as it is
+                    I32Const(0),
+                    SetLocal(5),
+                ],
+                [
+                    (b'\\', b'\\'),
+                    (b'"', b'"'),
+                    (b'\n', b'n'),
+                    (b'\t', b't'),
+                    (b'\r', b'r'),
+                    (b'\0', b'0'),
+                ]
+                .iter()
+                .flat_map(|(byte, escaped)| {
+                    vec![
+                        I32Const(*escaped as i32),
+                        GetLocal(5),
+                        GetLocal(4),
+                        I32Const(*byte as i32),
+                        I32Eq,
+                        Select,
+                        SetLocal(5),
+                    ]
+                })
+                .collect(),
+                vec![
+                    GetLocal(5),
+                    If(BlockType::NoResult),
+                    GetLocal(3),
+                    I32Const(b'\\' as i32),
+                    I32Store8(0, 0),
+                    GetLocal(3),
+                    I32Const(1),
+                    I32Add,
+                    SetLocal(3),
+                    GetLocal(3),
+                    GetLocal(5),
+                    I32Store8(0, 0),
+                    Else,
+                    GetLocal(3),
+                    GetLocal(4),
+                    I32Store8(0, 0),
+                    End,
+                    GetLocal(3),
+                    I32Const(1),
+                    I32Add,
+                    SetLocal(3),
+                    GetLocal(2),
+                    I32Const(1),
+                    I32Add,
+                    SetLocal(2),
+                    Br(0),
+                    End,
+                    End,
+                ],
+                finish(1, 3),
+            ]
+            .concat(),
+        },
+    ]
+}
+
+// In the same order as `functions`
+pub fn names() -> Vec<&'static str> {
+    vec![
+        ALLOC,
+        COPY,
+        CONCAT,
+        STR_EQ,
+        NEW_ARRAY,
+        CHECK_INDEX,
+        DIGITS,
+        WRITE_DIGITS,
+        WRITE_POINT,
+        SCALE,
+        ROUND_SCALED,
+        SHOW_INT,
+        SHOW_FLOAT,
+        ESCAPE,
+    ]
+}
+
+// Writes the bytes of `text` at the address in the local `pos`, and moves it past them
+fn put(pos: u32, text: &str) -> Vec<Instruction> {
+    use Instruction::*;
+
+    text.bytes()
+        .flat_map(|b| {
+            vec![
+                GetLocal(pos),
+                I32Const(b as i32),
+                I32Store8(0, 0),
+                GetLocal(pos),
+                I32Const(1),
+                I32Add,
+                SetLocal(pos),
+            ]
+        })
+        .collect()
+}
+
+// Stores the length of the string at the local `string`, which ends at the local `pos`, and returns it
+fn finish(string: u32, pos: u32) -> Vec<Instruction> {
+    use Instruction::*;
+
+    vec![
+        GetLocal(string),
+        GetLocal(pos),
+        GetLocal(string),
+        I32Sub,
+        I32Const(4),
+        I32Sub,
+        I32Store(2, 0),
+        GetLocal(string),
+    ]
+}
+
+// Splits the float in the local `from` into two halves of 26 bits, which multiply without rounding
+fn split(from: u32, high: u32, low: u32) -> Vec<Instruction> {
+    use Instruction::*;
+
+    vec![
+        GetLocal(from),
+        F64Const(134217729f64.to_bits()),
+        F64Mul,
+        SetLocal(high),
+        GetLocal(high),
+        GetLocal(high),
+        GetLocal(from),
+        F64Sub,
+        F64Sub,
+        SetLocal(high),
+        GetLocal(from),
+        GetLocal(high),
+        F64Sub,
+        SetLocal(low),
+    ]
+}
+
+// The closest integer to `x` times ten to the power of `n`,
+// For `n` from 0 to 22 the rounding error of the multiplication is found exactly and taken into account,
+// Which matters once the product has more digits than a float can hold
+// round_scaled(x: f64, n: i32) -> i64
+fn round_scaled(functions: &HashMap<String, u32>) -> Function {
+    use Instruction::*;
+
+    // Locals after `x` and `n`
+    let (power, product, error, rounded, x_high, x_low, power_high, power_low, fraction) =
+        (2, 3, 4, 5, 6, 7, 8, 9, 10);
+
+    let body = [
+        vec![
+            GetLocal(1),
+            I32Const(0),
+            I32LtS,
+            GetLocal(1),
+            I32Const(22),
+            I32GtS,
+            I32Or,
+            If(BlockType::NoResult),
+            GetLocal(0),
+            GetLocal(1),
+            Call(functions[SCALE]),
+            F64Nearest,
+            I64TruncSF64,
+            Return,
+            End,
+            F64Const(1f64.to_bits()),
+            GetLocal(1),
+            Call(functions[SCALE]),
+            SetLocal(power),
+            GetLocal(0),
+            GetLocal(power),
+            F64Mul,
+            SetLocal(product),
+        ],
+        split(0, x_high, x_low),
+        split(power, power_high, power_low),
+        vec![
+            // The exact product is `product + error`
+            GetLocal(x_high),
+            GetLocal(power_high),
+            F64Mul,
+            GetLocal(product),
+            F64Sub,
+            GetLocal(x_high),
+            GetLocal(power_low),
+            F64Mul,
+            F64Add,
+            GetLocal(x_low),
+            GetLocal(power_high),
+            F64Mul,
+            F64Add,
+            GetLocal(x_low),
+            GetLocal(power_low),
+            F64Mul,
+            F64Add,
+            SetLocal(error),
+            GetLocal(product),
+            F64Nearest,
+            SetLocal(rounded),
+            GetLocal(product),
+            GetLocal(rounded),
+            F64Sub,
+            SetLocal(fraction),
+            // Products too big to have a fraction are rounded by their error,
+            // Otherwise the error only matters when the product is exactly halfway,
+            // Halfway rounds up, like Rust does when two numbers with the fewest digits are as close
+            // The adjustment is added as an integer since the float may not have room for it
+            GetLocal(rounded),
+            I64TruncSF64,
+            GetLocal(error),
+            F64Const(0.5f64.to_bits()),
+            F64Add,
+            F64Floor,
+            F64Const(0),
+            GetLocal(fraction),
+            F64Const(0),
+            F64Eq,
+            Select,
+            F64Const(1f64.to_bits()),
+            F64Const(0),
+            GetLocal(fraction),
+            F64Const(0.5f64.to_bits()),
+            F64Eq,
+            GetLocal(error),
+            F64Const(0),
+            F64Ge,
+            I32And,
+            Select,
+            F64Add,
+            F64Const((-1f64).to_bits()),
+            F64Const(0),
+            GetLocal(fraction),
+            F64Const((-0.5f64).to_bits()),
+            F64Eq,
+            GetLocal(error),
+            F64Const(0),
+            F64Lt,
+            I32And,
+            Select,
+            F64Add,
+            I64TruncSF64,
+            I64Add,
+        ],
+    ]
+    .concat();
+
+    Function {
+        params: vec![ValueType::F64, ValueType::I32],
+        result: Some(ValueType::I64),
+        locals: vec![ValueType::F64; 9],
+        body,
+    }
+}
+
+// Formats floats the same way as `{:?}` does in Rust, like `2.5`, `1e-7` or `NaN`
+// The digits are the fewest that read back as the same number, found by trying every count up to 17,
+// Which matches Rust for plain decimals since the powers of ten used are then exact,
+// Except that 16 digits too large for the 53 bits of a float are checked inexactly and may become 17,
+// Numbers that need an exponent may get a last digit that differs
+// show_float(x: f64) -> i32
+fn show_float(functions: &HashMap<String, u32>) -> Function {
+    use Instruction::*;
+
+    // Locals after `x`
+    let (string, pos, k, p, digits) = (1, 2, 3, 4, 5);
+    let scale_x = |exponent: Vec<Instruction>| {
+        [vec![GetLocal(0)], exponent, vec![Call(functions[SCALE])]].concat()
+    };
+
+    let special_cases = [
+        vec![
+            // Long enough for a sign, 17 digits, a point and either `0.000` or an exponent
+            I32Const(36),
+            Call(functions[ALLOC]),
+            SetLocal(string),
+            GetLocal(string),
+            I32Const(4),
+            I32Add,
+            SetLocal(pos),
+            GetLocal(0),
+            GetLocal(0),
+            F64Ne,
+            If(BlockType::NoResult),
+        ],
+        put(pos, "NaN"),
+        finish(string, pos),
+        vec![
+            Return,
+            End,
+            // The sign bit is also set for `-0.0`
+            GetLocal(0),
+            I64ReinterpretF64,
+            I64Const(0),
+            I64LtS,
+            If(BlockType::NoResult),
+        ],
+        put(pos, "-"),
+        vec![
+            GetLocal(0),
+            F64Neg,
+            SetLocal(0),
+            End,
+            GetLocal(0),
+            F64Const(f64::INFINITY.to_bits()),
+            F64Eq,
+            If(BlockType::NoResult),
+        ],
+        put(pos, "inf"),
+        finish(string, pos),
+        vec![
+            Return,
+            End,
+            GetLocal(0),
+            F64Const(0),
+            F64Eq,
+            If(BlockType::NoResult),
+        ],
+        put(pos, "0.0"),
+        finish(string, pos),
+        vec![Return, End],
+    ]
+    .concat();
+
+    // `k` is the exponent of the first digit, guessed from the binary exponent and then corrected
+    let exponent = [
+        vec![
+            GetLocal(0),
+            I64ReinterpretF64,
+            I64Const(52),
+            I64ShrU,
+            I32WrapI64,
+            I32Const(0x7ff),
+            I32And,
+            I32Const(1023),
+            I32Sub,
+            // Multiplying by log10(2) as a fixed point number
+            I32Const(78913),
+            I32Mul,
+            I32Const(18),
+            I32ShrS,
+            SetLocal(k),
+            Block(BlockType::NoResult),
+            Loop(BlockType::NoResult),
+        ],
+        scale_x(vec![I32Const(0), GetLocal(k), I32Sub]),
+        vec![
+            F64Const(10f64.to_bits()),
+            F64Lt,
+            BrIf(1),
+            GetLocal(k),
+            I32Const(1),
+            I32Add,
+            SetLocal(k),
+            Br(0),
+            End,
+            End,
+            Block(BlockType::NoResult),
+            Loop(BlockType::NoResult),
+        ],
+        scale_x(vec![I32Const(0), GetLocal(k), I32Sub]),
+        vec![
+            F64Const(1f64.to_bits()),
+            F64Ge,
+            BrIf(1),
+            GetLocal(k),
+            I32Const(1),
+            I32Sub,
+            SetLocal(k),
+            Br(0),
+            End,
+            End,
+        ],
+    ]
+    .concat();
+
+    let precision = [
+        vec![
+            Block(BlockType::NoResult),
+            Loop(BlockType::NoResult),
+            GetLocal(p),
+            I32Const(1),
+            I32Add,
+            SetLocal(p),
+        ],
+        vec![
+            GetLocal(0),
+            GetLocal(p),
+            I32Const(1),
+            I32Sub,
+            GetLocal(k),
+            I32Sub,
+            Call(functions[ROUND_SCALED]),
+            SetLocal(digits),
+            GetLocal(p),
+            I32Const(17),
+            I32GeS,
+            BrIf(1),
+            GetLocal(digits),
+            F64ConvertSI64,
+            GetLocal(k),
+            GetLocal(p),
+            I32Sub,
+            I32Const(1),
+            I32Add,
+            Call(functions[SCALE]),
+            GetLocal(0),
+            F64Eq,
+            BrIf(1),
+            Br(0),
+            End,
+            End,
+            // Rounding up can carry into a new digit, like `9.99` to `10.0`
+            GetLocal(digits),
+            Call(functions[DIGITS]),
+            GetLocal(p),
+            I32GtS,
+            If(BlockType::NoResult),
+            GetLocal(digits),
+            I64Const(10),
+            I64DivU,
+            SetLocal(digits),
+            GetLocal(k),
+            I32Const(1),
+            I32Add,
+            SetLocal(k),
+            End,
+            // Rounding can also leave zeros at the end, which are never written
+            Block(BlockType::NoResult),
+            Loop(BlockType::NoResult),
+            GetLocal(p),
+            I32Const(1),
+            I32LeS,
+            BrIf(1),
+            GetLocal(digits),
+            I64Const(10),
+            I64RemU,
+            I64Const(0),
+            I64Ne,
+            BrIf(1),
+            GetLocal(digits),
+            I64Const(10),
+            I64DivU,
+            SetLocal(digits),
+            GetLocal(p),
+            I32Const(1),
+            I32Sub,
+            SetLocal(p),
+            Br(0),
+            End,
+            End,
+        ],
+    ]
+    .concat();
+
+    // Plain decimals from 1e-4 up to 1e16, like Rust
+    let layout = [
+        vec![
+            GetLocal(k),
+            I32Const(-4),
+            I32GeS,
+            GetLocal(k),
+            I32Const(16),
+            I32LtS,
+            I32And,
+            If(BlockType::NoResult),
+            GetLocal(k),
+            I32Const(0),
+            I32GeS,
+            If(BlockType::NoResult),
+            GetLocal(p),
+            GetLocal(k),
+            I32Const(1),
+            I32Add,
+            I32LeS,
+            If(BlockType::NoResult),
+            // Whole numbers are padded with zeros up to the point, which is followed by `.0`
+            GetLocal(pos),
+            GetLocal(digits),
+            F64Const(1f64.to_bits()),
+            GetLocal(k),
+            I32Const(1),
+            I32Add,
+            GetLocal(p),
+            I32Sub,
+            Call(functions[SCALE]),
+            I64TruncSF64,
+            I64Mul,
+            GetLocal(k),
+            I32Const(1),
+            I32Add,
+            Call(functions[WRITE_DIGITS]),
+            GetLocal(pos),
+            GetLocal(k),
+            I32Const(1),
+            I32Add,
+            I32Add,
+            SetLocal(pos),
+        ],
+        put(pos, ".0"),
+        vec![
+            Else,
+            GetLocal(pos),
+            GetLocal(digits),
+            GetLocal(p),
+            GetLocal(k),
+            I32Const(1),
+            I32Add,
+            Call(functions[WRITE_POINT]),
+            SetLocal(pos),
+            End,
+            Else,
+        ],
+        // The zeros between the point and the digits are padding
+        put(pos, "0."),
+        vec![
+            GetLocal(pos),
+            GetLocal(digits),
+            GetLocal(p),
+            GetLocal(k),
+            I32Sub,
+            I32Const(1),
+            I32Sub,
+            Call(functions[WRITE_DIGITS]),
+            GetLocal(pos),
+            GetLocal(p),
+            GetLocal(k),
+            I32Sub,
+            I32Const(1),
+            I32Sub,
+            I32Add,
+            SetLocal(pos),
+            End,
+            Else,
+            GetLocal(pos),
+            GetLocal(digits),
+            GetLocal(p),
+            I32Const(1),
+            Call(functions[WRITE_POINT]),
+            SetLocal(pos),
+        ],
+        put(pos, "e"),
+        vec![GetLocal(k), I32Const(0), I32LtS, If(BlockType::NoResult)],
+        put(pos, "-"),
+        vec![
+            I32Const(0),
+            GetLocal(k),
+            I32Sub,
+            SetLocal(k),
+            End,
+            GetLocal(pos),
+            GetLocal(k),
+            I64ExtendSI32,
+            GetLocal(k),
+            I64ExtendSI32,
+            Call(functions[DIGITS]),
+            Call(functions[WRITE_DIGITS]),
+            GetLocal(pos),
+            GetLocal(k),
+            I64ExtendSI32,
+            Call(functions[DIGITS]),
+            I32Add,
+            SetLocal(pos),
+            End,
+        ],
+        finish(string, pos),
+    ]
+    .concat();
+
+    Function {
+        params: vec![ValueType::F64],
+        result: Some(ValueType::I32),
+        locals: vec![
+            ValueType::I32,
+            ValueType::I32,
+            ValueType::I32,
+            ValueType::I32,
+            ValueType::I64,
+        ],
+        body: [special_cases, exponent, precision, layout].concat(),
+    }
+}
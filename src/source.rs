@@ -0,0 +1,92 @@
+// Which file of the `SourceMap` a span points into
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(pub u32);
+
+// Every source file of a program, so that diagnostics can show the file that a span is in
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+#[derive(Debug)]
+pub struct SourceFile {
+    pub name: String,
+    pub source: String,
+    // The offset where each line starts, so that finding a line does not scan the whole file
+    line_starts: Vec<usize>,
+}
+
+impl SourceMap {
+    pub fn add(&mut self, name: &str, source: &str) -> FileId {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        self.files.push(SourceFile {
+            name: name.into(),
+            source: source.into(),
+            line_starts,
+        });
+
+        FileId(self.files.len() as u32 - 1)
+    }
+
+    pub fn get(&self, file: FileId) -> &SourceFile {
+        &self.files[file.0 as usize]
+    }
+}
+
+impl SourceFile {
+    // The line and column of `offset`, both counted from 0
+    // Offsets past the end are moved to one after the last character of the last line
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.source.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+
+        (line, offset - self.line_starts[line])
+    }
+
+    // Without the newline at the end
+    pub fn line(&self, line: usize) -> &str {
+        let start = self.line_starts[line];
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map(|next| next - 1)
+            .unwrap_or_else(|| self.source.len());
+
+        &self.source[start..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_coversion() {
+        let mut sources = SourceMap::default();
+        let file = sources.add("a.mi", "abc\ndf");
+        let file = sources.get(file);
+
+        assert_eq!(file.position(0), (0, 0));
+        assert_eq!(file.position(2), (0, 2));
+        assert_eq!(file.position(4), (1, 0));
+        assert_eq!(file.position(6), (1, 2));
+        assert_eq!(file.position(20), (1, 2));
+    }
+
+    #[test]
+    fn finds_lines() {
+        let mut sources = SourceMap::default();
+        let a = sources.add("a.mi", "abc\n\nd\n");
+        let b = sources.add("b.mi", "xy");
+
+        assert_eq!(sources.get(a).line(0), "abc");
+        assert_eq!(sources.get(a).line(1), "");
+        assert_eq!(sources.get(a).line(3), "");
+        assert_eq!(sources.get(a).position(5), (2, 0));
+        assert_eq!(sources.get(b).name, "b.mi");
+        assert_eq!(sources.get(b).line(0), "xy");
+    }
+}
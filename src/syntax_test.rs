@@ -1,46 +1,288 @@
 #[cfg(test)]
 mod tests {
+    use crate::source::FileId;
     use crate::syntax;
 
     #[test]
     fn bool_lit() {
-        assert!(syntax::TermParser::new().parse("true").is_ok());
-        assert!(syntax::TermParser::new().parse("false").is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "true").is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "false").is_ok());
     }
 
     #[test]
     fn nat_lit() {
-        assert!(syntax::TermParser::new().parse("0").is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "0").is_ok());
 
         let max = &i64::MAX.to_string();
         let min = &i64::MIN.to_string();
-        assert!(syntax::TermParser::new().parse(max).is_ok());
-        assert!(syntax::TermParser::new().parse(min).is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), max).is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), min).is_ok());
 
         assert!(syntax::TermParser::new()
-            .parse(&((i64::MAX as i128) + 1).to_string())
+            .parse(FileId(0), &((i64::MAX as i128) + 1).to_string())
             .is_err());
         assert!(syntax::TermParser::new()
-            .parse(&((i64::MIN as i128) - 1).to_string())
+            .parse(FileId(0), &((i64::MIN as i128) - 1).to_string())
             .is_err());
     }
 
     #[test]
     fn anno() {
-        assert!(syntax::TermParser::new().parse("1: int").is_ok());
-        assert!(syntax::TermParser::new().parse("int: 1").is_err());
+        assert!(syntax::TermParser::new().parse(FileId(0), "1: int").is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "int: 1")
+            .is_err());
     }
 
     #[test]
     fn if_flow() {
         assert!(syntax::TermParser::new()
-            .parse("if true then 1 else 0 end")
+            .parse(FileId(0), "if true then 1 else 0 end")
+            .is_ok());
+    }
+
+    #[test]
+    fn string_lit() {
+        assert!(syntax::TermParser::new().parse(FileId(0), r#""""#).is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), r#""a \"b\" \\ \n""#)
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), r#""\q""#)
+            .is_err());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "\"a\nb\"")
+            .is_err());
+    }
+
+    #[test]
+    fn concat() {
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), r#""a" ++ "b" ++ "c": string"#)
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), r#"len("a" ++ "b")"#)
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), r#""a" ++"#)
+            .is_err());
+    }
+
+    #[test]
+    fn float_lit() {
+        assert!(syntax::TermParser::new().parse(FileId(0), "1.5").is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "-0.25e-3")
+            .is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "2E10").is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "1.").is_err());
+        assert!(syntax::TermParser::new().parse(FileId(0), ".5").is_err());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "1.7976931348623157e308")
+            .is_ok());
+        // Too small rounds to zero, but too large would be infinity
+        assert!(syntax::TermParser::new().parse(FileId(0), "1e-400").is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "1e400").is_err());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "-2.5e308")
+            .is_err());
+    }
+
+    #[test]
+    fn operators() {
+        assert!(syntax::TermParser::new().parse(FileId(0), "1-2").is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "1 - -2 * 3 % 4")
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "to_float(1) / 2.0 >= 0.5: bool")
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "1 < 2 < 3")
+            .is_err());
+    }
+
+    #[test]
+    fn tuples() {
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "(1, (true, \"a\"))")
+            .is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "t.0.1").is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "t.0.1e5")
+            .is_err());
+        assert!(syntax::TermParser::new().parse(FileId(0), "(1,)").is_err());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "let (a, _) = (1, 2) in a: int")
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), r#"let "a" = 1 in 1"#)
+            .is_err());
+    }
+
+    #[test]
+    fn records() {
+        assert!(syntax::ProgramParser::new()
+            .parse(
+                FileId(0),
+                "type Point = { x: int, y: (int, Point) } Point { x = 1, y = (2, p) }.y"
+            )
+            .is_ok());
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "{ p with x = 1, y = let a = 1 in a }.x")
+            .is_ok());
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "type point = { x: int } 1")
+            .is_err());
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "type P = {} 1")
+            .is_err());
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "1 type P = { x: int }")
+            .is_err());
+    }
+
+    #[test]
+    fn variants() {
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "type Shape = Circle(float) | Rect(int, int) | Empty match Circle(1.0) with | Circle(r) => 1 | _ => 2 end")
             .is_ok());
+        assert!(syntax::ProgramParser::new()
+            .parse(
+                FileId(0),
+                "match (1, true) with (0, true) => 1 | (n, false) => n end"
+            )
+            .is_ok());
+        // The arguments of a constructor have to directly follow its name
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "type S = A (int) 1")
+            .is_err());
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "match 1 with end")
+            .is_err());
     }
 
     #[test]
     fn types() {
-        assert!(syntax::TermParser::new().parse("true").is_ok());
-        assert!(syntax::TermParser::new().parse("1").is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "true").is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "1").is_ok());
+        assert!(syntax::TypeNameParser::new()
+            .parse(FileId(0), "(int, (bool, string))")
+            .is_ok());
+        assert!(syntax::TypeNameParser::new()
+            .parse(FileId(0), "(int)")
+            .is_err());
+        assert!(syntax::TypeNameParser::new()
+            .parse(FileId(0), "Map<string, List<a>>")
+            .is_ok());
+        assert!(syntax::TypeNameParser::new()
+            .parse(FileId(0), "Option<>")
+            .is_err());
+    }
+
+    #[test]
+    fn functions() {
+        assert!(syntax::ProgramParser::new()
+            .parse(
+                FileId(0),
+                "fn add(x: int, y: int): int = x + y end add(1, 2)"
+            )
+            .is_ok());
+        assert!(syntax::ProgramParser::new()
+            .parse(
+                FileId(0),
+                "type Option<a> = Some(a) | None fn id<a>(x: a): a = x end id(None)"
+            )
+            .is_ok());
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "fn zero(): int = 0 end zero()")
+            .is_ok());
+        // The body of a function has to be closed with `end`, generic or not,
+        // Otherwise a program starting with `-1` or `(` would continue it, like `x (1, 2)` or `x -1`
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "fn one(): int = 1 one()")
+            .is_err());
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "fn id<a>(x: a): a = x id(1)")
+            .is_err());
+    }
+
+    #[test]
+    fn sequences() {
+        assert!(syntax::TermParser::new().parse(FileId(0), "()").is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "(); 1; 2: int")
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "let x = 1 in (); x")
+            .is_ok());
+        assert!(syntax::TypeNameParser::new()
+            .parse(FileId(0), "(unit, int)")
+            .is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "1;").is_err());
+    }
+
+    #[test]
+    fn loops() {
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "var i = 0 in while i < 10 do i := i + 1 end; i")
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "while true do if x then break else () end end")
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "x := y := 1")
+            .is_err());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "1 := 2")
+            .is_err());
+    }
+
+    #[test]
+    fn arrays() {
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "[1, 2][0]")
+            .is_ok());
+        assert!(syntax::TermParser::new().parse(FileId(0), "[]").is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "a[0][1] := length(a)")
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "array(3, [])")
+            .is_ok());
+        assert!(syntax::TermParser::new()
+            .parse(FileId(0), "[1, 2,]")
+            .is_err());
+        assert!(syntax::TermParser::new().parse(FileId(0), "a[]").is_err());
+        assert!(syntax::ProgramParser::new()
+            .parse(
+                FileId(0),
+                "fn f(a: array<fn(int, bool) -> int>, g: fn() -> unit): unit = () end 1"
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn modules() {
+        assert!(syntax::ProgramParser::new()
+            .parse(
+                FileId(0),
+                "import math import util pub fn f(x: int): int = x end f(1)"
+            )
+            .is_ok());
+        assert!(syntax::LibraryParser::new()
+            .parse(
+                FileId(0),
+                "import util pub type T = A | B fn f(): int = 1 end"
+            )
+            .is_ok());
+        assert!(syntax::LibraryParser::new().parse(FileId(0), "").is_ok());
+        assert!(syntax::LibraryParser::new().parse(FileId(0), "1").is_err());
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "fn f(): int = 1 end import math 1")
+            .is_err());
+        assert!(syntax::ProgramParser::new()
+            .parse(FileId(0), "pub 1")
+            .is_err());
     }
 }
@@ -0,0 +1,192 @@
+use std::sync::{Arc, Mutex};
+
+use colored::*;
+
+use crate::ast;
+use crate::error_fmt;
+use crate::ir;
+use crate::source::SourceMap;
+use crate::wasm;
+
+// How running a single test went
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Passed,
+    // The test gave back `false`
+    Failed,
+    Error(wasm::RuntimeError),
+}
+
+#[derive(Clone, Debug)]
+pub struct TestRun {
+    pub name: String,
+    // The whole `test` declaration
+    pub span: ast::Span,
+    // The expression of the test, which is pointed at when it fails
+    pub body: ast::Span,
+    pub outcome: Outcome,
+    // What the test wrote with `print` and `debug`, which is only shown when it does not pass
+    pub output: String,
+}
+
+// Runs every test with `filter` in its name, or all of them without a filter,
+// Also gives how many were left out by the filter
+// Each one is an export of the same module, and runs in an instance of its own so that tests never affect each other
+pub fn run_tests(
+    p: &ast::TypedProgram,
+    sources: &SourceMap,
+    filter: Option<&str>,
+) -> (Vec<TestRun>, usize) {
+    let all = p
+        .decls
+        .iter()
+        .filter(|d| matches!(d, ast::Decl::Test { .. }))
+        .collect::<Vec<_>>();
+    let tests = all
+        .iter()
+        .copied()
+        .filter(|d| filter.is_none_or(|f| d.name().contains(f)))
+        .collect::<Vec<_>>();
+
+    let program = wasm::tests_to_wasm(p, &tests);
+    // Compiled once for every test, which is most of the time that running them takes
+    let module = wasm::compile(&program, &wasm::store());
+
+    let runs = tests
+        .iter()
+        .enumerate()
+        .map(|(i, test)| {
+            let output = Arc::new(Mutex::new(vec![]));
+            let result = wasm::run_entry(
+                &program,
+                &module,
+                &ir::test_function(i),
+                sources,
+                output.clone(),
+            );
+            let outcome = match result {
+                Ok(passed) if passed == "true" => Outcome::Passed,
+                Ok(_) => Outcome::Failed,
+                Err(e) => Outcome::Error(e),
+            };
+            let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+
+            match test {
+                ast::Decl::Test { extra, body, .. } => TestRun {
+                    name: test.name().into(),
+                    span: extra.0,
+                    body: body.extra().0,
+                    outcome,
+                    output,
+                },
+                _ => panic!("Internal compiler error"),
+            }
+        })
+        .collect();
+
+    (runs, all.len() - tests.len())
+}
+
+// Prints how each test went, like `test adds (math.mi:3) ... ok`, followed by why the ones that did not pass failed
+// Gives back whether every test passed
+pub fn run(p: &ast::TypedProgram, sources: &SourceMap, filter: Option<&str>) -> bool {
+    let (runs, filtered_out) = run_tests(p, sources, filter);
+
+    println!(
+        "running {} test{}",
+        runs.len(),
+        if runs.len() == 1 { "" } else { "s" }
+    );
+
+    for run in &runs {
+        let file = sources.get(run.span.file);
+        let result = match run.outcome {
+            Outcome::Passed => "ok".green(),
+            _ => "FAILED".bright_red(),
+        };
+
+        println!(
+            "test {} ({}:{}) ... {}",
+            run.name,
+            file.name,
+            file.position(run.span.start).0 + 1,
+            result
+        );
+    }
+
+    let total = runs.len();
+    let failures = runs
+        .into_iter()
+        .filter(|r| r.outcome != Outcome::Passed)
+        .collect::<Vec<_>>();
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+    }
+    for failure in &failures {
+        println!("\n---- {} ----", failure.name);
+        print!("{}", failure.output);
+
+        match &failure.outcome {
+            Outcome::Error(e) => println!("{}", error_fmt::format_runtime_err(e.clone(), sources)),
+            _ => println!(
+                "{}",
+                error_fmt::format_test_failure(&failure.name, failure.body, sources)
+            ),
+        }
+    }
+
+    let passed = failures.is_empty();
+    println!(
+        "\ntest result: {}. {} passed; {} failed; {} filtered out",
+        if passed {
+            "ok".green()
+        } else {
+            "FAILED".bright_red()
+        },
+        total - failures.len(),
+        failures.len(),
+        filtered_out
+    );
+
+    passed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend_test::compile;
+
+    #[test]
+    fn runs_each_test_on_its_own() {
+        let source = r#"
+            fn double(x: int): int = x * 2 end
+            test "doubles" = double(2) == 4 end
+            test "prints" = print(double(1)); double(1) == 3 end
+            test "indexes" = [1, 2][double(1)] == 2 end
+            test "doubles again" = double(double(1)) == 4 end
+            ()
+        "#;
+        let (typed, sources) = compile(source);
+
+        let (runs, filtered_out) = run_tests(&typed, &sources, None);
+        let outcomes = runs.iter().map(|r| &r.outcome).collect::<Vec<_>>();
+        assert_eq!(filtered_out, 0);
+        assert_eq!(outcomes[..2], [&Outcome::Passed, &Outcome::Failed]);
+        assert!(matches!(
+            outcomes[2],
+            Outcome::Error(wasm::RuntimeError::IndexOutOfBounds { index: 2, .. })
+        ));
+        assert_eq!(outcomes[3], &Outcome::Passed);
+        assert_eq!(runs[1].output, "2\n");
+        assert_eq!(
+            &source[runs[1].body.start..runs[1].body.end],
+            "print(double(1)); double(1) == 3"
+        );
+
+        let (runs, filtered_out) = run_tests(&typed, &sources, Some("doubles"));
+        let names = runs.iter().map(|r| r.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["doubles", "doubles again"]);
+        assert_eq!(filtered_out, 2);
+    }
+}
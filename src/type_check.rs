@@ -1,5 +1,10 @@
 use crate::ast;
+use crate::exhaustive;
+use crate::source::FileId;
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 #[derive(Clone, Debug)]
 pub enum TypeError {
@@ -8,36 +13,318 @@ pub enum TypeError {
         got: ast::JustType,
         annotation: ast::JustType,
     },
+    // `span` is the condition
     IfCondMustBeBool {
-        end: usize,
+        span: ast::Span,
+        got: ast::JustType,
+    },
+    WhileCondMustBeBool {
+        span: ast::Span,
         got: ast::JustType,
     },
+    // The variable was bound by `let`, a pattern or a parameter, instead of `var`
+    AssignImmutable {
+        span: ast::Span,
+        name: String,
+    },
+    BreakOutsideLoop {
+        span: ast::Span,
+    },
+    // `span` is the whole `if`
     IfBranchesMustBeSame {
-        start: usize,
+        span: ast::Span,
+        first: ast::JustType,
+        second: ast::JustType,
+    },
+    // For both operators and built-in functions, `name` is what is written in the source
+    ArgumentIncorrect {
+        span: ast::Span,
+        name: String,
+        got: ast::JustType,
+        expected: ast::JustType,
+    },
+    // The operator `name` has no meaning for `got`, `allowed` are the types it works on
+    OperandNotSupported {
+        span: ast::Span,
+        name: String,
+        got: ast::JustType,
+        allowed: Vec<ast::JustType>,
+    },
+    // `int` and `float` are never converted implicitly, `span` is the operand that is an `int`
+    NumericMixing {
+        span: ast::Span,
+        name: String,
+    },
+    // `suggestion` is the closest variable in scope, if one is close enough
+    UnknownVariable {
+        span: ast::Span,
+        name: String,
+        suggestion: Option<String>,
+    },
+    // Either `got` is not a tuple, or it does not have enough items
+    ProjectionIncorrect {
+        span: ast::Span,
+        index: usize,
+        got: ast::JustType,
+    },
+    PatternMismatch {
+        span: ast::Span,
+        pattern: String,
+        got: ast::JustType,
+    },
+    UnknownType {
+        span: ast::Span,
+        name: String,
+        suggestion: Option<String>,
+    },
+    DuplicateType {
+        span: ast::Span,
+        name: String,
+    },
+    // Either in a declaration or in a record expression
+    DuplicateField {
+        span: ast::Span,
+        name: String,
+    },
+    MissingFields {
+        span: ast::Span,
+        record: String,
+        fields: Vec<String>,
+    },
+    UnknownField {
+        span: ast::Span,
+        field: String,
+        record: String,
+        suggestion: Option<String>,
+    },
+    NotARecord {
+        span: ast::Span,
+        got: ast::JustType,
+    },
+    DuplicateConstructor {
+        span: ast::Span,
+        name: String,
+    },
+    UnknownConstructor {
+        span: ast::Span,
+        name: String,
+        suggestion: Option<String>,
+    },
+    // For both constructors and functions
+    ArgumentCount {
+        span: ast::Span,
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    // `span` is the body of the arm that differs from the first one
+    MatchArmsMustBeSame {
+        span: ast::Span,
+        first: ast::JustType,
+        second: ast::JustType,
+    },
+    // `missing` are values that no pattern matches, written like patterns
+    NonExhaustive {
+        span: ast::Span,
+        missing: Vec<String>,
+    },
+    TypeArgumentCount {
+        span: ast::Span,
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    UnknownFunction {
+        span: ast::Span,
+        name: String,
+        suggestion: Option<String>,
+    },
+    DuplicateFunction {
+        span: ast::Span,
+        name: String,
+    },
+    // Either a parameter of a function or a type parameter of a declaration
+    DuplicateParameter {
+        span: ast::Span,
+        name: String,
+    },
+    // `span` is the body of the function
+    ReturnIncorrect {
+        span: ast::Span,
+        name: String,
+        got: ast::JustType,
+        expected: ast::JustType,
+    },
+    // Nothing in the program decides part of the type of this expression, like `None` on its own
+    CannotInfer {
+        span: ast::Span,
+        got: ast::JustType,
+    },
+    // `span` is the item that differs from the first one
+    ArrayItemsMustBeSame {
+        span: ast::Span,
         first: ast::JustType,
         second: ast::JustType,
     },
+    // Something other than an array is indexed, like `(1, 2)[0]`
+    NotAnArray {
+        span: ast::Span,
+        got: ast::JustType,
+    },
+    // A variable that is called like a function, but is not one
+    NotAFunction {
+        span: ast::Span,
+        name: String,
+        got: ast::JustType,
+    },
+    // Two imports both export a function called `name`, `span` is the later `import`
+    ImportConflict {
+        span: ast::Span,
+        name: String,
+        first: String,
+    },
+    // `span` is the body of the test
+    TestMustBeBool {
+        span: ast::Span,
+        name: String,
+        got: ast::JustType,
+    },
+    // Tests are only told apart by their names, so they have to be unique in a module
+    DuplicateTest {
+        span: ast::Span,
+        name: String,
+    },
+    // A generic function that gets back to itself through `span` with `ty` for its type parameter `param`,
+    // Which contains that parameter, so it would have to be compiled for ever larger types
+    PolymorphicRecursion {
+        span: ast::Span,
+        name: String,
+        param: String,
+        ty: ast::JustType,
+    },
 }
 
 impl TypeError {
-    fn first_location(&self) -> usize {
+    // Shown with the error, E0001 is a parse error and E0034 to E0037 are in `modules` and `wasm`,
+    // New errors are given the next free code, so that codes never change
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeError::AnnotationIncorrect { .. } => "E0002",
+            TypeError::IfCondMustBeBool { .. } => "E0003",
+            TypeError::WhileCondMustBeBool { .. } => "E0004",
+            TypeError::AssignImmutable { .. } => "E0005",
+            TypeError::BreakOutsideLoop { .. } => "E0006",
+            TypeError::IfBranchesMustBeSame { .. } => "E0007",
+            TypeError::ArgumentIncorrect { .. } => "E0008",
+            TypeError::NumericMixing { .. } => "E0009",
+            TypeError::UnknownVariable { .. } => "E0010",
+            TypeError::ProjectionIncorrect { .. } => "E0011",
+            TypeError::PatternMismatch { .. } => "E0012",
+            TypeError::UnknownType { .. } => "E0013",
+            TypeError::DuplicateType { .. } => "E0014",
+            TypeError::DuplicateField { .. } => "E0015",
+            TypeError::MissingFields { .. } => "E0016",
+            TypeError::UnknownField { .. } => "E0017",
+            TypeError::NotARecord { .. } => "E0018",
+            TypeError::DuplicateConstructor { .. } => "E0019",
+            TypeError::UnknownConstructor { .. } => "E0020",
+            TypeError::ArgumentCount { .. } => "E0021",
+            TypeError::MatchArmsMustBeSame { .. } => "E0022",
+            TypeError::NonExhaustive { .. } => "E0023",
+            TypeError::TypeArgumentCount { .. } => "E0024",
+            TypeError::UnknownFunction { .. } => "E0025",
+            TypeError::DuplicateFunction { .. } => "E0026",
+            TypeError::DuplicateParameter { .. } => "E0027",
+            TypeError::ReturnIncorrect { .. } => "E0028",
+            TypeError::CannotInfer { .. } => "E0029",
+            TypeError::ArrayItemsMustBeSame { .. } => "E0030",
+            TypeError::NotAnArray { .. } => "E0031",
+            TypeError::NotAFunction { .. } => "E0032",
+            TypeError::ImportConflict { .. } => "E0033",
+            TypeError::TestMustBeBool { .. } => "E0038",
+            TypeError::DuplicateTest { .. } => "E0039",
+            TypeError::PolymorphicRecursion { .. } => "E0040",
+            TypeError::OperandNotSupported { .. } => "E0041",
+        }
+    }
+
+    fn first_location(&self) -> (FileId, usize) {
         match self {
-            TypeError::AnnotationIncorrect { span, .. } => span.0,
-            TypeError::IfCondMustBeBool { end, .. } => *end,
-            TypeError::IfBranchesMustBeSame { start, .. } => *start,
+            TypeError::AnnotationIncorrect { span, .. } => (span.file, span.start),
+            TypeError::IfCondMustBeBool { span, .. } => (span.file, span.end),
+            TypeError::WhileCondMustBeBool { span, .. } => (span.file, span.end),
+            TypeError::AssignImmutable { span, .. } => (span.file, span.start),
+            TypeError::BreakOutsideLoop { span } => (span.file, span.start),
+            TypeError::IfBranchesMustBeSame { span, .. } => (span.file, span.start),
+            TypeError::ArgumentIncorrect { span, .. } => (span.file, span.start),
+            TypeError::OperandNotSupported { span, .. } => (span.file, span.start),
+            TypeError::NumericMixing { span, .. } => (span.file, span.start),
+            TypeError::UnknownVariable { span, .. } => (span.file, span.start),
+            TypeError::ProjectionIncorrect { span, .. } => (span.file, span.start),
+            TypeError::PatternMismatch { span, .. } => (span.file, span.start),
+            TypeError::UnknownType { span, .. } => (span.file, span.start),
+            TypeError::DuplicateType { span, .. } => (span.file, span.start),
+            TypeError::DuplicateField { span, .. } => (span.file, span.start),
+            TypeError::MissingFields { span, .. } => (span.file, span.start),
+            TypeError::UnknownField { span, .. } => (span.file, span.start),
+            TypeError::NotARecord { span, .. } => (span.file, span.start),
+            TypeError::DuplicateConstructor { span, .. } => (span.file, span.start),
+            TypeError::UnknownConstructor { span, .. } => (span.file, span.start),
+            TypeError::ArgumentCount { span, .. } => (span.file, span.start),
+            TypeError::MatchArmsMustBeSame { span, .. } => (span.file, span.start),
+            TypeError::NonExhaustive { span, .. } => (span.file, span.start),
+            TypeError::TypeArgumentCount { span, .. } => (span.file, span.start),
+            TypeError::UnknownFunction { span, .. } => (span.file, span.start),
+            TypeError::DuplicateFunction { span, .. } => (span.file, span.start),
+            TypeError::DuplicateParameter { span, .. } => (span.file, span.start),
+            TypeError::ReturnIncorrect { span, .. } => (span.file, span.start),
+            TypeError::CannotInfer { span, .. } => (span.file, span.start),
+            TypeError::ArrayItemsMustBeSame { span, .. } => (span.file, span.start),
+            TypeError::NotAnArray { span, .. } => (span.file, span.start),
+            TypeError::NotAFunction { span, .. } => (span.file, span.start),
+            TypeError::ImportConflict { span, .. } => (span.file, span.start),
+            TypeError::TestMustBeBool { span, .. } => (span.file, span.start),
+            TypeError::DuplicateTest { span, .. } => (span.file, span.start),
+            TypeError::PolymorphicRecursion { span, .. } => (span.file, span.start),
         }
     }
 }
 
 #[derive(Clone, Debug)]
 pub enum TypeWarning {
-    CondAlways { span: ast::Span, value: bool },
+    // `keyword` is either `if` or `while`, a `while` that is always `true` is fine when it has a `break`
+    CondAlways {
+        span: ast::Span,
+        value: bool,
+        keyword: &'static str,
+    },
+    // The arm of a `match` can never be reached, since the arms before it match everything it does
+    UnreachableArm {
+        span: ast::Span,
+    },
+    // An expression has a value other than `()` that is thrown away,
+    // `by` is what throws it away, either the `;` after it or the `while` loop it is the body of
+    UnusedValue {
+        span: ast::Span,
+        ty: ast::JustType,
+        by: &'static str,
+    },
 }
 
 impl TypeWarning {
-    fn first_location(&self) -> usize {
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeWarning::CondAlways { .. } => "W0001",
+            TypeWarning::UnreachableArm { .. } => "W0002",
+            TypeWarning::UnusedValue { .. } => "W0003",
+        }
+    }
+
+    fn first_location(&self) -> (FileId, usize) {
         match self {
-            TypeWarning::CondAlways { span, .. } => span.0,
+            TypeWarning::CondAlways { span, .. } => (span.file, span.start),
+            TypeWarning::UnreachableArm { span } => (span.file, span.start),
+            TypeWarning::UnusedValue { span, .. } => (span.file, span.start),
         }
     }
 }
@@ -49,7 +336,7 @@ pub enum TypeIssue {
 }
 
 impl TypeIssue {
-    fn first_location(&self) -> usize {
+    fn first_location(&self) -> (FileId, usize) {
         match self {
             TypeIssue::Error(e) => e.first_location(),
             TypeIssue::Warning(w) => w.first_location(),
@@ -67,7 +354,7 @@ impl Eq for TypeIssue {}
 
 impl PartialOrd for TypeIssue {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.first_location().cmp(&other.first_location()))
+        Some(self.cmp(other))
     }
 }
 
@@ -132,7 +419,17 @@ impl<A> Outcome<A> {
         }
     }
 
-    #[allow(dead_code)]
+    // Like `recover_to`, but only used when there is no type yet
+    fn or_recover(self, f: impl FnOnce() -> A) -> Outcome<A> {
+        match self.result {
+            Some(_) => self,
+            None => {
+                let a = f();
+                self.recover_to(a)
+            }
+        }
+    }
+
     fn map<B>(self, f: impl FnOnce(A) -> B) -> Outcome<B> {
         match self.result {
             Some(t) => Outcome {
@@ -194,87 +491,2549 @@ impl<A> Outcome<A> {
     }
 }
 
-fn infer(e: &ast::SpanExpr) -> Outcome<ast::JustType> {
+// Stand-in for an expression that failed to type check, so that checking can continue
+// These trees are never seen outside of this module, since `check` throws away trees with errors
+fn poisoned(e: &ast::SpanExpr, ty: ast::JustType) -> ast::TypedExpr {
+    e.map_extra(&|span| (*span, ty.clone()))
+}
+
+// The type parameters, then the names and types of the fields of each record, by name
+type Records = HashMap<String, (Vec<String>, Vec<(String, ast::JustType)>)>;
+
+// The types of a function declared with `fn`, which can refer to its type parameters
+// `link_name` is what the function is called once every module is linked together
+#[derive(Clone, Debug)]
+struct Signature {
+    type_params: Vec<String>,
+    params: Vec<ast::JustType>,
+    result: ast::JustType,
+    link_name: String,
+}
+
+// The declarations of a module, and its body when it is the main module
+pub type CheckedModule = (Vec<ast::TypedDecl>, Option<ast::TypedExpr>);
+
+// The declarations that a module can use without declaring them itself
+#[derive(Clone, Debug, Default)]
+pub struct Scope {
+    records: Records,
+    variants: exhaustive::Variants,
+    functions: HashMap<String, Signature>,
+}
+
+impl Scope {
+    pub fn function_names(&self) -> impl Iterator<Item = &String> {
+        self.functions.keys()
+    }
+
+    // Adds everything from `other`, replacing declarations with the same name
+    pub fn import(&mut self, other: &Scope) {
+        self.records
+            .extend(other.records.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self.variants
+            .extend(other.variants.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self.functions
+            .extend(other.functions.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+}
+
+// The variables in scope, which are extended functionally so that scopes end by themselves
+// A variable without a type is bound to a value that had errors,
+// So using it is silently skipped instead of reported again
+#[derive(Clone, Debug, Default)]
+struct Env {
+    vars: HashMap<String, Option<ast::JustType>>,
+    // The variables in `vars` that were declared with `var`
+    mutable: HashSet<String>,
+    // Set when the innermost `while` has a `break`, `None` outside of loops
+    breaks: Option<Rc<Cell<bool>>>,
+    // The type parameters of the function being checked
+    type_vars: Vec<String>,
+    // The type parameters and fields of every declared record, these never change while checking
+    records: Rc<Records>,
+    // Same as `records`, but for the constructors of every declared variant
+    variants: Rc<exhaustive::Variants>,
+    functions: Rc<HashMap<String, Signature>>,
+    // What each `Type::Unknown` has turned out to be, indexed by its id
+    // Shared between every `Env`, since an unknown can be found out anywhere in the program
+    unknowns: Rc<RefCell<Vec<Option<ast::JustType>>>>,
+}
+
+impl Env {
+    fn with(&self, name: &str, ty: Option<ast::JustType>) -> Env {
+        let mut env = self.clone();
+        env.vars.insert(name.to_string(), ty);
+        env.mutable.remove(name);
+
+        env
+    }
+
+    fn with_mutable(&self, name: &str, ty: Option<ast::JustType>) -> Env {
+        let mut env = self.with(name, ty);
+        env.mutable.insert(name.to_string());
+
+        env
+    }
+
+    fn type_names(&self) -> impl Iterator<Item = &String> {
+        self.records
+            .keys()
+            .chain(self.variants.keys())
+            .chain(self.type_vars.iter())
+    }
+
+    // The number of type arguments that the declared type `name` takes
+    fn type_arity(&self, name: &str) -> Option<usize> {
+        self.records
+            .get(name)
+            .map(|(params, _)| params.len())
+            .or_else(|| self.variants.get(name).map(|(params, _)| params.len()))
+    }
+
+    // The name and type parameters of the variant that has the constructor `name`,
+    // And the types of the constructor's arguments
+    fn constructor(&self, name: &str) -> Option<(&String, &Vec<String>, &Vec<ast::JustType>)> {
+        self.variants
+            .iter()
+            .find_map(|(ty, (params, constructors))| {
+                constructors
+                    .iter()
+                    .find(|(c, _)| c == name)
+                    .map(|(_, args)| (ty, params, args))
+            })
+    }
+
+    fn constructor_names(&self) -> impl Iterator<Item = &String> {
+        self.variants
+            .values()
+            .flat_map(|(_, cs)| cs)
+            .map(|(c, _)| c)
+    }
+
+    fn fresh(&self) -> ast::JustType {
+        let mut unknowns = self.unknowns.borrow_mut();
+        unknowns.push(None);
+
+        ast::Type::Unknown((), unknowns.len() - 1)
+    }
+
+    // A new unknown for each type parameter, every use of a generic declaration gets its own
+    fn instantiate(&self, type_params: &[String]) -> Vec<ast::JustType> {
+        type_params.iter().map(|_| self.fresh()).collect()
+    }
+
+    // Replaces every unknown that has been found out with what it is
+    fn resolve(&self, ty: &ast::JustType) -> ast::JustType {
+        match ty {
+            ast::Type::Unknown(_, id) => match &self.unknowns.borrow()[*id] {
+                Some(known) => self.resolve(known),
+                None => ty.clone(),
+            },
+            ast::Type::Tuple(_, items) => {
+                ast::Type::Tuple((), items.iter().map(|t| self.resolve(t)).collect())
+            }
+            ast::Type::Array(_, item) => ast::Type::Array((), Box::new(self.resolve(item))),
+            ast::Type::Function(_, params, result) => ast::Type::Function(
+                (),
+                params.iter().map(|t| self.resolve(t)).collect(),
+                Box::new(self.resolve(result)),
+            ),
+            ast::Type::Named(_, name, args) => ast::Type::Named(
+                (),
+                name.clone(),
+                args.iter().map(|t| self.resolve(t)).collect(),
+            ),
+            _ => ty.clone(),
+        }
+    }
+
+    // The parameters and result of a use of `signature`, with new unknowns for its type parameters
+    fn instantiate_signature(&self, signature: &Signature) -> (Vec<ast::JustType>, ast::JustType) {
+        let type_args = self.instantiate(&signature.type_params);
+        let params = signature
+            .params
+            .iter()
+            .map(|p| p.substitute(&signature.type_params, &type_args))
+            .collect();
+
+        (
+            params,
+            signature
+                .result
+                .substitute(&signature.type_params, &type_args),
+        )
+    }
+
+    // Makes `a` and `b` the same type by filling in unknowns, fails when they are different anyway
+    fn unify(&self, a: &ast::JustType, b: &ast::JustType) -> bool {
+        let (a, b) = (self.resolve(a), self.resolve(b));
+
+        match (&a, &b) {
+            (ast::Type::Unknown(_, l), ast::Type::Unknown(_, r)) if l == r => true,
+            (ast::Type::Unknown(_, id), other) | (other, ast::Type::Unknown(_, id)) => {
+                // An unknown can't contain itself, that would be an infinitely large type
+                if occurs(*id, other) {
+                    return false;
+                }
+
+                self.unknowns.borrow_mut()[*id] = Some(other.clone());
+                true
+            }
+            (ast::Type::Tuple(_, l), ast::Type::Tuple(_, r)) => {
+                l.len() == r.len() && l.iter().zip(r).all(|(l, r)| self.unify(l, r))
+            }
+            (ast::Type::Array(_, l), ast::Type::Array(_, r)) => self.unify(l, r),
+            (ast::Type::Function(_, l, l_result), ast::Type::Function(_, r, r_result)) => {
+                l.len() == r.len()
+                    && l.iter().zip(r).all(|(l, r)| self.unify(l, r))
+                    && self.unify(l_result, r_result)
+            }
+            (ast::Type::Named(_, l, l_args), ast::Type::Named(_, r, r_args)) => {
+                l == r && l_args.iter().zip(r_args).all(|(l, r)| self.unify(l, r))
+            }
+            _ => a == b,
+        }
+    }
+}
+
+fn occurs(id: usize, ty: &ast::JustType) -> bool {
+    match ty {
+        ast::Type::Unknown(_, other) => id == *other,
+        ast::Type::Tuple(_, items) | ast::Type::Named(_, _, items) => {
+            items.iter().any(|t| occurs(id, t))
+        }
+        ast::Type::Array(_, item) => occurs(id, item),
+        ast::Type::Function(_, params, result) => {
+            params.iter().any(|t| occurs(id, t)) || occurs(id, result)
+        }
+        _ => false,
+    }
+}
+
+// Combines many outcomes into one, keeping the errors of all of them
+fn all<A>(outcomes: impl Iterator<Item = Outcome<A>>) -> Outcome<Vec<A>> {
+    outcomes.fold(Outcome::new(vec![]), |all, outcome| {
+        all.and_zip(outcome).map(|(mut items, item)| {
+            items.push(item);
+            items
+        })
+    })
+}
+
+// Checks that every name in `ty` refers to a declared type or a type parameter in scope,
+// And that declared types are given the right number of type arguments
+fn check_type(env: &Env, ty: &ast::SpanType) -> Outcome<()> {
+    let unknown = |span: &ast::Span, name: &String| {
+        Outcome::new_err(TypeError::UnknownType {
+            span: *span,
+            name: name.clone(),
+            suggestion: suggest(name, env.type_names()),
+        })
+    };
+
+    match ty {
+        ast::Type::Tuple(_, items) => all(items.iter().map(|t| check_type(env, t))).map(|_| ()),
+        ast::Type::Array(_, item) => check_type(env, item),
+        ast::Type::Function(_, params, result) => all(params.iter().map(|t| check_type(env, t)))
+            .and_zip(check_type(env, result))
+            .map(|_| ()),
+        ast::Type::Named(span, name, args) => match env.type_arity(name) {
+            Some(arity) if arity == args.len() => {
+                all(args.iter().map(|t| check_type(env, t))).map(|_| ())
+            }
+            Some(arity) => Outcome::new_err(TypeError::TypeArgumentCount {
+                span: *span,
+                name: name.clone(),
+                expected: arity,
+                got: args.len(),
+            }),
+            None => unknown(span, name),
+        },
+        ast::Type::Var(span, name) if !env.type_vars.contains(name) => unknown(span, name),
+        _ => Outcome::new(()),
+    }
+}
+
+// The part of a span that is just `name`, when the span starts with it
+fn name_span(span: &ast::Span, name: &str) -> ast::Span {
+    ast::Span {
+        end: span.start + name.len(),
+        ..*span
+    }
+}
+
+// Still checks `fields` when the record itself is wrong, so that their errors are reported too
+fn infer_field_values(env: &Env, fields: &[ast::FieldValue<ast::Span>]) -> Outcome<()> {
+    all(fields.iter().map(|f| infer(env, &f.value))).map(|_| ())
+}
+
+// Checks the fields given to `record`, which has the `declared` fields
+// Leaving out fields is allowed here, since record updates only replace some of them
+fn infer_fields(
+    env: &Env,
+    record: &str,
+    declared: &[(String, ast::JustType)],
+    fields: &[ast::FieldValue<ast::Span>],
+) -> Outcome<Vec<ast::FieldValue<(ast::Span, ast::JustType)>>> {
+    all(fields.iter().enumerate().map(|(i, f)| {
+        let span = name_span(&f.extra, &f.name);
+
+        if fields[..i].iter().any(|other| other.name == f.name) {
+            return Outcome::<()>::new_err(TypeError::DuplicateField {
+                span,
+                name: f.name.clone(),
+            })
+            .and_zip(infer(env, &f.value))
+            .and_then(|_| Outcome::new_empty());
+        }
+
+        match declared.iter().find(|(name, _)| name == &f.name) {
+            Some((_, ty)) => {
+                infer_argument(env, &f.value, format!("{}.{}", record, f.name), ty.clone()).map(
+                    |value| ast::FieldValue {
+                        extra: (f.extra, ty.clone()),
+                        name: f.name.clone(),
+                        value,
+                    },
+                )
+            }
+            None => Outcome::<()>::new_err(TypeError::UnknownField {
+                span,
+                field: f.name.clone(),
+                record: record.into(),
+                suggestion: suggest(&f.name, declared.iter().map(|(name, _)| name)),
+            })
+            .and_zip(infer(env, &f.value))
+            .and_then(|_| Outcome::new_empty()),
+        }
+    }))
+}
+
+// Number of single character insertions, deletions and substitutions to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+// Picks the closest of `candidates` to `name`, if any of them are close enough to be a typo
+pub fn suggest<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Option<String> {
+    candidates
+        .map(|c| (edit_distance(name, c), c))
+        .filter(|(distance, c)| *distance <= 2 && *distance < c.len().max(name.len()))
+        .min()
+        .map(|(_, c)| c.clone())
+}
+
+// Binds every variable in `pattern` to the matching part of `ty`
+// If `ty` is unknown or the wrong shape, all of the variables are still bound but without types
+fn bind_pattern(
+    env: &Env,
+    pattern: &ast::SpanPattern,
+    ty: Option<&ast::JustType>,
+) -> (Env, Outcome<ast::TypedPattern>) {
+    let ty = ty.map(|ty| match env.resolve(ty) {
+        // The pattern decides the shape of a type that is not known yet
+        ast::Type::Unknown(_, _) => match pattern_shape(env, pattern) {
+            Some(shape) => {
+                env.unify(ty, &shape);
+                shape
+            }
+            None => env.resolve(ty),
+        },
+        ty => ty,
+    });
+    let ty = ty.as_ref();
+
+    let typed = |p: ast::Pattern<()>| {
+        // Only ever called once `ty` is known to be correct
+        let ty = ty.unwrap().clone();
+        p.map_extra(&|_| (*pattern.extra(), ty.clone()))
+    };
+
+    match (pattern, ty) {
+        (ast::Pattern::Var(span, name), _) => (
+            env.with(name, ty.cloned()),
+            match ty {
+                Some(ty) => Outcome::new(ast::Pattern::Var((*span, ty.clone()), name.clone())),
+                None => Outcome::new_empty(),
+            },
+        ),
+        (ast::Pattern::Wildcard(_), Some(_)) => {
+            (env.clone(), Outcome::new(typed(ast::Pattern::Wildcard(()))))
+        }
+        (ast::Pattern::Bool(_, b), Some(ast::Type::Bool(_))) => {
+            (env.clone(), Outcome::new(typed(ast::Pattern::Bool((), *b))))
+        }
+        (ast::Pattern::Int(_, n), Some(ast::Type::Int(_))) => {
+            (env.clone(), Outcome::new(typed(ast::Pattern::Int((), *n))))
+        }
+        (ast::Pattern::Tuple(span, patterns), Some(ast::Type::Tuple(_, tys)))
+            if patterns.len() == tys.len() =>
+        {
+            let (env, items) = bind_patterns(env, patterns, tys);
+
+            (
+                env,
+                items.map(|items| {
+                    ast::Pattern::Tuple((*span, ast::Type::Tuple((), tys.clone())), items)
+                }),
+            )
+        }
+        (ast::Pattern::Constructor(span, name, patterns), _) if env.constructor(name).is_none() => {
+            (
+                unbind_pattern(env, pattern),
+                Outcome::<()>::new_err(TypeError::UnknownConstructor {
+                    span: name_span(span, name),
+                    name: name.clone(),
+                    suggestion: suggest(name, env.constructor_names()),
+                })
+                .and_zip(bind_patterns(env, patterns, &[]).1)
+                .and_then(|_| Outcome::new_empty()),
+            )
+        }
+        (
+            ast::Pattern::Constructor(span, name, patterns),
+            Some(ast::Type::Named(_, ty_name, type_args)),
+        ) if env.constructor(name).map(|(t, _, args)| (t, args.len()))
+            == Some((ty_name, patterns.len())) =>
+        {
+            let (_, type_params, args) = env.constructor(name).unwrap();
+            let args = args
+                .iter()
+                .map(|a| a.substitute(type_params, type_args))
+                .collect::<Vec<_>>();
+            let (env, items) = bind_patterns(env, patterns, &args);
+
+            (
+                env,
+                items.map(|items| {
+                    ast::Pattern::Constructor((*span, ty.unwrap().clone()), name.clone(), items)
+                }),
+            )
+        }
+        (_, _) => {
+            let outcome = match ty {
+                Some(ty) => Outcome::new_err(TypeError::PatternMismatch {
+                    span: *pattern.extra(),
+                    pattern: pattern.to_string(),
+                    got: ty.clone(),
+                }),
+                None => Outcome::new_empty(),
+            };
+
+            (unbind_pattern(env, pattern), outcome)
+        }
+    }
+}
+
+// The most that a pattern says about the type of the value it matches
+fn pattern_shape(env: &Env, pattern: &ast::SpanPattern) -> Option<ast::JustType> {
+    match pattern {
+        ast::Pattern::Bool(_, _) => Some(ast::Type::Bool(())),
+        ast::Pattern::Int(_, _) => Some(ast::Type::Int(())),
+        ast::Pattern::Tuple(_, patterns) => Some(ast::Type::Tuple(
+            (),
+            patterns.iter().map(|_| env.fresh()).collect(),
+        )),
+        ast::Pattern::Constructor(_, name, _) => {
+            let (ty, type_params, _) = env.constructor(name)?;
+            Some(ast::Type::Named(
+                (),
+                ty.clone(),
+                env.instantiate(type_params),
+            ))
+        }
+        ast::Pattern::Var(_, _) | ast::Pattern::Wildcard(_) => None,
+    }
+}
+
+// Binds each pattern to the type at the same index, patterns without a type are left untyped
+fn bind_patterns(
+    env: &Env,
+    patterns: &[ast::SpanPattern],
+    tys: &[ast::JustType],
+) -> (Env, Outcome<Vec<ast::TypedPattern>>) {
+    let mut env = env.clone();
+    let mut typed = vec![];
+
+    for (i, pattern) in patterns.iter().enumerate() {
+        let (new_env, outcome) = bind_pattern(&env, pattern, tys.get(i));
+        env = new_env;
+        typed.push(outcome);
+    }
+
+    (env, all(typed.into_iter()))
+}
+
+fn unbind_pattern(env: &Env, pattern: &ast::SpanPattern) -> Env {
+    match pattern {
+        ast::Pattern::Var(_, name) => env.with(name, None),
+        ast::Pattern::Wildcard(_) | ast::Pattern::Bool(_, _) | ast::Pattern::Int(_, _) => {
+            env.clone()
+        }
+        ast::Pattern::Tuple(_, patterns) | ast::Pattern::Constructor(_, _, patterns) => patterns
+            .iter()
+            .fold(env.clone(), |env, p| unbind_pattern(&env, p)),
+    }
+}
+
+// Reports values that none of `patterns` match, and patterns that never match anything
+fn check_exhaustive(
+    env: &Env,
+    span: ast::Span,
+    ty: &ast::JustType,
+    patterns: &[&ast::TypedPattern],
+) -> Outcome<()> {
+    let report = exhaustive::check(&env.variants, ty, patterns);
+
+    let missing = if report.missing.is_empty() {
+        Outcome::new(())
+    } else {
+        Outcome::new_err(TypeError::NonExhaustive {
+            span,
+            missing: report.missing,
+        })
+    };
+
+    report.unreachable.iter().fold(missing, |outcome, i| {
+        outcome
+            .and_zip(
+                Outcome::new_warn(TypeWarning::UnreachableArm {
+                    span: patterns[*i].extra().0,
+                })
+                .recover_to(()),
+            )
+            .map(|_| ())
+    })
+}
+
+// Infers the type of an argument to `name`, then checks that it is the type `expected`
+fn infer_argument(
+    env: &Env,
+    e: &ast::SpanExpr,
+    name: String,
+    expected: ast::JustType,
+) -> Outcome<ast::TypedExpr> {
+    infer(env, e)
+        .and_then(|typed| {
+            let got = typed.extra().1.clone();
+
+            if env.unify(&got, &expected) {
+                Outcome::new(typed)
+            } else {
+                Outcome::new_err(TypeError::ArgumentIncorrect {
+                    span: *e.extra(),
+                    name,
+                    got: env.resolve(&got),
+                    expected: env.resolve(&expected),
+                })
+                .recover_to(typed)
+            }
+        })
+        // Every operator and built-in has a fixed result type, so always recover
+        .or_recover(|| poisoned(e, expected))
+}
+
+// Both operands of a binary operator have to be the same type, which has to be one of `allowed`
+// The type of the left operand decides what the right one should be
+fn infer_operands(
+    env: &Env,
+    op: ast::BinOp,
+    left: &ast::SpanExpr,
+    right: &ast::SpanExpr,
+    allowed: &[ast::JustType],
+) -> Outcome<(ast::TypedExpr, ast::TypedExpr, ast::JustType)> {
+    let left_outcome = infer(env, left);
+    let right_outcome = infer(env, right);
+    let resolved = |outcome: &Outcome<ast::TypedExpr>| {
+        outcome
+            .result
+            .as_ref()
+            .map(|typed| env.resolve(&typed.extra().1))
+    };
+    let not_supported = |span: ast::Span, got: ast::JustType| TypeError::OperandNotSupported {
+        span,
+        name: op.to_string(),
+        got,
+        allowed: allowed.to_vec(),
+    };
+
+    let left_got = resolved(&left_outcome);
+    let left_ty = match (left_got.clone(), resolved(&right_outcome)) {
+        (Some(ty), _) if allowed.contains(&ty) => Some(ty),
+        // When the left operand is not known yet, the right one decides, otherwise the first allowed type
+        (Some(unknown @ ast::Type::Unknown(_, _)), right_ty) => {
+            let ty = right_ty
+                .filter(|ty| allowed.contains(ty))
+                .unwrap_or_else(|| allowed[0].clone());
+            env.unify(&unknown, &ty);
+            Some(ty)
+        }
+        // The left operand is already wrong, so the right one is only checked against `allowed`
+        _ => None,
+    };
+    let operand_ty = left_ty.clone().unwrap_or_else(|| allowed[0].clone());
+
+    left_outcome
+        .and_then(|typed_left| {
+            let got = env.resolve(&typed_left.extra().1);
+
+            if allowed.contains(&got) {
+                Outcome::new(typed_left)
+            } else {
+                Outcome::new_err(not_supported(*left.extra(), got)).recover_to(typed_left)
+            }
+        })
+        .or_recover(|| poisoned(left, operand_ty.clone()))
+        .and_zip(
+            right_outcome
+                .and_then(|typed_right| {
+                    let got = env.resolve(&typed_right.extra().1);
+                    let numeric = [ast::Type::Int(()), ast::Type::Float(())];
+
+                    let left_ty = match &left_ty {
+                        Some(left_ty) => left_ty,
+                        // The same mistake as the left operand is only reported for the left one
+                        None if allowed.contains(&got)
+                            || Some(&got) == left_got.as_ref()
+                            || env.unify(&got, &allowed[0]) =>
+                        {
+                            return Outcome::new(typed_right);
+                        }
+                        None => {
+                            return Outcome::new_err(not_supported(*right.extra(), got))
+                                .recover_to(typed_right);
+                        }
+                    };
+
+                    if env.unify(&got, left_ty) {
+                        Outcome::new(typed_right)
+                    } else if numeric.contains(&got) && numeric.contains(left_ty) {
+                        // Point at whichever side is the `int`, since converting to `float` never fails
+                        let int_span = if got == ast::Type::Int(()) {
+                            *right.extra()
+                        } else {
+                            *left.extra()
+                        };
+
+                        Outcome::new_err(TypeError::NumericMixing {
+                            span: int_span,
+                            name: op.to_string(),
+                        })
+                        .recover_to(typed_right)
+                    } else if !allowed.contains(&got) {
+                        Outcome::new_err(not_supported(*right.extra(), got)).recover_to(typed_right)
+                    } else {
+                        Outcome::new_err(TypeError::ArgumentIncorrect {
+                            span: *right.extra(),
+                            name: op.to_string(),
+                            got,
+                            expected: left_ty.clone(),
+                        })
+                        .recover_to(typed_right)
+                    }
+                })
+                .or_recover(|| poisoned(right, operand_ty.clone())),
+        )
+        .map(|(typed_left, typed_right)| (typed_left, typed_right, operand_ty.clone()))
+}
+
+// Checks that `term` is an array and that `index` is an `int`, and finds out the type of the items
+fn infer_indexed(
+    env: &Env,
+    term: &ast::SpanExpr,
+    index: &ast::SpanExpr,
+) -> Outcome<(ast::TypedExpr, ast::TypedExpr, ast::JustType)> {
+    let item_ty = env.fresh();
+
+    infer(env, term)
+        .and_then(|typed_term| {
+            let got = typed_term.extra().1.clone();
+
+            if env.unify(&got, &ast::Type::Array((), Box::new(item_ty.clone()))) {
+                Outcome::new(typed_term)
+            } else {
+                Outcome::new_err(TypeError::NotAnArray {
+                    span: *term.extra(),
+                    got: env.resolve(&got),
+                })
+            }
+        })
+        .and_zip(infer_argument(env, index, "[]".into(), ast::Type::Int(())))
+        .map(|(typed_term, typed_index)| (typed_term, typed_index, item_ty.clone()))
+}
+
+fn infer(env: &Env, e: &ast::SpanExpr) -> Outcome<ast::TypedExpr> {
     match e {
-        ast::Expr::IntLit(_, _) => Outcome::new(ast::Type::Int(())),
-        ast::Expr::BoolLit(_, _) => Outcome::new(ast::Type::Bool(())),
-        ast::Expr::TypeAnno { term, ty, .. } => infer(term)
-            .and_then(|term_ty| {
+        ast::Expr::UnitLit(span) => Outcome::new(ast::Expr::UnitLit((*span, ast::Type::Unit(())))),
+        ast::Expr::IntLit(span, n) => {
+            Outcome::new(ast::Expr::IntLit((*span, ast::Type::Int(())), *n))
+        }
+        ast::Expr::BoolLit(span, b) => {
+            Outcome::new(ast::Expr::BoolLit((*span, ast::Type::Bool(())), *b))
+        }
+        ast::Expr::FloatLit(span, x) => {
+            Outcome::new(ast::Expr::FloatLit((*span, ast::Type::Float(())), *x))
+        }
+        ast::Expr::StringLit(span, s) => Outcome::new(ast::Expr::StringLit(
+            (*span, ast::Type::String(())),
+            s.clone(),
+        )),
+        ast::Expr::TypeAnno { term, ty, extra } => check_type(env, ty)
+            .and_zip(infer(env, term))
+            .and_then(|((), typed_term)| {
+                let term_ty = typed_term.extra().1.clone();
+
                 // If inferring the type was successful, then check if the annotation matches the inferred type
-                if term_ty == ty.strip() {
-                    Outcome::new_empty()
+                if env.unify(&term_ty, &ty.strip()) {
+                    Outcome::new(typed_term)
                 } else {
                     Outcome::new_err(TypeError::AnnotationIncorrect {
                         span: *ty.extra(),
-                        got: term_ty,
+                        got: env.resolve(&term_ty),
                         annotation: ty.strip(),
                     })
+                    .recover_to(typed_term)
                 }
             })
             // Recover to the annotated type no matter what
-            .recover_to(ty.strip()),
+            .or_recover(|| poisoned(term, ty.strip()))
+            .map(|typed_term| ast::Expr::TypeAnno {
+                extra: (*extra, ty.strip()),
+                term: Box::new(typed_term),
+                ty: ty.map_extra(&|span| (*span, ty.strip())),
+            }),
         ast::Expr::IfFlow {
             cond,
             on_true,
             on_false,
             extra,
-        } => infer(cond)
-            .and_then(|ty| {
+        } => infer(env, cond)
+            .and_then(|typed_cond| {
+                let ty = typed_cond.extra().1.clone();
+
                 // If inferring the type was successful, then check that condition is of the type `bool`
-                if ty.strip() == ast::Type::Bool(()) {
+                if env.unify(&ty, &ast::Type::Bool(())) {
                     match **cond {
-                        ast::Expr::BoolLit(span, b) => {
-                            Outcome::new_warn(TypeWarning::CondAlways { span, value: b })
-                        }
-                        _ => Outcome::new_empty(),
+                        ast::Expr::BoolLit(span, b) => Outcome::new_warn(TypeWarning::CondAlways {
+                            span,
+                            value: b,
+                            keyword: "if",
+                        })
+                        .recover_to(typed_cond),
+                        _ => Outcome::new(typed_cond),
                     }
                 } else {
                     Outcome::new_err(TypeError::IfCondMustBeBool {
-                        end: cond.extra().1,
-                        got: ty,
+                        span: *cond.extra(),
+                        got: env.resolve(&ty),
                     })
+                    .recover_to(typed_cond)
                 }
             })
-            .recover_to(())
+            .or_recover(|| poisoned(cond, ast::Type::Bool(())))
             // Check if both branches of the if are of the same type
-            // `(_)` means we ignore whatever value is being passes, cause its `Unit` in this case
-            .and_then(|_| infer(on_true).and_zip(infer(on_false)))
+            .and_zip(infer(env, on_true).and_zip(infer(env, on_false)))
             // If they are of different types, don't recover cause it can't be known which one is the "correct" type
-            .and_then(|(first, second)| {
-                if first == second {
-                    Outcome::new(first)
+            .and_then(|(typed_cond, (first, second))| {
+                let first_ty = first.extra().1.clone();
+                let second_ty = second.extra().1.clone();
+
+                if env.unify(&first_ty, &second_ty) {
+                    Outcome::new(ast::Expr::IfFlow {
+                        extra: (*extra, first_ty),
+                        cond: Box::new(typed_cond),
+                        on_true: Box::new(first),
+                        on_false: Box::new(second),
+                    })
                 } else {
                     Outcome::new_err(TypeError::IfBranchesMustBeSame {
-                        start: extra.0,
-                        first,
-                        second,
+                        span: *extra,
+                        first: env.resolve(&first_ty),
+                        second: env.resolve(&second_ty),
+                    })
+                }
+            }),
+        ast::Expr::BinOp {
+            extra,
+            op,
+            left,
+            right,
+        } => {
+            let int = ast::Type::Int(());
+            let float = ast::Type::Float(());
+            let bool = ast::Type::Bool(());
+            let string = ast::Type::String(());
+
+            let allowed = match op {
+                ast::BinOp::Concat => vec![string],
+                ast::BinOp::Rem => vec![int],
+                ast::BinOp::Add
+                | ast::BinOp::Sub
+                | ast::BinOp::Mul
+                | ast::BinOp::Div
+                | ast::BinOp::Lt
+                | ast::BinOp::Le
+                | ast::BinOp::Gt
+                | ast::BinOp::Ge => vec![int, float],
+                ast::BinOp::Eq | ast::BinOp::Ne => vec![int, float, bool.clone(), string],
+            };
+
+            infer_operands(env, *op, left, right, &allowed).map(|(left, right, operand_ty)| {
+                let result_ty = match op {
+                    ast::BinOp::Eq
+                    | ast::BinOp::Ne
+                    | ast::BinOp::Lt
+                    | ast::BinOp::Le
+                    | ast::BinOp::Gt
+                    | ast::BinOp::Ge => bool,
+                    _ => operand_ty,
+                };
+
+                ast::Expr::BinOp {
+                    extra: (*extra, result_ty),
+                    op: *op,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            })
+        }
+        ast::Expr::Builtin {
+            extra,
+            builtin,
+            arg,
+        } => {
+            let (arg_ty, result_ty) = match builtin {
+                ast::Builtin::Len => (ast::Type::String(()), ast::Type::Int(())),
+                ast::Builtin::Length => (
+                    ast::Type::Array((), Box::new(env.fresh())),
+                    ast::Type::Int(()),
+                ),
+                ast::Builtin::ToFloat => (ast::Type::Int(()), ast::Type::Float(())),
+                ast::Builtin::ToInt => (ast::Type::Float(()), ast::Type::Int(())),
+                // Every type can be shown
+                ast::Builtin::Print | ast::Builtin::Debug => (env.fresh(), ast::Type::Unit(())),
+            };
+
+            infer_argument(env, arg, builtin.to_string(), arg_ty).map(|arg| ast::Expr::Builtin {
+                extra: (*extra, result_ty),
+                builtin: *builtin,
+                arg: Box::new(arg),
+            })
+        }
+        ast::Expr::Var(span, name) => match (env.vars.get(name), env.functions.get(name)) {
+            (Some(Some(ty)), _) => Outcome::new(ast::Expr::Var((*span, ty.clone()), name.clone())),
+            // The error was already reported where the variable was bound
+            (Some(None), _) => Outcome::new_empty(),
+            // A function used as a value, which gets its own type arguments like a call does
+            (None, Some(signature)) => {
+                let (params, result) = env.instantiate_signature(signature);
+
+                Outcome::new(ast::Expr::Var(
+                    (*span, ast::Type::Function((), params, Box::new(result))),
+                    signature.link_name.clone(),
+                ))
+            }
+            (None, None) => Outcome::new_err(TypeError::UnknownVariable {
+                span: *span,
+                name: name.clone(),
+                suggestion: suggest(name, env.vars.keys().chain(env.functions.keys())),
+            }),
+        },
+        ast::Expr::Tuple { extra, items } => {
+            all(items.iter().map(|item| infer(env, item))).map(|typed_items| ast::Expr::Tuple {
+                extra: (
+                    *extra,
+                    ast::Type::Tuple(
+                        (),
+                        typed_items.iter().map(|i| i.extra().1.clone()).collect(),
+                    ),
+                ),
+                items: typed_items,
+            })
+        }
+        ast::Expr::Project { extra, term, index } => {
+            infer(env, term).and_then(|typed_term| match &env.resolve(&typed_term.extra().1) {
+                ast::Type::Tuple(_, items) if *index < items.len() => {
+                    Outcome::new(ast::Expr::Project {
+                        extra: (*extra, items[*index].clone()),
+                        term: Box::new(typed_term.clone()),
+                        index: *index,
+                    })
+                }
+                got => Outcome::new_err(TypeError::ProjectionIncorrect {
+                    span: *extra,
+                    index: *index,
+                    got: got.clone(),
+                }),
+            })
+        }
+        ast::Expr::Let {
+            extra,
+            pattern,
+            value,
+            body,
+        } => {
+            let value_outcome = infer(env, value);
+            let value_ty = value_outcome
+                .result
+                .as_ref()
+                .map(|typed_value| typed_value.extra().1.clone());
+            // The body is still checked when the value has errors, with the variables left untyped
+            let (body_env, pattern_outcome) = bind_pattern(env, pattern, value_ty.as_ref());
+            // A `let` has only one pattern, so it has to match every value
+            let pattern_outcome = pattern_outcome.and_then(|typed_pattern| {
+                let ty = env.resolve(&typed_pattern.extra().1);
+                check_exhaustive(env, *pattern.extra(), &ty, &[&typed_pattern])
+                    .map(|()| typed_pattern)
+            });
+
+            value_outcome
+                .and_zip(pattern_outcome)
+                .and_zip(infer(&body_env, body))
+                .map(
+                    |((typed_value, typed_pattern), typed_body)| ast::Expr::Let {
+                        extra: (*extra, typed_body.extra().1.clone()),
+                        pattern: typed_pattern,
+                        value: Box::new(typed_value),
+                        body: Box::new(typed_body),
+                    },
+                )
+        }
+        ast::Expr::Record {
+            extra,
+            name,
+            fields,
+        } => match env.records.get(name) {
+            Some((type_params, declared)) => {
+                let type_args = env.instantiate(type_params);
+                let declared = declared
+                    .iter()
+                    .map(|(field, ty)| (field.clone(), ty.substitute(type_params, &type_args)))
+                    .collect::<Vec<_>>();
+
+                let missing = declared
+                    .iter()
+                    .map(|(field, _)| field)
+                    .filter(|field| fields.iter().all(|f| &&f.name != field))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                let missing_outcome = if missing.is_empty() {
+                    Outcome::new(())
+                } else {
+                    Outcome::new_err(TypeError::MissingFields {
+                        span: name_span(extra, name),
+                        record: name.clone(),
+                        fields: missing,
+                    })
+                };
+
+                missing_outcome
+                    .and_zip(infer_fields(env, name, &declared, fields))
+                    .map(|((), typed_fields)| ast::Expr::Record {
+                        extra: (*extra, ast::Type::Named((), name.clone(), type_args)),
+                        name: name.clone(),
+                        fields: typed_fields,
+                    })
+            }
+            None => Outcome::<()>::new_err(TypeError::UnknownType {
+                span: name_span(extra, name),
+                name: name.clone(),
+                suggestion: suggest(name, env.records.keys()),
+            })
+            .and_zip(infer_field_values(env, fields))
+            .and_then(|_| Outcome::new_empty()),
+        },
+        ast::Expr::Field { extra, term, field } => infer(env, term).and_then(|typed_term| {
+            let ty = env.resolve(&typed_term.extra().1);
+            let declared = match &ty {
+                ast::Type::Named(_, name, type_args) => {
+                    env.records.get(name).map(|(type_params, declared)| {
+                        let declared = declared
+                            .iter()
+                            .map(|(f, ty)| (f.clone(), ty.substitute(type_params, type_args)))
+                            .collect::<Vec<_>>();
+
+                        (name, declared)
+                    })
+                }
+                _ => None,
+            };
+
+            match declared {
+                Some((name, declared)) => match declared.iter().find(|(f, _)| f == field) {
+                    Some((_, field_ty)) => Outcome::new(ast::Expr::Field {
+                        extra: (*extra, field_ty.clone()),
+                        term: Box::new(typed_term),
+                        field: field.clone(),
+                    }),
+                    None => Outcome::new_err(TypeError::UnknownField {
+                        span: ast::Span {
+                            start: extra.end - field.len(),
+                            ..*extra
+                        },
+                        field: field.clone(),
+                        record: name.clone(),
+                        suggestion: suggest(field, declared.iter().map(|(f, _)| f)),
+                    }),
+                },
+                None => Outcome::new_err(TypeError::NotARecord {
+                    span: *term.extra(),
+                    got: ty,
+                }),
+            }
+        }),
+        ast::Expr::Update {
+            extra,
+            term,
+            fields,
+        } => infer(env, term).and_then(|typed_term| {
+            let ty = env.resolve(&typed_term.extra().1);
+            let declared = match &ty {
+                ast::Type::Named(_, name, type_args) => {
+                    env.records.get(name).map(|(type_params, declared)| {
+                        let declared = declared
+                            .iter()
+                            .map(|(f, ty)| (f.clone(), ty.substitute(type_params, type_args)))
+                            .collect::<Vec<_>>();
+
+                        (name, declared)
+                    })
+                }
+                _ => None,
+            };
+
+            match declared {
+                Some((name, declared)) => {
+                    infer_fields(env, name, &declared, fields).map(|typed_fields| {
+                        ast::Expr::Update {
+                            extra: (*extra, ty.clone()),
+                            term: Box::new(typed_term),
+                            fields: typed_fields,
+                        }
+                    })
+                }
+                None => Outcome::<()>::new_err(TypeError::NotARecord {
+                    span: *term.extra(),
+                    got: ty,
+                })
+                .and_zip(infer_field_values(env, fields))
+                .and_then(|_| Outcome::new_empty()),
+            }
+        }),
+        ast::Expr::Construct { extra, name, args } => match env.constructor(name) {
+            Some((ty, type_params, arg_tys)) if arg_tys.len() == args.len() => {
+                let type_args = env.instantiate(type_params);
+
+                all(args.iter().zip(arg_tys).map(|(arg, arg_ty)| {
+                    let arg_ty = arg_ty.substitute(type_params, &type_args);
+                    infer_argument(env, arg, name.clone(), arg_ty)
+                }))
+                .map(|typed_args| ast::Expr::Construct {
+                    extra: (*extra, ast::Type::Named((), ty.clone(), type_args)),
+                    name: name.clone(),
+                    args: typed_args,
+                })
+            }
+            Some((_, _, arg_tys)) => Outcome::<()>::new_err(TypeError::ArgumentCount {
+                span: *extra,
+                name: name.clone(),
+                expected: arg_tys.len(),
+                got: args.len(),
+            })
+            .and_zip(all(args.iter().map(|arg| infer(env, arg))))
+            .and_then(|_| Outcome::new_empty()),
+            None => Outcome::<()>::new_err(TypeError::UnknownConstructor {
+                span: name_span(extra, name),
+                name: name.clone(),
+                suggestion: suggest(name, env.constructor_names()),
+            })
+            .and_zip(all(args.iter().map(|arg| infer(env, arg))))
+            .and_then(|_| Outcome::new_empty()),
+        },
+        // Variables hide functions with the same name, so a parameter `f` can be called like `f(1)`
+        ast::Expr::Call { extra, name, args } if env.vars.contains_key(name) => {
+            let ty = match &env.vars[name] {
+                Some(ty) => env.resolve(ty),
+                None => {
+                    return all(args.iter().map(|arg| infer(env, arg)))
+                        .and_then(|_| Outcome::new_empty())
+                }
+            };
+            // Nothing decided the type of the variable yet, so this call does
+            let ty = match ty {
+                ast::Type::Unknown(_, _) => {
+                    let params = args.iter().map(|_| env.fresh()).collect();
+                    let function = ast::Type::Function((), params, Box::new(env.fresh()));
+                    env.unify(&ty, &function);
+                    function
+                }
+                ty => ty,
+            };
+
+            match ty {
+                ast::Type::Function(_, params, result) if params.len() == args.len() => all(args
+                    .iter()
+                    .zip(params)
+                    .map(|(arg, param)| infer_argument(env, arg, name.clone(), param)))
+                .map(|typed_args| ast::Expr::Call {
+                    extra: (*extra, *result),
+                    name: name.clone(),
+                    args: typed_args,
+                }),
+                ast::Type::Function(_, params, _) => {
+                    Outcome::<()>::new_err(TypeError::ArgumentCount {
+                        span: *extra,
+                        name: name.clone(),
+                        expected: params.len(),
+                        got: args.len(),
+                    })
+                    .and_zip(all(args.iter().map(|arg| infer(env, arg))))
+                    .and_then(|_| Outcome::new_empty())
+                }
+                got => Outcome::<()>::new_err(TypeError::NotAFunction {
+                    span: name_span(extra, name),
+                    name: name.clone(),
+                    got,
+                })
+                .and_zip(all(args.iter().map(|arg| infer(env, arg))))
+                .and_then(|_| Outcome::new_empty()),
+            }
+        }
+        ast::Expr::Call { extra, name, args } => match env.functions.get(name) {
+            Some(signature) if signature.params.len() == args.len() => {
+                // Every call gets its own type arguments, which are found out from the arguments
+                let (params, result) = env.instantiate_signature(signature);
+
+                all(args
+                    .iter()
+                    .zip(params)
+                    .map(|(arg, param)| infer_argument(env, arg, name.clone(), param)))
+                .map(|typed_args| ast::Expr::Call {
+                    extra: (*extra, result),
+                    name: signature.link_name.clone(),
+                    args: typed_args,
+                })
+            }
+            Some(signature) => Outcome::<()>::new_err(TypeError::ArgumentCount {
+                span: *extra,
+                name: name.clone(),
+                expected: signature.params.len(),
+                got: args.len(),
+            })
+            .and_zip(all(args.iter().map(|arg| infer(env, arg))))
+            .and_then(|_| Outcome::new_empty()),
+            None => Outcome::<()>::new_err(TypeError::UnknownFunction {
+                span: name_span(extra, name),
+                name: name.clone(),
+                suggestion: suggest(name, env.functions.keys()),
+            })
+            .and_zip(all(args.iter().map(|arg| infer(env, arg))))
+            .and_then(|_| Outcome::new_empty()),
+        },
+        ast::Expr::Seq {
+            extra,
+            first,
+            second,
+        } => infer(env, first)
+            .and_then(|typed_first| match env.resolve(&typed_first.extra().1) {
+                // An unknown type is reported later on if nothing else decides it
+                ast::Type::Unit(_) | ast::Type::Unknown(_, _) => Outcome::new(typed_first),
+                ty => Outcome::new_warn(TypeWarning::UnusedValue {
+                    span: *first.extra(),
+                    ty,
+                    by: "`;`",
+                })
+                .recover_to(typed_first),
+            })
+            .and_zip(infer(env, second))
+            .map(|(typed_first, typed_second)| ast::Expr::Seq {
+                extra: (*extra, typed_second.extra().1.clone()),
+                first: Box::new(typed_first),
+                second: Box::new(typed_second),
+            }),
+        ast::Expr::VarDecl {
+            extra,
+            name,
+            value,
+            body,
+        } => {
+            let value_outcome = infer(env, value);
+            let value_ty = value_outcome
+                .result
+                .as_ref()
+                .map(|typed_value| env.resolve(&typed_value.extra().1));
+            let body_env = env.with_mutable(name, value_ty);
+
+            value_outcome
+                .and_zip(infer(&body_env, body))
+                .map(|(typed_value, typed_body)| ast::Expr::VarDecl {
+                    extra: (*extra, typed_body.extra().1.clone()),
+                    name: name.clone(),
+                    value: Box::new(typed_value),
+                    body: Box::new(typed_body),
+                })
+        }
+        ast::Expr::Assign { extra, name, value } => {
+            let target = match env.vars.get(name) {
+                Some(Some(ty)) if env.mutable.contains(name) => Outcome::new(Some(ty.clone())),
+                Some(Some(_)) => Outcome::new_err(TypeError::AssignImmutable {
+                    span: name_span(extra, name),
+                    name: name.clone(),
+                }),
+                Some(None) => Outcome::new(None),
+                None => Outcome::new_err(TypeError::UnknownVariable {
+                    span: name_span(extra, name),
+                    name: name.clone(),
+                    suggestion: suggest(name, env.mutable.iter()),
+                }),
+            };
+
+            // The value is still checked when the variable is wrong, and the other way around
+            match target.result.clone() {
+                Some(Some(ty)) => target.and_zip(infer_argument(env, value, ":=".into(), ty)),
+                _ => target
+                    .and_zip(infer(env, value))
+                    .and_then(|_| Outcome::new_empty()),
+            }
+            .map(|(_, typed_value)| ast::Expr::Assign {
+                extra: (*extra, ast::Type::Unit(())),
+                name: name.clone(),
+                value: Box::new(typed_value),
+            })
+        }
+        ast::Expr::While { extra, cond, body } => {
+            // The condition is not part of the loop it belongs to, and leaving an outer loop from it
+            // Would skip over the inner one, so `break` is not allowed there at all
+            let cond_env = Env {
+                breaks: None,
+                ..env.clone()
+            };
+            let cond_outcome = infer(&cond_env, cond).and_then(|typed_cond| {
+                let ty = typed_cond.extra().1.clone();
+
+                if env.unify(&ty, &ast::Type::Bool(())) {
+                    Outcome::new(typed_cond)
+                } else {
+                    Outcome::new_err(TypeError::WhileCondMustBeBool {
+                        span: *cond.extra(),
+                        got: env.resolve(&ty),
+                    })
+                    .recover_to(typed_cond)
+                }
+            });
+
+            let breaks = Rc::new(Cell::new(false));
+            let body_env = Env {
+                breaks: Some(breaks.clone()),
+                ..env.clone()
+            };
+            let body_outcome = infer(&body_env, body).and_then(|typed_body| {
+                match env.resolve(&typed_body.extra().1) {
+                    ast::Type::Unit(_) | ast::Type::Unknown(_, _) => Outcome::new(typed_body),
+                    ty => Outcome::new_warn(TypeWarning::UnusedValue {
+                        span: *body.extra(),
+                        ty,
+                        by: "the `while` loop",
+                    })
+                    .recover_to(typed_body),
+                }
+            });
+
+            // `while false` never runs its body, and `while true` never ends unless it has a `break`
+            let always = match **cond {
+                ast::Expr::BoolLit(span, value) if !(value && breaks.get()) => {
+                    Outcome::new_warn(TypeWarning::CondAlways {
+                        span,
+                        value,
+                        keyword: "while",
+                    })
+                    .recover_to(())
+                }
+                _ => Outcome::new(()),
+            };
+
+            cond_outcome.and_zip(body_outcome).and_zip(always).map(
+                |((typed_cond, typed_body), ())| ast::Expr::While {
+                    extra: (*extra, ast::Type::Unit(())),
+                    cond: Box::new(typed_cond),
+                    body: Box::new(typed_body),
+                },
+            )
+        }
+        ast::Expr::Break(span) => match &env.breaks {
+            Some(breaks) => {
+                breaks.set(true);
+                Outcome::new(ast::Expr::Break((*span, ast::Type::Unit(()))))
+            }
+            None => Outcome::new_err(TypeError::BreakOutsideLoop { span: *span }),
+        },
+        ast::Expr::Array { extra, items } => {
+            all(items.iter().map(|item| infer(env, item))).and_then(|typed_items| {
+                // The first item decides the type of the others
+                let item_ty = match typed_items.first() {
+                    Some(first) => first.extra().1.clone(),
+                    None => env.fresh(),
+                };
+                let same = all(typed_items.iter().skip(1).map(|item| {
+                    if env.unify(&item.extra().1, &item_ty) {
+                        Outcome::new(())
+                    } else {
+                        Outcome::new_err(TypeError::ArrayItemsMustBeSame {
+                            span: item.extra().0,
+                            first: env.resolve(&item_ty),
+                            second: env.resolve(&item.extra().1),
+                        })
+                    }
+                }));
+
+                same.map(|_| ast::Expr::Array {
+                    extra: (*extra, ast::Type::Array((), Box::new(item_ty))),
+                    items: typed_items,
+                })
+            })
+        }
+        ast::Expr::Repeat {
+            extra,
+            length,
+            value,
+        } => infer_argument(env, length, "array".into(), ast::Type::Int(()))
+            .and_zip(infer(env, value))
+            .map(|(typed_length, typed_value)| ast::Expr::Repeat {
+                extra: (
+                    *extra,
+                    ast::Type::Array((), Box::new(typed_value.extra().1.clone())),
+                ),
+                length: Box::new(typed_length),
+                value: Box::new(typed_value),
+            }),
+        ast::Expr::Index { extra, term, index } => {
+            infer_indexed(env, term, index).map(|(typed_term, typed_index, item_ty)| {
+                ast::Expr::Index {
+                    extra: (*extra, item_ty),
+                    term: Box::new(typed_term),
+                    index: Box::new(typed_index),
+                }
+            })
+        }
+        ast::Expr::SetIndex {
+            extra,
+            term,
+            index,
+            value,
+        } => {
+            let indexed = infer_indexed(env, term, index);
+            let value_outcome = match &indexed.result {
+                Some((_, _, item_ty)) => infer_argument(env, value, ":=".into(), item_ty.clone()),
+                None => infer(env, value),
+            };
+
+            indexed
+                .and_zip(value_outcome)
+                .map(
+                    |((typed_term, typed_index, _), typed_value)| ast::Expr::SetIndex {
+                        extra: (*extra, ast::Type::Unit(())),
+                        term: Box::new(typed_term),
+                        index: Box::new(typed_index),
+                        value: Box::new(typed_value),
+                    },
+                )
+        }
+        ast::Expr::Match { extra, term, arms } => {
+            let term_outcome = infer(env, term);
+            let term_ty = term_outcome
+                .result
+                .as_ref()
+                .map(|typed_term| typed_term.extra().1.clone());
+
+            // Every arm is checked, even when the scrutinee or an earlier arm has errors
+            let typed_arms = all(arms.iter().map(|arm| {
+                let (arm_env, pattern_outcome) = bind_pattern(env, &arm.pattern, term_ty.as_ref());
+
+                pattern_outcome.and_zip(infer(&arm_env, &arm.body)).map(
+                    |(typed_pattern, typed_body)| ast::Arm {
+                        extra: (arm.extra, typed_body.extra().1.clone()),
+                        pattern: typed_pattern,
+                        body: typed_body,
+                    },
+                )
+            }));
+
+            term_outcome
+                .and_zip(typed_arms)
+                .and_then(|(typed_term, typed_arms)| {
+                    let first_ty = typed_arms[0].extra.1.clone();
+                    let same = all(typed_arms[1..].iter().map(|arm| {
+                        if env.unify(&arm.extra.1, &first_ty) {
+                            Outcome::new(())
+                        } else {
+                            Outcome::new_err(TypeError::MatchArmsMustBeSame {
+                                span: arm.body.extra().0,
+                                first: env.resolve(&first_ty),
+                                second: env.resolve(&arm.extra.1),
+                            })
+                        }
+                    }));
+
+                    let patterns = typed_arms.iter().map(|a| &a.pattern).collect::<Vec<_>>();
+                    let exhaustive = check_exhaustive(
+                        env,
+                        name_span(extra, "match"),
+                        &env.resolve(&typed_term.extra().1),
+                        &patterns,
+                    );
+
+                    same.and_zip(exhaustive).map(|_| ast::Expr::Match {
+                        extra: (*extra, first_ty),
+                        term: Box::new(typed_term),
+                        arms: typed_arms,
+                    })
+                })
+        }
+    }
+}
+
+// Declarations are checked before the body, and can refer to each other in any order
+// Functions of the module `module` are renamed to `module::name`, so that they never clash when linked,
+// The main module has no name and keeps the names as they are
+// The public declarations are returned even when there are errors, so that importers can still be checked
+fn infer_module(
+    scope: &Scope,
+    p_decls: &[ast::SpanDecl],
+    body: Option<&ast::SpanExpr>,
+    module: Option<&str>,
+) -> (Outcome<CheckedModule>, Scope) {
+    let mut records = scope.records.clone();
+    let mut variants = scope.variants.clone();
+    let mut functions = scope.functions.clone();
+    // Imported functions can be replaced, but ones declared in this module cannot
+    let mut own_functions = HashSet::new();
+    let mut tests = HashSet::new();
+    let mut exports = Scope::default();
+    let mut checks = Outcome::new(());
+    let mut decls = vec![];
+
+    let report = |checks: Outcome<()>, e| checks.and_zip(Outcome::<()>::new_err(e)).map(|_| ());
+
+    for decl in p_decls {
+        let name = decl.name();
+
+        // Functions, types and tests have separate names, so `type List` and `fn List` can both exist
+        let duplicate = match decl {
+            ast::Decl::Function { .. } => !own_functions.insert(name.to_string()),
+            ast::Decl::Test { .. } => !tests.insert(name.to_string()),
+            _ => records.contains_key(name) || variants.contains_key(name),
+        };
+
+        if duplicate {
+            let e = match decl {
+                ast::Decl::Function { .. } => TypeError::DuplicateFunction {
+                    span: *decl.extra(),
+                    name: name.into(),
+                },
+                ast::Decl::Test { .. } => TypeError::DuplicateTest {
+                    span: *decl.extra(),
+                    name: name.into(),
+                },
+                _ => TypeError::DuplicateType {
+                    span: *decl.extra(),
+                    name: name.into(),
+                },
+            };
+
+            checks = report(checks, e);
+            continue;
+        }
+
+        let type_params = decl.type_params();
+        for (i, param) in type_params.iter().enumerate() {
+            if type_params[..i].contains(param) {
+                checks = report(
+                    checks,
+                    TypeError::DuplicateParameter {
+                        span: *decl.extra(),
+                        name: param.clone(),
+                    },
+                );
+            }
+        }
+
+        match decl {
+            ast::Decl::Record { fields, .. } => {
+                for (i, f) in fields.iter().enumerate() {
+                    if fields[..i].iter().any(|other| other.name == f.name) {
+                        checks = report(
+                            checks,
+                            TypeError::DuplicateField {
+                                span: name_span(&f.extra, &f.name),
+                                name: f.name.clone(),
+                            },
+                        );
+                    }
+                }
+
+                let declared = fields
+                    .iter()
+                    .map(|f| (f.name.clone(), f.ty.strip()))
+                    .collect::<Vec<_>>();
+
+                if decl.public() {
+                    exports
+                        .records
+                        .insert(name.to_string(), (type_params.to_vec(), declared.clone()));
+                }
+
+                records.insert(name.to_string(), (type_params.to_vec(), declared));
+            }
+            ast::Decl::Variant { constructors, .. } => {
+                // Constructors are looked up without their type, so they have to be unique across every variant
+                for (i, c) in constructors.iter().enumerate() {
+                    let declared_before = variants
+                        .values()
+                        .any(|(_, cs)| cs.iter().any(|(other, _)| other == &c.name));
+
+                    if declared_before || constructors[..i].iter().any(|o| o.name == c.name) {
+                        checks = report(
+                            checks,
+                            TypeError::DuplicateConstructor {
+                                span: name_span(&c.extra, &c.name),
+                                name: c.name.clone(),
+                            },
+                        );
+                    }
+                }
+
+                let declared = constructors
+                    .iter()
+                    .map(|c| (c.name.clone(), c.args.iter().map(|a| a.strip()).collect()))
+                    .collect::<Vec<_>>();
+
+                if decl.public() {
+                    exports
+                        .variants
+                        .insert(name.to_string(), (type_params.to_vec(), declared.clone()));
+                }
+
+                variants.insert(name.to_string(), (type_params.to_vec(), declared));
+            }
+            ast::Decl::Function { params, result, .. } => {
+                for (i, param) in params.iter().enumerate() {
+                    if params[..i].iter().any(|other| other.name == param.name) {
+                        checks = report(
+                            checks,
+                            TypeError::DuplicateParameter {
+                                span: name_span(&param.extra, &param.name),
+                                name: param.name.clone(),
+                            },
+                        );
+                    }
+                }
+
+                let signature = Signature {
+                    type_params: type_params.to_vec(),
+                    params: params.iter().map(|p| p.ty.strip()).collect(),
+                    result: result.strip(),
+                    link_name: match module {
+                        Some(module) => format!("{}::{}", module, name),
+                        None => name.to_string(),
+                    },
+                };
+
+                if decl.public() {
+                    exports
+                        .functions
+                        .insert(name.to_string(), signature.clone());
+                }
+
+                functions.insert(name.to_string(), signature);
+            }
+            // Nothing can refer to a test, so there is nothing to declare
+            ast::Decl::Test { .. } => {}
+        }
+
+        decls.push(decl);
+    }
+
+    let env = Env {
+        records: Rc::new(records),
+        variants: Rc::new(variants),
+        functions: Rc::new(functions),
+        ..Env::default()
+    };
+
+    let typed_decls = all(decls.into_iter().map(|decl| {
+        // Types in a declaration can only refer to its own type parameters
+        let env = Env {
+            type_vars: decl.type_params().to_vec(),
+            ..env.clone()
+        };
+        let declared = |name: &String, type_params: &[String]| {
+            let args = type_params
+                .iter()
+                .map(|p| ast::Type::Var((), p.clone()))
+                .collect();
+
+            ast::Type::Named((), name.clone(), args)
+        };
+
+        match decl {
+            ast::Decl::Record {
+                extra,
+                public,
+                name,
+                type_params,
+                fields,
+            } => all(fields.iter().map(|f| {
+                check_type(&env, &f.ty).map(|()| ast::FieldDecl {
+                    extra: (f.extra, f.ty.strip()),
+                    name: f.name.clone(),
+                    ty: f.ty.map_extra(&|span| (*span, f.ty.strip())),
+                })
+            }))
+            .map(|typed_fields| ast::Decl::Record {
+                extra: (*extra, declared(name, type_params)),
+                public: *public,
+                name: name.clone(),
+                type_params: type_params.clone(),
+                fields: typed_fields,
+            }),
+            ast::Decl::Variant {
+                extra,
+                public,
+                name,
+                type_params,
+                constructors,
+            } => all(constructors.iter().map(|c| {
+                all(c.args.iter().map(|a| check_type(&env, a))).map(|_| ast::ConstructorDecl {
+                    extra: (c.extra, declared(name, type_params)),
+                    name: c.name.clone(),
+                    args: c
+                        .args
+                        .iter()
+                        .map(|a| a.map_extra(&|span| (*span, a.strip())))
+                        .collect(),
+                })
+            }))
+            .map(|typed_constructors| ast::Decl::Variant {
+                extra: (*extra, declared(name, type_params)),
+                public: *public,
+                name: name.clone(),
+                type_params: type_params.clone(),
+                constructors: typed_constructors,
+            }),
+            ast::Decl::Function {
+                extra,
+                public,
+                name,
+                type_params,
+                params,
+                result,
+                body,
+            } => {
+                let param_checks = params
+                    .iter()
+                    .map(|p| check_type(&env, &p.ty))
+                    .collect::<Vec<_>>();
+
+                // A parameter with a wrong type is still in scope, so that using it is not reported
+                let body_env = params
+                    .iter()
+                    .zip(&param_checks)
+                    .fold(env.clone(), |body_env, (p, c)| {
+                        body_env.with(&p.name, c.result.map(|()| p.ty.strip()))
+                    });
+
+                all(param_checks.into_iter())
+                    .and_zip(check_type(&env, result))
+                    .and_zip(infer(&body_env, body))
+                    .and_then(|(_, typed_body)| {
+                        let got = typed_body.extra().1.clone();
+
+                        // The result can decide what the body is, so unknowns are only resolved after
+                        if !env.unify(&got, &result.strip()) {
+                            return Outcome::new_err(TypeError::ReturnIncorrect {
+                                span: *body.extra(),
+                                name: name.clone(),
+                                got: env.resolve(&got),
+                                expected: result.strip(),
+                            });
+                        }
+
+                        resolve_expr(&env, typed_body).map(|typed_body| ast::Decl::Function {
+                            extra: (*extra, result.strip()),
+                            public: *public,
+                            name: env.functions[name].link_name.clone(),
+                            type_params: type_params.clone(),
+                            params: params
+                                .iter()
+                                .map(|p| ast::Param {
+                                    extra: (p.extra, p.ty.strip()),
+                                    name: p.name.clone(),
+                                    ty: p.ty.map_extra(&|span| (*span, p.ty.strip())),
+                                })
+                                .collect(),
+                            result: result.map_extra(&|span| (*span, result.strip())),
+                            body: typed_body,
+                        })
                     })
+            }
+            // Tests of other modules are named like their functions, which tells them apart when they are run
+            ast::Decl::Test { extra, name, body } => infer(&env, body).and_then(|typed_body| {
+                let got = typed_body.extra().1.clone();
+
+                if !env.unify(&got, &ast::Type::Bool(())) {
+                    return Outcome::new_err(TypeError::TestMustBeBool {
+                        span: *body.extra(),
+                        name: name.clone(),
+                        got: env.resolve(&got),
+                    });
                 }
+
+                resolve_expr(&env, typed_body).map(|typed_body| ast::Decl::Test {
+                    extra: (*extra, ast::Type::Bool(())),
+                    name: match module {
+                        Some(module) => format!("{}::{}", module, name),
+                        None => name.clone(),
+                    },
+                    body: typed_body,
+                })
             }),
+        }
+    }));
+
+    let typed_body = match body {
+        Some(body) => infer(&env, body)
+            .and_then(|b| resolve_expr(&env, b))
+            .map(Some),
+        None => Outcome::new(None),
+    };
+
+    (
+        checks
+            .and_zip(typed_decls.and_then(reject_polymorphic_recursion))
+            .and_zip(typed_body)
+            .map(|(((), decls), body)| (decls, body)),
+        exports,
+    )
+}
+
+// Every call of a function in `e`, with the span of its name, its argument types and its result type
+// Functions used as values are included, since they are compiled for their types like calls are
+fn calls<'a>(
+    e: &'a ast::TypedExpr,
+    functions: &HashMap<&str, &ast::TypedDecl>,
+    found: &mut Vec<(&'a str, ast::Span, Vec<ast::JustType>, ast::JustType)>,
+) {
+    match e {
+        ast::Expr::Call { extra, name, args } if functions.contains_key(name.as_str()) => {
+            let arg_tys = args.iter().map(|a| a.extra().1.clone()).collect();
+            found.push((name, name_span(&extra.0, name), arg_tys, extra.1.clone()));
+        }
+        ast::Expr::Var((span, ast::Type::Function(_, params, result)), name)
+            if functions.contains_key(name.as_str()) =>
+        {
+            found.push((name, *span, params.clone(), (**result).clone()));
+        }
+        _ => {}
+    }
+
+    for child in e.children() {
+        calls(child, functions, found);
+    }
+}
+
+fn mentions(ty: &ast::JustType, param: &str) -> bool {
+    match ty {
+        ast::Type::Var(_, name) => name == param,
+        ast::Type::Tuple(_, items) | ast::Type::Named(_, _, items) => {
+            items.iter().any(|i| mentions(i, param))
+        }
+        ast::Type::Array(_, item) => mentions(item, param),
+        ast::Type::Function(_, params, result) => {
+            params.iter().any(|p| mentions(p, param)) || mentions(result, param)
+        }
+        _ => false,
+    }
+}
+
+// Generic functions are compiled once for every combination of types they are used with,
+// Which never ends when one gets back to itself with a type that is larger than its own type parameter,
+// Like `fn f<a>(x: a): int = f((x, x)) end`
+// Each type parameter of a generic function is a node, with an edge to the type parameters of the generic
+// Functions it calls that are given a type mentioning it, and any cycle through a larger type is an error
+fn reject_polymorphic_recursion(decls: Vec<ast::TypedDecl>) -> Outcome<Vec<ast::TypedDecl>> {
+    let functions = decls
+        .iter()
+        .filter(|d| matches!(d, ast::Decl::Function { .. }) && !d.type_params().is_empty())
+        .map(|d| (d.name(), d))
+        .collect::<HashMap<_, _>>();
+
+    // A type parameter of a function, by their names
+    type Param<'a> = (&'a str, &'a str);
+    // From a type parameter of the caller to one of the function it calls,
+    // With where and what it is called with when the type is larger
+    type Edge<'a> = (Param<'a>, Param<'a>, Option<(ast::Span, ast::JustType)>);
+    let mut edges: Vec<Edge> = vec![];
+    for caller in functions.values() {
+        let body = match caller {
+            ast::Decl::Function { body, .. } => body,
+            _ => continue,
+        };
+        let mut found = vec![];
+        calls(body, &functions, &mut found);
+
+        for (name, span, arg_tys, result) in found {
+            let (callee, decl) = functions.get_key_value(name).unwrap();
+            let type_args = ast::type_args(decl, &arg_tys, &result);
+
+            for (param, ty) in decl.type_params().iter().zip(type_args) {
+                for from in caller.type_params().iter().filter(|p| mentions(&ty, p)) {
+                    let larger = !matches!(&ty, ast::Type::Var(_, name) if name == from);
+                    edges.push((
+                        (caller.name(), from),
+                        (callee, param),
+                        larger.then(|| (span, ty.clone())),
+                    ));
+                }
+            }
+        }
+    }
+
+    let reaches = |from: Param, to: Param| {
+        let mut seen = HashSet::new();
+        let mut next = vec![from];
+        while let Some(at) = next.pop() {
+            if at == to {
+                return true;
+            }
+            if seen.insert(at) {
+                next.extend(
+                    edges
+                        .iter()
+                        .filter(|(f, _, _)| *f == at)
+                        .map(|(_, t, _)| *t),
+                );
+            }
+        }
+        false
+    };
+
+    let mut errors = edges
+        .iter()
+        .filter_map(|(from, to, larger)| {
+            let (span, ty) = larger.as_ref()?;
+
+            reaches(*to, *from).then(|| TypeError::PolymorphicRecursion {
+                span: *span,
+                name: to.0.into(),
+                param: to.1.into(),
+                ty: ty.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    // A call that makes several type parameters larger is only reported once
+    errors.sort_by_key(|e| e.first_location());
+    errors.dedup_by_key(|e| e.first_location());
+
+    if errors.is_empty() {
+        Outcome::new(decls)
+    } else {
+        Outcome {
+            result: None,
+            errors,
+            warnings: vec![],
+        }
+    }
+}
+
+// Replaces every `Type::Unknown` in the tree with what it turned out to be,
+// Every one of them has to be found out by the end of the expression they are in
+fn resolve_expr(env: &Env, e: ast::TypedExpr) -> Outcome<ast::TypedExpr> {
+    // The smallest node is reported, since the nodes around it are unknown because of it
+    let unresolved: RefCell<Option<(ast::Span, ast::JustType)>> = RefCell::new(None);
+    let resolved = e.map_extra(&|(span, ty)| {
+        let ty = env.resolve(ty);
+        let smaller = match &*unresolved.borrow() {
+            Some((other, _)) => span.end - span.start < other.end - other.start,
+            None => true,
+        };
+
+        if has_unknown(&ty) && smaller {
+            *unresolved.borrow_mut() = Some((*span, ty.clone()));
+        }
+
+        (*span, ty)
+    });
+
+    match unresolved.into_inner() {
+        Some((span, got)) => Outcome::new_err(TypeError::CannotInfer { span, got }),
+        None => Outcome::new(resolved),
+    }
+}
+
+fn has_unknown(ty: &ast::JustType) -> bool {
+    match ty {
+        ast::Type::Unknown(..) => true,
+        ast::Type::Tuple(_, items) | ast::Type::Named(_, _, items) => items.iter().any(has_unknown),
+        ast::Type::Array(_, item) => has_unknown(item),
+        ast::Type::Function(_, params, result) => {
+            params.iter().any(has_unknown) || has_unknown(result)
+        }
+        _ => false,
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct CheckResult {
-    pub result: Result<ast::JustType, Vec<TypeError>>,
+pub struct CheckResult<T = ast::TypedProgram> {
+    // Every node of the tree is annotated with its span and type
+    pub result: Result<T, Vec<TypeError>>,
     pub warnings: Vec<TypeWarning>,
 }
 
+impl<T> CheckResult<T> {
+    fn from_outcome(inferred: Outcome<T>) -> CheckResult<T> {
+        // If there are any errors, then return only the errors
+        if !inferred.errors.is_empty() {
+            CheckResult {
+                result: Err(inferred.errors),
+                warnings: inferred.warnings,
+            }
+        } else {
+            CheckResult {
+                // This unwraps the `Some`, since we already verified it should have some value
+                result: Ok(inferred.result.unwrap()),
+                warnings: inferred.warnings,
+            }
+        }
+    }
+}
+
+// Checks a program on its own, without the prelude or anything it imports
+// Programs are checked through `modules::check` when run, this is for tests of the checker itself
+#[cfg(test)]
+pub fn check(p: &ast::SpanProgram) -> CheckResult {
+    let (inferred, _) = infer_module(&Scope::default(), &p.decls, Some(&p.body), None);
+
+    CheckResult::from_outcome(inferred.map(|(decls, body)| ast::Program {
+        imports: vec![],
+        decls,
+        // This unwraps the `Some`, since the body was given
+        body: body.unwrap(),
+    }))
+}
+
 // Wrapper for `infer`, so that it has a safer API
-pub fn check(e: &ast::SpanExpr) -> CheckResult {
-    let inferred = infer(e);
+// Checks one module of a program that is split over several files, `scope` is what it imports
+// The body is only given for the main module, which is the one that is run
+pub fn check_module(
+    scope: &Scope,
+    decls: &[ast::SpanDecl],
+    body: Option<&ast::SpanExpr>,
+    module: Option<&str>,
+) -> (CheckResult<CheckedModule>, Scope) {
+    let (inferred, exports) = infer_module(scope, decls, body, module);
+
+    (CheckResult::from_outcome(inferred), exports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::FileId;
+    use crate::syntax;
+
+    fn check_source(source: &str) -> CheckResult {
+        check(
+            &syntax::ProgramParser::new()
+                .parse(FileId(0), source)
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn annotates_every_node() {
+        let typed = check_source("if true then 1 else 2: int end")
+            .result
+            .unwrap();
 
-    // If there are any errors, then return only the errors
-    if inferred.errors.len() > 0 {
-        CheckResult {
-            result: Err(inferred.errors),
-            warnings: inferred.warnings,
+        match typed.body {
+            ast::Expr::IfFlow {
+                extra,
+                cond,
+                on_false,
+                ..
+            } => {
+                assert_eq!(extra.1, ast::Type::Int(()));
+                assert_eq!(cond.extra().1, ast::Type::Bool(()));
+                assert_eq!(on_false.extra().0.start, 20);
+            }
+            _ => panic!("Expected an `if`"),
         }
-    } else {
-        CheckResult {
-            // This unwraps the `Some`, since we already verified it should have some value
-            result: Ok(inferred.result.unwrap()),
-            warnings: inferred.warnings,
+    }
+
+    #[test]
+    fn operator_arguments() {
+        let typed = check_source(r#"len("ab" ++ "c")"#).result.unwrap();
+        assert_eq!(typed.body.extra().1, ast::Type::Int(()));
+
+        let errors = check_source(r#"len(1 ++ true) ++ "c""#).result.unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            &errors[..],
+            [
+                TypeError::OperandNotSupported {
+                    got: ast::Type::Int(()),
+                    ..
+                },
+                TypeError::OperandNotSupported {
+                    got: ast::Type::Bool(()),
+                    ..
+                },
+                TypeError::OperandNotSupported {
+                    got: ast::Type::Int(()),
+                    ..
+                },
+            ]
+        ));
+
+        // Each operand is reported once, along with every type the operator works on
+        let errors = check_source(r#"(() == (), "a" < "b", 1 == ())"#)
+            .result
+            .unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().all(|e| matches!(
+            e,
+            TypeError::OperandNotSupported { allowed, .. } if allowed.len() > 1
+        )));
+    }
+
+    #[test]
+    fn numeric_operators() {
+        let typed = check_source("1 + 2 * 3 < 4 - 5").result.unwrap();
+        assert_eq!(typed.body.extra().1, ast::Type::Bool(()));
+
+        let typed = check_source("to_float(1) / 2.5e3").result.unwrap();
+        assert_eq!(typed.body.extra().1, ast::Type::Float(()));
+
+        let errors = check_source("1.5 + 2").result.unwrap_err();
+        assert!(matches!(
+            errors[0],
+            TypeError::NumericMixing {
+                span: ast::Span {
+                    start: 6,
+                    end: 7,
+                    ..
+                },
+                ..
+            }
+        ));
+
+        let errors = check_source("1.5 % 2.0").result.unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [TypeError::OperandNotSupported { .. }]
+        ));
+    }
+
+    #[test]
+    fn recovers_after_errors() {
+        let errors = check_source("if (1: bool) then 1 else true end")
+            .result
+            .unwrap_err();
+
+        // The annotation is wrong, but checking continues on to the branches
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], TypeError::AnnotationIncorrect { .. }));
+        assert!(matches!(errors[1], TypeError::IfBranchesMustBeSame { .. }));
+    }
+
+    #[test]
+    fn tuples_and_scopes() {
+        let typed = check_source("let (a, (b, _)) = (1, (true, 2.0)) in (b, a)")
+            .result
+            .unwrap();
+        assert_eq!(
+            typed.body.extra().1,
+            ast::Type::Tuple((), vec![ast::Type::Bool(()), ast::Type::Int(())])
+        );
+
+        let errors = check_source("let count = 1 in cuont + (1, 2).2")
+            .result
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            &errors[0],
+            TypeError::UnknownVariable { suggestion: Some(s), .. } if s == "count"
+        ));
+        assert!(matches!(
+            errors[1],
+            TypeError::ProjectionIncorrect { index: 2, .. }
+        ));
+
+        // Variables bound by a mismatched pattern are not reported again when used
+        let errors = check_source("let (a, b) = 1 in a + b").result.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], TypeError::PatternMismatch { .. }));
+    }
+
+    #[test]
+    fn records() {
+        let decls = "type Point = { x: int, y: float } type Line = { from: Point, to: Point }";
+
+        let typed = check_source(&format!(
+            "{} let p = Point {{ y = 1.0, x = 2 }} in Line {{ from = p, to = {{ p with x = 3 }} }}.to.y",
+            decls
+        ))
+        .result
+        .unwrap();
+        assert_eq!(typed.decls.len(), 2);
+        assert_eq!(typed.body.extra().1, ast::Type::Float(()));
+
+        let errors = check_source(&format!(
+            "{} (Point {{ x = 1, x = 2 }}, Point {{ x = 1, y = 1.0 }}.yy, Pont {{ x = true }})",
+            decls
+        ))
+        .result
+        .unwrap_err();
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(&errors[0], TypeError::MissingFields { fields, .. } if fields == &["y"]));
+        assert!(matches!(errors[1], TypeError::DuplicateField { .. }));
+        assert!(matches!(
+            &errors[2],
+            TypeError::UnknownField { suggestion: Some(s), .. } if s == "y"
+        ));
+        assert!(matches!(
+            &errors[3],
+            TypeError::UnknownType { suggestion: Some(s), .. } if s == "Point"
+        ));
+
+        let errors = check_source("type A = { a: B } type A = { b: int } 1")
+            .result
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        // Duplicates are found before any of the field types are checked
+        assert!(matches!(errors[0], TypeError::DuplicateType { .. }));
+        assert!(matches!(errors[1], TypeError::UnknownType { .. }));
+    }
+
+    #[test]
+    fn variants() {
+        let decls = "type Shape = Circle(float) | Rect(int, int) | Empty";
+
+        let typed = check_source(&format!(
+            "{} match Rect(1, 2) with Circle(_) => 0 | Rect(w, 2) => w | Rect(_, h) => h | Empty => 0 end",
+            decls
+        ))
+        .result
+        .unwrap();
+        assert_eq!(typed.body.extra().1, ast::Type::Int(()));
+
+        let errors = check_source(&format!(
+            "{} (Circle(1), Rect(1), Squar, match Empty with Circle(_) => 1 | Rect(_, _) => true end)",
+            decls
+        ))
+        .result
+        .unwrap_err();
+        assert_eq!(errors.len(), 5);
+        assert!(matches!(errors[0], TypeError::ArgumentIncorrect { .. }));
+        assert!(matches!(
+            errors[1],
+            TypeError::ArgumentCount {
+                expected: 2,
+                got: 1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &errors[2],
+            TypeError::UnknownConstructor {
+                suggestion: None,
+                ..
+            }
+        ));
+        assert!(matches!(errors[3], TypeError::MatchArmsMustBeSame { .. }));
+        assert!(
+            matches!(&errors[4], TypeError::NonExhaustive { missing, .. } if missing == &["Empty"])
+        );
+
+        let errors = check_source("type A = B | C type D = C 1")
+            .result
+            .unwrap_err();
+        assert!(
+            matches!(&errors[..], [TypeError::DuplicateConstructor { name, .. }] if name == "C")
+        );
+    }
+
+    #[test]
+    fn exhaustiveness() {
+        let result = check_source("match (1, true) with (_, true) => 1 | (0, false) => 2 end");
+        assert!(matches!(
+            &result.result.unwrap_err()[..],
+            [TypeError::NonExhaustive { missing, .. }] if missing == &["(_, false)"]
+        ));
+
+        // A `let` pattern has to cover every value too
+        let result = check_source("type S = A(int) | B let A(n) = A(1) in n");
+        assert!(matches!(
+            &result.result.unwrap_err()[..],
+            [TypeError::NonExhaustive { missing, .. }] if missing == &["B"]
+        ));
+
+        let result = check_source("match true with _ => 1 | false => 2 end");
+        assert!(result.result.is_ok());
+        assert!(matches!(
+            result.warnings[..],
+            [TypeWarning::UnreachableArm {
+                span: ast::Span {
+                    start: 25,
+                    end: 30,
+                    ..
+                }
+            }]
+        ));
+    }
+
+    #[test]
+    fn tests_must_be_bool() {
+        let typed = check_source(
+            "fn double(x: int): int = x * 2 end test \"doubles\" = double(2) == 4 end ()",
+        )
+        .result
+        .unwrap();
+        assert!(matches!(
+            &typed.decls[1],
+            ast::Decl::Test { extra: (_, ast::Type::Bool(())), name, .. } if name == "doubles"
+        ));
+
+        let errors = check_source("test \"a\" = 1 end test \"a\" = true end ()")
+            .result
+            .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [
+                TypeError::DuplicateTest { .. },
+                TypeError::TestMustBeBool {
+                    got: ast::Type::Int(()),
+                    ..
+                }
+            ]
+        ));
+    }
+
+    #[test]
+    fn rejects_polymorphic_recursion() {
+        let errors = check_source(
+            "fn rec<a>(x: a, n: int): int = if n == 0 then 0 else rec((x, x), n - 1) end end rec(1, 3)",
+        )
+        .result
+        .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [TypeError::PolymorphicRecursion { name, param, ty: ast::Type::Tuple(_, _), .. }]
+                if name == "rec" && param == "a"
+        ));
+
+        // Through another function, and as a value
+        let errors = check_source(
+            "fn f<a>(x: a): int = g([x]) end fn g<b>(y: b): int = let h = f in h(y) end f(1)",
+        )
+        .result
+        .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [TypeError::PolymorphicRecursion { name, ty: ast::Type::Array(_, _), .. }] if name == "g"
+        ));
+
+        // Recursion with the same or a smaller type only needs a few instances
+        let checked = check_source(
+            "type Option<a> = Some(a) | None
+            fn same<a>(x: a, n: int): a = if n == 0 then x else same(x, n - 1) end end
+            fn smaller<a>(x: Option<a>): int = match x with Some(y) => smaller(None: Option<int>) | None => 0 end end
+            fn swap<a, b>(x: a, y: b, n: int): int = if n == 0 then 0 else swap(y, x, n - 1) end end
+            (same(1, 2), smaller(Some(true)), swap(1, true, 3))",
+        );
+        assert!(checked.result.is_ok(), "{:?}", checked.result);
+    }
+
+    #[test]
+    fn functions() {
+        let typed = check_source("fn double(x: int): int = x * 2 end double(double(1)) > 3")
+            .result
+            .unwrap();
+        assert_eq!(typed.body.extra().1, ast::Type::Bool(()));
+
+        let errors =
+            check_source("fn f(x: int, x: bool): int = 1 end (f(1), f(true, true), ff(1))")
+                .result
+                .unwrap_err();
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(errors[0], TypeError::DuplicateParameter { .. }));
+        assert!(matches!(
+            errors[1],
+            TypeError::ArgumentCount {
+                expected: 2,
+                got: 1,
+                ..
+            }
+        ));
+        assert!(matches!(errors[2], TypeError::ArgumentIncorrect { .. }));
+        assert!(matches!(
+            &errors[3],
+            TypeError::UnknownFunction {
+                suggestion: Some(s),
+                ..
+            } if s == "f"
+        ));
+
+        let errors = check_source("fn f(): bool = 1 end 1").result.unwrap_err();
+        assert!(matches!(
+            errors[..],
+            [TypeError::ReturnIncorrect {
+                span: ast::Span {
+                    start: 15,
+                    end: 16,
+                    ..
+                },
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn generics() {
+        let decls = "type Option<a> = Some(a) | None fn id<a>(x: a): a = x end";
+
+        let typed = check_source(&format!(
+            "{} (id(1), id(Some(true)), id(None): Option<int>)",
+            decls
+        ))
+        .result
+        .unwrap();
+        assert_eq!(
+            typed.body.extra().1.to_string(),
+            "(int, Option<bool>, Option<int>)"
+        );
+
+        // Holes are found out from what comes later
+        let typed = check_source(&format!(
+            "{} let x = None in match x with Some(n) => n + 1 | None => 0 end",
+            decls
+        ))
+        .result
+        .unwrap();
+        match typed.body {
+            ast::Expr::Let { value, .. } => {
+                assert_eq!(value.extra().1.to_string(), "Option<int>")
+            }
+            _ => panic!("Expected a `let`"),
         }
+
+        let errors = check_source(&format!(
+            "{} fn f<a>(x: a): Option = x + 1 end Some(1) == Some(1)",
+            decls
+        ))
+        .result
+        .unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            errors[0],
+            TypeError::TypeArgumentCount {
+                expected: 1,
+                got: 0,
+                ..
+            }
+        ));
+        assert!(matches!(errors[1], TypeError::OperandNotSupported { .. }));
+        assert!(matches!(errors[2], TypeError::OperandNotSupported { .. }));
+
+        let errors = check_source(&format!("{} (None, 1)", decls))
+            .result
+            .unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [TypeError::CannotInfer { got, span }] if got.to_string() == "Option<_>" && span.start == 59
+        ));
+    }
+
+    #[test]
+    fn sequences() {
+        let result = check_source("fn f(): unit = () end f(); 1 + 1; f()");
+        assert_eq!(result.result.unwrap().body.extra().1, ast::Type::Unit(()));
+        assert!(matches!(
+            &result.warnings[..],
+            [TypeWarning::UnusedValue { span: ast::Span {
+                    start: 27,
+                    end: 32,
+                    ..
+                }, ty, by: "`;`" }] if ty == &ast::Type::Int(())
+        ));
+
+        let errors = check_source("(1 + true); 2 + false").result.unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn loops() {
+        let result = check_source("var i = 0 in while i < 10 do i := i + 1 end; i");
+        assert_eq!(result.result.unwrap().body.extra().1, ast::Type::Int(()));
+        assert!(result.warnings.is_empty());
+
+        let errors = check_source("let x = 1 in x := 2").result.unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [TypeError::AssignImmutable { name, .. }] if name == "x"
+        ));
+
+        let errors = check_source("var x = 1 in x := true").result.unwrap_err();
+        assert!(matches!(&errors[..], [TypeError::ArgumentIncorrect { .. }]));
+
+        // A `let` hides the `var` with the same name
+        let errors = check_source("var x = 1 in let x = 2 in x := 3")
+            .result
+            .unwrap_err();
+        assert!(matches!(&errors[..], [TypeError::AssignImmutable { .. }]));
+
+        let errors = check_source("while 1 do () end").result.unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [TypeError::WhileCondMustBeBool {
+                got: ast::Type::Int(()),
+                ..
+            }]
+        ));
+
+        let errors = check_source("break; while (break; true) do () end")
+            .result
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], TypeError::BreakOutsideLoop { .. }));
+
+        let result = check_source("while true do () end");
+        assert!(matches!(
+            &result.warnings[..],
+            [TypeWarning::CondAlways { value: true, .. }]
+        ));
+        let result = check_source("while true do break end");
+        assert!(result.warnings.is_empty());
+
+        let result = check_source("while false do 1 end");
+        assert_eq!(result.warnings.len(), 2);
+
+        // The body of a loop has no `;` after it, but its value is thrown away all the same
+        let result = check_source("var i = 0 in while i < 1 do i := 1; 2 end");
+        assert!(matches!(
+            &result.warnings[..],
+            [TypeWarning::UnusedValue {
+                span: ast::Span {
+                    start: 28,
+                    end: 37,
+                    ..
+                },
+                by: "the `while` loop",
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn arrays() {
+        let result = check_source("let a = [[1], []] in a[0][0] := length(a); a");
+        assert_eq!(
+            result.result.unwrap().body.extra().1,
+            ast::Type::Array(
+                (),
+                Box::new(ast::Type::Array((), Box::new(ast::Type::Int(()))))
+            )
+        );
+
+        let errors = check_source("[1, true]").result.unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [TypeError::ArrayItemsMustBeSame {
+                first: ast::Type::Int(()),
+                second: ast::Type::Bool(()),
+                ..
+            }]
+        ));
+
+        let errors = check_source("(1, 2)[0]").result.unwrap_err();
+        assert!(matches!(&errors[..], [TypeError::NotAnArray { .. }]));
+
+        let errors = check_source("[1][true]").result.unwrap_err();
+        assert!(matches!(&errors[..], [TypeError::ArgumentIncorrect { .. }]));
+
+        let errors = check_source("let a = [1] in a[0] := 1.5")
+            .result
+            .unwrap_err();
+        assert!(matches!(&errors[..], [TypeError::ArgumentIncorrect { .. }]));
+
+        let errors = check_source("length([])").result.unwrap_err();
+        assert!(matches!(&errors[..], [TypeError::CannotInfer { .. }]));
+    }
+
+    #[test]
+    fn print_and_debug() {
+        let result =
+            check_source("fn f<a>(x: a): unit = debug(x) end print((1, [true])); f(\"a\")");
+        assert_eq!(result.result.unwrap().body.extra().1, ast::Type::Unit(()));
+
+        let errors = check_source("print([])").result.unwrap_err();
+        assert!(matches!(&errors[..], [TypeError::CannotInfer { .. }]));
+
+        let errors = check_source("print(1) + 1").result.unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [TypeError::OperandNotSupported { .. }]
+        ));
+    }
+
+    #[test]
+    fn function_values() {
+        let decls = "
+            fn inc(x: int): int = x + 1 end
+            fn apply<a, b>(f: fn(a) -> b, x: a): b = f(x) end
+        ";
+
+        let result = check_source(&format!("{} (apply(inc, 1), inc)", decls));
+        assert_eq!(
+            result.result.unwrap().body.extra().1,
+            ast::Type::Tuple(
+                (),
+                vec![
+                    ast::Type::Int(()),
+                    ast::Type::Function((), vec![ast::Type::Int(())], Box::new(ast::Type::Int(())))
+                ]
+            )
+        );
+
+        let errors = check_source(&format!("{} apply(inc, true)", decls))
+            .result
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+
+        let errors = check_source("let x = 1 in x(2)").result.unwrap_err();
+        assert!(matches!(
+            &errors[..],
+            [TypeError::NotAFunction { name, .. }] if name == "x"
+        ));
+
+        let errors = check_source(&format!("{} let f = inc in f(1, 2)", decls))
+            .result
+            .unwrap_err();
+        assert!(matches!(&errors[..], [TypeError::ArgumentCount { .. }]));
     }
 }
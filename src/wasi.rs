@@ -0,0 +1,730 @@
+use parity_wasm::elements;
+
+use crate::ast;
+use crate::ir;
+use crate::runtime;
+use crate::source;
+use crate::wasm;
+
+// Modules for WASI export `_start`, which prints the result of the program to stdout and exits,
+// There is no host that reads linear memory, so values are formatted by functions in the module
+
+const FD_WRITE: &str = "wasi.fd_write";
+const PROC_EXIT: &str = "wasi.proc_exit";
+const STDOUT: i32 = 1;
+
+fn imports() -> Vec<wasm::Import> {
+    use elements::ValueType::I32;
+
+    vec![
+        // fd_write(fd: i32, iovecs: i32, iovecs_len: i32, written: i32) -> i32
+        wasm::Import {
+            name: FD_WRITE.into(),
+            module: "wasi_snapshot_preview1",
+            field: "fd_write".into(),
+            params: vec![I32, I32, I32, I32],
+            result: Some(I32),
+        },
+        // proc_exit(code: i32)
+        wasm::Import {
+            name: PROC_EXIT.into(),
+            module: "wasi_snapshot_preview1",
+            field: "proc_exit".into(),
+            params: vec![I32],
+            result: None,
+        },
+    ]
+}
+
+fn fresh(temps: &mut Vec<ir::Ty>, ty: ir::Ty) -> ir::Temp {
+    temps.push(ty);
+    ir::Temp(temps.len() as u32 - 1)
+}
+
+fn op(
+    op: ir::Op,
+    args: Vec<ir::Operand>,
+    ty: ir::Ty,
+    temps: &mut Vec<ir::Ty>,
+    insts: &mut Vec<ir::Inst>,
+) -> ir::Operand {
+    let dest = fresh(temps, ty);
+    insts.push(ir::Inst::Op { dest, op, args });
+
+    ir::Operand::Temp(dest)
+}
+
+fn call(
+    function: &str,
+    args: Vec<ir::Operand>,
+    temps: &mut Vec<ir::Ty>,
+    insts: &mut Vec<ir::Inst>,
+) -> ir::Operand {
+    let dest = fresh(temps, ir::Ty::I32);
+    insts.push(ir::Inst::Call {
+        dest: Some(dest),
+        function: function.into(),
+        args,
+    });
+
+    ir::Operand::Temp(dest)
+}
+
+fn concat(
+    parts: Vec<ir::Operand>,
+    temps: &mut Vec<ir::Ty>,
+    insts: &mut Vec<ir::Inst>,
+) -> ir::Operand {
+    parts
+        .into_iter()
+        .reduce(|left, right| op(ir::Op::Concat, vec![left, right], ir::Ty::I32, temps, insts))
+        .unwrap()
+}
+
+// Builds the IR functions that turn values into strings, the same way that `wasm::run` shows them
+struct Formatter<'a> {
+    // Needed to know the fields of records and the constructors of variants
+    decls: &'a [ast::TypedDecl],
+    strings: Vec<String>,
+    // One function for every tuple, array, record and variant type that is shown
+    functions: Vec<ir::Function>,
+}
+
+impl<'a> Formatter<'a> {
+    fn string(&mut self, s: &str) -> ir::Operand {
+        let index = match self.strings.iter().position(|existing| existing == s) {
+            Some(index) => index,
+            None => {
+                self.strings.push(s.into());
+                self.strings.len() - 1
+            }
+        };
+
+        ir::Operand::Str(index as u32)
+    }
+
+    // The type of a field or constructor argument of `name`, given the type arguments it is used with
+    fn declared(
+        &self,
+        ty: &ast::Type<(ast::Span, ast::JustType)>,
+        name: &str,
+        type_args: &[ast::JustType],
+    ) -> ast::JustType {
+        ty.strip()
+            .substitute(ast::type_params(self.decls, name), type_args)
+    }
+
+    // Items inside of tuples and records are formatted like literals, so strings are quoted
+    fn item(
+        &mut self,
+        ty: &ast::JustType,
+        value: ir::Operand,
+        temps: &mut Vec<ir::Ty>,
+        insts: &mut Vec<ir::Inst>,
+    ) -> ir::Operand {
+        match ty {
+            ast::Type::Unit(_) => self.string("()"),
+            ast::Type::Int(_) => call(runtime::SHOW_INT, vec![value], temps, insts),
+            ast::Type::Float(_) => call(runtime::SHOW_FLOAT, vec![value], temps, insts),
+            ast::Type::Bool(_) => {
+                let dest = fresh(temps, ir::Ty::I32);
+                insts.push(ir::Inst::If {
+                    dest,
+                    cond: value,
+                    on_true: ir::Block {
+                        insts: vec![],
+                        result: self.string("true"),
+                    },
+                    on_false: ir::Block {
+                        insts: vec![],
+                        result: self.string("false"),
+                    },
+                });
+
+                ir::Operand::Temp(dest)
+            }
+            ast::Type::String(_) => {
+                let escaped = call(runtime::ESCAPE, vec![value], temps, insts);
+                let quote = self.string("\"");
+
+                concat(vec![quote, escaped, quote], temps, insts)
+            }
+            ast::Type::Tuple(_, _) | ast::Type::Array(_, _) | ast::Type::Named(_, _, _) => {
+                let function = self.function(ty);
+
+                call(&function, vec![value], temps, insts)
+            }
+            // Only the index in the table is known at runtime, which means nothing outside of it
+            ast::Type::Function(_, _, _) => self.string(&format!("<{}>", ty)),
+            ast::Type::Var(_, _) | ast::Type::Unknown(_, _) => panic!("Internal compiler error"),
+        }
+    }
+
+    // Loads the slot at `offset` in `address` and formats it
+    fn slot(
+        &mut self,
+        ty: &ast::JustType,
+        address: ir::Operand,
+        offset: u32,
+        temps: &mut Vec<ir::Ty>,
+        insts: &mut Vec<ir::Inst>,
+    ) -> ir::Operand {
+        // The slot of `unit` holds nothing worth loading
+        let value = match ty {
+            ast::Type::Unit(_) => ir::Operand::I32(0),
+            _ => op(
+                ir::Op::Load(offset),
+                vec![address],
+                ir::ty_of(ty),
+                temps,
+                insts,
+            ),
+        };
+
+        self.item(ty, value, temps, insts)
+    }
+
+    // The items of `tys` at the slots of `address` starting at `first`, with `, ` between them
+    fn slots(
+        &mut self,
+        tys: &[(Option<&str>, ast::JustType)],
+        address: ir::Operand,
+        first: u32,
+        temps: &mut Vec<ir::Ty>,
+        insts: &mut Vec<ir::Inst>,
+    ) -> Vec<ir::Operand> {
+        let mut parts = vec![];
+
+        for (i, (label, ty)) in tys.iter().enumerate() {
+            if i > 0 {
+                parts.push(self.string(", "));
+            }
+            if let Some(label) = label {
+                parts.push(self.string(&format!("{} = ", label)));
+            }
+
+            let offset = (first + i as u32) * ir::SLOT_SIZE;
+            parts.push(self.slot(ty, address, offset, temps, insts));
+        }
+
+        parts
+    }
+
+    // The name of the function that formats values of `ty`, which is built the first time it is needed
+    fn function(&mut self, ty: &ast::JustType) -> String {
+        let name = format!("show.{}", ty);
+
+        // Recursive types call the function while it is still being built
+        if self.functions.iter().any(|f| f.name == name) {
+            return name;
+        }
+        let index = self.functions.len();
+        self.functions.push(ir::Function {
+            name: name.clone(),
+            params: vec![ir::Ty::I32],
+            result: Some(ir::Ty::I32),
+            temps: vec![],
+            body: ir::Block {
+                insts: vec![],
+                result: ir::Operand::I32(0),
+            },
+        });
+
+        let mut temps = vec![ir::Ty::I32];
+        let mut insts = vec![];
+        let value = ir::Operand::Temp(ir::Temp(0));
+        let result = self.pointed(ty, value, &mut temps, &mut insts);
+
+        self.functions[index].temps = temps;
+        self.functions[index].body = ir::Block { insts, result };
+
+        name
+    }
+
+    // Values that are a pointer to their slots
+    fn pointed(
+        &mut self,
+        ty: &ast::JustType,
+        value: ir::Operand,
+        temps: &mut Vec<ir::Ty>,
+        insts: &mut Vec<ir::Inst>,
+    ) -> ir::Operand {
+        match ty {
+            ast::Type::Tuple(_, items) => {
+                let items = items
+                    .iter()
+                    .map(|ty| (None, ty.clone()))
+                    .collect::<Vec<_>>();
+                let mut parts = vec![self.string("(")];
+                parts.append(&mut self.slots(&items, value, 0, temps, insts));
+                parts.push(self.string(")"));
+
+                concat(parts, temps, insts)
+            }
+            ast::Type::Array(_, item) => self.array(item, value, temps, insts),
+            ast::Type::Named(_, name, type_args)
+                if ast::record_fields(self.decls, name).is_none() =>
+            {
+                let constructors = ast::variant_constructors(self.decls, name).unwrap();
+                let tag = op(ir::Op::Load(0), vec![value], ir::Ty::I32, temps, insts);
+
+                let cases = constructors
+                    .iter()
+                    .map(|c| {
+                        let mut insts = vec![];
+                        let result = if c.args.is_empty() {
+                            self.string(&c.name)
+                        } else {
+                            let args = c
+                                .args
+                                .iter()
+                                .map(|ty| (None, self.declared(ty, name, type_args)))
+                                .collect::<Vec<_>>();
+                            let mut parts = vec![self.string(&format!("{}(", c.name))];
+                            parts.append(&mut self.slots(&args, value, 1, temps, &mut insts));
+                            parts.push(self.string(")"));
+
+                            concat(parts, temps, &mut insts)
+                        };
+
+                        ir::Block { insts, result }
+                    })
+                    .collect();
+
+                let dest = fresh(temps, ir::Ty::I32);
+                insts.push(ir::Inst::Switch {
+                    dest,
+                    index: tag,
+                    cases,
+                });
+
+                ir::Operand::Temp(dest)
+            }
+            ast::Type::Named(_, name, type_args) => {
+                let fields = ast::record_fields(self.decls, name)
+                    .unwrap()
+                    .iter()
+                    .map(|f| (Some(f.name.as_str()), self.declared(&f.ty, name, type_args)))
+                    .collect::<Vec<_>>();
+                let mut parts = vec![self.string(&format!("{} {{ ", name))];
+                parts.append(&mut self.slots(&fields, value, 0, temps, insts));
+                parts.push(self.string(" }"));
+
+                concat(parts, temps, insts)
+            }
+            _ => panic!("Internal compiler error"),
+        }
+    }
+
+    // Adds every item to the end of the string so far, in a loop over the array
+    fn array(
+        &mut self,
+        item: &ast::JustType,
+        value: ir::Operand,
+        temps: &mut Vec<ir::Ty>,
+        insts: &mut Vec<ir::Inst>,
+    ) -> ir::Operand {
+        let shown = fresh(temps, ir::Ty::I32);
+        let i = fresh(temps, ir::Ty::I64);
+        let open = self.string("[");
+        insts.push(ir::Inst::Assign {
+            dest: shown,
+            value: open,
+        });
+        insts.push(ir::Inst::Assign {
+            dest: i,
+            value: ir::Operand::I64(0),
+        });
+        let length = op(ir::Op::Len, vec![value], ir::Ty::I64, temps, insts);
+
+        let mut cond = vec![];
+        let more = op(
+            ir::Op::Lt,
+            vec![ir::Operand::Temp(i), length],
+            ir::Ty::I32,
+            temps,
+            &mut cond,
+        );
+
+        let mut body = vec![];
+        let not_first = op(
+            ir::Op::Gt,
+            vec![ir::Operand::Temp(i), ir::Operand::I64(0)],
+            ir::Ty::I32,
+            temps,
+            &mut body,
+        );
+        let separator = fresh(temps, ir::Ty::I32);
+        body.push(ir::Inst::If {
+            dest: separator,
+            cond: not_first,
+            on_true: ir::Block {
+                insts: vec![],
+                result: self.string(", "),
+            },
+            on_false: ir::Block {
+                insts: vec![],
+                result: self.string(""),
+            },
+        });
+        let address = op(
+            ir::Op::Element,
+            vec![value, ir::Operand::Temp(i)],
+            ir::Ty::I32,
+            temps,
+            &mut body,
+        );
+        let text = self.slot(item, address, 0, temps, &mut body);
+        let next = concat(
+            vec![ir::Operand::Temp(shown), ir::Operand::Temp(separator), text],
+            temps,
+            &mut body,
+        );
+        body.push(ir::Inst::Assign {
+            dest: shown,
+            value: next,
+        });
+        let next = op(
+            ir::Op::Add,
+            vec![ir::Operand::Temp(i), ir::Operand::I64(1)],
+            ir::Ty::I64,
+            temps,
+            &mut body,
+        );
+        body.push(ir::Inst::Assign {
+            dest: i,
+            value: next,
+        });
+
+        insts.push(ir::Inst::Loop {
+            cond: ir::Block {
+                insts: cond,
+                result: more,
+            },
+            body,
+        });
+        let close = self.string("]");
+
+        concat(vec![ir::Operand::Temp(shown), close], temps, insts)
+    }
+
+    // Writes `text` to stdout, followed by a newline
+    fn write_line(
+        &mut self,
+        text: ir::Operand,
+        temps: &mut Vec<ir::Ty>,
+        insts: &mut Vec<ir::Inst>,
+    ) {
+        let newline = self.string("\n");
+        let line = concat(vec![text, newline], temps, insts);
+
+        // A single iovec of the address and length of the bytes, followed by where the number written goes
+        let iovec = op(
+            ir::Op::Alloc,
+            vec![ir::Operand::I32(12)],
+            ir::Ty::I32,
+            temps,
+            insts,
+        );
+        let bytes = op(
+            ir::Op::Add,
+            vec![line, ir::Operand::I32(4)],
+            ir::Ty::I32,
+            temps,
+            insts,
+        );
+        let length = op(ir::Op::Load(0), vec![line], ir::Ty::I32, temps, insts);
+        let written = op(
+            ir::Op::Add,
+            vec![iovec, ir::Operand::I32(8)],
+            ir::Ty::I32,
+            temps,
+            insts,
+        );
+        insts.push(ir::Inst::Store {
+            address: iovec,
+            offset: 0,
+            value: bytes,
+        });
+        insts.push(ir::Inst::Store {
+            address: iovec,
+            offset: 4,
+            value: length,
+        });
+        call(
+            FD_WRITE,
+            vec![
+                ir::Operand::I32(STDOUT),
+                iovec,
+                ir::Operand::I32(1),
+                written,
+            ],
+            temps,
+            insts,
+        );
+    }
+
+    // Calls `main` and writes what it returns on its own line, nothing is written for `unit`
+    fn start(&mut self, final_ty: &ast::JustType) -> ir::Function {
+        let mut temps = vec![];
+        let mut insts = vec![];
+
+        if let ast::Type::Unit(_) = final_ty {
+            insts.push(ir::Inst::Call {
+                dest: None,
+                function: "main".into(),
+                args: vec![],
+            });
+        } else {
+            let result = fresh(&mut temps, ir::ty_of(final_ty));
+            insts.push(ir::Inst::Call {
+                dest: Some(result),
+                function: "main".into(),
+                args: vec![],
+            });
+
+            // The result is not quoted when it is a string, only the strings inside of it are
+            let text = match final_ty {
+                ast::Type::String(_) => ir::Operand::Temp(result),
+                _ => self.item(final_ty, ir::Operand::Temp(result), &mut temps, &mut insts),
+            };
+            self.write_line(text, &mut temps, &mut insts);
+        }
+
+        insts.push(ir::Inst::Call {
+            dest: None,
+            function: PROC_EXIT.into(),
+            args: vec![ir::Operand::I32(0)],
+        });
+
+        ir::Function {
+            name: "_start".into(),
+            params: vec![],
+            result: None,
+            temps,
+            body: ir::Block {
+                insts,
+                result: ir::Operand::I32(0),
+            },
+        }
+    }
+
+    // The function that the host would provide for `print` and `debug` of values that are a `ty`,
+    // Which picks how to show the value by the index of the call
+    fn print(
+        &mut self,
+        ty: ir::Ty,
+        printed: &[ir::Printed],
+        sources: &source::SourceMap,
+    ) -> ir::Function {
+        let mut temps = vec![ty, ir::Ty::I32];
+        let mut insts = vec![];
+        let value = ir::Operand::Temp(ir::Temp(0));
+
+        let cases = printed
+            .iter()
+            .map(|p| {
+                let mut insts = vec![];
+
+                // Calls with a value of another type never get here
+                if ir::ty_of(&p.ty) == ty {
+                    let prefix = self.string(&wasm::print_prefix(p, sources));
+                    let shown = match (&p.ty, p.debug) {
+                        (ast::Type::String(_), None) => value,
+                        _ => self.item(&p.ty, value, &mut temps, &mut insts),
+                    };
+                    let text = concat(vec![prefix, shown], &mut temps, &mut insts);
+                    self.write_line(text, &mut temps, &mut insts);
+                }
+
+                ir::Block {
+                    insts,
+                    result: ir::Operand::I32(0),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if !cases.is_empty() {
+            let dest = fresh(&mut temps, ir::Ty::I32);
+            insts.push(ir::Inst::Switch {
+                dest,
+                index: ir::Operand::Temp(ir::Temp(1)),
+                cases,
+            });
+        }
+
+        ir::Function {
+            name: ir::print_function(ty),
+            params: vec![ty, ir::Ty::I32],
+            result: None,
+            temps,
+            body: ir::Block {
+                insts,
+                result: ir::Operand::I32(0),
+            },
+        }
+    }
+}
+
+// `sources` is needed for the locations that `debug` writes
+pub fn ast_to_wasm(p: &ast::TypedProgram, sources: &source::SourceMap) -> elements::Module {
+    let mut m = ir::lower(p);
+    let mut formatter = Formatter {
+        decls: &p.decls,
+        strings: std::mem::take(&mut m.strings),
+        functions: vec![],
+    };
+
+    let start = formatter.start(&p.body.extra().1);
+    m.functions.push(start);
+    for ty in [ir::Ty::I32, ir::Ty::I64, ir::Ty::F64] {
+        let print = formatter.print(ty, &m.printed, sources);
+        m.functions.push(print);
+    }
+    m.functions.append(&mut formatter.functions);
+    m.strings = formatter.strings;
+
+    wasm::ir_to_wasm(&m, &imports(), &["_start".into()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend_test::compile;
+    use std::io::Read;
+    use std::sync::{Arc, Mutex};
+
+    // Runs the module through wasmer's WASI support, and gives what it wrote to stdout and the exit code
+    fn run_wasi(source: &str) -> (String, u32) {
+        let (typed, sources) = compile(source);
+        let bytes = ast_to_wasm(&typed, &sources).to_bytes().unwrap();
+
+        let compiler = wasmer_compiler_cranelift::Cranelift::new();
+        let store = wasmer::Store::new(&wasmer::Universal::new(compiler).engine());
+        let module = wasmer::Module::from_binary(&store, &bytes).unwrap();
+
+        let mut env = wasmer_wasi::WasiState::new("test")
+            .stdout(Box::new(wasmer_wasi::Pipe::new()))
+            .finalize()
+            .unwrap();
+        let import_object = env.import_object(&module).unwrap();
+        let instance = wasmer::Instance::new(&module, &import_object).unwrap();
+
+        let start = instance.exports.get_function("_start").unwrap();
+        let code = match start.call(&[]) {
+            Ok(_) => panic!("expected `proc_exit` to be called"),
+            Err(e) => match e.downcast::<wasmer_wasi::WasiError>() {
+                Ok(wasmer_wasi::WasiError::Exit(code)) => code,
+                other => panic!("expected an exit, got {:?}", other),
+            },
+        };
+
+        let mut output = String::new();
+        env.state()
+            .fs
+            .stdout_mut()
+            .unwrap()
+            .as_mut()
+            .unwrap()
+            .read_to_string(&mut output)
+            .unwrap();
+
+        (output, code)
+    }
+
+    // The output of WASI is the same as the host prints
+    fn assert_same(source: &str) {
+        let (typed, sources) = compile(source);
+        let output = Arc::new(Mutex::new(vec![]));
+        let result = wasm::run(&wasm::ast_to_wasm(&typed), &sources, output.clone()).unwrap();
+
+        let mut expected = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        if typed.body.extra().1 != ast::Type::Unit(()) {
+            expected += &format!("{}\n", result);
+        }
+
+        assert_eq!(run_wasi(source), (expected, 0));
+    }
+
+    #[test]
+    fn prints_primitives() {
+        assert_same("1 + 2");
+        assert_same("0 - 9223372036854775807 - 1");
+        assert_same("(true, false, ())");
+        assert_same(r#""a\tb" ++ "c""#);
+        assert_same(r#"("a\"\\\n\r\t\0é", "")"#);
+        assert_eq!(run_wasi("fn f(): unit = () end f()"), ("".into(), 0));
+    }
+
+    #[test]
+    fn prints_floats() {
+        let floats = [
+            "0.0",
+            "-0.0",
+            "2.5",
+            "0.1",
+            "100.0",
+            "123.456",
+            "1e15",
+            "1e16",
+            "1.5e16",
+            "0.0001",
+            "0.00012",
+            "1e-5",
+            "2.5e-7",
+            "1e300",
+            "1e-300",
+            "0.3",
+            "5e-324",
+            "1e22",
+            "1e23",
+            "9.5",
+            "123456789.125",
+        ];
+
+        for x in floats {
+            assert_same(x);
+            assert_same(&format!("0.0 - {}", x));
+        }
+        assert_same("(0.1 + 0.2, 1.0 / 3.0, 2.0 / 3.0, 1e300 * 1e10, 0.0 - 1e300 * 1e10)");
+        assert_same("let inf = 1e300 * 1e10 in inf - inf");
+    }
+
+    #[test]
+    fn prints_values() {
+        let decls = r#"
+            type Shape = Circle(float) | Rect(int, int) | Empty
+            type List<a> = Cons(a, List<a>) | Nil
+            type Point = { x: int, y: float, name: string }
+            fn inc(x: int): int = x + 1 end
+            fn name(x: int): string = if x > 2 then "big" else "small" end end
+        "#;
+
+        assert_same(&format!("{} (Rect(1, 2), Empty, Circle(0.5))", decls));
+        assert_same(&format!(
+            "{} Cons((1, \"a\"), Cons((2, \"b\"), Nil))",
+            decls
+        ));
+        assert_same(&format!(
+            r#"{} [Point {{ x = 1, y = 2.5, name = "p" }}]"#,
+            decls
+        ));
+        assert_same(&format!("{} (inc, [[1, 2], [], [3]], [(), ()])", decls));
+        assert_same(&format!("{} map(range(0, 5), name)", decls));
+    }
+
+    #[test]
+    fn prints_output() {
+        assert_same(
+            r#"
+            type Shape = Circle(float) | Rect(int, int)
+            print("a\tb");
+            debug("a\tb");
+            debug(Rect(1, 2));
+            var i = 0 in
+            while i < 3 do print(to_float(i) / 4.0); i := i + 1 end;
+            print(())
+            "#,
+        );
+    }
+}
@@ -1,73 +1,1271 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+
 use parity_wasm::elements;
 
 use crate::ast;
+use crate::ir;
+use crate::runtime;
+use crate::source;
 
-pub fn expr_to_ins(a: &ast::JustExpr) -> Vec<elements::Instruction> {
-    match a {
-        ast::Expr::IntLit(_, n) => vec![elements::Instruction::I64Const(*n)],
-        ast::Expr::BoolLit(_, b) => vec![elements::Instruction::I64Const(*b as i64)],
-        ast::Expr::TypeAnno { term, .. } => expr_to_ins(term),
-        ast::Expr::IfFlow {
-            cond,
-            on_true,
-            on_false,
-            ..
-        } => {
-            let mut if_ins = vec![];
-
-            if_ins.append(&mut expr_to_ins(cond));
-            if_ins.push(elements::Instruction::I32WrapI64);
-
-            if_ins.push(elements::Instruction::If(elements::BlockType::Value(
-                elements::ValueType::I64,
-            )));
-            if_ins.append(&mut expr_to_ins(on_true));
-            if_ins.push(elements::Instruction::Else);
-            if_ins.append(&mut expr_to_ins(on_false));
-            if_ins.push(elements::Instruction::End);
-
-            if_ins
-        }
+fn ty_to_wasm(ty: ir::Ty) -> elements::ValueType {
+    match ty {
+        ir::Ty::I32 => elements::ValueType::I32,
+        ir::Ty::I64 => elements::ValueType::I64,
+        ir::Ty::F64 => elements::ValueType::F64,
     }
 }
 
-pub fn ast_to_wasm(a: &ast::JustExpr) -> elements::Module {
-    let mut ins = expr_to_ins(a);
+// What is needed to translate the body of one function
+struct Context<'a> {
+    // Types of the temporaries in the function
+    temps: &'a [ir::Ty],
+    // Index of every function in the module, by name
+    functions: &'a HashMap<String, u32>,
+    // Address of every string literal in linear memory
+    strings: &'a [u32],
+    // The type section of the module, indirect calls need the index of their signature in it
+    types: &'a RefCell<Vec<elements::FunctionType>>,
+}
+
+impl<'a> Context<'a> {
+    fn operand_to_ins(&self, o: &ir::Operand) -> elements::Instruction {
+        match o {
+            ir::Operand::I32(n) => elements::Instruction::I32Const(*n),
+            ir::Operand::I64(n) => elements::Instruction::I64Const(*n),
+            // Floats constants are stored as their bits
+            ir::Operand::F64(x) => elements::Instruction::F64Const(x.to_bits()),
+            ir::Operand::Str(i) => {
+                elements::Instruction::I32Const(self.strings[*i as usize] as i32)
+            }
+            // Functions used as values are placed in the table in the same order as `Module::table`
+            ir::Operand::Func(i) => elements::Instruction::I32Const(*i as i32),
+            ir::Operand::Temp(t) => elements::Instruction::GetLocal(t.0),
+        }
+    }
+
+    // The index of the signature in the type section, which is added when it is not there yet
+    fn type_index(
+        &self,
+        params: Vec<elements::ValueType>,
+        result: Option<elements::ValueType>,
+    ) -> u32 {
+        let ty = elements::FunctionType::new(params, result.into_iter().collect());
+        let mut types = self.types.borrow_mut();
+
+        match types.iter().position(|existing| existing == &ty) {
+            Some(index) => index as u32,
+            None => {
+                types.push(ty);
+                types.len() as u32 - 1
+            }
+        }
+    }
+
+    fn operand_ty(&self, o: &ir::Operand) -> ir::Ty {
+        match o {
+            ir::Operand::I32(_) | ir::Operand::Str(_) | ir::Operand::Func(_) => ir::Ty::I32,
+            ir::Operand::I64(_) => ir::Ty::I64,
+            ir::Operand::F64(_) => ir::Ty::F64,
+            ir::Operand::Temp(t) => self.temps[t.0 as usize],
+        }
+    }
+
+    // `ty` is the type of the first argument, which picks between the wasm instructions for each type,
+    // Except for loads which are picked by the type of `dest`
+    fn op_to_ins(&self, op: &ir::Op, ty: ir::Ty, dest_ty: ir::Ty) -> Vec<elements::Instruction> {
+        use elements::Instruction::*;
+
+        let ins = match (op, ty) {
+            (ir::Op::Concat, _) => Call(self.functions[runtime::CONCAT]),
+            (ir::Op::Alloc, _) => Call(self.functions[runtime::ALLOC]),
+            (ir::Op::Load(offset), _) => match dest_ty {
+                ir::Ty::I32 => I32Load(2, *offset),
+                ir::Ty::I64 => I64Load(3, *offset),
+                ir::Ty::F64 => F64Load(3, *offset),
+            },
+            (ir::Op::StrEq, _) => Call(self.functions[runtime::STR_EQ]),
+            // The length is stored right at the address of the string or array
+            (ir::Op::Len, _) => return vec![I32Load(2, 0), I64ExtendUI32],
+            (ir::Op::NewArray, _) => Call(self.functions[runtime::NEW_ARRAY]),
+            // The items start after the slot of the length
+            (ir::Op::Element, _) => {
+                return vec![
+                    I32WrapI64,
+                    I32Const(3),
+                    I32Shl,
+                    I32Add,
+                    I32Const(ir::SLOT_SIZE as i32),
+                    I32Add,
+                ]
+            }
+            (ir::Op::Not, _) => I32Eqz,
+            (ir::Op::Add, ir::Ty::I32) => I32Add,
+            (ir::Op::Eq, ir::Ty::I32) => I32Eq,
+            (ir::Op::Ne, ir::Ty::I32) => I32Ne,
+            (ir::Op::Add, ir::Ty::I64) => I64Add,
+            (ir::Op::Sub, ir::Ty::I64) => I64Sub,
+            (ir::Op::Mul, ir::Ty::I64) => I64Mul,
+            (ir::Op::Div, ir::Ty::I64) => I64DivS,
+            (ir::Op::Rem, ir::Ty::I64) => I64RemS,
+            (ir::Op::Eq, ir::Ty::I64) => I64Eq,
+            (ir::Op::Ne, ir::Ty::I64) => I64Ne,
+            (ir::Op::Lt, ir::Ty::I64) => I64LtS,
+            (ir::Op::Le, ir::Ty::I64) => I64LeS,
+            (ir::Op::Gt, ir::Ty::I64) => I64GtS,
+            (ir::Op::Ge, ir::Ty::I64) => I64GeS,
+            (ir::Op::ToFloat, ir::Ty::I64) => F64ConvertSI64,
+            (ir::Op::Add, ir::Ty::F64) => F64Add,
+            (ir::Op::Sub, ir::Ty::F64) => F64Sub,
+            (ir::Op::Mul, ir::Ty::F64) => F64Mul,
+            (ir::Op::Div, ir::Ty::F64) => F64Div,
+            (ir::Op::Eq, ir::Ty::F64) => F64Eq,
+            (ir::Op::Ne, ir::Ty::F64) => F64Ne,
+            (ir::Op::Lt, ir::Ty::F64) => F64Lt,
+            (ir::Op::Le, ir::Ty::F64) => F64Le,
+            (ir::Op::Gt, ir::Ty::F64) => F64Gt,
+            (ir::Op::Ge, ir::Ty::F64) => F64Ge,
+            (ir::Op::ToInt, ir::Ty::F64) => I64TruncSF64,
+            _ => panic!("Internal compiler error"),
+        };
+
+        vec![ins]
+    }
+
+    // `depth` is the number of blocks between the instruction and the end of the innermost loop,
+    // Which is where `break` jumps to
+    fn inst_to_ins(&self, i: &ir::Inst, depth: u32) -> Vec<elements::Instruction> {
+        match i {
+            ir::Inst::Op { dest, op, args } => {
+                let mut op_ins = args
+                    .iter()
+                    .map(|a| self.operand_to_ins(a))
+                    .collect::<Vec<_>>();
+
+                op_ins.append(&mut self.op_to_ins(
+                    op,
+                    self.operand_ty(&args[0]),
+                    self.temps[dest.0 as usize],
+                ));
+                op_ins.push(elements::Instruction::SetLocal(dest.0));
+
+                op_ins
+            }
+            ir::Inst::If {
+                dest,
+                cond,
+                on_true,
+                on_false,
+            } => {
+                let mut if_ins = vec![self.operand_to_ins(cond)];
+
+                if_ins.push(elements::Instruction::If(elements::BlockType::Value(
+                    ty_to_wasm(self.temps[dest.0 as usize]),
+                )));
+                if_ins.append(&mut self.block_to_ins(on_true, depth + 1));
+                if_ins.push(elements::Instruction::Else);
+                if_ins.append(&mut self.block_to_ins(on_false, depth + 1));
+                if_ins.push(elements::Instruction::End);
+                if_ins.push(elements::Instruction::SetLocal(dest.0));
+
+                if_ins
+            }
+            ir::Inst::Store {
+                address,
+                offset,
+                value,
+            } => {
+                let store = match self.operand_ty(value) {
+                    ir::Ty::I32 => elements::Instruction::I32Store(2, *offset),
+                    ir::Ty::I64 => elements::Instruction::I64Store(3, *offset),
+                    ir::Ty::F64 => elements::Instruction::F64Store(3, *offset),
+                };
+
+                vec![
+                    self.operand_to_ins(address),
+                    self.operand_to_ins(value),
+                    store,
+                ]
+            }
+            // One block per case nested inside each other, `br_table` exits the block of the chosen case,
+            // Which is followed by the code of that case and a jump past the remaining cases
+            ir::Inst::Switch { dest, index, cases } => {
+                let n = cases.len() as u32;
+                let mut switch_ins = vec![elements::Instruction::Block(
+                    elements::BlockType::Value(ty_to_wasm(self.temps[dest.0 as usize])),
+                )];
+
+                for _ in cases {
+                    switch_ins.push(elements::Instruction::Block(elements::BlockType::NoResult));
+                }
+
+                switch_ins.push(self.operand_to_ins(index));
+                switch_ins.push(elements::Instruction::BrTable(Box::new(
+                    elements::BrTableData {
+                        table: (0..n).collect::<Vec<_>>().into_boxed_slice(),
+                        default: n - 1,
+                    },
+                )));
+
+                for (k, case) in cases.iter().enumerate() {
+                    switch_ins.push(elements::Instruction::End);
+                    // The blocks of the later cases are still open, and so is the outer block
+                    switch_ins.append(&mut self.block_to_ins(case, depth + n - k as u32));
+
+                    // The last case is followed by the end of the outer block anyway
+                    if k as u32 != n - 1 {
+                        switch_ins.push(elements::Instruction::Br(n - 1 - k as u32));
+                    }
+                }
+
+                switch_ins.push(elements::Instruction::End);
+                switch_ins.push(elements::Instruction::SetLocal(dest.0));
 
+                switch_ins
+            }
+            ir::Inst::Call {
+                dest,
+                function,
+                args,
+            } => {
+                let mut call_ins = args
+                    .iter()
+                    .map(|a| self.operand_to_ins(a))
+                    .collect::<Vec<_>>();
+
+                call_ins.push(elements::Instruction::Call(self.functions[function]));
+                if let Some(dest) = dest {
+                    call_ins.push(elements::Instruction::SetLocal(dest.0));
+                }
+
+                call_ins
+            }
+            ir::Inst::CallIndirect {
+                dest,
+                function,
+                args,
+            } => {
+                let mut call_ins = args
+                    .iter()
+                    .map(|a| self.operand_to_ins(a))
+                    .collect::<Vec<_>>();
+
+                let type_index = self.type_index(
+                    args.iter()
+                        .map(|a| ty_to_wasm(self.operand_ty(a)))
+                        .collect(),
+                    dest.map(|d| ty_to_wasm(self.temps[d.0 as usize])),
+                );
+                call_ins.push(self.operand_to_ins(function));
+                call_ins.push(elements::Instruction::CallIndirect(type_index, 0));
+                if let Some(dest) = dest {
+                    call_ins.push(elements::Instruction::SetLocal(dest.0));
+                }
+
+                call_ins
+            }
+            ir::Inst::CheckIndex { array, index, span } => vec![
+                self.operand_to_ins(array),
+                self.operand_to_ins(index),
+                elements::Instruction::I32Const(span.file.0 as i32),
+                elements::Instruction::I32Const(span.start as i32),
+                elements::Instruction::I32Const(span.end as i32),
+                elements::Instruction::Call(self.functions[runtime::CHECK_INDEX]),
+            ],
+            ir::Inst::Assign { dest, value } => vec![
+                self.operand_to_ins(value),
+                elements::Instruction::SetLocal(dest.0),
+            ],
+            // The loop is inside of a block, so that jumping to the end of the block leaves the loop
+            ir::Inst::Loop { cond, body } => {
+                let mut loop_ins = vec![
+                    elements::Instruction::Block(elements::BlockType::NoResult),
+                    elements::Instruction::Loop(elements::BlockType::NoResult),
+                ];
+
+                loop_ins.append(&mut self.block_to_ins(cond, 1));
+                loop_ins.push(elements::Instruction::I32Eqz);
+                loop_ins.push(elements::Instruction::BrIf(1));
+                loop_ins.extend(body.iter().flat_map(|i| self.inst_to_ins(i, 1)));
+                loop_ins.push(elements::Instruction::Br(0));
+                loop_ins.push(elements::Instruction::End);
+                loop_ins.push(elements::Instruction::End);
+
+                loop_ins
+            }
+            ir::Inst::Break => vec![elements::Instruction::Br(depth)],
+            ir::Inst::Trap => vec![elements::Instruction::Unreachable],
+        }
+    }
+
+    // Leaves the result of the block on the stack
+    fn block_to_ins(&self, b: &ir::Block, depth: u32) -> Vec<elements::Instruction> {
+        let mut ins = b
+            .insts
+            .iter()
+            .flat_map(|i| self.inst_to_ins(i, depth))
+            .collect::<Vec<_>>();
+        ins.push(self.operand_to_ins(&b.result));
+
+        ins
+    }
+}
+
+fn function_to_wasm(
+    builder: parity_wasm::builder::ModuleBuilder,
+    params: Vec<elements::ValueType>,
+    result: Option<elements::ValueType>,
+    locals: Vec<elements::ValueType>,
+    mut ins: Vec<elements::Instruction>,
+) -> parity_wasm::builder::ModuleBuilder {
     // Functions have to finish with an `End` instruction
     ins.push(elements::Instruction::End);
 
-    parity_wasm::builder::module()
-        .function()
-        .signature()
-        .with_result(elements::ValueType::I64)
+    let signature = builder.function().signature().with_params(params);
+    let signature = match result {
+        Some(ty) => signature.with_result(ty),
+        None => signature,
+    };
+
+    signature
         .build()
         .body()
+        .with_locals(
+            locals
+                .into_iter()
+                .map(|ty| elements::Local::new(1, ty))
+                .collect(),
+        )
         .with_instructions(elements::Instructions::new(ins))
         .build()
         .build()
+}
+
+fn push_type(builder: &mut parity_wasm::builder::ModuleBuilder, ty: &elements::FunctionType) {
+    builder.push_signature(
+        parity_wasm::builder::signature()
+            .with_params(ty.params().to_vec())
+            .with_results(ty.results().to_vec())
+            .build_sig(),
+    );
+}
+
+// A function that the host provides, which the IR calls by `name`
+pub struct Import {
+    pub name: String,
+    pub module: &'static str,
+    pub field: String,
+    pub params: Vec<elements::ValueType>,
+    pub result: Option<elements::ValueType>,
+}
+
+// `entries` are the functions that are exported for the host to call
+pub fn ir_to_wasm(m: &ir::Module, imports: &[Import], entries: &[String]) -> elements::Module {
+    let mut builder = parity_wasm::builder::module();
+
+    // Every string literal is placed one after the other in a single data segment
+    let mut data = vec![];
+    let mut strings = vec![];
+    for s in &m.strings {
+        strings.push(runtime::DATA_START + data.len() as u32);
+        data.extend(runtime::string_bytes(s));
+        data.resize(runtime::align(data.len() as u32) as usize, 0);
+    }
+
+    // Imported functions come first, then the functions from the IR, followed by the runtime
+    let functions = imports
+        .iter()
+        .map(|i| i.name.clone())
+        .chain(m.functions.iter().map(|f| f.name.clone()))
+        .chain(runtime::names().into_iter().map(|name| name.into()))
+        .enumerate()
+        .map(|(index, name)| (name, index as u32))
+        .collect::<HashMap<_, _>>();
+
+    // The signatures of every function are added first, in the same order that the builder would,
+    // So that signatures that only indirect calls use can be added after them with known indices
+    let mut types: Vec<elements::FunctionType> = vec![];
+    let signatures = imports
+        .iter()
+        .map(|i| (i.params.clone(), i.result))
+        .chain(m.functions.iter().map(|f| {
+            (
+                f.params.iter().map(|ty| ty_to_wasm(*ty)).collect(),
+                f.result.map(ty_to_wasm),
+            )
+        }))
+        .chain(
+            runtime::functions(&functions)
+                .into_iter()
+                .map(|f| (f.params, f.result)),
+        );
+    for (params, result) in signatures {
+        let ty = elements::FunctionType::new(params, result.into_iter().collect());
+
+        if !types.contains(&ty) {
+            push_type(&mut builder, &ty);
+            types.push(ty);
+        }
+    }
+    for i in imports {
+        let ty = elements::FunctionType::new(i.params.clone(), i.result.into_iter().collect());
+        let type_index = types.iter().position(|existing| existing == &ty).unwrap();
+
+        builder.push_import(elements::ImportEntry::new(
+            i.module.into(),
+            i.field.clone(),
+            elements::External::Function(type_index as u32),
+        ));
+    }
+    let types = RefCell::new(types);
+
+    for f in &m.functions {
+        let context = Context {
+            temps: &f.temps,
+            functions: &functions,
+            strings: &strings,
+            types: &types,
+        };
+
+        // Functions are never inside of a loop, so the depth is never used
+        let mut ins = context.block_to_ins(&f.body, 0);
+
+        // The result of the body is the last instruction, which has nowhere to go without a result type
+        if f.result.is_none() {
+            ins.pop();
+        }
+
+        // The parameters are the first locals, so only the temporaries after them are declared
+        builder = function_to_wasm(
+            builder,
+            f.params.iter().map(|ty| ty_to_wasm(*ty)).collect(),
+            f.result.map(ty_to_wasm),
+            f.temps[f.params.len()..]
+                .iter()
+                .map(|ty| ty_to_wasm(*ty))
+                .collect(),
+            ins,
+        );
+    }
+
+    for f in runtime::functions(&functions) {
+        builder = function_to_wasm(builder, f.params, f.result, f.locals, f.body);
+    }
+
+    // Types that are already there are not added again
+    for ty in types.into_inner() {
+        push_type(&mut builder, &ty);
+    }
+
+    let table = m.table.iter().map(|f| functions[f]).collect::<Vec<_>>();
+    let mut builder = builder
+        .table()
+        .with_min(table.len() as u32)
+        .with_element(0, table)
+        .build();
+
+    // What went wrong when the program stops early, the span is `0..0` when nothing did
+    let error_globals = [
+        ("error_start", elements::ValueType::I32),
+        ("error_end", elements::ValueType::I32),
+        ("error_index", elements::ValueType::I64),
+        ("error_length", elements::ValueType::I32),
+        ("error_file", elements::ValueType::I32),
+    ];
+    let heap = (runtime::DATA_START + data.len() as u32) as i32;
+    // Enough pages for every string literal, the heap after them grows while the program runs
+    let pages = (heap as u32).div_ceil(runtime::PAGE_SIZE);
+    builder = builder
+        .global()
+        .value_type()
+        .i32()
+        .mutable()
+        .init_expr(elements::Instruction::I32Const(heap))
+        .build();
+
+    for (i, (name, ty)) in error_globals.iter().enumerate() {
+        let init = match ty {
+            elements::ValueType::I64 => elements::Instruction::I64Const(0),
+            _ => elements::Instruction::I32Const(0),
+        };
+
+        builder = builder
+            .global()
+            .with_type(*ty)
+            .mutable()
+            .init_expr(init)
+            .build()
+            .export()
+            .field(name)
+            .internal()
+            .global(runtime::ERROR_START + i as u32)
+            .build();
+    }
+
+    for entry in entries {
+        builder = builder
+            .export()
+            .field(entry)
+            .internal()
+            .func(functions[entry])
+            .build();
+    }
+
+    builder
+        .memory()
+        .with_min(pages)
+        .build()
         .export()
-        .field("main")
+        .field("memory")
         .internal()
-        .func(0)
+        .memory(0)
         .build()
+        .data()
+        .offset(elements::Instruction::I32Const(runtime::DATA_START as i32))
+        .value(data)
         .build()
+        .build()
+}
+
+// `print` and `debug` for each type of value, like `print_i64(value: i64, index: i32)`,
+// Where `index` is the call in `ir::Module::printed`
+fn host_imports() -> Vec<Import> {
+    [ir::Ty::I32, ir::Ty::I64, ir::Ty::F64]
+        .iter()
+        .map(|ty| Import {
+            name: ir::print_function(*ty),
+            module: "mizzle",
+            field: format!("print_{}", ty),
+            params: vec![ty_to_wasm(*ty), elements::ValueType::I32],
+            result: None,
+        })
+        .collect()
 }
 
-pub fn eval(parity_module: elements::Module, final_ty: &ast::JustType) {
+// A module together with what the host needs to show the values it gives back
+pub struct Program {
+    pub module: elements::Module,
+    pub final_ty: ast::JustType,
+    // Needed to know the fields of records and the constructors of variants
+    pub decls: Vec<ast::TypedDecl>,
+    pub printed: Vec<ir::Printed>,
+}
+
+pub fn ast_to_wasm(a: &ast::TypedProgram) -> Program {
+    let m = ir::lower(a);
+
+    Program {
+        module: ir_to_wasm(&m, &host_imports(), &["main".into()]),
+        final_ty: a.body.extra().1.clone(),
+        decls: a.decls.clone(),
+        printed: m.printed,
+    }
+}
+
+// Exports each of `tests` as `ir::test_function` of its index, instead of `main`
+pub fn tests_to_wasm(a: &ast::TypedProgram, tests: &[&ast::TypedDecl]) -> Program {
+    let m = ir::lower_tests(a, tests);
+    let entries = (0..tests.len()).map(ir::test_function).collect::<Vec<_>>();
+
+    Program {
+        module: ir_to_wasm(&m, &host_imports(), &entries),
+        final_ty: ast::Type::Bool(()),
+        decls: a.decls.clone(),
+        printed: m.printed,
+    }
+}
+
+// Reads values that `main` left in linear memory
+struct Reader<'a> {
+    memory: &'a wasmer::Memory,
+    // Needed to know the fields of records and the constructors of variants
+    decls: &'a [ast::TypedDecl],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes<const N: usize>(&self, address: usize) -> [u8; N] {
+        let mut bytes = [0; N];
+        for (b, cell) in bytes
+            .iter_mut()
+            .zip(&self.memory.view::<u8>()[address..address + N])
+        {
+            *b = cell.get();
+        }
+
+        bytes
+    }
+
+    fn string(&self, address: usize) -> String {
+        let len = u32::from_le_bytes(self.bytes(address)) as usize;
+        let contents = self.memory.view::<u8>()[address + 4..address + 4 + len]
+            .iter()
+            .map(|b| b.get())
+            .collect::<Vec<_>>();
+
+        // Only valid UTF-8 can be written in source, and concatenation keeps it valid
+        String::from_utf8(contents).unwrap()
+    }
+
+    // Items inside of tuples and records are formatted like literals, so strings are quoted
+    fn slot(&self, address: usize, ty: &ast::JustType) -> String {
+        let pointer = || u32::from_le_bytes(self.bytes(address)) as usize;
+
+        match ty {
+            ast::Type::Unit(_) => "()".into(),
+            ast::Type::Int(_) => i64::from_le_bytes(self.bytes(address)).to_string(),
+            ast::Type::Bool(_) => (pointer() == 1).to_string(),
+            ast::Type::Float(_) => format!("{:?}", f64::from_le_bytes(self.bytes(address))),
+            ast::Type::String(_) => format!("\"{}\"", ast::escape(&self.string(pointer()))),
+            ast::Type::Tuple(_, _) | ast::Type::Array(_, _) | ast::Type::Named(_, _, _) => {
+                self.pointed(pointer(), ty)
+            }
+            // Only the index in the table is known at runtime, which means nothing outside of it
+            ast::Type::Function(_, _, _) => format!("<{}>", ty),
+            ast::Type::Var(_, _) | ast::Type::Unknown(_, _) => panic!("Internal compiler error"),
+        }
+    }
+
+    // The type of a field or constructor argument of `name`, given the type arguments it is used with
+    fn declared(
+        &self,
+        ty: &ast::Type<(ast::Span, ast::JustType)>,
+        name: &str,
+        type_args: &[ast::JustType],
+    ) -> ast::JustType {
+        ty.strip()
+            .substitute(ast::type_params(self.decls, name), type_args)
+    }
+
+    // Values that wasm gives to the host directly, strings are not quoted
+    fn value(&self, value: Option<&wasmer::Value>, ty: &ast::JustType) -> String {
+        match (value, ty) {
+            // Functions that return `unit` have no result
+            (_, ast::Type::Unit(_)) => "()".into(),
+            (Some(wasmer::Value::I64(n)), ast::Type::Int(_)) => n.to_string(),
+            (Some(wasmer::Value::I32(n)), ast::Type::Bool(_)) => (n == &1).to_string(),
+            (Some(wasmer::Value::F64(x)), ast::Type::Float(_)) => format!("{:?}", x),
+            (Some(wasmer::Value::I32(address)), ast::Type::String(_)) => {
+                self.string(*address as usize)
+            }
+            (Some(wasmer::Value::I32(_)), ast::Type::Function(_, _, _)) => format!("<{}>", ty),
+            (Some(wasmer::Value::I32(address)), _) => self.pointed(*address as usize, ty),
+            _ => panic!("Internal compiler error"),
+        }
+    }
+
+    // Values that are a pointer to their slots
+    fn pointed(&self, address: usize, ty: &ast::JustType) -> String {
+        let slot = |i: usize| address + i * ir::SLOT_SIZE as usize;
+
+        match ty {
+            ast::Type::Tuple(_, items) => {
+                let items = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ty)| self.slot(slot(i), ty))
+                    .collect::<Vec<_>>();
+
+                format!("({})", items.join(", "))
+            }
+            ast::Type::Array(_, item) => {
+                let length = u32::from_le_bytes(self.bytes(address)) as usize;
+                let items = (0..length)
+                    .map(|i| self.slot(slot(i + 1), item))
+                    .collect::<Vec<_>>();
+
+                format!("[{}]", items.join(", "))
+            }
+            ast::Type::Named(_, name, type_args)
+                if ast::record_fields(self.decls, name).is_none() =>
+            {
+                let constructors = ast::variant_constructors(self.decls, name).unwrap();
+                let tag = u32::from_le_bytes(self.bytes(address)) as usize;
+                let constructor = &constructors[tag];
+
+                let args = constructor
+                    .args
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ty)| self.slot(slot(i + 1), &self.declared(ty, name, type_args)))
+                    .collect::<Vec<_>>();
+
+                if args.is_empty() {
+                    constructor.name.clone()
+                } else {
+                    format!("{}({})", constructor.name, args.join(", "))
+                }
+            }
+            ast::Type::Named(_, name, type_args) => {
+                let fields = ast::record_fields(self.decls, name)
+                    .unwrap()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| {
+                        let ty = self.declared(&f.ty, name, type_args);
+                        format!("{} = {}", f.name, self.slot(slot(i), &ty))
+                    })
+                    .collect::<Vec<_>>();
+
+                format!("{} {{ {} }}", name, fields.join(", "))
+            }
+            _ => panic!("Internal compiler error"),
+        }
+    }
+}
+
+// Why a program stopped before it finished
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuntimeError {
+    // `span` is the index that is not in the array
+    IndexOutOfBounds {
+        span: ast::Span,
+        index: i64,
+        length: u32,
+    },
+    // Anything else that stops wasm, like dividing by zero, with the message from wasmer
+    Trap(String),
+}
+
+impl RuntimeError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            RuntimeError::IndexOutOfBounds { .. } => "E0036",
+            RuntimeError::Trap(_) => "E0037",
+        }
+    }
+}
+
+// What is written before a value given to `print` or `debug`, which is where the call is for `debug`
+pub fn print_prefix(printed: &ir::Printed, sources: &source::SourceMap) -> String {
+    match printed.debug {
+        Some(span) => {
+            let file = sources.get(span.file);
+
+            format!("[{}:{}] ", file.name, file.position(span.start).0 + 1)
+        }
+        None => "".into(),
+    }
+}
+
+// What the functions that the host provides to the module can see
+#[derive(Clone)]
+struct HostEnv {
+    // Set once the module is instantiated, which is before any function is called
+    memory: wasmer::LazyInit<wasmer::Memory>,
+    decls: Arc<Vec<ast::TypedDecl>>,
+    // For each call of `print` or `debug`, what it is given and what is written before that
+    printed: Arc<Vec<(ir::Printed, String)>>,
+    output: Arc<Mutex<dyn io::Write + Send>>,
+}
+
+impl wasmer::WasmerEnv for HostEnv {
+    fn init_with_instance(
+        &mut self,
+        instance: &wasmer::Instance,
+    ) -> Result<(), wasmer::HostEnvInitError> {
+        self.memory
+            .initialize(instance.exports.get_memory("memory")?.clone());
+
+        Ok(())
+    }
+}
+
+impl HostEnv {
+    // `debug` shows strings like literals, `print` writes them as they are
+    fn print(&self, value: wasmer::Value, index: i32) {
+        let (printed, prefix) = &self.printed[index as usize];
+        let reader = Reader {
+            memory: self.memory.get_ref().unwrap(),
+            decls: &self.decls,
+        };
+
+        let shown = reader.value(Some(&value), &printed.ty);
+        let shown = match (&printed.ty, printed.debug) {
+            (ast::Type::String(_), Some(_)) => format!("\"{}\"", ast::escape(&shown)),
+            _ => shown,
+        };
+
+        writeln!(self.output.lock().unwrap(), "{}{}", prefix, shown).unwrap();
+    }
+}
+
+fn print_i32(env: &HostEnv, value: i32, index: i32) {
+    env.print(wasmer::Value::I32(value), index)
+}
+
+fn print_i64(env: &HostEnv, value: i64, index: i32) {
+    env.print(wasmer::Value::I64(value), index)
+}
+
+fn print_f64(env: &HostEnv, value: f64, index: i32) {
+    env.print(wasmer::Value::F64(value), index)
+}
+
+// The engine that compiles modules with Cranelift, which serialized modules are also loaded into
+pub fn store() -> wasmer::Store {
     let compiler = wasmer_compiler_cranelift::Cranelift::new();
-    let store = wasmer::Store::new(&wasmer::Universal::new(compiler).engine());
-    let module = wasmer::Module::from_binary(&store, &parity_module.to_bytes().unwrap()).unwrap();
+    wasmer::Store::new(&wasmer::Universal::new(compiler).engine())
+}
+
+pub fn compile(program: &Program, store: &wasmer::Store) -> wasmer::Module {
+    wasmer::Module::from_binary(store, &program.module.clone().to_bytes().unwrap()).unwrap()
+}
+
+// Runs the `main` function of a module, and formats the value it returns
+// What `print` and `debug` write goes to `output`, so that it can be captured instead of going to stdout
+pub fn run(
+    program: &Program,
+    sources: &source::SourceMap,
+    output: Arc<Mutex<dyn io::Write + Send>>,
+) -> Result<String, RuntimeError> {
+    run_module(program, &compile(program, &store()), sources, output)
+}
+
+// Like `run`, with `module` already compiled from the program
+pub fn run_module(
+    program: &Program,
+    module: &wasmer::Module,
+    sources: &source::SourceMap,
+    output: Arc<Mutex<dyn io::Write + Send>>,
+) -> Result<String, RuntimeError> {
+    run_entry(program, module, "main", sources, output)
+}
+
+// Like `run_module`, calling the export `entry` instead of `main`, in an instance of its own
+pub fn run_entry(
+    program: &Program,
+    module: &wasmer::Module,
+    entry: &str,
+    sources: &source::SourceMap,
+    output: Arc<Mutex<dyn io::Write + Send>>,
+) -> Result<String, RuntimeError> {
+    let store = module.store();
+
+    let env = HostEnv {
+        memory: wasmer::LazyInit::new(),
+        decls: Arc::new(program.decls.clone()),
+        printed: Arc::new(
+            program
+                .printed
+                .iter()
+                .map(|p| (p.clone(), print_prefix(p, sources)))
+                .collect(),
+        ),
+        output,
+    };
+    let import_object = wasmer::imports! {
+        "mizzle" => {
+            "print_i32" => wasmer::Function::new_native_with_env(store, env.clone(), print_i32),
+            "print_i64" => wasmer::Function::new_native_with_env(store, env.clone(), print_i64),
+            "print_f64" => wasmer::Function::new_native_with_env(store, env, print_f64),
+        }
+    };
+    let instance = wasmer::Instance::new(module, &import_object).unwrap();
+
+    let entry = instance.exports.get_function(entry).unwrap();
+    let global = |name: &str| instance.exports.get_global(name).unwrap().get();
+    let output = entry.call(&[]).map_err(|e| match global("error_end") {
+        wasmer::Value::I32(0) => RuntimeError::Trap(e.message()),
+        end => RuntimeError::IndexOutOfBounds {
+            span: ast::Span::new(
+                source::FileId(global("error_file").unwrap_i32() as u32),
+                global("error_start").unwrap_i32() as usize,
+                end.unwrap_i32() as usize,
+            ),
+            index: global("error_index").unwrap_i64(),
+            length: global("error_length").unwrap_i32() as u32,
+        },
+    })?;
+    let reader = Reader {
+        memory: instance.exports.get_memory("memory").unwrap(),
+        decls: &program.decls,
+    };
+
+    Ok(reader.value(output.first(), &program.final_ty))
+}
+
+// Programs that are `unit` are only run for what they do, so nothing is printed for them
+pub fn eval(program: &Program, sources: &source::SourceMap) -> Result<(), RuntimeError> {
+    let output = run(program, sources, Arc::new(Mutex::new(io::stdout())))?;
+    print_result(program, &output);
+
+    Ok(())
+}
+
+// Like `eval`, with `module` already compiled from the program
+pub fn eval_module(
+    program: &Program,
+    module: &wasmer::Module,
+    sources: &source::SourceMap,
+) -> Result<(), RuntimeError> {
+    let output = run_module(program, module, sources, Arc::new(Mutex::new(io::stdout())))?;
+    print_result(program, &output);
+
+    Ok(())
+}
+
+fn print_result(program: &Program, output: &str) {
+    if program.final_ty != ast::Type::Unit(()) {
+        println!("{}", output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend_test::compile;
+
+    // Also gives what the program printed
+    fn run_printing(source: &str) -> (Result<String, RuntimeError>, String) {
+        let (typed, sources) = compile(source);
+
+        let output = Arc::new(Mutex::new(vec![]));
+        let result = run(&ast_to_wasm(&typed), &sources, output.clone());
+        let printed = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+
+        (result, printed)
+    }
+
+    fn try_run_source(source: &str) -> Result<String, RuntimeError> {
+        run_printing(source).0
+    }
+
+    fn run_source(source: &str) -> String {
+        try_run_source(source).unwrap()
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(run_source(r#""a\tb" ++ "" ++ "c""#), "a\tbc");
+        assert_eq!(run_source(r#"len("ab" ++ "cd")"#), "4");
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(run_source("1 + 2 * 3 - 10 / 3 % 2"), "6");
+        assert_eq!(run_source("0-1-2"), "-3");
+        assert_eq!(run_source("to_float(3) / 2.0"), "1.5");
+        assert_eq!(run_source("to_int(-2.5e1)"), "-25");
+        assert_eq!(run_source("1e300 * 1e10"), "inf");
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(run_source("1 < 2"), "true");
+        assert_eq!(run_source("2.5 >= 2.6"), "false");
+        assert_eq!(run_source("true != false"), "true");
+        assert_eq!(run_source(r#""ab" ++ "c" == "abc""#), "true");
+        assert_eq!(run_source(r#""ab" != "abc""#), "true");
+        assert_eq!(run_source(r#""abd" == "abc""#), "false");
+    }
+
+    #[test]
+    fn memory_grows() {
+        // Each concatenation copies the whole string again, so this needs more than one page
+        let long = format!("\"{}\"", "a".repeat(10_000));
+        let source = format!("len({})", vec![long; 10].join(" ++ "));
+
+        assert_eq!(run_source(&source), "100000");
+    }
+
+    #[test]
+    fn literals_larger_than_a_page() {
+        let source = format!("len(\"{}\")", "a".repeat(70_000));
+
+        assert_eq!(run_source(&source), "70000");
+    }
+
+    #[test]
+    fn tuples() {
+        assert_eq!(
+            run_source(r#"(1, (2.5, "a\n"), true)"#),
+            r#"(1, (2.5, "a\n"), true)"#
+        );
+        assert_eq!(run_source("let (a, (_, b)) = (1, (2, 3)) in a + b"), "4");
+        assert_eq!(run_source("let t = (1, (false, 3)) in t.1.0"), "false");
+        assert_eq!(run_source("let x = 1 in let x = x + 1 in x * 10"), "20");
+    }
+
+    #[test]
+    fn records() {
+        let decls = r#"
+            type Point = { x: int, y: float }
+            type Named = { name: string, at: Point }
+        "#;
+
+        assert_eq!(
+            run_source(&format!(
+                r#"{} Named {{ at = Point {{ y = 2.5, x = 1 }}, name = "a" }}"#,
+                decls
+            )),
+            r#"Named { name = "a", at = Point { x = 1, y = 2.5 } }"#
+        );
+        assert_eq!(
+            run_source(&format!(
+                "{} let p = Point {{ x = 1, y = 2.0 }} in let q = {{ p with x = 3 }} in (p.x, q.x, q.y)",
+                decls
+            )),
+            "(1, 3, 2.0)"
+        );
+    }
+
+    #[test]
+    fn variants() {
+        let decls = r#"
+            type Shape = Circle(float) | Rect(int, int) | Empty
+            type Tree = Leaf | Node(Tree, int, Tree)
+        "#;
+
+        assert_eq!(
+            run_source(&format!(r#"{} (Rect(1, 2), Empty, Circle(0.5))"#, decls)),
+            "(Rect(1, 2), Empty, Circle(0.5))"
+        );
+        assert_eq!(
+            run_source(&format!(
+                "{} match Node(Node(Leaf, 1, Leaf), 2, Leaf) with
+                | Node(Node(_, n, _), m, Leaf) => n * 10 + m
+                | Node(_, m, _) => m
+                | Leaf => 0
+                end",
+                decls
+            )),
+            "12"
+        );
+        assert_eq!(
+            run_source(&format!(
+                "{} match (Rect(3, 0), 1) with
+                | (Rect(w, 0), 1) => w
+                | (Rect(_, h), _) => h
+                | (_, n) => n
+                end",
+                decls
+            )),
+            "3"
+        );
+        assert_eq!(
+            run_source("match 2 with 1 => false | 2 => true | _ => false end"),
+            "true"
+        );
+    }
+
+    #[test]
+    fn functions() {
+        let decls = r#"
+            fn fact(n: int): int = if n <= 1 then 1 else n * fact(n - 1) end end
+            fn even(n: int): bool = if n == 0 then true else odd(n - 1) end end
+            fn odd(n: int): bool = if n == 0 then false else even(n - 1) end end
+        "#;
+
+        assert_eq!(
+            run_source(&format!("{} (fact(10), even(7), odd(7))", decls)),
+            "(3628800, false, true)"
+        );
+    }
+
+    #[test]
+    fn generics() {
+        let decls = r#"
+            type Option<a> = Some(a) | None
+            type List<a> = Cons(a, List<a>) | Nil
+            type Pair<a, b> = { first: a, second: b }
+
+            fn id<a>(x: a): a = x end
+            fn size<a>(l: List<a>): int = match l with Cons(_, rest) => 1 + size(rest) | Nil => 0 end end
+            fn swap<a, b>(p: Pair<a, b>): Pair<b, a> = Pair { first = p.second, second = p.first } end
+        "#;
+
+        assert_eq!(
+            run_source(&format!(r#"{} (id(1), id(2.5), id("a"))"#, decls)),
+            r#"(1, 2.5, "a")"#
+        );
+        assert_eq!(
+            run_source(&format!(
+                "{} (size(Cons(true, Nil)), size(Cons(1.5, Cons(2.5, Nil))))",
+                decls
+            )),
+            "(1, 2)"
+        );
+        assert_eq!(
+            run_source(&format!(
+                "{} swap(Pair {{ first = Some(1.5), second = (1, true) }})",
+                decls
+            )),
+            "Pair { first = (1, true), second = Some(1.5) }"
+        );
+    }
+
+    #[test]
+    fn unit() {
+        assert_eq!(run_source("()"), "()");
+        assert_eq!(run_source("fn f(x: int): unit = () end f(1); f(2)"), "()");
+        assert_eq!(
+            run_source("fn f(x: int): unit = () end (f(1), 1; 2)"),
+            "((), 2)"
+        );
+    }
+
+    #[test]
+    fn loops() {
+        assert_eq!(
+            run_source("var i = 0 in var s = 0 in while i < 10 do s := s + i; i := i + 1 end; s"),
+            "45"
+        );
+        assert_eq!(
+            run_source(
+                "var i = 0 in while true do if i == 7 then break else i := i + 1 end end; i"
+            ),
+            "7"
+        );
+        // `break` only leaves the innermost loop
+        assert_eq!(
+            run_source(
+                r#"
+                var i = 0 in var n = 0 in
+                while i < 3 do
+                    var j = 0 in
+                    while true do
+                        match j with 2 => break | _ => n := n + 1; j := j + 1 end
+                    end;
+                    i := i + 1
+                end;
+                n
+                "#
+            ),
+            "6"
+        );
+        // The value of a variable is read when it is used, not when it is assigned to later
+        assert_eq!(run_source("var x = 1 in (x, (x := 2; x))"), "(1, 2)");
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(run_source("[1, 2, 3]"), "[1, 2, 3]");
+        assert_eq!(
+            run_source("let a = [1, 2, 3] in (a[2], length(a))"),
+            "(3, 3)"
+        );
+        assert_eq!(
+            run_source("let a = array(3, 0) in a[1] := 5; a"),
+            "[0, 5, 0]"
+        );
+        assert_eq!(
+            run_source(r#"let a = [["a"], []] in a[1] := ["b", "c"]; a"#),
+            r#"[["a"], ["b", "c"]]"#
+        );
+        assert_eq!(run_source("array(0 - 2, true)"), "[]");
+        // Arrays are shared, not copied
+        assert_eq!(
+            run_source("let a = [1] in let b = a in b[0] := 2; a[0]"),
+            "2"
+        );
+    }
+
+    #[test]
+    fn index_out_of_bounds() {
+        let source = "let a = [1, 2] in a[1 + 1]";
+        match try_run_source(source) {
+            Err(RuntimeError::IndexOutOfBounds {
+                span,
+                index,
+                length,
+            }) => {
+                assert_eq!(&source[span.start..span.end], "1 + 1");
+                assert_eq!((index, length), (2, 2));
+            }
+            other => panic!("expected an index error, got {:?}", other),
+        }
+
+        assert!(matches!(
+            try_run_source("let a = [1] in a[0 - 1] := 2"),
+            Err(RuntimeError::IndexOutOfBounds { index: -1, .. })
+        ));
+        assert!(matches!(
+            try_run_source("1 / 0"),
+            Err(RuntimeError::Trap(_))
+        ));
+    }
+
+    #[test]
+    fn function_values() {
+        let decls = r#"
+            fn inc(x: int): int = x + 1 end
+            fn twice<a>(f: fn(a) -> a, x: a): a = f(f(x)) end
+        "#;
+
+        assert_eq!(
+            run_source(&format!(
+                "{} (twice(inc, 1), twice(inc, twice(inc, 0)))",
+                decls
+            )),
+            "(3, 4)"
+        );
+        assert_eq!(run_source(&format!("{} let f = inc in f(10)", decls)), "11");
+        assert_eq!(run_source(&format!("{} inc", decls)), "<fn(int) -> int>");
+    }
+
+    #[test]
+    fn prelude() {
+        let decls = r#"
+            fn square(x: int): int = x * x end
+            fn add(a: int, b: int): int = a + b end
+            fn show(x: int): string = if x > 2 then "big" else "small" end end
+        "#;
+
+        assert_eq!(run_source("range(2, 5)"), "[2, 3, 4]");
+        assert_eq!(
+            run_source(&format!("{} map(range(0, 4), square)", decls)),
+            "[0, 1, 4, 9]"
+        );
+        assert_eq!(
+            run_source(&format!("{} map([1, 3], show)", decls)),
+            r#"["small", "big"]"#
+        );
+        assert_eq!(
+            run_source(&format!("{} fold(range(1, 11), 0, add)", decls)),
+            "55"
+        );
+        // Declaring a function with the same name replaces the one from the prelude
+        assert_eq!(run_source("fn range(n: int): int = n end range(3)"), "3");
+    }
 
-    let import_object = wasmer::imports! {};
-    let instance = wasmer::Instance::new(&module, &import_object).unwrap();
+    #[test]
+    fn print_and_debug() {
+        let (result, printed) = run_printing(
+            r#"
+            type P = { x: int, s: string }
+            fn show<a>(x: a): unit = print(x) end
+            print("a\tb");
+            debug("a\tb");
+            show(1.5);
+            show(P { x = 1, s = "c" });
+            var i = 0 in
+            while i < 2 do
+                debug((i, [true]));
+                i := i + 1
+            end;
+            print(());
+            3
+            "#,
+        );
 
-    let main = instance.exports.get_function("main").unwrap();
-    let output = main.call(&[]).unwrap();
+        assert_eq!(result.unwrap(), "3");
+        assert_eq!(
+            printed,
+            r#"a	b
+[test.mi:5] "a\tb"
+1.5
+P { x = 1, s = "c" }
+[test.mi:10] (0, [true])
+[test.mi:10] (1, [true])
+()
+"#
+        );
 
-    match (&output[0], final_ty) {
-        (wasmer::Value::I64(n), ast::Type::Int(_)) => println!("{}", n),
-        (wasmer::Value::I64(n), ast::Type::Bool(_)) => println!("{}", n == &1),
-        _ => panic!("Internal compiler error"),
+        // What was printed before the program stopped is kept
+        let (result, printed) = run_printing("print(1); print(1 / 0)");
+        assert!(matches!(result, Err(RuntimeError::Trap(_))));
+        assert_eq!(printed, "1\n");
     }
 }
@@ -0,0 +1,180 @@
+// Runs every `.mi` file in this directory with mizzle, and checks what it writes against the headers at its top
+// `# expect: 3` is a line the program writes, in order, which has to be all it writes
+// `# expect-error: E0002` and `# expect-warning: W0003` are codes of diagnostics the program has to give,
+// And then what mizzle writes is also compared with the snapshot next to the file, like `name.diag`,
+// With `# expect:` lines only having to be among the lines it writes, in order
+// Pass `--bless` to write the snapshots from what mizzle writes now, like `$ cargo test --test golden -- --bless`,
+// Any other argument only runs the files with it in their name
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+#[derive(Debug, Default)]
+struct Expected {
+    lines: Vec<String>,
+    codes: Vec<String>,
+}
+
+// The headers are the comments at the top of the file
+fn expected(source: &str) -> Expected {
+    let mut expected = Expected::default();
+
+    for line in source.lines().take_while(|l| l.starts_with('#')) {
+        if let Some(line) = line.strip_prefix("# expect: ") {
+            expected.lines.push(line.into());
+        } else if let Some(code) = line
+            .strip_prefix("# expect-error: ")
+            .or_else(|| line.strip_prefix("# expect-warning: "))
+        {
+            expected.codes.push(code.trim().into());
+        }
+    }
+
+    expected.codes.sort();
+    expected
+}
+
+// Codes like `[E0002]` after the kind of each diagnostic
+fn codes(output: &str) -> Vec<String> {
+    let mut codes = output
+        .split('[')
+        .skip(1)
+        .filter_map(|rest| {
+            let code = rest.get(..5)?;
+            let is_code = rest[5..].starts_with(']')
+                && (code.starts_with('E') || code.starts_with('W'))
+                && code[1..].chars().all(|c| c.is_ascii_digit());
+
+            is_code.then(|| code.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    codes.sort();
+    codes
+}
+
+// Everything mizzle writes for the file, without colors
+fn run(path: &Path) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_mizzle"))
+        .args(["run", "--no-cache"])
+        .arg(path.file_name().unwrap())
+        .current_dir(path.parent().unwrap())
+        .env("NO_COLOR", "1")
+        .env_remove("CLICOLOR_FORCE")
+        .output()
+        .unwrap();
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn check(path: &Path, bless: bool) -> Result<(), String> {
+    let source = fs::read_to_string(path).unwrap();
+    let expected = expected(&source);
+    let output = run(path);
+    let snapshot = path.with_extension("diag");
+
+    let found = codes(&output);
+    if found != expected.codes {
+        return Err(format!(
+            "expected the codes {:?}, but got {:?}:\n{}",
+            expected.codes, found, output
+        ));
+    }
+
+    let lines = expected
+        .lines
+        .iter()
+        .map(|l| format!("{}\n", l))
+        .collect::<String>();
+
+    if expected.codes.is_empty() {
+        if bless && snapshot.exists() {
+            fs::remove_file(&snapshot).unwrap();
+        }
+        if output != lines {
+            return Err(format!("expected:\n{}\nbut got:\n{}", lines, output));
+        }
+
+        return Ok(());
+    }
+
+    if bless {
+        fs::write(&snapshot, &output).unwrap();
+    }
+    match fs::read_to_string(&snapshot) {
+        Ok(diagnostics) if diagnostics == output => {}
+        Ok(diagnostics) => {
+            return Err(format!(
+                "expected what is in {}:\n{}\nbut got:\n{}\nrun with `--bless` if this is right",
+                snapshot.display(),
+                diagnostics,
+                output
+            ))
+        }
+        Err(_) => {
+            return Err(format!(
+                "{} is missing, run with `--bless` to write it:\n{}",
+                snapshot.display(),
+                output
+            ))
+        }
+    }
+    let mut written = output.lines();
+    if !expected.lines.iter().all(|l| written.any(|w| w == l)) {
+        return Err(format!("expected it to write, in order:\n{}", lines));
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let bless = args.iter().any(|a| a == "--bless");
+    let filters = args
+        .iter()
+        .filter(|a| !a.starts_with('-'))
+        .collect::<Vec<_>>();
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let mut files = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "mi"))
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            filters.is_empty() || filters.iter().any(|f| name.contains(f.as_str()))
+        })
+        .collect::<Vec<PathBuf>>();
+    files.sort();
+
+    println!("\nrunning {} golden files", files.len());
+
+    let mut failed = vec![];
+    for path in &files {
+        let name = path.file_name().unwrap().to_string_lossy();
+
+        match check(path, bless) {
+            Ok(()) => println!("test {} ... ok", name),
+            Err(message) => {
+                println!("test {} ... FAILED", name);
+                failed.push((name.to_string(), message));
+            }
+        }
+    }
+
+    for (name, message) in &failed {
+        println!("\n---- {} ----\n{}", name, message);
+    }
+    println!(
+        "\ngolden result: {}. {} passed; {} failed\n",
+        if failed.is_empty() { "ok" } else { "FAILED" },
+        files.len() - failed.len(),
+        failed.len()
+    );
+
+    if !failed.is_empty() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,1203 @@
+// type Shape = Circle(float) | Rect(int, int) | Empty
+//
+// fn area(s: Shape): int =
+// match s with
+// | Circle(r) => to_int(r * r * 3.0)
+// | Rect(1, h) => h
+// | Rect(w, h) => w * h
+// | Empty => 0
+// end
+// end
+//
+// fn triangle(n: int): int =
+// var i = 0 in var sum = 0 in
+// while true do
+// if i > n then break else sum := sum + i; i := i + 1 end
+// end;
+// sum
+// end
+//
+// let (a, (_, b)) = (area(Rect(2, 3)) + area(Rect(1, 5)), (true, triangle(4))) in
+// let a = a + 1 in
+// match (a, b > 5) with
+// | (0, _) => "zero"
+// | (_, true) => "big " ++ (if a > 10 then "a" else if a > 5 then "b" else "c" end end)
+// | _ => "small"
+// end
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_string *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_string *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect, TAG_Empty };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+static int64_t mz_mul(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a * (uint64_t)b);
+}
+
+static int64_t mz_to_int(double x) {
+    if (isnan(x)) {
+        mz_trap("invalid conversion to integer");
+    }
+    if (x < -9223372036854775808.0 || x >= 9223372036854775808.0) {
+        mz_trap("integer overflow");
+    }
+    return (int64_t)x;
+}
+
+// The bytes of the new string are right after it
+static mz_string *mz_concat(const mz_string *a, const mz_string *b) {
+    mz_string *s = malloc(sizeof(mz_string) + (size_t)(a->len + b->len));
+    char *bytes;
+    if (s == NULL) {
+        mz_trap("out of memory");
+    }
+    bytes = (char *)(s + 1);
+    memcpy(bytes, a->bytes, (size_t)a->len);
+    memcpy(bytes + a->len, b->bytes, (size_t)b->len);
+    s->len = a->len + b->len;
+    s->bytes = bytes;
+    return s;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_string(const mz_string *s) {
+    mz_write_bytes(s->bytes, (size_t)s->len);
+}
+
+static mz_string mz_str_0 = { 4, "zero" };
+static mz_string mz_str_1 = { 4, "big " };
+static mz_string mz_str_2 = { 1, "a" };
+static mz_string mz_str_3 = { 1, "b" };
+static mz_string mz_str_4 = { 1, "c" };
+static mz_string mz_str_5 = { 5, "small" };
+
+static int64_t fn_area(mz_slot *s);
+static int64_t fn_triangle(int64_t n);
+
+static int64_t fn_area(mz_slot *s) {
+    if (s[0].i == TAG_Circle) {
+        double r = s[1].f;
+        return mz_to_int(r * r * 3.0);
+    } else if (s[0].i == TAG_Rect && s[1].i == 1) {
+        int64_t h = s[2].i;
+        return h;
+    } else if (s[0].i == TAG_Rect) {
+        int64_t w = s[1].i;
+        int64_t h = s[2].i;
+        return mz_mul(w, h);
+    } else {
+        return 0;
+    }
+}
+
+static int64_t fn_triangle(int64_t n) {
+    int64_t i = 0;
+    int64_t sum = 0;
+    while (true) {
+        if (i > n) {
+            break;
+        } else {
+            sum = mz_add(sum, i);
+            i = mz_add(i, 1);
+        }
+    }
+    return sum;
+}
+
+mz_string *mizzle_main(void) {
+    mz_slot *t0 = mz_alloc(3);
+    t0[0].i = TAG_Rect;
+    t0[1].i = 2;
+    t0[2].i = 3;
+    int64_t t2 = fn_area(t0);
+    mz_slot *t1 = mz_alloc(3);
+    t1[0].i = TAG_Rect;
+    t1[1].i = 1;
+    t1[2].i = 5;
+    int64_t t4 = mz_add(t2, fn_area(t1));
+    mz_slot *t3 = mz_alloc(2);
+    t3[0].b = true;
+    t3[1].i = fn_triangle(4);
+    mz_slot *t5 = mz_alloc(2);
+    t5[0].i = t4;
+    t5[1].p = t3;
+    int64_t a = t5[0].i;
+    int64_t b = t5[1].p[1].i;
+    int64_t a_1 = mz_add(a, 1);
+    mz_slot *t6 = mz_alloc(2);
+    t6[0].i = a_1;
+    t6[1].b = b > 5;
+    if (t6[0].i == 0) {
+        return &mz_str_0;
+    } else if (t6[1].b) {
+        return mz_concat(&mz_str_1, a_1 > 10 ? &mz_str_2 : (a_1 > 5 ? &mz_str_3 : &mz_str_4));
+    } else {
+        return &mz_str_5;
+    }
+}
+
+void mizzle_show(mz_string *value) {
+    mz_write_string(value);
+}
+
+// var x = 1 in x + (x := 10; x)
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+int64_t mizzle_main(void) {
+    int64_t x = 1;
+    int64_t t0 = x;
+    x = 10;
+    return mz_add(t0, x);
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// var x = 1 in (x := x + 1; x, let y = x in (x := 5; y), x)
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static void mz_show_int_int_int(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    int64_t x = 1;
+    x = mz_add(x, 1);
+    int64_t t0 = x;
+    int64_t y = x;
+    x = 5;
+    mz_slot *t1 = mz_alloc(3);
+    t1[0].i = t0;
+    t1[1].i = y;
+    t1[2].i = x;
+    return t1;
+}
+
+static void mz_show_int_int_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ");
+    mz_write_int(value[1].i);
+    mz_write_text(", ");
+    mz_write_int(value[2].i);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_int_int_int(value);
+}
+
+// let char = 1 in let double = 2 in let char = char + double in char
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+int64_t mizzle_main(void) {
+    int64_t char_ = 1;
+    int64_t double_ = 2;
+    int64_t char__1 = mz_add(char_, double_);
+    return char__1;
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// let new = 1 in let this = 2 in let new = new + this in new
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+int64_t mizzle_main(void) {
+    int64_t new = 1;
+    int64_t this = 2;
+    int64_t new_1 = mz_add(new, this);
+    return new_1;
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// fn main(): int = 1 end fn mz_show(x: int): int = x end mz_show(main())
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static int64_t fn_mz_show(int64_t x);
+static int64_t fn_main(void);
+
+static int64_t fn_mz_show(int64_t x) {
+    return x;
+}
+
+static int64_t fn_main(void) {
+    return 1;
+}
+
+int64_t mizzle_main(void) {
+    return fn_mz_show(fn_main());
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// fn main(): int = 1 end fn show(x: int): int = x end show(main())
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static int64_t fn_show(int64_t x);
+static int64_t fn_main(void);
+
+static int64_t fn_show(int64_t x) {
+    return x;
+}
+
+static int64_t fn_main(void) {
+    return 1;
+}
+
+int64_t mizzle_main(void) {
+    return fn_show(fn_main());
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// var i = 0 in while (i := i + 1; print(i); i < 3) do print(if i == 2 then 0 else i end) end
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_unit mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_unit value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+mz_unit mizzle_main(void) {
+    int64_t i = 0;
+    while (true) {
+        i = mz_add(i, 1);
+        mz_write_int(i);
+        mz_write_text("\n");
+        if (!(i < 3)) {
+            break;
+        }
+        mz_write_int(i == 2 ? 0 : i);
+        mz_write_text("\n");
+    }
+    return 0;
+}
+
+void mizzle_show(mz_unit value) {
+    (void)value;
+    mz_write_text("()");
+}
+
+// let items = [1, 2, 3] in items[1] := 5; print(items); items[3]
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// An array has its length in the first slot and its items after that, it is empty for a negative length
+static mz_slot *mz_array(int64_t length) {
+    mz_slot *items;
+    if (length < 0) {
+        length = 0;
+    }
+    if (length >= INT64_MAX / (int64_t)sizeof(mz_slot)) {
+        mz_trap("out of memory");
+    }
+    items = mz_alloc(length + 1);
+    items[0].i = length;
+    return items;
+}
+
+// The slot of `index` in `items`, which stops the program when it is outside of them,
+// `at` is where the index is in the source
+static mz_slot *mz_item(mz_slot *items, int64_t index, const char *at) {
+    if (index < 0 || index >= items[0].i) {
+        char message[512];
+        snprintf(
+            message,
+            sizeof message,
+            "%s: index `%lld` is out of bounds of an array with length `%lld`",
+            at,
+            (long long)index,
+            (long long)items[0].i
+        );
+        mz_trap(message);
+    }
+    return &items[index + 1];
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static void mz_show_array_int(mz_slot *value);
+
+int64_t mizzle_main(void) {
+    mz_slot *t0 = mz_array(3);
+    t0[1].i = 1;
+    t0[2].i = 2;
+    t0[3].i = 3;
+    mz_slot *items = t0;
+    mz_item(items, 1, "test.mi:1:32")->i = 5;
+    mz_show_array_int(items);
+    mz_write_text("\n");
+    return mz_item(items, 3, "test.mi:1:61")->i;
+}
+
+static void mz_show_array_int(mz_slot *value) {
+    int64_t i;
+    mz_write_text("[");
+    for (i = 1; i <= value[0].i; i++) {
+        if (i > 1) {
+            mz_write_text(", ");
+        }
+        mz_write_int(value[i].i);
+    }
+    mz_write_text("]");
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// let t0 = 1 in (t0, if t0 > 0 then (t0, 2) else (3, t0) end)
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static void mz_show_int_int(mz_slot *value);
+static void mz_show_int_int_int(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    int64_t t0 = 1;
+    mz_slot *t1;
+    if (t0 > 0) {
+        mz_slot *t2 = mz_alloc(2);
+        t2[0].i = t0;
+        t2[1].i = 2;
+        t1 = t2;
+    } else {
+        mz_slot *t3 = mz_alloc(2);
+        t3[0].i = 3;
+        t3[1].i = t0;
+        t1 = t3;
+    }
+    mz_slot *t4 = mz_alloc(2);
+    t4[0].i = t0;
+    t4[1].p = t1;
+    return t4;
+}
+
+static void mz_show_int_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ");
+    mz_write_int(value[1].i);
+    mz_write_text(")");
+}
+
+static void mz_show_int_int_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ");
+    mz_show_int_int(value[1].p);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_int_int_int(value);
+}
+
+// match (1, true, ()) with (1, false, _) => (0, ()) | (n, true, u) => (n, u) | _ => (2, ()) end
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static void mz_show_int_unit(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    mz_slot *t0 = mz_alloc(3);
+    t0[0].i = 1;
+    t0[1].b = true;
+    if (t0[0].i == 1 && !t0[1].b) {
+        mz_slot *t1 = mz_alloc(2);
+        t1[0].i = 0;
+        return t1;
+    } else if (t0[1].b) {
+        int64_t n = t0[0].i;
+        mz_unit u = 0;
+        mz_slot *t2 = mz_alloc(2);
+        t2[0].i = n;
+        return t2;
+    } else {
+        mz_slot *t3 = mz_alloc(2);
+        t3[0].i = 2;
+        return t3;
+    }
+}
+
+static void mz_show_int_unit(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ())");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_int_unit(value);
+}
+
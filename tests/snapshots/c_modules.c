@@ -0,0 +1,143 @@
+// shapes.mi:
+// fn area(x: int): int = x * x end
+// pub fn total(x: int): int = area(x) + 1 end
+//
+// main.mi:
+// import shapes
+// fn shapes__area(x: int): int = x end
+// fn shapes_area(x: int): int = x + 10 end
+// (total(3), shapes__area(2), shapes_area(2))
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+static int64_t mz_mul(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a * (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static int64_t fn_shapes__total(int64_t x);
+static int64_t fn_shapes__area(int64_t x);
+static int64_t fn_shapes_area(int64_t x);
+static int64_t fn_shapes__area_1(int64_t x);
+static void mz_show_int_int_int(mz_slot *value);
+
+static int64_t fn_shapes__total(int64_t x) {
+    return mz_add(fn_shapes__area_1(x), 1);
+}
+
+static int64_t fn_shapes__area(int64_t x) {
+    return x;
+}
+
+static int64_t fn_shapes_area(int64_t x) {
+    return mz_add(x, 10);
+}
+
+static int64_t fn_shapes__area_1(int64_t x) {
+    return mz_mul(x, x);
+}
+
+mz_slot *mizzle_main(void) {
+    int64_t t0 = fn_shapes__total(3);
+    int64_t t1 = fn_shapes__area(2);
+    mz_slot *t2 = mz_alloc(3);
+    t2[0].i = t0;
+    t2[1].i = t1;
+    t2[2].i = fn_shapes_area(2);
+    return t2;
+}
+
+static void mz_show_int_int_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ");
+    mz_write_int(value[1].i);
+    mz_write_text(", ");
+    mz_write_int(value[2].i);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_int_int_int(value);
+}
+
@@ -0,0 +1,360 @@
+// type Shape = Circle(float) | Rect(int, int)
+// fn show<a>(x: a): unit = debug(x) end
+// fn say(x: int): int = print(x); x end
+// print("a\tb");
+// debug("a\tb");
+// debug(Rect(1, 2));
+// show(1);
+// show((Circle(0.5), [true]));
+// print(print(1));
+// print(say(1) + say(2) * say(3));
+// debug((say(4), say(5)));
+// var i = 0 in
+// while i < 3 do print(to_float(i) / 4.0); i := i + 1 end
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_unit mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_unit value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// An array has its length in the first slot and its items after that, it is empty for a negative length
+static mz_slot *mz_array(int64_t length) {
+    mz_slot *items;
+    if (length < 0) {
+        length = 0;
+    }
+    if (length >= INT64_MAX / (int64_t)sizeof(mz_slot)) {
+        mz_trap("out of memory");
+    }
+    items = mz_alloc(length + 1);
+    items[0].i = length;
+    return items;
+}
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+static int64_t mz_mul(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a * (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static void mz_write_bool(bool b) {
+    mz_write_text(b ? "true" : "false");
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+static void mz_write_string(const mz_string *s) {
+    mz_write_bytes(s->bytes, (size_t)s->len);
+}
+
+// Strings inside of other values are written like literals
+static void mz_write_quoted(const mz_string *s) {
+    int64_t i;
+    mz_write_text("\"");
+    for (i = 0; i < s->len; i++) {
+        switch (s->bytes[i]) {
+        case '\\':
+            mz_write_text("\\\\");
+            break;
+        case '"':
+            mz_write_text("\\\"");
+            break;
+        case '\n':
+            mz_write_text("\\n");
+            break;
+        case '\t':
+            mz_write_text("\\t");
+            break;
+        case '\r':
+            mz_write_text("\\r");
+            break;
+        case '\0':
+            mz_write_text("\\0");
+            break;
+        default:
+            mz_write_bytes(&s->bytes[i], 1);
+        }
+    }
+    mz_write_text("\"");
+}
+
+static mz_string mz_str_0 = { 3, "a\tb" };
+
+static mz_unit fn_show_int(int64_t x);
+static mz_unit fn_show_Shape_array_bool(mz_slot *x);
+static int64_t fn_say(int64_t x);
+static void mz_show_Shape(mz_slot *value);
+static void mz_show_int_int(mz_slot *value);
+static void mz_show_array_bool(mz_slot *value);
+static void mz_show_Shape_array_bool(mz_slot *value);
+
+static mz_unit fn_show_int(int64_t x) {
+    mz_write_text("[test.mi:3] ");
+    mz_write_int(x);
+    mz_write_text("\n");
+    return 0;
+}
+
+static mz_unit fn_show_Shape_array_bool(mz_slot *x) {
+    mz_write_text("[test.mi:3] ");
+    mz_show_Shape_array_bool(x);
+    mz_write_text("\n");
+    return 0;
+}
+
+static int64_t fn_say(int64_t x) {
+    mz_write_int(x);
+    mz_write_text("\n");
+    return x;
+}
+
+mz_unit mizzle_main(void) {
+    mz_write_string(&mz_str_0);
+    mz_write_text("\n");
+    mz_write_text("[test.mi:6] ");
+    mz_write_quoted(&mz_str_0);
+    mz_write_text("\n");
+    mz_slot *t0 = mz_alloc(3);
+    t0[0].i = TAG_Rect;
+    t0[1].i = 1;
+    t0[2].i = 2;
+    mz_write_text("[test.mi:7] ");
+    mz_show_Shape(t0);
+    mz_write_text("\n");
+    fn_show_int(1);
+    mz_slot *t1 = mz_alloc(2);
+    t1[0].i = TAG_Circle;
+    t1[1].f = 0.5;
+    mz_slot *t2 = mz_array(1);
+    t2[1].b = true;
+    mz_slot *t3 = mz_alloc(2);
+    t3[0].p = t1;
+    t3[1].p = t2;
+    fn_show_Shape_array_bool(t3);
+    mz_write_int(1);
+    mz_write_text("\n");
+    mz_write_text("()\n");
+    int64_t t5 = fn_say(1);
+    int64_t t4 = fn_say(2);
+    int64_t t6 = mz_add(t5, mz_mul(t4, fn_say(3)));
+    mz_write_int(t6);
+    mz_write_text("\n");
+    int64_t t7 = fn_say(4);
+    mz_slot *t8 = mz_alloc(2);
+    t8[0].i = t7;
+    t8[1].i = fn_say(5);
+    mz_write_text("[test.mi:12] ");
+    mz_show_int_int(t8);
+    mz_write_text("\n");
+    int64_t i = 0;
+    while (i < 3) {
+        mz_write_float((double)i / 4.0);
+        mz_write_text("\n");
+        i = mz_add(i, 1);
+    }
+    return 0;
+}
+
+static void mz_show_Shape(mz_slot *value) {
+    switch (value[0].i) {
+    case TAG_Circle:
+        mz_write_text("Circle(");
+        mz_write_float(value[1].f);
+        mz_write_text(")");
+        break;
+    case TAG_Rect:
+        mz_write_text("Rect(");
+        mz_write_int(value[1].i);
+        mz_write_text(", ");
+        mz_write_int(value[2].i);
+        mz_write_text(")");
+        break;
+    }
+}
+
+static void mz_show_int_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ");
+    mz_write_int(value[1].i);
+    mz_write_text(")");
+}
+
+static void mz_show_array_bool(mz_slot *value) {
+    int64_t i;
+    mz_write_text("[");
+    for (i = 1; i <= value[0].i; i++) {
+        if (i > 1) {
+            mz_write_text(", ");
+        }
+        mz_write_bool(value[i].b);
+    }
+    mz_write_text("]");
+}
+
+static void mz_show_Shape_array_bool(mz_slot *value) {
+    mz_write_text("(");
+    mz_show_Shape(value[0].p);
+    mz_write_text(", ");
+    mz_show_array_bool(value[1].p);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_unit value) {
+    (void)value;
+    mz_write_text("()");
+}
+
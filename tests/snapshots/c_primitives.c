@@ -0,0 +1,4314 @@
+// 1 + 2 * 3
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+static int64_t mz_mul(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a * (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+int64_t mizzle_main(void) {
+    return mz_add(1, mz_mul(2, 3));
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// 0 - 9223372036854775807 - 1 - 1
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static int64_t mz_sub(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a - (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+int64_t mizzle_main(void) {
+    return mz_sub(mz_sub(mz_sub(0, 9223372036854775807), 1), 1);
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// (9223372036854775807 * 2, 7 / 2, (0 - 7) / 2, (0 - 7) % 3, 7 % 0 - 7)
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+static int64_t mz_sub(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a - (uint64_t)b);
+}
+
+static int64_t mz_mul(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a * (uint64_t)b);
+}
+
+// Stops the program in the same cases as wasm does
+static int64_t mz_div(int64_t a, int64_t b) {
+    if (b == 0) {
+        mz_trap("integer divide by zero");
+    }
+    if (a == INT64_MIN && b == -1) {
+        mz_trap("integer overflow");
+    }
+    return a / b;
+}
+
+static int64_t mz_rem(int64_t a, int64_t b) {
+    if (b == 0) {
+        mz_trap("integer divide by zero");
+    }
+    // `INT64_MIN % -1` is undefined, and every remainder of dividing by -1 is 0
+    if (b == -1) {
+        return 0;
+    }
+    return a % b;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static void mz_show_int_int_int_int_int(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    int64_t t0 = mz_div(7, 2);
+    int64_t t1 = mz_div(mz_sub(0, 7), 2);
+    int64_t t2 = mz_rem(mz_sub(0, 7), 3);
+    mz_slot *t3 = mz_alloc(5);
+    t3[0].i = mz_mul(9223372036854775807, 2);
+    t3[1].i = t0;
+    t3[2].i = t1;
+    t3[3].i = t2;
+    t3[4].i = mz_sub(mz_rem(7, 0), 7);
+    return t3;
+}
+
+static void mz_show_int_int_int_int_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ");
+    mz_write_int(value[1].i);
+    mz_write_text(", ");
+    mz_write_int(value[2].i);
+    mz_write_text(", ");
+    mz_write_int(value[3].i);
+    mz_write_text(", ");
+    mz_write_int(value[4].i);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_int_int_int_int_int(value);
+}
+
+// 1 / 0
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Stops the program in the same cases as wasm does
+static int64_t mz_div(int64_t a, int64_t b) {
+    if (b == 0) {
+        mz_trap("integer divide by zero");
+    }
+    if (a == INT64_MIN && b == -1) {
+        mz_trap("integer overflow");
+    }
+    return a / b;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+int64_t mizzle_main(void) {
+    return mz_div(1, 0);
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// (0 - 9223372036854775807 - 1) / (0 - 1)
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+static int64_t mz_sub(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a - (uint64_t)b);
+}
+
+// Stops the program in the same cases as wasm does
+static int64_t mz_div(int64_t a, int64_t b) {
+    if (b == 0) {
+        mz_trap("integer divide by zero");
+    }
+    if (a == INT64_MIN && b == -1) {
+        mz_trap("integer overflow");
+    }
+    return a / b;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+int64_t mizzle_main(void) {
+    return mz_div(mz_sub(mz_sub(0, 9223372036854775807), 1), mz_sub(0, 1));
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// (0 - 9223372036854775807 - 1) % (0 - 1)
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+int64_t mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(int64_t value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+static int64_t mz_sub(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a - (uint64_t)b);
+}
+
+static int64_t mz_rem(int64_t a, int64_t b) {
+    if (b == 0) {
+        mz_trap("integer divide by zero");
+    }
+    // `INT64_MIN % -1` is undefined, and every remainder of dividing by -1 is 0
+    if (b == -1) {
+        return 0;
+    }
+    return a % b;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+int64_t mizzle_main(void) {
+    return mz_rem(mz_sub(mz_sub(0, 9223372036854775807), 1), mz_sub(0, 1));
+}
+
+void mizzle_show(int64_t value) {
+    mz_write_int(value);
+}
+
+// (to_int(2.9), to_int(0.0 - 2.9), to_float(3), len("é"), to_int(1e300))
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+static int64_t mz_to_int(double x) {
+    if (isnan(x)) {
+        mz_trap("invalid conversion to integer");
+    }
+    if (x < -9223372036854775808.0 || x >= 9223372036854775808.0) {
+        mz_trap("integer overflow");
+    }
+    return (int64_t)x;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+static mz_string mz_str_0 = { 2, "\303\251" };
+
+static void mz_show_int_int_float_int_int(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    int64_t t0 = mz_to_int(2.9);
+    int64_t t1 = mz_to_int(0.0 - 2.9);
+    mz_slot *t2 = mz_alloc(5);
+    t2[0].i = t0;
+    t2[1].i = t1;
+    t2[2].f = (double)3;
+    t2[3].i = (&mz_str_0)->len;
+    t2[4].i = mz_to_int(1e300);
+    return t2;
+}
+
+static void mz_show_int_int_float_int_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ");
+    mz_write_int(value[1].i);
+    mz_write_text(", ");
+    mz_write_float(value[2].f);
+    mz_write_text(", ");
+    mz_write_int(value[3].i);
+    mz_write_text(", ");
+    mz_write_int(value[4].i);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_int_int_float_int_int(value);
+}
+
+// ("a\"\\\n\r\t\0é??=" ++ "", "", 1 == 1, "a" != "a", 1.5 < 2.0)
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// The bytes of the new string are right after it
+static mz_string *mz_concat(const mz_string *a, const mz_string *b) {
+    mz_string *s = malloc(sizeof(mz_string) + (size_t)(a->len + b->len));
+    char *bytes;
+    if (s == NULL) {
+        mz_trap("out of memory");
+    }
+    bytes = (char *)(s + 1);
+    memcpy(bytes, a->bytes, (size_t)a->len);
+    memcpy(bytes + a->len, b->bytes, (size_t)b->len);
+    s->len = a->len + b->len;
+    s->bytes = bytes;
+    return s;
+}
+
+static bool mz_str_eq(const mz_string *a, const mz_string *b) {
+    return a->len == b->len && memcmp(a->bytes, b->bytes, (size_t)a->len) == 0;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_bool(bool b) {
+    mz_write_text(b ? "true" : "false");
+}
+
+// Strings inside of other values are written like literals
+static void mz_write_quoted(const mz_string *s) {
+    int64_t i;
+    mz_write_text("\"");
+    for (i = 0; i < s->len; i++) {
+        switch (s->bytes[i]) {
+        case '\\':
+            mz_write_text("\\\\");
+            break;
+        case '"':
+            mz_write_text("\\\"");
+            break;
+        case '\n':
+            mz_write_text("\\n");
+            break;
+        case '\t':
+            mz_write_text("\\t");
+            break;
+        case '\r':
+            mz_write_text("\\r");
+            break;
+        case '\0':
+            mz_write_text("\\0");
+            break;
+        default:
+            mz_write_bytes(&s->bytes[i], 1);
+        }
+    }
+    mz_write_text("\"");
+}
+
+static mz_string mz_str_0 = { 12, "a\"\\\n\r\t\000\303\251\?\?=" };
+static mz_string mz_str_1 = { 0, "" };
+static mz_string mz_str_2 = { 1, "a" };
+
+static void mz_show_string_string_bool_bool_bool(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    mz_slot *t0 = mz_alloc(5);
+    t0[0].s = mz_concat(&mz_str_0, &mz_str_1);
+    t0[1].s = &mz_str_1;
+    t0[2].b = 1 == 1;
+    t0[3].b = !mz_str_eq(&mz_str_2, &mz_str_2);
+    t0[4].b = 1.5 < 2.0;
+    return t0;
+}
+
+static void mz_show_string_string_bool_bool_bool(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_quoted(value[0].s);
+    mz_write_text(", ");
+    mz_write_quoted(value[1].s);
+    mz_write_text(", ");
+    mz_write_bool(value[2].b);
+    mz_write_text(", ");
+    mz_write_bool(value[3].b);
+    mz_write_text(", ");
+    mz_write_bool(value[4].b);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_string_string_bool_bool_bool(value);
+}
+
+// ((1 < 2) == (3 < 4)) != false
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+bool mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(bool value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_bool(bool b) {
+    mz_write_text(b ? "true" : "false");
+}
+
+bool mizzle_main(void) {
+    return ((1 < 2) == (3 < 4)) != false;
+}
+
+void mizzle_show(bool value) {
+    mz_write_bool(value);
+}
+
+// 0.0
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 0.0
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 0.0;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 2.5
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 2.5;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 2.5
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 2.5;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.1
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.1;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 0.1
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 0.1;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 1e15
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 1000000000000000.0;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 1e15
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 1000000000000000.0;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 1e16
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 1e16;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 1e16
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 1e16;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 1.5e16
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 1.5e16;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 1.5e16
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 1.5e16;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0001
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0001;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 0.0001
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 0.0001;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 1e-5
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 1e-5;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 1e-5
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 1e-5;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 1e300
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 1e300;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 1e300
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 1e300;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 5e-324
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 5e-324;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 5e-324
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 5e-324;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 123456.789
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 123456.789;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// 0.0 - 123456.789
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+double mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(double value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+double mizzle_main(void) {
+    return 0.0 - 123456.789;
+}
+
+void mizzle_show(double value) {
+    mz_write_float(value);
+}
+
+// (0.1 + 0.2, 1.0 / 3.0, 1e300 * 1e10, 0.0 - 1e300 * 1e10, 0.0 / 0.0)
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+static void mz_show_float_float_float_float_float(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    mz_slot *t0 = mz_alloc(5);
+    t0[0].f = 0.1 + 0.2;
+    t0[1].f = 1.0 / 3.0;
+    t0[2].f = 1e300 * 10000000000.0;
+    t0[3].f = 0.0 - 1e300 * 10000000000.0;
+    t0[4].f = 0.0 / 0.0;
+    return t0;
+}
+
+static void mz_show_float_float_float_float_float(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_float(value[0].f);
+    mz_write_text(", ");
+    mz_write_float(value[1].f);
+    mz_write_text(", ");
+    mz_write_float(value[2].f);
+    mz_write_text(", ");
+    mz_write_float(value[3].f);
+    mz_write_text(", ");
+    mz_write_float(value[4].f);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_float_float_float_float_float(value);
+}
+
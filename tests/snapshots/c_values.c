@@ -0,0 +1,1593 @@
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// (Rect(1, 2), Empty, Circle(0.5))
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect, TAG_Empty };
+
+// `List`
+enum { TAG_Cons, TAG_Nil };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+static void mz_show_Shape(mz_slot *value);
+static void mz_show_Shape_Shape_Shape(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    mz_slot *t0 = mz_alloc(3);
+    t0[0].i = TAG_Rect;
+    t0[1].i = 1;
+    t0[2].i = 2;
+    mz_slot *t1 = mz_alloc(1);
+    t1[0].i = TAG_Empty;
+    mz_slot *t2 = mz_alloc(2);
+    t2[0].i = TAG_Circle;
+    t2[1].f = 0.5;
+    mz_slot *t3 = mz_alloc(3);
+    t3[0].p = t0;
+    t3[1].p = t1;
+    t3[2].p = t2;
+    return t3;
+}
+
+static void mz_show_Shape(mz_slot *value) {
+    switch (value[0].i) {
+    case TAG_Circle:
+        mz_write_text("Circle(");
+        mz_write_float(value[1].f);
+        mz_write_text(")");
+        break;
+    case TAG_Rect:
+        mz_write_text("Rect(");
+        mz_write_int(value[1].i);
+        mz_write_text(", ");
+        mz_write_int(value[2].i);
+        mz_write_text(")");
+        break;
+    case TAG_Empty:
+        mz_write_text("Empty");
+        break;
+    }
+}
+
+static void mz_show_Shape_Shape_Shape(mz_slot *value) {
+    mz_write_text("(");
+    mz_show_Shape(value[0].p);
+    mz_write_text(", ");
+    mz_show_Shape(value[1].p);
+    mz_write_text(", ");
+    mz_show_Shape(value[2].p);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_Shape_Shape_Shape(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// Cons((1, "a"), Cons((2, "b"), Nil))
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect, TAG_Empty };
+
+// `List`
+enum { TAG_Cons, TAG_Nil };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+// Strings inside of other values are written like literals
+static void mz_write_quoted(const mz_string *s) {
+    int64_t i;
+    mz_write_text("\"");
+    for (i = 0; i < s->len; i++) {
+        switch (s->bytes[i]) {
+        case '\\':
+            mz_write_text("\\\\");
+            break;
+        case '"':
+            mz_write_text("\\\"");
+            break;
+        case '\n':
+            mz_write_text("\\n");
+            break;
+        case '\t':
+            mz_write_text("\\t");
+            break;
+        case '\r':
+            mz_write_text("\\r");
+            break;
+        case '\0':
+            mz_write_text("\\0");
+            break;
+        default:
+            mz_write_bytes(&s->bytes[i], 1);
+        }
+    }
+    mz_write_text("\"");
+}
+
+static mz_string mz_str_0 = { 1, "a" };
+static mz_string mz_str_1 = { 1, "b" };
+
+static void mz_show_int_string(mz_slot *value);
+static void mz_show_List_int_string(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    mz_slot *t0 = mz_alloc(2);
+    t0[0].i = 1;
+    t0[1].s = &mz_str_0;
+    mz_slot *t1 = mz_alloc(2);
+    t1[0].i = 2;
+    t1[1].s = &mz_str_1;
+    mz_slot *t2 = mz_alloc(1);
+    t2[0].i = TAG_Nil;
+    mz_slot *t3 = mz_alloc(3);
+    t3[0].i = TAG_Cons;
+    t3[1].p = t1;
+    t3[2].p = t2;
+    mz_slot *t4 = mz_alloc(3);
+    t4[0].i = TAG_Cons;
+    t4[1].p = t0;
+    t4[2].p = t3;
+    return t4;
+}
+
+static void mz_show_int_string(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ");
+    mz_write_quoted(value[1].s);
+    mz_write_text(")");
+}
+
+static void mz_show_List_int_string(mz_slot *value) {
+    switch (value[0].i) {
+    case TAG_Cons:
+        mz_write_text("Cons(");
+        mz_show_int_string(value[1].p);
+        mz_write_text(", ");
+        mz_show_List_int_string(value[2].p);
+        mz_write_text(")");
+        break;
+    case TAG_Nil:
+        mz_write_text("Nil");
+        break;
+    }
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_List_int_string(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// let p = Point { name = "p", x = 1, y = 2.5 } in [p, { p with x = 3 }]
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect, TAG_Empty };
+
+// `List`
+enum { TAG_Cons, TAG_Nil };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+static mz_slot *mz_copy(const mz_slot *from, int64_t slots) {
+    mz_slot *items = mz_alloc(slots);
+    memcpy(items, from, (size_t)slots * sizeof(mz_slot));
+    return items;
+}
+
+// An array has its length in the first slot and its items after that, it is empty for a negative length
+static mz_slot *mz_array(int64_t length) {
+    mz_slot *items;
+    if (length < 0) {
+        length = 0;
+    }
+    if (length >= INT64_MAX / (int64_t)sizeof(mz_slot)) {
+        mz_trap("out of memory");
+    }
+    items = mz_alloc(length + 1);
+    items[0].i = length;
+    return items;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+// Strings inside of other values are written like literals
+static void mz_write_quoted(const mz_string *s) {
+    int64_t i;
+    mz_write_text("\"");
+    for (i = 0; i < s->len; i++) {
+        switch (s->bytes[i]) {
+        case '\\':
+            mz_write_text("\\\\");
+            break;
+        case '"':
+            mz_write_text("\\\"");
+            break;
+        case '\n':
+            mz_write_text("\\n");
+            break;
+        case '\t':
+            mz_write_text("\\t");
+            break;
+        case '\r':
+            mz_write_text("\\r");
+            break;
+        case '\0':
+            mz_write_text("\\0");
+            break;
+        default:
+            mz_write_bytes(&s->bytes[i], 1);
+        }
+    }
+    mz_write_text("\"");
+}
+
+static mz_string mz_str_0 = { 1, "p" };
+
+static void mz_show_Point(mz_slot *value);
+static void mz_show_array_Point(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    mz_slot *t0 = mz_alloc(3);
+    t0[2].s = &mz_str_0;
+    t0[0].i = 1;
+    t0[1].f = 2.5;
+    mz_slot *p = t0;
+    mz_slot *t1 = mz_copy(p, 3);
+    t1[0].i = 3;
+    mz_slot *t2 = mz_array(2);
+    t2[1].p = p;
+    t2[2].p = t1;
+    return t2;
+}
+
+static void mz_show_Point(mz_slot *value) {
+    mz_write_text("Point { x = ");
+    mz_write_int(value[0].i);
+    mz_write_text(", y = ");
+    mz_write_float(value[1].f);
+    mz_write_text(", name = ");
+    mz_write_quoted(value[2].s);
+    mz_write_text(" }");
+}
+
+static void mz_show_array_Point(mz_slot *value) {
+    int64_t i;
+    mz_write_text("[");
+    for (i = 1; i <= value[0].i; i++) {
+        if (i > 1) {
+            mz_write_text(", ");
+        }
+        mz_show_Point(value[i].p);
+    }
+    mz_write_text("]");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_array_Point(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// let p = Point { name = "p", x = 1, y = 2.5 } in (p.name, p.y, len(p.name))
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect, TAG_Empty };
+
+// `List`
+enum { TAG_Cons, TAG_Nil };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+// The fewest digits that read back as `x`, written like Rust's `{:?}` writes them
+static void mz_write_float(double x) {
+    char scientific[32];
+    char digits[24];
+    char text[64];
+    const char *p;
+    int precision, exponent, i;
+    int count = 0;
+    int length = 0;
+
+    if (isnan(x)) {
+        mz_write_text("NaN");
+        return;
+    }
+    if (isinf(x)) {
+        mz_write_text(x > 0 ? "inf" : "-inf");
+        return;
+    }
+    if (x == 0) {
+        mz_write_text(signbit(x) ? "-0.0" : "0.0");
+        return;
+    }
+    // 17 digits always read back the same
+    for (precision = 0; precision < 16; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+        if (strtod(scientific, NULL) == x) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision, x);
+
+    p = scientific;
+    if (*p == '-') {
+        text[length++] = '-';
+        p++;
+    }
+    for (; *p != 'e'; p++) {
+        if (*p != '.') {
+            digits[count++] = *p;
+        }
+    }
+    exponent = atoi(p + 1);
+
+    if (exponent < -4 || exponent >= 16) {
+        text[length++] = digits[0];
+        if (count > 1) {
+            text[length++] = '.';
+            for (i = 1; i < count; i++) {
+                text[length++] = digits[i];
+            }
+        }
+        length += sprintf(text + length, "e%d", exponent);
+    } else if (exponent < 0) {
+        text[length++] = '0';
+        text[length++] = '.';
+        for (i = 0; i < -exponent - 1; i++) {
+            text[length++] = '0';
+        }
+        for (i = 0; i < count; i++) {
+            text[length++] = digits[i];
+        }
+    } else {
+        for (i = 0; i < count || i <= exponent; i++) {
+            if (i == exponent + 1) {
+                text[length++] = '.';
+            }
+            text[length++] = i < count ? digits[i] : '0';
+        }
+        if (count <= exponent + 1) {
+            text[length++] = '.';
+            text[length++] = '0';
+        }
+    }
+    mz_write_bytes(text, (size_t)length);
+}
+
+// Strings inside of other values are written like literals
+static void mz_write_quoted(const mz_string *s) {
+    int64_t i;
+    mz_write_text("\"");
+    for (i = 0; i < s->len; i++) {
+        switch (s->bytes[i]) {
+        case '\\':
+            mz_write_text("\\\\");
+            break;
+        case '"':
+            mz_write_text("\\\"");
+            break;
+        case '\n':
+            mz_write_text("\\n");
+            break;
+        case '\t':
+            mz_write_text("\\t");
+            break;
+        case '\r':
+            mz_write_text("\\r");
+            break;
+        case '\0':
+            mz_write_text("\\0");
+            break;
+        default:
+            mz_write_bytes(&s->bytes[i], 1);
+        }
+    }
+    mz_write_text("\"");
+}
+
+static mz_string mz_str_0 = { 1, "p" };
+
+static void mz_show_string_float_int(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    mz_slot *t0 = mz_alloc(3);
+    t0[2].s = &mz_str_0;
+    t0[0].i = 1;
+    t0[1].f = 2.5;
+    mz_slot *p = t0;
+    mz_slot *t1 = mz_alloc(3);
+    t1[0].s = p[2].s;
+    t1[1].f = p[1].f;
+    t1[2].i = p[2].s->len;
+    return t1;
+}
+
+static void mz_show_string_float_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_quoted(value[0].s);
+    mz_write_text(", ");
+    mz_write_float(value[1].f);
+    mz_write_text(", ");
+    mz_write_int(value[2].i);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_string_float_int(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// (inc, [[1, 2], [], [3]], [(), ()])
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect, TAG_Empty };
+
+// `List`
+enum { TAG_Cons, TAG_Nil };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// An array has its length in the first slot and its items after that, it is empty for a negative length
+static mz_slot *mz_array(int64_t length) {
+    mz_slot *items;
+    if (length < 0) {
+        length = 0;
+    }
+    if (length >= INT64_MAX / (int64_t)sizeof(mz_slot)) {
+        mz_trap("out of memory");
+    }
+    items = mz_alloc(length + 1);
+    items[0].i = length;
+    return items;
+}
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static int64_t fn_inc(int64_t x);
+static void mz_show_array_int(mz_slot *value);
+static void mz_show_array_array_int(mz_slot *value);
+static void mz_show_array_unit(mz_slot *value);
+static void mz_show_fn_int_int_array_array_int_array_unit(mz_slot *value);
+
+static int64_t fn_inc(int64_t x) {
+    return mz_add(x, 1);
+}
+
+mz_slot *mizzle_main(void) {
+    mz_fn t4 = (mz_fn)fn_inc;
+    mz_slot *t0 = mz_array(2);
+    t0[1].i = 1;
+    t0[2].i = 2;
+    mz_slot *t1 = mz_array(0);
+    mz_slot *t2 = mz_array(1);
+    t2[1].i = 3;
+    mz_slot *t3 = mz_array(3);
+    t3[1].p = t0;
+    t3[2].p = t1;
+    t3[3].p = t2;
+    mz_slot *t5 = mz_array(2);
+    mz_slot *t6 = mz_alloc(3);
+    t6[0].fn = t4;
+    t6[1].p = t3;
+    t6[2].p = t5;
+    return t6;
+}
+
+static void mz_show_array_int(mz_slot *value) {
+    int64_t i;
+    mz_write_text("[");
+    for (i = 1; i <= value[0].i; i++) {
+        if (i > 1) {
+            mz_write_text(", ");
+        }
+        mz_write_int(value[i].i);
+    }
+    mz_write_text("]");
+}
+
+static void mz_show_array_array_int(mz_slot *value) {
+    int64_t i;
+    mz_write_text("[");
+    for (i = 1; i <= value[0].i; i++) {
+        if (i > 1) {
+            mz_write_text(", ");
+        }
+        mz_show_array_int(value[i].p);
+    }
+    mz_write_text("]");
+}
+
+static void mz_show_array_unit(mz_slot *value) {
+    int64_t i;
+    mz_write_text("[");
+    for (i = 1; i <= value[0].i; i++) {
+        if (i > 1) {
+            mz_write_text(", ");
+        }
+        mz_write_text("()");
+    }
+    mz_write_text("]");
+}
+
+static void mz_show_fn_int_int_array_array_int_array_unit(mz_slot *value) {
+    mz_write_text("(<fn(int) -> int>, ");
+    mz_show_array_array_int(value[1].p);
+    mz_write_text(", ");
+    mz_show_array_unit(value[2].p);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_fn_int_int_array_array_int_array_unit(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// map(range(0, 5), name)
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect, TAG_Empty };
+
+// `List`
+enum { TAG_Cons, TAG_Nil };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// An array has its length in the first slot and its items after that, it is empty for a negative length
+static mz_slot *mz_array(int64_t length) {
+    mz_slot *items;
+    if (length < 0) {
+        length = 0;
+    }
+    if (length >= INT64_MAX / (int64_t)sizeof(mz_slot)) {
+        mz_trap("out of memory");
+    }
+    items = mz_alloc(length + 1);
+    items[0].i = length;
+    return items;
+}
+
+// `array(length, item)`
+static mz_slot *mz_repeat(int64_t length, mz_slot item) {
+    mz_slot *items = mz_array(length);
+    int64_t i;
+    for (i = 1; i <= items[0].i; i++) {
+        items[i] = item;
+    }
+    return items;
+}
+
+// The slot of `index` in `items`, which stops the program when it is outside of them,
+// `at` is where the index is in the source
+static mz_slot *mz_item(mz_slot *items, int64_t index, const char *at) {
+    if (index < 0 || index >= items[0].i) {
+        char message[512];
+        snprintf(
+            message,
+            sizeof message,
+            "%s: index `%lld` is out of bounds of an array with length `%lld`",
+            at,
+            (long long)index,
+            (long long)items[0].i
+        );
+        mz_trap(message);
+    }
+    return &items[index + 1];
+}
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+static int64_t mz_sub(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a - (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+// Strings inside of other values are written like literals
+static void mz_write_quoted(const mz_string *s) {
+    int64_t i;
+    mz_write_text("\"");
+    for (i = 0; i < s->len; i++) {
+        switch (s->bytes[i]) {
+        case '\\':
+            mz_write_text("\\\\");
+            break;
+        case '"':
+            mz_write_text("\\\"");
+            break;
+        case '\n':
+            mz_write_text("\\n");
+            break;
+        case '\t':
+            mz_write_text("\\t");
+            break;
+        case '\r':
+            mz_write_text("\\r");
+            break;
+        case '\0':
+            mz_write_text("\\0");
+            break;
+        default:
+            mz_write_bytes(&s->bytes[i], 1);
+        }
+    }
+    mz_write_text("\"");
+}
+
+static mz_string mz_str_0 = { 3, "big" };
+static mz_string mz_str_1 = { 5, "small" };
+
+static mz_slot *fn_prelude__map_int_string(mz_slot *items, mz_fn f);
+static mz_slot *fn_prelude__range(int64_t start, int64_t stop);
+static mz_string *fn_name(int64_t x);
+static void mz_show_array_string(mz_slot *value);
+
+static mz_slot *fn_prelude__map_int_string(mz_slot *items, mz_fn f) {
+    if (items[0].i == 0) {
+        mz_slot *t0 = mz_array(0);
+        return t0;
+    } else {
+        mz_slot *mapped = mz_repeat(items[0].i, (mz_slot){ .s = ((mz_string *(*)(int64_t))f)(mz_item(items, 0, "prelude.mi:15:51")->i) });
+        int64_t i = 1;
+        while (i < items[0].i) {
+            mz_item(mapped, i, "prelude.mi:18:20")->s = ((mz_string *(*)(int64_t))f)(mz_item(items, i, "prelude.mi:18:34")->i);
+            i = mz_add(i, 1);
+        }
+        return mapped;
+    }
+}
+
+static mz_slot *fn_prelude__range(int64_t start, int64_t stop) {
+    mz_slot *items = mz_repeat(mz_sub(stop, start), (mz_slot){ .i = 0 });
+    int64_t i = 0;
+    while (i < items[0].i) {
+        mz_item(items, i, "prelude.mi:5:15")->i = mz_add(start, i);
+        i = mz_add(i, 1);
+    }
+    return items;
+}
+
+static mz_string *fn_name(int64_t x) {
+    return x > 2 ? &mz_str_0 : &mz_str_1;
+}
+
+mz_slot *mizzle_main(void) {
+    return fn_prelude__map_int_string(fn_prelude__range(0, 5), (mz_fn)fn_name);
+}
+
+static void mz_show_array_string(mz_slot *value) {
+    int64_t i;
+    mz_write_text("[");
+    for (i = 1; i <= value[0].i; i++) {
+        if (i > 1) {
+            mz_write_text(", ");
+        }
+        mz_write_quoted(value[i].s);
+    }
+    mz_write_text("]");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_array_string(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// (array(2, ()), array(0 - 1, 1), length(array(3, 1.5)))
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect, TAG_Empty };
+
+// `List`
+enum { TAG_Cons, TAG_Nil };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// An array has its length in the first slot and its items after that, it is empty for a negative length
+static mz_slot *mz_array(int64_t length) {
+    mz_slot *items;
+    if (length < 0) {
+        length = 0;
+    }
+    if (length >= INT64_MAX / (int64_t)sizeof(mz_slot)) {
+        mz_trap("out of memory");
+    }
+    items = mz_alloc(length + 1);
+    items[0].i = length;
+    return items;
+}
+
+// `array(length, item)`
+static mz_slot *mz_repeat(int64_t length, mz_slot item) {
+    mz_slot *items = mz_array(length);
+    int64_t i;
+    for (i = 1; i <= items[0].i; i++) {
+        items[i] = item;
+    }
+    return items;
+}
+
+static int64_t mz_sub(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a - (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static void mz_show_array_unit(mz_slot *value);
+static void mz_show_array_int(mz_slot *value);
+static void mz_show_array_unit_array_int_int(mz_slot *value);
+
+mz_slot *mizzle_main(void) {
+    mz_slot *t0 = mz_alloc(3);
+    t0[0].p = mz_repeat(2, (mz_slot){ .i = 0 });
+    t0[1].p = mz_repeat(mz_sub(0, 1), (mz_slot){ .i = 1 });
+    t0[2].i = mz_repeat(3, (mz_slot){ .f = 1.5 })[0].i;
+    return t0;
+}
+
+static void mz_show_array_unit(mz_slot *value) {
+    int64_t i;
+    mz_write_text("[");
+    for (i = 1; i <= value[0].i; i++) {
+        if (i > 1) {
+            mz_write_text(", ");
+        }
+        mz_write_text("()");
+    }
+    mz_write_text("]");
+}
+
+static void mz_show_array_int(mz_slot *value) {
+    int64_t i;
+    mz_write_text("[");
+    for (i = 1; i <= value[0].i; i++) {
+        if (i > 1) {
+            mz_write_text(", ");
+        }
+        mz_write_int(value[i].i);
+    }
+    mz_write_text("]");
+}
+
+static void mz_show_array_unit_array_int_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_show_array_unit(value[0].p);
+    mz_write_text(", ");
+    mz_show_array_int(value[1].p);
+    mz_write_text(", ");
+    mz_write_int(value[2].i);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_array_unit_array_int_int(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// let f = inc in (f(1), f(f(2)))
+
+// Generated by mizzle
+#ifndef MIZZLE_PROGRAM_H
+#define MIZZLE_PROGRAM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// `unit` has a single value, which is 0
+typedef uint8_t mz_unit;
+
+// The bytes of a string are UTF-8, and are not followed by a 0
+typedef struct {
+    int64_t len;
+    const char *bytes;
+} mz_string;
+
+// Functions are cast back to their own type before they are called
+typedef void (*mz_fn)(void);
+
+// Tuples, records, variants and arrays point to slots, which each hold one of their values,
+// Variants have the index of their constructor in the first slot, and arrays have their length
+typedef union mz_slot {
+    int64_t i;
+    double f;
+    bool b;
+    mz_string *s;
+    union mz_slot *p;
+    mz_fn fn;
+} mz_slot;
+
+// Runs the program and gives back its value
+mz_slot *mizzle_main(void);
+
+// Writes a value given back by `mizzle_main` to stdout, the same way as running the program directly
+void mizzle_show(mz_slot *value);
+
+#endif
+
+// Generated by mizzle
+
+#include "program.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// `Shape`
+enum { TAG_Circle, TAG_Rect, TAG_Empty };
+
+// `List`
+enum { TAG_Cons, TAG_Nil };
+
+// Stops the program with `message`, after flushing what it already wrote
+static void mz_trap(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+// Space for `slots` values, which is never freed
+static mz_slot *mz_alloc(int64_t slots) {
+    mz_slot *items = calloc(slots > 0 ? (size_t)slots : 1, sizeof(mz_slot));
+    if (items == NULL) {
+        mz_trap("out of memory");
+    }
+    return items;
+}
+
+// Overflowing an `int64_t` is undefined, so arithmetic goes through `uint64_t` to wrap around like wasm
+static int64_t mz_add(int64_t a, int64_t b) {
+    return (int64_t)((uint64_t)a + (uint64_t)b);
+}
+
+// Everything the program writes goes through here
+static void mz_write_bytes(const char *bytes, size_t length) {
+    fwrite(bytes, 1, length, stdout);
+}
+
+static void mz_write_text(const char *text) {
+    mz_write_bytes(text, strlen(text));
+}
+
+static void mz_write_int(int64_t n) {
+    char text[24];
+    snprintf(text, sizeof text, "%lld", (long long)n);
+    mz_write_text(text);
+}
+
+static int64_t fn_inc(int64_t x);
+static void mz_show_int_int(mz_slot *value);
+
+static int64_t fn_inc(int64_t x) {
+    return mz_add(x, 1);
+}
+
+mz_slot *mizzle_main(void) {
+    mz_fn f = (mz_fn)fn_inc;
+    int64_t t0 = ((int64_t (*)(int64_t))f)(1);
+    mz_slot *t1 = mz_alloc(2);
+    t1[0].i = t0;
+    t1[1].i = ((int64_t (*)(int64_t))f)(((int64_t (*)(int64_t))f)(2));
+    return t1;
+}
+
+static void mz_show_int_int(mz_slot *value) {
+    mz_write_text("(");
+    mz_write_int(value[0].i);
+    mz_write_text(", ");
+    mz_write_int(value[1].i);
+    mz_write_text(")");
+}
+
+void mizzle_show(mz_slot *value) {
+    mz_show_int_int(value);
+}
+
@@ -0,0 +1,324 @@
+// type Shape = Circle(float) | Rect(int, int) | Empty
+//
+// fn area(s: Shape): int =
+// match s with
+// | Circle(r) => to_int(r * r * 3.0)
+// | Rect(1, h) => h
+// | Rect(w, h) => w * h
+// | Empty => 0
+// end
+// end
+//
+// fn triangle(n: int): int =
+// var i = 0 in var sum = 0 in
+// while true do
+// if i > n then break else sum := sum + i; i := i + 1 end
+// end;
+// sum
+// end
+//
+// let (a, (_, b)) = (area(Rect(2, 3)) + area(Rect(1, 5)), (true, triangle(4))) in
+// let a = a + 1 in
+// match (a, b > 5) with
+// | (0, _) => "zero"
+// | (_, true) => "big " ++ (if a > 10 then "a" else if a > 5 then "b" else "c" end end)
+// | _ => "small"
+// end
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+function $toInt(x) {
+    if (Number.isNaN(x)) {
+        throw new RangeError("invalid conversion to integer");
+    }
+    if (x < -(2 ** 63) || x >= 2 ** 63) {
+        throw new RangeError("integer overflow");
+    }
+    return BigInt(Math.trunc(x));
+}
+
+function area(s) {
+    if (s.tag === "Circle") {
+        const { args: [r] } = s;
+        return $toInt(r * r * 3.0);
+    } else if (s.tag === "Rect" && s.args[0] === 1n) {
+        const { args: [, h] } = s;
+        return h;
+    } else if (s.tag === "Rect") {
+        const { args: [w, h] } = s;
+        return $wrap(w * h);
+    } else if (s.tag === "Empty") {
+        return 0n;
+    }
+}
+
+function triangle(n) {
+    let i = 0n;
+    let sum = 0n;
+    while (true) {
+        if (i > n) {
+            break;
+        } else {
+            sum = $wrap(sum + i);
+            i = $wrap(i + 1n);
+        }
+    }
+    return sum;
+}
+
+export function main() {
+    const [a, [, b]] = [$wrap(area({ tag: "Rect", args: [2n, 3n] }) + area({ tag: "Rect", args: [1n, 5n] })), [true, triangle(4n)]];
+    const a$1 = $wrap(a + 1n);
+    const $0 = [a$1, b > 5n];
+    if ($0[0] === 0n) {
+        return "zero";
+    } else if ($0[1]) {
+        return "big " + (a$1 > 10n ? "a" : (a$1 > 5n ? "b" : "c"));
+    } else {
+        return "small";
+    }
+}
+
+export function show(value) {
+    return value;
+}
+
+// var x = 1 in x + (x := 10; x)
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+export function main() {
+    let x = 1n;
+    const $0 = x;
+    x = 10n;
+    return $wrap($0 + x);
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// var x = 1 in (x := x + 1; x, let y = x in (x := 5; y), x)
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+export function main() {
+    let x = 1n;
+    x = $wrap(x + 1n);
+    const $0 = x;
+    const y = x;
+    x = 5n;
+    return [$0, y, x];
+}
+
+function $show_int_int_int(value) {
+    return "(" + String(value[0]) + ", " + String(value[1]) + ", " + String(value[2]) + ")";
+}
+
+export function show(value) {
+    return $show_int_int_int(value);
+}
+
+// let char = 1 in let double = 2 in let char = char + double in char
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+export function main() {
+    const char = 1n;
+    const double = 2n;
+    const char$1 = $wrap(char + double);
+    return char$1;
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// let new = 1 in let this = 2 in let new = new + this in new
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+export function main() {
+    const new$ = 1n;
+    const this$ = 2n;
+    const new$$1 = $wrap(new$ + this$);
+    return new$$1;
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// fn main(): int = 1 end fn mz_show(x: int): int = x end mz_show(main())
+
+// Generated by mizzle
+
+function mz_show(x) {
+    return x;
+}
+
+function main$() {
+    return 1n;
+}
+
+export function main() {
+    return mz_show(main$());
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// fn main(): int = 1 end fn show(x: int): int = x end show(main())
+
+// Generated by mizzle
+
+function show$(x) {
+    return x;
+}
+
+function main$() {
+    return 1n;
+}
+
+export function main() {
+    return show$(main$());
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// var i = 0 in while (i := i + 1; print(i); i < 3) do print(if i == 2 then 0 else i end) end
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+export function main() {
+    let i = 0n;
+    while (true) {
+        i = $wrap(i + 1n);
+        console.log(String(i));
+        if (!(i < 3n)) {
+            break;
+        }
+        console.log(String(i === 2n ? 0n : i));
+    }
+}
+
+export function show(value) {
+    return "()";
+}
+
+// let items = [1, 2, 3] in items[1] := 5; print(items); items[3]
+
+// Generated by mizzle
+
+// Stops the program when `index` is outside of `items`, `at` is where the index is in the source
+function $check(items, index, at) {
+    if (index < 0n || index >= BigInt(items.length)) {
+        throw new RangeError(
+            at + ": index `" + index + "` is out of bounds of an array with length `" + items.length + "`"
+        );
+    }
+}
+
+function $get(items, index, at) {
+    $check(items, index, at);
+    return items[Number(index)];
+}
+
+function $set(items, index, value, at) {
+    $check(items, index, at);
+    items[Number(index)] = value;
+}
+
+export function main() {
+    const items = [1n, 2n, 3n];
+    $set(items, 1n, 5n, "test.mi:1:32");
+    console.log($show_array_int(items));
+    return $get(items, 3n, "test.mi:1:61");
+}
+
+function $show_array_int(value) {
+    return "[" + value.map((item) => String(item)).join(", ") + "]";
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// let t0 = 1 in (t0, if t0 > 0 then (t0, 2) else (3, t0) end)
+
+// Generated by mizzle
+
+export function main() {
+    const t0 = 1n;
+    return [t0, t0 > 0n ? [t0, 2n] : [3n, t0]];
+}
+
+function $show_int_int(value) {
+    return "(" + String(value[0]) + ", " + String(value[1]) + ")";
+}
+
+function $show_int_int_int(value) {
+    return "(" + String(value[0]) + ", " + $show_int_int(value[1]) + ")";
+}
+
+export function show(value) {
+    return $show_int_int_int(value);
+}
+
+// match (1, true, ()) with (1, false, _) => (0, ()) | (n, true, u) => (n, u) | _ => (2, ()) end
+
+// Generated by mizzle
+
+export function main() {
+    const $0 = [1n, true, undefined];
+    if ($0[0] === 1n && !$0[1]) {
+        return [0n, undefined];
+    } else if ($0[1]) {
+        const [n, , u] = $0;
+        return [n, u];
+    } else {
+        return [2n, undefined];
+    }
+}
+
+function $show_int_unit(value) {
+    return "(" + String(value[0]) + ", " + "()" + ")";
+}
+
+export function show(value) {
+    return $show_int_unit(value);
+}
+
@@ -0,0 +1,45 @@
+// shapes.mi:
+// fn area(x: int): int = x * x end
+// pub fn total(x: int): int = area(x) + 1 end
+//
+// main.mi:
+// import shapes
+// fn shapes__area(x: int): int = x end
+// fn shapes_area(x: int): int = x + 10 end
+// (total(3), shapes__area(2), shapes_area(2))
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+function shapes$total(x) {
+    return $wrap(shapes$area(x) + 1n);
+}
+
+function shapes__area(x) {
+    return x;
+}
+
+function shapes_area(x) {
+    return $wrap(x + 10n);
+}
+
+function shapes$area(x) {
+    return $wrap(x * x);
+}
+
+export function main() {
+    return [shapes$total(3n), shapes__area(2n), shapes_area(2n)];
+}
+
+function $show_int_int_int(value) {
+    return "(" + String(value[0]) + ", " + String(value[1]) + ", " + String(value[2]) + ")";
+}
+
+export function show(value) {
+    return $show_int_int_int(value);
+}
+
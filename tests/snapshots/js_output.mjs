@@ -0,0 +1,109 @@
+// type Shape = Circle(float) | Rect(int, int)
+// fn show<a>(x: a): unit = debug(x) end
+// fn say(x: int): int = print(x); x end
+// print("a\tb");
+// debug("a\tb");
+// debug(Rect(1, 2));
+// show(1);
+// show((Circle(0.5), [true]));
+// print(print(1));
+// print(say(1) + say(2) * say(3));
+// debug((say(4), say(5)));
+// var i = 0 in
+// while i < 3 do print(to_float(i) / 4.0); i := i + 1 end
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+// Strings inside of other values are shown like literals
+function $quote(s) {
+    const escapes = { "\\": "\\\\", '"': '\\"', "\n": "\\n", "\t": "\\t", "\r": "\\r", "\0": "\\0" };
+    return '"' + s.replace(/[\\"\n\t\r\0]/g, (c) => escapes[c]) + '"';
+}
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+function show$$int(x) {
+    console.log("[test.mi:3] " + String(x));
+}
+
+function show$$Shape_array_bool(x) {
+    console.log("[test.mi:3] " + $show_Shape_array_bool(x));
+}
+
+function say(x) {
+    console.log(String(x));
+    return x;
+}
+
+export function main() {
+    console.log("a\tb");
+    console.log("[test.mi:6] " + $quote("a\tb"));
+    console.log("[test.mi:7] " + $show_Shape({ tag: "Rect", args: [1n, 2n] }));
+    show$$int(1n);
+    show$$Shape_array_bool([{ tag: "Circle", args: [0.5] }, [true]]);
+    console.log(String(1n));
+    console.log("()");
+    console.log(String($wrap(say(1n) + $wrap(say(2n) * say(3n)))));
+    console.log("[test.mi:12] " + $show_int_int([say(4n), say(5n)]));
+    let i = 0n;
+    while (i < 3n) {
+        console.log($float(Number(i) / 4.0));
+        i = $wrap(i + 1n);
+    }
+}
+
+function $show_Shape(value) {
+    switch (value.tag) {
+        case "Circle":
+            return "Circle(" + $float(value.args[0]) + ")";
+        case "Rect":
+            return "Rect(" + String(value.args[0]) + ", " + String(value.args[1]) + ")";
+    }
+}
+
+function $show_int_int(value) {
+    return "(" + String(value[0]) + ", " + String(value[1]) + ")";
+}
+
+function $show_array_bool(value) {
+    return "[" + value.map((item) => String(item)).join(", ") + "]";
+}
+
+function $show_Shape_array_bool(value) {
+    return "(" + $show_Shape(value[0]) + ", " + $show_array_bool(value[1]) + ")";
+}
+
+export function show(value) {
+    return "()";
+}
+
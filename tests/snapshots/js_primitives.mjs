@@ -0,0 +1,1141 @@
+// 1 + 2 * 3
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+export function main() {
+    return $wrap(1n + $wrap(2n * 3n));
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// 0 - 9223372036854775807 - 1 - 1
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+export function main() {
+    return $wrap($wrap($wrap(0n - 9223372036854775807n) - 1n) - 1n);
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// (9223372036854775807 * 2, 7 / 2, (0 - 7) / 2, (0 - 7) % 3, 7 % 0 - 7)
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+// Stops the program in the same cases as wasm does
+function $div(a, b) {
+    if (b === 0n) {
+        throw new RangeError("integer divide by zero");
+    }
+    if (a === -9223372036854775808n && b === -1n) {
+        throw new RangeError("integer overflow");
+    }
+    return a / b;
+}
+
+function $rem(a, b) {
+    if (b === 0n) {
+        throw new RangeError("integer divide by zero");
+    }
+    return a % b;
+}
+
+export function main() {
+    return [$wrap(9223372036854775807n * 2n), $div(7n, 2n), $div($wrap(0n - 7n), 2n), $rem($wrap(0n - 7n), 3n), $wrap($rem(7n, 0n) - 7n)];
+}
+
+function $show_int_int_int_int_int(value) {
+    return "(" + String(value[0]) + ", " + String(value[1]) + ", " + String(value[2]) + ", " + String(value[3]) + ", " + String(value[4]) + ")";
+}
+
+export function show(value) {
+    return $show_int_int_int_int_int(value);
+}
+
+// 1 / 0
+
+// Generated by mizzle
+
+// Stops the program in the same cases as wasm does
+function $div(a, b) {
+    if (b === 0n) {
+        throw new RangeError("integer divide by zero");
+    }
+    if (a === -9223372036854775808n && b === -1n) {
+        throw new RangeError("integer overflow");
+    }
+    return a / b;
+}
+
+export function main() {
+    return $div(1n, 0n);
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// (0 - 9223372036854775807 - 1) / (0 - 1)
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+// Stops the program in the same cases as wasm does
+function $div(a, b) {
+    if (b === 0n) {
+        throw new RangeError("integer divide by zero");
+    }
+    if (a === -9223372036854775808n && b === -1n) {
+        throw new RangeError("integer overflow");
+    }
+    return a / b;
+}
+
+export function main() {
+    return $div($wrap($wrap(0n - 9223372036854775807n) - 1n), $wrap(0n - 1n));
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// (0 - 9223372036854775807 - 1) % (0 - 1)
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+function $rem(a, b) {
+    if (b === 0n) {
+        throw new RangeError("integer divide by zero");
+    }
+    return a % b;
+}
+
+export function main() {
+    return $rem($wrap($wrap(0n - 9223372036854775807n) - 1n), $wrap(0n - 1n));
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// (to_int(2.9), to_int(0.0 - 2.9), to_float(3), len("é"), to_int(1e300))
+
+// Generated by mizzle
+
+// Strings are counted in bytes of UTF-8, like `len` does
+function $len(s) {
+    return BigInt(new TextEncoder().encode(s).length);
+}
+
+function $toInt(x) {
+    if (Number.isNaN(x)) {
+        throw new RangeError("invalid conversion to integer");
+    }
+    if (x < -(2 ** 63) || x >= 2 ** 63) {
+        throw new RangeError("integer overflow");
+    }
+    return BigInt(Math.trunc(x));
+}
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return [$toInt(2.9), $toInt(0.0 - 2.9), Number(3n), $len("é"), $toInt(1e300)];
+}
+
+function $show_int_int_float_int_int(value) {
+    return "(" + String(value[0]) + ", " + String(value[1]) + ", " + $float(value[2]) + ", " + String(value[3]) + ", " + String(value[4]) + ")";
+}
+
+export function show(value) {
+    return $show_int_int_float_int_int(value);
+}
+
+// ("a\"\\\n\r\t\0é??=" ++ "", "", 1 == 1, "a" != "a", 1.5 < 2.0)
+
+// Generated by mizzle
+
+// Strings inside of other values are shown like literals
+function $quote(s) {
+    const escapes = { "\\": "\\\\", '"': '\\"', "\n": "\\n", "\t": "\\t", "\r": "\\r", "\0": "\\0" };
+    return '"' + s.replace(/[\\"\n\t\r\0]/g, (c) => escapes[c]) + '"';
+}
+
+export function main() {
+    return ["a\"\\\n\r\t\u0000é??=" + "", "", 1n === 1n, "a" !== "a", 1.5 < 2.0];
+}
+
+function $show_string_string_bool_bool_bool(value) {
+    return "(" + $quote(value[0]) + ", " + $quote(value[1]) + ", " + String(value[2]) + ", " + String(value[3]) + ", " + String(value[4]) + ")";
+}
+
+export function show(value) {
+    return $show_string_string_bool_bool_bool(value);
+}
+
+// ((1 < 2) == (3 < 4)) != false
+
+// Generated by mizzle
+
+export function main() {
+    return 1n < 2n === 3n < 4n !== false;
+}
+
+export function show(value) {
+    return String(value);
+}
+
+// 0.0
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 0.0
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 0.0;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 2.5
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 2.5;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 2.5
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 2.5;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.1
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.1;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 0.1
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 0.1;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 1e15
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 1000000000000000.0;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 1e15
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 1000000000000000.0;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 1e16
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 1e16;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 1e16
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 1e16;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 1.5e16
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 1.5e16;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 1.5e16
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 1.5e16;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0001
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0001;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 0.0001
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 0.0001;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 1e-5
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 1e-5;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 1e-5
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 1e-5;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 1e300
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 1e300;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 1e300
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 1e300;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 5e-324
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 5e-324;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 5e-324
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 5e-324;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 123456.789
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 123456.789;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// 0.0 - 123456.789
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return 0.0 - 123456.789;
+}
+
+export function show(value) {
+    return $float(value);
+}
+
+// (0.1 + 0.2, 1.0 / 3.0, 1e300 * 1e10, 0.0 - 1e300 * 1e10, 0.0 / 0.0)
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return [0.1 + 0.2, 1.0 / 3.0, 1e300 * 10000000000.0, 0.0 - 1e300 * 10000000000.0, 0.0 / 0.0];
+}
+
+function $show_float_float_float_float_float(value) {
+    return "(" + $float(value[0]) + ", " + $float(value[1]) + ", " + $float(value[2]) + ", " + $float(value[3]) + ", " + $float(value[4]) + ")";
+}
+
+export function show(value) {
+    return $show_float_float_float_float_float(value);
+}
+
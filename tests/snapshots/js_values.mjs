@@ -0,0 +1,410 @@
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// (Rect(1, 2), Empty, Circle(0.5))
+
+// Generated by mizzle
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    return [{ tag: "Rect", args: [1n, 2n] }, { tag: "Empty", args: [] }, { tag: "Circle", args: [0.5] }];
+}
+
+function $show_Shape(value) {
+    switch (value.tag) {
+        case "Circle":
+            return "Circle(" + $float(value.args[0]) + ")";
+        case "Rect":
+            return "Rect(" + String(value.args[0]) + ", " + String(value.args[1]) + ")";
+        case "Empty":
+            return "Empty";
+    }
+}
+
+function $show_Shape_Shape_Shape(value) {
+    return "(" + $show_Shape(value[0]) + ", " + $show_Shape(value[1]) + ", " + $show_Shape(value[2]) + ")";
+}
+
+export function show(value) {
+    return $show_Shape_Shape_Shape(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// Cons((1, "a"), Cons((2, "b"), Nil))
+
+// Generated by mizzle
+
+// Strings inside of other values are shown like literals
+function $quote(s) {
+    const escapes = { "\\": "\\\\", '"': '\\"', "\n": "\\n", "\t": "\\t", "\r": "\\r", "\0": "\\0" };
+    return '"' + s.replace(/[\\"\n\t\r\0]/g, (c) => escapes[c]) + '"';
+}
+
+export function main() {
+    return { tag: "Cons", args: [[1n, "a"], { tag: "Cons", args: [[2n, "b"], { tag: "Nil", args: [] }] }] };
+}
+
+function $show_int_string(value) {
+    return "(" + String(value[0]) + ", " + $quote(value[1]) + ")";
+}
+
+function $show_List_int_string(value) {
+    switch (value.tag) {
+        case "Cons":
+            return "Cons(" + $show_int_string(value.args[0]) + ", " + $show_List_int_string(value.args[1]) + ")";
+        case "Nil":
+            return "Nil";
+    }
+}
+
+export function show(value) {
+    return $show_List_int_string(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// let p = Point { name = "p", x = 1, y = 2.5 } in [p, { p with x = 3 }]
+
+// Generated by mizzle
+
+// Strings inside of other values are shown like literals
+function $quote(s) {
+    const escapes = { "\\": "\\\\", '"': '\\"', "\n": "\\n", "\t": "\\t", "\r": "\\r", "\0": "\\0" };
+    return '"' + s.replace(/[\\"\n\t\r\0]/g, (c) => escapes[c]) + '"';
+}
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    const p = { name: "p", x: 1n, y: 2.5 };
+    return [p, { ...p, x: 3n }];
+}
+
+function $show_Point(value) {
+    return "Point { x = " + String(value.x) + ", y = " + $float(value.y) + ", name = " + $quote(value.name) + " }";
+}
+
+function $show_array_Point(value) {
+    return "[" + value.map((item) => $show_Point(item)).join(", ") + "]";
+}
+
+export function show(value) {
+    return $show_array_Point(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// let p = Point { name = "p", x = 1, y = 2.5 } in (p.name, p.y, len(p.name))
+
+// Generated by mizzle
+
+// Strings are counted in bytes of UTF-8, like `len` does
+function $len(s) {
+    return BigInt(new TextEncoder().encode(s).length);
+}
+
+// Strings inside of other values are shown like literals
+function $quote(s) {
+    const escapes = { "\\": "\\\\", '"': '\\"', "\n": "\\n", "\t": "\\t", "\r": "\\r", "\0": "\\0" };
+    return '"' + s.replace(/[\\"\n\t\r\0]/g, (c) => escapes[c]) + '"';
+}
+
+// The shortest digits that read back as `x`, written like Rust's `{:?}` writes them
+function $float(x) {
+    if (Number.isNaN(x)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    if (x === 0) {
+        return Object.is(x, -0) ? "-0.0" : "0.0";
+    }
+    const sign = x < 0 ? "-" : "";
+    const [mantissa, exponent] = Math.abs(x).toExponential().split("e");
+    const e = Number(exponent);
+    if (e < -4 || e >= 16) {
+        return sign + mantissa + "e" + e;
+    }
+    const digits = mantissa.replace(".", "");
+    if (e < 0) {
+        return sign + "0." + "0".repeat(-e - 1) + digits;
+    }
+    if (digits.length <= e + 1) {
+        return sign + digits + "0".repeat(e + 1 - digits.length) + ".0";
+    }
+    return sign + digits.slice(0, e + 1) + "." + digits.slice(e + 1);
+}
+
+export function main() {
+    const p = { name: "p", x: 1n, y: 2.5 };
+    return [p.name, p.y, $len(p.name)];
+}
+
+function $show_string_float_int(value) {
+    return "(" + $quote(value[0]) + ", " + $float(value[1]) + ", " + String(value[2]) + ")";
+}
+
+export function show(value) {
+    return $show_string_float_int(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// (inc, [[1, 2], [], [3]], [(), ()])
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+function inc(x) {
+    return $wrap(x + 1n);
+}
+
+export function main() {
+    return [inc, [[1n, 2n], [], [3n]], [undefined, undefined]];
+}
+
+function $show_array_int(value) {
+    return "[" + value.map((item) => String(item)).join(", ") + "]";
+}
+
+function $show_array_array_int(value) {
+    return "[" + value.map((item) => $show_array_int(item)).join(", ") + "]";
+}
+
+function $show_array_unit(value) {
+    return "[" + value.map((item) => "()").join(", ") + "]";
+}
+
+function $show_fn_int_int_array_array_int_array_unit(value) {
+    return "(" + "<fn(int) -> int>" + ", " + $show_array_array_int(value[1]) + ", " + $show_array_unit(value[2]) + ")";
+}
+
+export function show(value) {
+    return $show_fn_int_int_array_array_int_array_unit(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// map(range(0, 5), name)
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+// `array(length, value)`, which is empty for a negative length
+function $array(length, value) {
+    return length > 0n ? new Array(Number(length)).fill(value) : [];
+}
+
+// Stops the program when `index` is outside of `items`, `at` is where the index is in the source
+function $check(items, index, at) {
+    if (index < 0n || index >= BigInt(items.length)) {
+        throw new RangeError(
+            at + ": index `" + index + "` is out of bounds of an array with length `" + items.length + "`"
+        );
+    }
+}
+
+function $get(items, index, at) {
+    $check(items, index, at);
+    return items[Number(index)];
+}
+
+function $set(items, index, value, at) {
+    $check(items, index, at);
+    items[Number(index)] = value;
+}
+
+// Strings inside of other values are shown like literals
+function $quote(s) {
+    const escapes = { "\\": "\\\\", '"': '\\"', "\n": "\\n", "\t": "\\t", "\r": "\\r", "\0": "\\0" };
+    return '"' + s.replace(/[\\"\n\t\r\0]/g, (c) => escapes[c]) + '"';
+}
+
+function prelude$map$int$string(items, f) {
+    if (BigInt(items.length) === 0n) {
+        return [];
+    } else {
+        const mapped = $array(BigInt(items.length), f($get(items, 0n, "prelude.mi:15:51")));
+        let i = 1n;
+        while (i < BigInt(items.length)) {
+            $set(mapped, i, f($get(items, i, "prelude.mi:18:34")), "prelude.mi:18:20");
+            i = $wrap(i + 1n);
+        }
+        return mapped;
+    }
+}
+
+function prelude$range(start, stop) {
+    const items = $array($wrap(stop - start), 0n);
+    let i = 0n;
+    while (i < BigInt(items.length)) {
+        $set(items, i, $wrap(start + i), "prelude.mi:5:15");
+        i = $wrap(i + 1n);
+    }
+    return items;
+}
+
+function name(x) {
+    return x > 2n ? "big" : "small";
+}
+
+export function main() {
+    return prelude$map$int$string(prelude$range(0n, 5n), name);
+}
+
+function $show_array_string(value) {
+    return "[" + value.map((item) => $quote(item)).join(", ") + "]";
+}
+
+export function show(value) {
+    return $show_array_string(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// (array(2, ()), array(0 - 1, 1), length(array(3, 1.5)))
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+// `array(length, value)`, which is empty for a negative length
+function $array(length, value) {
+    return length > 0n ? new Array(Number(length)).fill(value) : [];
+}
+
+export function main() {
+    return [$array(2n, undefined), $array($wrap(0n - 1n), 1n), BigInt($array(3n, 1.5).length)];
+}
+
+function $show_array_unit(value) {
+    return "[" + value.map((item) => "()").join(", ") + "]";
+}
+
+function $show_array_int(value) {
+    return "[" + value.map((item) => String(item)).join(", ") + "]";
+}
+
+function $show_array_unit_array_int_int(value) {
+    return "(" + $show_array_unit(value[0]) + ", " + $show_array_int(value[1]) + ", " + String(value[2]) + ")";
+}
+
+export function show(value) {
+    return $show_array_unit_array_int_int(value);
+}
+
+// type Shape = Circle(float) | Rect(int, int) | Empty
+// type List<a> = Cons(a, List<a>) | Nil
+// type Point = { x: int, y: float, name: string }
+// fn inc(x: int): int = x + 1 end
+// fn name(x: int): string = if x > 2 then "big" else "small" end end
+// let f = inc in (f(1), f(f(2)))
+
+// Generated by mizzle
+
+// Wraps around like an `i64`
+function $wrap(n) {
+    return BigInt.asIntN(64, n);
+}
+
+function inc(x) {
+    return $wrap(x + 1n);
+}
+
+export function main() {
+    const f = inc;
+    return [f(1n), f(f(2n))];
+}
+
+function $show_int_int(value) {
+    return "(" + String(value[0]) + ", " + String(value[1]) + ")";
+}
+
+export function show(value) {
+    return $show_int_int(value);
+}
+